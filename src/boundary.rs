@@ -0,0 +1,168 @@
+use crate::{
+    coord::{coordinates::Coordinates, polygon::Polygon},
+    model::{element::Element, id::RelationId, tags::Tags},
+    multipolygon::{Assembler, error::Error},
+};
+
+/// An administrative boundary like a country, state or municipality.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Tag:boundary%3Dadministrative>
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdminBoundary {
+    pub id: RelationId,
+    pub name: Option<String>,
+    /// The level from 2 for countries up to 11 for the smallest subdivisions.
+    ///
+    /// See <https://wiki.openstreetmap.org/wiki/Key:admin_level>
+    pub admin_level: u8,
+    pub tags: Tags,
+    pub polygons: Vec<Polygon>,
+}
+
+impl AdminBoundary {
+    /// Check if [`Coordinates`] lie inside this boundary.
+    pub fn contains(&self, coordinates: &Coordinates) -> bool {
+        self.polygons
+            .iter()
+            .any(|polygon| polygon.contains(coordinates))
+    }
+}
+
+/// Extract the `boundary=administrative` relations at the given `admin_levels` from a dataset,
+/// e.g. a local file or an Overpass result that includes the member ways and their nodes.
+///
+/// Every relation is assembled on its own with the [`Assembler`], so relations that are
+/// incomplete or broken are reported with their [`Error`] without affecting the others.
+/// All levels are extracted if `admin_levels` is empty.
+pub fn admin_boundaries(
+    elements: impl IntoIterator<Item = Element>,
+    admin_levels: &[u8],
+) -> Vec<(RelationId, Result<AdminBoundary, Error>)> {
+    let assembler: Assembler = elements.into_iter().collect();
+
+    assembler
+        .relations()
+        .iter()
+        .filter(|relation| {
+            relation.tags.get("boundary").map(String::as_str) == Some("administrative")
+        })
+        .filter_map(|relation| {
+            let admin_level = relation.tags.get("admin_level")?.trim().parse().ok()?;
+            if !admin_levels.is_empty() && !admin_levels.contains(&admin_level) {
+                return None;
+            }
+
+            let boundary = assembler.assemble(relation).map(|polygons| AdminBoundary {
+                id: relation.id,
+                name: relation.tags.get("name").cloned(),
+                admin_level,
+                tags: relation.tags.clone(),
+                polygons,
+            });
+
+            Some((relation.id, boundary))
+        })
+        .collect()
+}
+
+/// Find the boundaries containing [`Coordinates`], ordered by their `admin_level`, e.g. country,
+/// state, municipality.
+pub fn lookup<'a>(
+    boundaries: &'a [AdminBoundary],
+    coordinates: &Coordinates,
+) -> Vec<&'a AdminBoundary> {
+    let mut containing: Vec<&AdminBoundary> = boundaries
+        .iter()
+        .filter(|boundary| boundary.contains(coordinates))
+        .collect();
+    containing.sort_by_key(|boundary| boundary.admin_level);
+
+    containing
+}
+
+#[cfg(test)]
+mod boundary_test {
+    use crate::{
+        boundary::{AdminBoundary, admin_boundaries, lookup},
+        coord::coordinates::Coordinates,
+        model::{
+            element::{Element, Relation, RelationMember},
+            fixture::{node, way},
+            id::{RelationId, WayId},
+            tags::Tags,
+        },
+        multipolygon::error::Error,
+    };
+
+    fn boundary(id: i64, way: i64, admin_level: &str, name: &str) -> Element {
+        Element::Relation(Relation {
+            id: RelationId::new(id),
+            members: vec![RelationMember::new(WayId::new(way), "outer")],
+            tags: Tags::from([
+                ("type".to_string(), "boundary".to_string()),
+                ("boundary".to_string(), "administrative".to_string()),
+                ("admin_level".to_string(), admin_level.to_string()),
+                ("name".to_string(), name.to_string()),
+            ]),
+            ..Default::default()
+        })
+    }
+
+    fn elements() -> Vec<Element> {
+        vec![
+            node(1, 0.0, 0.0),
+            node(2, 0.0, 10.0),
+            node(3, 10.0, 10.0),
+            node(4, 10.0, 0.0),
+            node(5, 1.0, 1.0),
+            node(6, 1.0, 3.0),
+            node(7, 3.0, 3.0),
+            node(8, 3.0, 1.0),
+            way(10, &[1, 2, 3, 4, 1]),
+            way(11, &[5, 6, 7, 8, 5]),
+            boundary(20, 10, "2", "Country"),
+            boundary(21, 11, "8", "Town"),
+            boundary(22, 12, "8", "Broken"),
+            boundary(23, 11, "unknown", "Invalid"),
+        ]
+    }
+
+    fn extracted(admin_levels: &[u8]) -> Vec<AdminBoundary> {
+        admin_boundaries(elements(), admin_levels)
+            .into_iter()
+            .filter_map(|(_, boundary)| boundary.ok())
+            .collect()
+    }
+
+    #[test]
+    fn extract() {
+        let boundaries = admin_boundaries(elements(), &[]);
+
+        assert_eq!(boundaries.len(), 3);
+        assert_eq!(
+            boundaries[0].1.as_ref().unwrap().name.as_deref(),
+            Some("Country")
+        );
+        assert_eq!(boundaries[1].1.as_ref().unwrap().admin_level, 8);
+        assert!(matches!(boundaries[2], (_, Err(Error::MissingWay(_)))));
+
+        let countries = extracted(&[2]);
+        assert_eq!(countries.len(), 1);
+        assert_eq!(countries[0].id, RelationId::new(20));
+    }
+
+    #[test]
+    fn reverse_geocode() {
+        let boundaries = extracted(&[]);
+        let names = |lat, lon| {
+            lookup(&boundaries, &Coordinates::from_unchecked(lat, lon))
+                .iter()
+                .map(|boundary| boundary.name.clone().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(2.0, 2.0), vec!["Country", "Town"]);
+        assert_eq!(names(5.0, 5.0), vec!["Country"]);
+        assert!(names(20.0, 5.0).is_empty());
+    }
+}
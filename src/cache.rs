@@ -0,0 +1,488 @@
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    io,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use reqwest::{
+    Method, StatusCode,
+    header::{
+        AUTHORIZATION, CACHE_CONTROL, ETAG, HeaderMap, HeaderName, HeaderValue, IF_MODIFIED_SINCE,
+        IF_NONE_MATCH, LAST_MODIFIED, RANGE,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "blocking")]
+use crate::transport::BlockingHttpTransport;
+#[cfg(feature = "async")]
+use crate::transport::{Execution, HttpTransport};
+
+/// The default maximum size of a cached body, 10 MiB.
+const DEFAULT_MAX_ENTRY_SIZE: u64 = 10 * 1024 * 1024;
+
+/// A response stored by a [`CachingTransport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedResponse {
+    /// The headers of the response, with the names in lowercase.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+/// Stores the responses of a [`CachingTransport`] by url.
+pub trait CacheStore: fmt::Debug + Send + Sync {
+    /// Get the response stored for `url`.
+    fn get(&self, url: &str) -> Option<CachedResponse>;
+
+    /// Store the response for `url`, replacing an earlier one.
+    fn put(&self, url: &str, response: CachedResponse);
+}
+
+/// A [`CacheStore`] keeping the responses in memory.
+#[derive(Debug, Default)]
+pub struct MemoryCache {
+    entries: Mutex<HashMap<String, CachedResponse>>,
+}
+
+/// A [`CacheStore`] keeping the responses as files in a directory, so they survive restarts.
+///
+/// Every response is stored as a `.json` file with the headers next to a `.body` file. Failing
+/// to read or write the files is treated like a missing entry.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    directory: PathBuf,
+}
+
+/// The header file of a [`DiskCache`] entry.
+#[derive(Debug, Serialize, Deserialize)]
+struct DiskEntry {
+    url: String,
+    headers: Vec<(String, String)>,
+}
+
+/// An [`HttpTransport`] or [`BlockingHttpTransport`] answering `GET` requests from a cache if the
+/// server confirms that nothing changed.
+///
+/// Responses with an `ETag` or `Last-Modified` header are stored. When the url is requested
+/// again, `If-None-Match` and `If-Modified-Since` are sent, and a `304 Not Modified` is answered
+/// with the stored response as `200 OK`. This suits resources that rarely change, e.g. the
+/// capabilities of the OSM API, tiles or the state files of the replication.
+///
+/// Requests with their own conditional, `Range` or `Authorization` headers are passed through, as
+/// are responses marked `no-store` or `private` by their `Cache-Control` header and responses
+/// without a `Content-Length` or larger than the [maximum entry
+/// size](CachingTransport::with_max_entry_size).
+#[derive(Debug)]
+pub struct CachingTransport<T> {
+    inner: T,
+    store: Arc<dyn CacheStore>,
+    max_entry_size: u64,
+}
+
+impl CachedResponse {
+    /// Get a header by its lowercase name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(other, _)| other == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn from_parts(headers: &HeaderMap, body: Vec<u8>) -> Self {
+        Self {
+            headers: headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect(),
+            body,
+        }
+    }
+
+    fn into_http(self) -> http::Response<Vec<u8>> {
+        let mut response = http::Response::new(self.body);
+        for (name, value) in self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                response.headers_mut().append(name, value);
+            }
+        }
+
+        response
+    }
+}
+
+impl MemoryCache {
+    /// Construct a new empty [`MemoryCache`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the amount of stored responses.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Check if no responses are stored.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Remove all stored responses.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CachedResponse>> {
+        // The entries stay consistent even if a holder panicked.
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl CacheStore for MemoryCache {
+    fn get(&self, url: &str) -> Option<CachedResponse> {
+        self.lock().get(url).cloned()
+    }
+
+    fn put(&self, url: &str, response: CachedResponse) {
+        self.lock().insert(url.to_string(), response);
+    }
+}
+
+impl DiskCache {
+    /// Construct a new [`DiskCache`] in `directory`, creating it if necessary.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`io::Error`] if the directory can not be created.
+    pub fn new(directory: impl Into<PathBuf>) -> io::Result<Self> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+
+        Ok(Self { directory })
+    }
+
+    fn path(&self, url: &str, extension: &str) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        url.hash(&mut hasher);
+
+        self.directory
+            .join(format!("{:016x}.{extension}", hasher.finish()))
+    }
+
+    fn write(&self, url: &str, response: CachedResponse) -> io::Result<()> {
+        let entry = DiskEntry {
+            url: url.to_string(),
+            headers: response.headers,
+        };
+        // The body is written first, so the header file only exists for a complete entry.
+        fs::write(self.path(url, "body"), response.body)?;
+        fs::write(self.path(url, "json"), serde_json::to_vec(&entry)?)
+    }
+}
+
+impl CacheStore for DiskCache {
+    fn get(&self, url: &str) -> Option<CachedResponse> {
+        let entry: DiskEntry =
+            serde_json::from_slice(&fs::read(self.path(url, "json")).ok()?).ok()?;
+        // Different urls can share a file name.
+        if entry.url != url {
+            return None;
+        }
+
+        Some(CachedResponse {
+            headers: entry.headers,
+            body: fs::read(self.path(url, "body")).ok()?,
+        })
+    }
+
+    fn put(&self, url: &str, response: CachedResponse) {
+        // A failed write only costs a later cache miss.
+        let _ = fs::remove_file(self.path(url, "json"));
+        let _ = self.write(url, response);
+    }
+}
+
+impl<T> CachingTransport<T> {
+    /// Construct a new [`CachingTransport`] sending requests with `inner`, e.g. a
+    /// [`reqwest::Client`], and storing the responses in `store`.
+    pub fn new(inner: T, store: Arc<dyn CacheStore>) -> Self {
+        Self {
+            inner,
+            store,
+            max_entry_size: DEFAULT_MAX_ENTRY_SIZE,
+        }
+    }
+
+    /// Set the maximum size of a stored body in bytes, 10 MiB by default.
+    pub fn with_max_entry_size(mut self, max_entry_size: u64) -> Self {
+        self.max_entry_size = max_entry_size;
+        self
+    }
+
+    /// Get the stored response for a request and make the request conditional on it.
+    ///
+    /// Returns [`None`] if the request is not cached at all.
+    fn prepare(
+        &self,
+        method: &Method,
+        url: &str,
+        headers: &mut HeaderMap,
+    ) -> Option<Option<CachedResponse>> {
+        if method != Method::GET
+            || [IF_NONE_MATCH, IF_MODIFIED_SINCE, RANGE, AUTHORIZATION]
+                .iter()
+                .any(|name| headers.contains_key(name))
+        {
+            return None;
+        }
+
+        let cached = self.store.get(url);
+        if let Some(ref cached) = cached {
+            for (header, condition) in [(ETAG, IF_NONE_MATCH), (LAST_MODIFIED, IF_MODIFIED_SINCE)] {
+                if let Some(value) = cached
+                    .header(header.as_str())
+                    .and_then(|value| HeaderValue::from_str(value).ok())
+                {
+                    headers.insert(condition, value);
+                }
+            }
+        }
+
+        Some(cached)
+    }
+
+    /// Check if a response with `status`, `headers` and `length` is stored.
+    fn storable(&self, status: StatusCode, headers: &HeaderMap, length: Option<u64>) -> bool {
+        status == StatusCode::OK
+            && (headers.contains_key(ETAG) || headers.contains_key(LAST_MODIFIED))
+            && !forbids_storing(headers)
+            && length.is_some_and(|length| length <= self.max_entry_size)
+    }
+}
+
+/// Check if the `Cache-Control` headers of a response contain `no-store` or `private`.
+fn forbids_storing(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(CACHE_CONTROL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|directive| directive.split('=').next().unwrap_or_default().trim())
+        .any(|directive| {
+            directive.eq_ignore_ascii_case("no-store") || directive.eq_ignore_ascii_case("private")
+        })
+}
+
+#[cfg(feature = "async")]
+impl<T: HttpTransport> HttpTransport for CachingTransport<T> {
+    fn execute(&self, mut request: reqwest::Request) -> Execution<'_> {
+        Box::pin(async move {
+            let url = request.url().to_string();
+            let Some(cached) = self.prepare(&request.method().clone(), &url, request.headers_mut())
+            else {
+                return self.inner.execute(request).await;
+            };
+
+            let response = self.inner.execute(request).await?;
+            let status = response.status();
+            if let Some(cached) = cached.filter(|_| status == StatusCode::NOT_MODIFIED) {
+                return Ok(reqwest::Response::from(cached.into_http()));
+            }
+            if !self.storable(status, response.headers(), response.content_length()) {
+                return Ok(response);
+            }
+
+            let headers = response.headers().clone();
+            let cached = CachedResponse::from_parts(&headers, response.bytes().await?.to_vec());
+            self.store.put(&url, cached.clone());
+
+            Ok(reqwest::Response::from(cached.into_http()))
+        })
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<T: BlockingHttpTransport> BlockingHttpTransport for CachingTransport<T> {
+    fn execute(
+        &self,
+        mut request: reqwest::blocking::Request,
+    ) -> Result<reqwest::blocking::Response, reqwest::Error> {
+        let url = request.url().to_string();
+        let Some(cached) = self.prepare(&request.method().clone(), &url, request.headers_mut())
+        else {
+            return self.inner.execute(request);
+        };
+
+        let response = self.inner.execute(request)?;
+        let status = response.status();
+        if let Some(cached) = cached.filter(|_| status == StatusCode::NOT_MODIFIED) {
+            return Ok(reqwest::blocking::Response::from(cached.into_http()));
+        }
+        if !self.storable(status, response.headers(), response.content_length()) {
+            return Ok(response);
+        }
+
+        let headers = response.headers().clone();
+        let cached = CachedResponse::from_parts(&headers, response.bytes()?.to_vec());
+        self.store.put(&url, cached.clone());
+
+        Ok(reqwest::blocking::Response::from(cached.into_http()))
+    }
+}
+
+#[cfg(test)]
+mod cache_test {
+    use std::sync::Arc;
+
+    use reqwest::header::{ETAG, HeaderMap};
+
+    use crate::cache::{CacheStore, CachedResponse, DiskCache, MemoryCache, forbids_storing};
+
+    fn response() -> CachedResponse {
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, "\"v1\"".parse().unwrap());
+
+        CachedResponse::from_parts(&headers, b"sequenceNumber=1".to_vec())
+    }
+
+    #[test]
+    fn memory() {
+        let cache = MemoryCache::new();
+        assert!(cache.get("https://example.com/state.txt").is_none());
+
+        cache.put("https://example.com/state.txt", response());
+        assert_eq!(cache.get("https://example.com/state.txt"), Some(response()));
+        assert_eq!(cache.len(), 1);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn disk() {
+        let directory = std::env::temp_dir().join(format!("rust-osm-cache-{}", std::process::id()));
+        let cache = DiskCache::new(&directory).unwrap();
+
+        cache.put("https://example.com/state.txt", response());
+        let cached = DiskCache::new(&directory)
+            .unwrap()
+            .get("https://example.com/state.txt")
+            .unwrap();
+        assert_eq!(cached, response());
+        assert_eq!(cached.header("etag"), Some("\"v1\""));
+        assert!(cache.get("https://example.com/other.txt").is_none());
+
+        let _: Arc<dyn CacheStore> = Arc::new(cache);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn cache_control() {
+        use reqwest::header::CACHE_CONTROL;
+
+        let forbids = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(CACHE_CONTROL, value.parse().unwrap());
+            forbids_storing(&headers)
+        };
+
+        assert!(forbids("no-store"));
+        assert!(forbids("max-age=60, Private"));
+        assert!(forbids("private=\"set-cookie\""));
+        assert!(!forbids("public, max-age=60"));
+        assert!(!forbids_storing(&HeaderMap::new()));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn conditional_requests() {
+        use reqwest::{StatusCode, header::IF_NONE_MATCH};
+
+        use crate::{
+            cache::CachingTransport,
+            transport::{BlockingHttpTransport, MockTransport},
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, "\"v1\"".parse().unwrap());
+        let mock = Arc::new(
+            MockTransport::new()
+                .with_response_headers(StatusCode::OK, headers, "sequenceNumber=1")
+                .with_response(StatusCode::NOT_MODIFIED, ""),
+        );
+        let store = Arc::new(MemoryCache::new());
+        let transport = CachingTransport::new(mock.clone(), store.clone());
+        let client = reqwest::blocking::Client::new();
+        let get = || client.get("https://example.com/state.txt").build().unwrap();
+
+        let first = transport.execute(get()).unwrap();
+        assert_eq!(first.text().unwrap(), "sequenceNumber=1");
+        assert_eq!(store.len(), 1);
+
+        let second = transport.execute(get()).unwrap();
+        assert_eq!(second.status(), StatusCode::OK);
+        assert_eq!(second.text().unwrap(), "sequenceNumber=1");
+
+        let requests = mock.requests();
+        assert!(!requests[0].headers.contains_key(IF_NONE_MATCH));
+        assert_eq!(requests[1].headers[IF_NONE_MATCH], "\"v1\"");
+
+        let post = client
+            .post("https://example.com/state.txt")
+            .build()
+            .unwrap();
+        assert_eq!(
+            transport.execute(post).unwrap().status(),
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn uncacheable() {
+        use reqwest::{
+            StatusCode,
+            header::{AUTHORIZATION, CACHE_CONTROL},
+        };
+
+        use crate::{
+            cache::CachingTransport,
+            transport::{BlockingHttpTransport, MockTransport},
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, "\"v1\"".parse().unwrap());
+        headers.insert(CACHE_CONTROL, "private".parse().unwrap());
+        let mut public = HeaderMap::new();
+        public.insert(ETAG, "\"v1\"".parse().unwrap());
+        let mock = Arc::new(
+            MockTransport::new()
+                .with_response_headers(StatusCode::OK, headers, "user")
+                .with_response_headers(StatusCode::OK, public, "user"),
+        );
+        let store = Arc::new(MemoryCache::new());
+        let transport = CachingTransport::new(mock.clone(), store.clone());
+        let client = reqwest::blocking::Client::new();
+
+        transport
+            .execute(client.get("https://example.com/user").build().unwrap())
+            .unwrap();
+        assert!(store.is_empty());
+
+        let authorized = client
+            .get("https://example.com/user")
+            .header(AUTHORIZATION, "Bearer token")
+            .build()
+            .unwrap();
+        transport.execute(authorized).unwrap();
+        assert!(store.is_empty());
+    }
+}
@@ -0,0 +1,355 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    coord::coordinates::{Coordinates, distance_m},
+    model::{
+        element::{Element, Relation, Way},
+        id::{ElementId, NodeId, RelationId, WayId},
+        osm_store::OsmStore,
+        tags::Tags,
+    },
+};
+
+/// The length of one degree of latitude in meters, rounded down so no pair is missed.
+const METERS_PER_DEGREE: f64 = 111_000.0;
+
+/// Keys whose meaning depends on the direction of a way, so ways with them are never reversed.
+const DIRECTIONAL_KEYS: [&str; 7] = [
+    "oneway",
+    "incline",
+    "direction",
+    "junction",
+    "waterway",
+    "natural",
+    "barrier",
+];
+
+/// Merge nodes closer than `tolerance` meters into one, e.g. duplicated nodes of imported data.
+///
+/// Nodes are only merged if their tags do not contradict each other, the remaining node gets
+/// the tags of both. Existing nodes with positive ids are kept in favor of new ones and the
+/// ways and relations referencing a merged node are updated.
+///
+/// Returns the removed nodes mapped to the node they were merged into.
+pub fn merge_nodes(store: &mut OsmStore, tolerance: f64) -> HashMap<NodeId, NodeId> {
+    let mut by_latitude: Vec<(NodeId, Coordinates)> = store
+        .nodes()
+        .filter_map(|node| Some((node.id, node.coordinates()?)))
+        .collect();
+    by_latitude.sort_by(|a, b| latitude(&a.1).total_cmp(&latitude(&b.1)));
+
+    let mut order: Vec<usize> = (0..by_latitude.len()).collect();
+    order.sort_by_key(|index| priority(by_latitude[*index].0.value()));
+
+    let window = tolerance / METERS_PER_DEGREE;
+    let mut merged: HashMap<NodeId, NodeId> = HashMap::new();
+    for index in order {
+        let (id, coordinates) = by_latitude[index];
+        if merged.contains_key(&id) {
+            continue;
+        }
+
+        let first = by_latitude
+            .partition_point(|(_, other)| latitude(other) < latitude(&coordinates) - window);
+        let candidates: Vec<NodeId> = by_latitude[first..]
+            .iter()
+            .take_while(|(_, other)| latitude(other) <= latitude(&coordinates) + window)
+            .filter(|(other, position)| {
+                *other != id
+                    && !merged.contains_key(other)
+                    && distance_m(coordinates, *position) <= tolerance
+            })
+            .map(|(other, _)| *other)
+            .collect();
+
+        for other in candidates {
+            let (Some(kept), Some(removed)) = (store.node(id), store.node(other)) else {
+                continue;
+            };
+            let Some(tags) = merged_tags(&kept.tags, &removed.tags) else {
+                continue;
+            };
+
+            let mut kept = kept.clone();
+            kept.tags = tags;
+            store.insert(Element::Node(kept));
+            store.remove(other);
+            merged.insert(other, id);
+        }
+    }
+
+    replace_nodes(store, &merged);
+
+    merged
+}
+
+/// Join ways with identical tags that share an endpoint into one way.
+///
+/// Ways are only joined if no other way with the same tags ends at the shared node and both
+/// are members of the same relations with the same roles. Ways with direction dependent tags
+/// like `oneway` are never reversed to join them. Existing ways with positive ids are kept in
+/// favor of new ones.
+///
+/// Returns the removed ways mapped to the way they were joined into.
+pub fn join_ways(store: &mut OsmStore) -> HashMap<WayId, WayId> {
+    let mut order: Vec<WayId> = store.ways().map(|way| way.id).collect();
+    order.sort_by_key(|id| priority(id.value()));
+
+    let mut joined: HashMap<WayId, WayId> = HashMap::new();
+    for id in order {
+        while let Some(way) = store.way(id) {
+            let Some((other, nodes)) = join_partner(store, way) else {
+                break;
+            };
+
+            let mut way = way.clone();
+            way.nodes = nodes;
+            store.insert(Element::Way(way));
+            remove_memberships(store, other);
+            store.remove(other);
+
+            for target in joined.values_mut().filter(|target| **target == other) {
+                *target = id;
+            }
+            joined.insert(other, id);
+        }
+    }
+
+    joined
+}
+
+/// Find a way to join with `way` and the nodes of the joined way.
+fn join_partner(store: &OsmStore, way: &Way) -> Option<(WayId, Vec<NodeId>)> {
+    let (&first, &last) = (way.nodes.first()?, way.nodes.last()?);
+    if first == last {
+        return None;
+    }
+
+    [last, first].into_iter().find_map(|end| {
+        let mut candidates = store.ways_of_node(end).filter(|other| {
+            other.id != way.id
+                && other.tags == way.tags
+                && other.nodes.first() != other.nodes.last()
+                && (other.nodes.first() == Some(&end) || other.nodes.last() == Some(&end))
+        });
+        let other = candidates.next()?;
+        if candidates.next().is_some() || memberships(store, way) != memberships(store, other) {
+            return None;
+        }
+
+        let reversed = |nodes: &[NodeId]| nodes.iter().rev().copied().collect::<Vec<_>>();
+        let (head, tail) = match (end == last, other.nodes.first() == Some(&end)) {
+            (true, true) => (way.nodes.clone(), other.nodes.clone()),
+            (false, false) => (other.nodes.clone(), way.nodes.clone()),
+            (true, false) | (false, true) if is_directional(&way.tags) => return None,
+            (true, false) => (way.nodes.clone(), reversed(&other.nodes)),
+            (false, true) => (reversed(&other.nodes), way.nodes.clone()),
+        };
+        let nodes = head.iter().chain(&tail[1..]).copied().collect();
+        Some((other.id, nodes))
+    })
+}
+
+/// Get the relations a way is a member of together with its roles.
+fn memberships(store: &OsmStore, way: &Way) -> HashSet<(RelationId, String)> {
+    let id = ElementId::from(way.id);
+
+    store
+        .relations_of(id)
+        .flat_map(|relation| {
+            relation
+                .members
+                .iter()
+                .filter(move |member| member.id() == id)
+                .map(|member| (relation.id, member.role.clone()))
+        })
+        .collect()
+}
+
+/// Remove all memberships of a way from its relations.
+fn remove_memberships(store: &mut OsmStore, id: WayId) {
+    let relations: Vec<Relation> = store.relations_of(id).cloned().collect();
+    for mut relation in relations {
+        relation
+            .members
+            .retain(|member| member.id() != ElementId::from(id));
+        store.insert(Element::Relation(relation));
+    }
+}
+
+/// Replace merged nodes in all ways and relations referencing them.
+fn replace_nodes(store: &mut OsmStore, merged: &HashMap<NodeId, NodeId>) {
+    let replace = |id: NodeId| merged.get(&id).copied().unwrap_or(id);
+
+    let mut ways: Vec<WayId> = merged
+        .keys()
+        .flat_map(|id| store.ways_of_node(*id).map(|way| way.id))
+        .collect();
+    ways.sort();
+    ways.dedup();
+    for id in ways {
+        let Some(way) = store.way(id) else {
+            continue;
+        };
+        let mut way = way.clone();
+        way.nodes = way.nodes.into_iter().map(replace).collect();
+        way.nodes.dedup();
+        store.insert(Element::Way(way));
+    }
+
+    let mut relations: Vec<RelationId> = merged
+        .keys()
+        .flat_map(|id| store.relations_of(*id).map(|relation| relation.id))
+        .collect();
+    relations.sort();
+    relations.dedup();
+    for id in relations {
+        let Some(relation) = store.relation(id) else {
+            continue;
+        };
+        let mut relation = relation.clone();
+        for member in relation.members.iter_mut() {
+            if let ElementId::Node(node) = member.id() {
+                member.reference = replace(node).value();
+            }
+        }
+        store.insert(Element::Relation(relation));
+    }
+}
+
+/// Get the union of two sets of tags, or [`None`] if they have different values for a key.
+fn merged_tags(a: &Tags, b: &Tags) -> Option<Tags> {
+    let mut tags = a.clone();
+    for (key, value) in b.iter() {
+        if tags.get(key).is_some_and(|existing| existing != value) {
+            return None;
+        }
+        tags.insert(key.clone(), value.clone());
+    }
+
+    Some(tags)
+}
+
+fn is_directional(tags: &Tags) -> bool {
+    tags.keys().any(|key| {
+        DIRECTIONAL_KEYS.contains(&key.as_str())
+            || key.contains(":forward")
+            || key.contains(":backward")
+    })
+}
+
+/// Order existing elements with positive ids before new ones, each with the smallest id first.
+fn priority(id: i64) -> (bool, u64) {
+    (id < 0, id.unsigned_abs())
+}
+
+fn latitude(coordinates: &Coordinates) -> f64 {
+    fn widen(value: impl Into<f64>) -> f64 {
+        value.into()
+    }
+
+    widen(coordinates.latitude().value())
+}
+
+#[cfg(test)]
+mod cleanup_test {
+    use std::collections::HashMap;
+
+    use crate::{
+        cleanup::{join_ways, merge_nodes},
+        model::{
+            element::{Element, Relation, RelationMember},
+            fixture::{node, tagged, way},
+            id::{NodeId, RelationId, WayId},
+            osm_store::OsmStore,
+        },
+    };
+
+    fn nodes(store: &OsmStore, id: i64) -> Vec<i64> {
+        store
+            .way(WayId::new(id))
+            .unwrap()
+            .nodes
+            .iter()
+            .map(|node| node.value())
+            .collect()
+    }
+
+    #[test]
+    fn merge() {
+        let mut store: OsmStore = [
+            tagged(node(1, 50.0, 8.0), &[("name", "A")]),
+            tagged(node(-1, 50.00001, 8.0), &[("highway", "crossing")]),
+            tagged(node(2, 50.00002, 8.0), &[("name", "B")]),
+            node(3, 50.001, 8.0),
+            tagged(way(10, &[1, -1, 3]), &[("highway", "residential")]),
+            Element::Relation(Relation {
+                id: RelationId::new(20),
+                members: vec![RelationMember::new(NodeId::new(-1), "stop")],
+                ..Default::default()
+            }),
+        ]
+        .into_iter()
+        .collect();
+
+        let merged = merge_nodes(&mut store, 2.0);
+
+        assert_eq!(merged, HashMap::from([(NodeId::new(-1), NodeId::new(1))]));
+        assert!(!store.contains(NodeId::new(-1)));
+        assert_eq!(store.node(NodeId::new(1)).unwrap().tags.len(), 2);
+        assert!(store.contains(NodeId::new(2)));
+        assert_eq!(nodes(&store, 10), vec![1, 3]);
+        assert_eq!(
+            store.relation(RelationId::new(20)).unwrap().members[0].reference,
+            1
+        );
+    }
+
+    #[test]
+    fn join() {
+        let mut store: OsmStore = (1..=7)
+            .map(|id| node(id, 50.0, 8.0 + id as f64 * 0.001))
+            .chain([
+                tagged(way(-1, &[3, 2]), &[("highway", "residential")]),
+                tagged(way(10, &[1, 2]), &[("highway", "residential")]),
+                tagged(way(11, &[4, 3]), &[("highway", "residential")]),
+                tagged(way(12, &[4, 5]), &[("highway", "primary")]),
+                tagged(way(13, &[5, 6]), &[("highway", "primary")]),
+                tagged(way(14, &[5, 7]), &[("highway", "primary")]),
+            ])
+            .collect();
+
+        let joined = join_ways(&mut store);
+
+        assert_eq!(
+            joined,
+            HashMap::from([
+                (WayId::new(-1), WayId::new(10)),
+                (WayId::new(11), WayId::new(10))
+            ])
+        );
+        assert_eq!(nodes(&store, 10), vec![1, 2, 3, 4]);
+        assert!(store.contains(WayId::new(12)));
+        assert!(store.contains(WayId::new(13)));
+    }
+
+    #[test]
+    fn join_keeps_direction() {
+        let mut store: OsmStore = (1..=3)
+            .map(|id| node(id, 50.0, 8.0 + id as f64 * 0.001))
+            .chain([
+                tagged(way(10, &[1, 2]), &[("highway", "primary")]),
+                tagged(way(11, &[3, 2]), &[("highway", "primary")]),
+            ])
+            .map(|element| match element {
+                Element::Way(mut way) => {
+                    way.tags.insert("oneway".to_string(), "yes".to_string());
+                    Element::Way(way)
+                }
+                element => element,
+            })
+            .collect();
+
+        assert!(join_ways(&mut store).is_empty());
+    }
+}
@@ -0,0 +1,580 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    coord::{CoordinateType, bbox::BBox, coordinates::Coordinates, polygon::Polygon},
+    model::{
+        element::{Element, ElementType, Node, Relation, Way},
+        id::{ElementId, NodeId, RelationId, WayId},
+        osm_store::OsmStore,
+    },
+};
+
+/// The area to extract.
+#[derive(Debug, Clone)]
+pub enum Region {
+    BBox(BBox),
+    Polygon(Polygon),
+}
+
+/// How elements crossing the boundary of a [`Region`] are handled.
+///
+/// The first three strategies match the strategies of `osmium extract`.
+///
+/// See <https://docs.osmcode.org/osmium/latest/osmium-extract.html>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Strategy {
+    /// Nodes inside the region, ways with at least one node inside and relations with at least
+    /// one extracted member. Ways crossing the boundary reference nodes that are not extracted.
+    Simple,
+    /// Like [`Strategy::Simple`], but with all nodes of the extracted ways.
+    CompleteWays,
+    /// Like [`Strategy::CompleteWays`], but also with all member ways and their nodes of extracted
+    /// `multipolygon` and `boundary` relations, so their areas can be assembled.
+    Smart,
+    /// Cut ways at the boundary, adding synthetic nodes with negative ids where they cross it.
+    ///
+    /// Ways leaving and entering the region again are split into multiple pieces, where the
+    /// first piece keeps the id of the way and further pieces get negative ids. Closed ways are
+    /// cut like any other way, so areas crossing the boundary are no longer closed. Relations
+    /// only keep their extracted members.
+    HardClip,
+}
+
+impl Region {
+    /// Check if [`Coordinates`] lie inside this region.
+    pub fn contains(&self, coordinates: &Coordinates) -> bool {
+        match self {
+            Self::BBox(bbox) => bbox.contains(coordinates),
+            Self::Polygon(polygon) => polygon.contains(coordinates),
+        }
+    }
+
+    /// Get the edges of the boundary as pairs of (longitude, latitude).
+    fn edges(&self) -> Vec<((f64, f64), (f64, f64))> {
+        let rings: Vec<Vec<Coordinates>> = match self {
+            // A box crossing the antimeridian is bounded by the rings of its halves.
+            Self::BBox(bbox) => bbox
+                .halves()
+                .iter()
+                .flatten()
+                .map(|half| {
+                    let (south_west, north_east) = (half.south_west(), half.north_east());
+                    vec![
+                        south_west,
+                        Coordinates::new(south_west.latitude(), north_east.longitude()),
+                        north_east,
+                        Coordinates::new(north_east.latitude(), south_west.longitude()),
+                        south_west,
+                    ]
+                })
+                .collect(),
+            Self::Polygon(polygon) => std::iter::once(polygon.exterior())
+                .chain(polygon.interiors())
+                .map(|ring| ring.coordinates().to_vec())
+                .collect(),
+        };
+
+        rings
+            .iter()
+            .flat_map(|ring| ring.windows(2))
+            .map(|pair| (position(&pair[0]), position(&pair[1])))
+            .collect()
+    }
+}
+
+impl From<BBox> for Region {
+    fn from(bbox: BBox) -> Self {
+        Self::BBox(bbox)
+    }
+}
+
+impl From<Polygon> for Region {
+    fn from(polygon: Polygon) -> Self {
+        Self::Polygon(polygon)
+    }
+}
+
+/// Extract the elements of `store` inside a [`Region`].
+///
+/// The elements are sorted by type and id, nodes first.
+pub fn extract(store: &OsmStore, region: &Region, strategy: Strategy) -> Vec<Element> {
+    let mut nodes: HashSet<NodeId> = store
+        .nodes()
+        .filter(|node| {
+            node.coordinates()
+                .is_some_and(|coordinates| region.contains(&coordinates))
+        })
+        .map(|node| node.id)
+        .collect();
+
+    let mut elements = Vec::new();
+    let mut ways: HashMap<WayId, Vec<WayId>> = HashMap::new();
+    if strategy == Strategy::HardClip {
+        let mut clipper = Clipper::new(store, region);
+        for way in store.ways() {
+            let pieces = clipper.clip(way);
+            if !pieces.is_empty() {
+                ways.insert(way.id, pieces.iter().map(|piece| piece.id).collect());
+                elements.extend(pieces.into_iter().map(Element::Way));
+            }
+        }
+        nodes.extend(clipper.nodes.iter().map(|node| node.id));
+        elements.extend(clipper.nodes.into_iter().map(Element::Node));
+    } else {
+        for way in store.ways() {
+            if way.nodes.iter().any(|node| nodes.contains(node)) {
+                ways.insert(way.id, vec![way.id]);
+            }
+        }
+    }
+
+    let relations = extracted_relations(store, &nodes, &ways);
+
+    if matches!(strategy, Strategy::CompleteWays | Strategy::Smart) {
+        let mut complete: Vec<WayId> = ways.keys().copied().collect();
+        if strategy == Strategy::Smart {
+            for relation in relations.iter().filter_map(|id| store.relation(*id)) {
+                if is_area_relation(relation) {
+                    complete.extend(
+                        relation
+                            .members
+                            .iter()
+                            .filter(|member| member.member_type == ElementType::Way)
+                            .map(|member| WayId::new(member.reference))
+                            .filter(|id| store.contains(*id)),
+                    );
+                }
+            }
+        }
+        for id in complete {
+            if let Some(way) = store.way(id) {
+                ways.insert(id, vec![id]);
+                nodes.extend(way.nodes.iter().filter(|node| store.contains(**node)));
+            }
+        }
+    }
+
+    elements.extend(
+        nodes
+            .iter()
+            .filter_map(|id| store.node(*id))
+            .cloned()
+            .map(Element::Node),
+    );
+    if strategy != Strategy::HardClip {
+        elements.extend(
+            ways.keys()
+                .filter_map(|id| store.way(*id))
+                .cloned()
+                .map(Element::Way),
+        );
+    }
+    for relation in relations.iter().filter_map(|id| store.relation(*id)) {
+        let mut relation = relation.clone();
+        if strategy == Strategy::HardClip {
+            relation.members = relation
+                .members
+                .into_iter()
+                .flat_map(|member| match member.id() {
+                    ElementId::Node(id) if nodes.contains(&id) => vec![member],
+                    ElementId::Way(id) => ways
+                        .get(&id)
+                        .into_iter()
+                        .flatten()
+                        .map(|piece| {
+                            let mut member = member.clone();
+                            member.reference = piece.value();
+                            member
+                        })
+                        .collect(),
+                    ElementId::Relation(id) if relations.contains(&id) => vec![member],
+                    _ => Vec::new(),
+                })
+                .collect();
+        }
+        elements.push(Element::Relation(relation));
+    }
+
+    elements.sort_by_key(Element::id);
+    elements.dedup_by_key(|element| element.id());
+
+    elements
+}
+
+/// Get the relations with at least one extracted member, including parents of such relations.
+fn extracted_relations(
+    store: &OsmStore,
+    nodes: &HashSet<NodeId>,
+    ways: &HashMap<WayId, Vec<WayId>>,
+) -> HashSet<RelationId> {
+    let mut relations = HashSet::new();
+    let mut pending: Vec<ElementId> = nodes
+        .iter()
+        .map(|id| ElementId::from(*id))
+        .chain(ways.keys().map(|id| ElementId::from(*id)))
+        .collect();
+
+    while let Some(id) = pending.pop() {
+        for relation in store.relations_of(id) {
+            if relations.insert(relation.id) {
+                pending.push(relation.id.into());
+            }
+        }
+    }
+
+    relations
+}
+
+fn is_area_relation(relation: &Relation) -> bool {
+    matches!(
+        relation.tags.get("type").map(String::as_str),
+        Some("multipolygon" | "boundary")
+    )
+}
+
+/// Cuts ways at the boundary of a [`Region`].
+struct Clipper<'a> {
+    store: &'a OsmStore,
+    region: &'a Region,
+    edges: Vec<((f64, f64), (f64, f64))>,
+    /// The synthetic nodes created so far.
+    nodes: Vec<Node>,
+    next_node_id: i64,
+    next_way_id: i64,
+}
+
+impl<'a> Clipper<'a> {
+    fn new(store: &'a OsmStore, region: &'a Region) -> Self {
+        let min_node = store.nodes().map(|node| node.id.value()).min().unwrap_or(0);
+        let min_way = store.ways().map(|way| way.id.value()).min().unwrap_or(0);
+
+        Self {
+            store,
+            region,
+            edges: region.edges(),
+            nodes: Vec::new(),
+            next_node_id: min_node.min(0) - 1,
+            next_way_id: min_way.min(0) - 1,
+        }
+    }
+
+    /// Cut a way into the pieces inside the region.
+    fn clip(&mut self, way: &Way) -> Vec<Way> {
+        let vertices: Vec<(NodeId, Coordinates)> = way
+            .nodes
+            .iter()
+            .filter_map(|id| Some((*id, self.store.node(*id)?.coordinates()?)))
+            .collect();
+        if let [(id, coordinates)] = vertices[..] {
+            return if self.region.contains(&coordinates) {
+                vec![Way {
+                    nodes: vec![id],
+                    ..way.clone()
+                }]
+            } else {
+                Vec::new()
+            };
+        }
+
+        let mut pieces: Vec<Vec<NodeId>> = Vec::new();
+        let mut current: Vec<NodeId> = Vec::new();
+        for pair in vertices.windows(2) {
+            let ((start_id, start), (end_id, end)) = (pair[0], pair[1]);
+            let (a, b) = (position(&start), position(&end));
+
+            let mut cuts = vec![0.0, 1.0];
+            cuts.extend(self.intersections(a, b));
+            cuts.sort_by(f64::total_cmp);
+            cuts.dedup();
+
+            for cut in cuts.windows(2) {
+                let (from, to) = (cut[0], cut[1]);
+                let middle = interpolate(a, b, (from + to) / 2.0);
+                if !self.region.contains(&coordinates(middle)) {
+                    if current.len() > 1 {
+                        pieces.push(std::mem::take(&mut current));
+                    }
+                    current.clear();
+                    continue;
+                }
+
+                if current.is_empty() {
+                    let first = if from == 0.0 {
+                        start_id
+                    } else {
+                        self.synthetic_node(interpolate(a, b, from))
+                    };
+                    current.push(first);
+                }
+                let last = if to == 1.0 {
+                    end_id
+                } else {
+                    self.synthetic_node(interpolate(a, b, to))
+                };
+                current.push(last);
+            }
+        }
+        if current.len() > 1 {
+            pieces.push(current);
+        }
+
+        pieces
+            .into_iter()
+            .enumerate()
+            .map(|(index, nodes)| {
+                let id = if index == 0 {
+                    way.id
+                } else {
+                    self.next_way_id -= 1;
+                    WayId::new(self.next_way_id + 1)
+                };
+                Way {
+                    id,
+                    nodes,
+                    ..way.clone()
+                }
+            })
+            .collect()
+    }
+
+    /// Get the positions along the segment from `a` to `b` where it crosses the boundary.
+    fn intersections(&self, a: (f64, f64), b: (f64, f64)) -> Vec<f64> {
+        let direction = (b.0 - a.0, b.1 - a.1);
+
+        self.edges
+            .iter()
+            .filter_map(|(c, d)| {
+                let edge = (d.0 - c.0, d.1 - c.1);
+                let denominator = cross(direction, edge);
+                if denominator == 0.0 {
+                    return None;
+                }
+
+                let offset = (c.0 - a.0, c.1 - a.1);
+                let t = cross(offset, edge) / denominator;
+                let u = cross(offset, direction) / denominator;
+
+                ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then_some(t)
+            })
+            .collect()
+    }
+
+    fn synthetic_node(&mut self, (longitude, latitude): (f64, f64)) -> NodeId {
+        let id = NodeId::new(self.next_node_id);
+        self.next_node_id -= 1;
+        self.nodes.push(Node {
+            id,
+            lat: Some(latitude as CoordinateType),
+            lon: Some(longitude as CoordinateType),
+            ..Default::default()
+        });
+
+        id
+    }
+}
+
+fn cross(a: (f64, f64), b: (f64, f64)) -> f64 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+fn interpolate(a: (f64, f64), b: (f64, f64), t: f64) -> (f64, f64) {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+/// Get the longitude and latitude as `f64`.
+fn position(coordinates: &Coordinates) -> (f64, f64) {
+    fn widen(value: impl Into<f64>) -> f64 {
+        value.into()
+    }
+
+    (
+        widen(coordinates.longitude().value()),
+        widen(coordinates.latitude().value()),
+    )
+}
+
+fn coordinates((longitude, latitude): (f64, f64)) -> Coordinates {
+    Coordinates::from_unchecked(latitude as CoordinateType, longitude as CoordinateType)
+}
+
+#[cfg(test)]
+mod clip_test {
+    use crate::{
+        clip::{Region, Strategy, extract},
+        coord::{bbox::BBox, coordinates::Coordinates},
+        model::{
+            element::{Element, Relation, RelationMember},
+            fixture::{node, way},
+            id::{ElementId, NodeId, RelationId, WayId},
+            osm_store::OsmStore,
+            tags::Tags,
+        },
+    };
+
+    fn relation(id: i64, kind: &str, members: Vec<RelationMember>) -> Element {
+        Element::Relation(Relation {
+            id: RelationId::new(id),
+            members,
+            tags: Tags::from([("type".to_string(), kind.to_string())]),
+            ..Default::default()
+        })
+    }
+
+    /// The region spans from (0, 0) to (2, 2).
+    ///
+    /// Way 10 runs from inside to outside, way 11 crosses the region without a node inside and
+    /// way 12 lies outside, but is part of a multipolygon with way 10.
+    fn store() -> OsmStore {
+        [
+            node(1, 1.0, 1.0),
+            node(2, 1.0, 3.0),
+            node(3, 1.5, -1.0),
+            node(4, 1.5, 3.0),
+            node(5, 5.0, 5.0),
+            node(6, 6.0, 6.0),
+            way(10, &[1, 2]),
+            way(11, &[3, 4]),
+            way(12, &[5, 6]),
+            relation(
+                20,
+                "multipolygon",
+                vec![
+                    RelationMember::new(WayId::new(10), "outer"),
+                    RelationMember::new(WayId::new(12), "outer"),
+                ],
+            ),
+            relation(21, "route", vec![RelationMember::new(WayId::new(12), "")]),
+            relation(
+                22,
+                "super",
+                vec![RelationMember::new(RelationId::new(20), "")],
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn region() -> Region {
+        BBox::new(
+            Coordinates::from_unchecked(0.0, 0.0),
+            Coordinates::from_unchecked(2.0, 2.0),
+        )
+        .unwrap()
+        .into()
+    }
+
+    fn ids(strategy: Strategy) -> Vec<ElementId> {
+        extract(&store(), &region(), strategy)
+            .iter()
+            .map(Element::id)
+            .collect()
+    }
+
+    #[test]
+    fn osmium_strategies() {
+        let expected = |nodes: &[i64], ways: &[i64]| {
+            nodes
+                .iter()
+                .map(|id| ElementId::from(NodeId::new(*id)))
+                .chain(ways.iter().map(|id| WayId::new(*id).into()))
+                .chain([RelationId::new(20).into(), RelationId::new(22).into()])
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(Strategy::Simple), expected(&[1], &[10]));
+        assert_eq!(ids(Strategy::CompleteWays), expected(&[1, 2], &[10]));
+        assert_eq!(ids(Strategy::Smart), expected(&[1, 2, 5, 6], &[10, 12]));
+    }
+
+    #[test]
+    fn hard_clip() {
+        let elements = extract(&store(), &region(), Strategy::HardClip);
+        let way = |id| {
+            elements.iter().find_map(|element| match element {
+                Element::Way(way) if way.id == WayId::new(id) => Some(way.clone()),
+                _ => None,
+            })
+        };
+        let position = |id: NodeId| {
+            elements.iter().find_map(|element| match element {
+                Element::Node(node) if node.id == id => Some((node.lat?, node.lon?)),
+                _ => None,
+            })
+        };
+
+        let clipped = way(10).unwrap();
+        assert_eq!(clipped.nodes.len(), 2);
+        assert_eq!(clipped.nodes[0], NodeId::new(1));
+        assert_eq!(position(clipped.nodes[1]), Some((1.0, 2.0)));
+
+        let crossing = way(11).unwrap();
+        assert!(crossing.nodes.iter().all(|id| id.value() < 0));
+        assert_eq!(position(crossing.nodes[0]), Some((1.5, 0.0)));
+        assert_eq!(position(crossing.nodes[1]), Some((1.5, 2.0)));
+
+        assert!(way(12).is_none());
+        let multipolygon = elements.iter().find_map(|element| match element {
+            Element::Relation(relation) if relation.id == RelationId::new(20) => Some(relation),
+            _ => None,
+        });
+        assert_eq!(multipolygon.unwrap().members.len(), 1);
+    }
+
+    #[test]
+    fn hard_clip_split() {
+        let store: OsmStore = [
+            node(1, 1.0, 1.0),
+            node(2, 1.0, 3.0),
+            node(3, 1.5, 1.0),
+            way(10, &[1, 2, 3]),
+            relation(20, "route", vec![RelationMember::new(WayId::new(10), "")]),
+        ]
+        .into_iter()
+        .collect();
+        let elements = extract(&store, &region(), Strategy::HardClip);
+
+        let ways: Vec<_> = elements
+            .iter()
+            .filter_map(|element| match element {
+                Element::Way(way) => Some(way.id.value()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ways, vec![-1, 10]);
+        let Some(Element::Relation(route)) = elements.last() else {
+            panic!("expected the route");
+        };
+        assert_eq!(route.members.len(), 2);
+    }
+
+    #[test]
+    fn hard_clip_antimeridian() {
+        let store: OsmStore = [
+            node(1, 0.0, 175.0),
+            node(2, 20.0, 175.0),
+            node(3, 0.0, -175.0),
+            node(4, 0.0, -165.0),
+            way(10, &[1, 2]),
+            way(11, &[3, 4]),
+        ]
+        .into_iter()
+        .collect();
+        let region = BBox::from_wrapped(-10.0, 170.0, 10.0, -170.0).into();
+        let elements = extract(&store, &region, Strategy::HardClip);
+        let end = |id| {
+            let way = elements.iter().find_map(|element| match element {
+                Element::Way(way) if way.id == WayId::new(id) => Some(way),
+                _ => None,
+            })?;
+            elements.iter().find_map(|element| match element {
+                Element::Node(node) if Some(&node.id) == way.nodes.last() => {
+                    Some((node.lat?, node.lon?))
+                }
+                _ => None,
+            })
+        };
+
+        assert_eq!(end(10), Some((10.0, 175.0)));
+        assert_eq!(end(11), Some((0.0, -170.0)));
+    }
+}
@@ -0,0 +1,143 @@
+pub mod gzip;
+
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read},
+    path::Path,
+};
+
+use crate::compression::gzip::GzDecoder;
+
+/// The magic bytes every bzip2 file starts with.
+const BZIP2_MAGIC: [u8; 3] = *b"BZh";
+
+/// The compression of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compression {
+    None,
+    /// Used by `.gz` files like `.osm.gz` and `.osc.gz`.
+    Gzip,
+    /// Used by `.bz2` files like `.osm.bz2`. Detected but not supported yet.
+    Bzip2,
+}
+
+impl Compression {
+    /// Detect the compression from the first bytes of a file.
+    pub fn detect(header: &[u8]) -> Self {
+        if header.starts_with(&gzip::MAGIC) {
+            Self::Gzip
+        } else if header.starts_with(&BZIP2_MAGIC) {
+            Self::Bzip2
+        } else {
+            Self::None
+        }
+    }
+}
+
+/// A reader that transparently decompresses its input.
+#[derive(Debug)]
+pub enum Decoder<R> {
+    Plain(R),
+    Gzip(GzDecoder<R>),
+}
+
+impl<R: BufRead> Decoder<R> {
+    /// Construct a new [`Decoder`], detecting the [`Compression`] from the first bytes of `input`.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`io::ErrorKind::Unsupported`] error if `input` is bzip2 compressed.
+    pub fn new(mut input: R) -> io::Result<Self> {
+        match Compression::detect(input.fill_buf()?) {
+            Compression::None => Ok(Self::Plain(input)),
+            Compression::Gzip => Ok(Self::Gzip(GzDecoder::new(input))),
+            Compression::Bzip2 => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "bzip2 compressed input is not supported",
+            )),
+        }
+    }
+
+    /// Get the [`Compression`] of the input.
+    pub fn compression(&self) -> Compression {
+        match self {
+            Self::Plain(_) => Compression::None,
+            Self::Gzip(_) => Compression::Gzip,
+        }
+    }
+}
+
+impl<R: BufRead> Read for Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(input) => input.read(buf),
+            Self::Gzip(decoder) => decoder.read(buf),
+        }
+    }
+}
+
+/// Open a file and decompress it transparently.
+///
+/// # Error
+///
+/// Returns an [`io::Error`] if the file can not be opened or is bzip2 compressed.
+pub fn open(path: impl AsRef<Path>) -> io::Result<Decoder<BufReader<File>>> {
+    Decoder::new(BufReader::new(File::open(path)?))
+}
+
+#[cfg(test)]
+mod compression_test {
+    use std::io::Read;
+
+    use crate::{
+        compression::{Compression, Decoder, gzip::crc32_update},
+        osm_xml::OsmXmlReader,
+    };
+
+    // `printf 'hello\n' | gzip -n`
+    const HELLO: [u8; 26] = [
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xcb, 0x48, 0xcd, 0xc9, 0xc9,
+        0xe7, 0x02, 0x00, 0x20, 0x30, 0x3a, 0x36, 0x06, 0x00, 0x00, 0x00,
+    ];
+
+    fn decode(input: &[u8]) -> std::io::Result<(Compression, String)> {
+        let mut decoder = Decoder::new(input)?;
+        let mut output = String::new();
+        decoder.read_to_string(&mut output)?;
+
+        Ok((decoder.compression(), output))
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut gzip = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff];
+        gzip.extend(miniz_oxide::deflate::compress_to_vec(data, 6));
+        gzip.extend_from_slice(&(!crc32_update(!0, data)).to_le_bytes());
+        gzip.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        gzip
+    }
+
+    #[test]
+    fn detect() {
+        assert_eq!(
+            decode(&HELLO).unwrap(),
+            (Compression::Gzip, "hello\n".to_string())
+        );
+        assert_eq!(
+            decode(b"<osm/>").unwrap(),
+            (Compression::None, "<osm/>".to_string())
+        );
+        assert_eq!(
+            decode(b"BZh91AY&SY").unwrap_err().kind(),
+            std::io::ErrorKind::Unsupported
+        );
+    }
+
+    #[test]
+    fn compressed_osm_xml() {
+        let document = r#"<osm><node id="1" lat="1" lon="2"/><node id="2" lat="3" lon="4"/></osm>"#;
+        let compressed = gzip(document.as_bytes());
+        let reader = OsmXmlReader::new(Decoder::new(compressed.as_slice()).unwrap());
+
+        assert_eq!(reader.filter(Result::is_ok).count(), 2);
+    }
+}
@@ -0,0 +1,279 @@
+use std::io::{self, BufRead, Read};
+
+use miniz_oxide::{
+    DataFormat, MZError, MZFlush, MZStatus,
+    inflate::stream::{InflateState, inflate},
+};
+
+const FHCRC: u8 = 0x02;
+const FEXTRA: u8 = 0x04;
+const FNAME: u8 = 0x08;
+const FCOMMENT: u8 = 0x10;
+
+/// The magic bytes every gzip member starts with.
+pub(crate) const MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// A streaming gzip decompressor, including concatenated members.
+///
+/// See <https://www.rfc-editor.org/rfc/rfc1952>
+pub struct GzDecoder<R> {
+    input: R,
+    inflate: Box<InflateState>,
+    state: State,
+    /// The CRC-32 of the data decompressed from the current member so far.
+    crc: u32,
+    /// The amount of bytes decompressed from the current member so far.
+    size: u32,
+}
+
+impl<R: std::fmt::Debug> std::fmt::Debug for GzDecoder<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // `InflateState` does not implement `Debug`.
+        f.debug_struct("GzDecoder")
+            .field("input", &self.input)
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Expecting the header of the first member.
+    First,
+    /// Expecting the header of another member or the end of the input.
+    Next,
+    Body,
+    Done,
+}
+
+impl<R: BufRead> GzDecoder<R> {
+    /// Construct a new [`GzDecoder`] that decompresses `input`.
+    pub fn new(input: R) -> Self {
+        Self {
+            input,
+            inflate: InflateState::new_boxed(DataFormat::Raw),
+            state: State::First,
+            crc: !0,
+            size: 0,
+        }
+    }
+
+    /// Get a reference to the compressed input.
+    pub fn get_ref(&self) -> &R {
+        &self.input
+    }
+
+    /// Read the header of a member.
+    ///
+    /// Returns `false` if the input ended before another member.
+    fn read_header(&mut self) -> io::Result<bool> {
+        if self.state == State::Next && self.input.fill_buf()?.is_empty() {
+            return Ok(false);
+        }
+
+        let mut header = [0; 10];
+        self.input.read_exact(&mut header).map_err(truncated)?;
+        if header[..2] != MAGIC {
+            return Err(invalid("invalid header"));
+        }
+        if header[2] != 8 {
+            return Err(invalid("unsupported compression method"));
+        }
+
+        let flags = header[3];
+        if flags & FEXTRA != 0 {
+            let mut length = [0; 2];
+            self.input.read_exact(&mut length).map_err(truncated)?;
+            self.skip(u16::from_le_bytes(length) as usize)?;
+        }
+        for flag in [FNAME, FCOMMENT] {
+            if flags & flag != 0 {
+                let mut field = Vec::new();
+                self.input.read_until(0, &mut field)?;
+                if field.last() != Some(&0) {
+                    return Err(invalid("truncated header"));
+                }
+            }
+        }
+        if flags & FHCRC != 0 {
+            self.skip(2)?;
+        }
+
+        self.inflate.reset(DataFormat::Raw);
+        self.crc = !0;
+        self.size = 0;
+
+        Ok(true)
+    }
+
+    /// Read and check the trailer of a member.
+    fn read_trailer(&mut self) -> io::Result<()> {
+        let mut trailer = [0; 8];
+        self.input.read_exact(&mut trailer).map_err(truncated)?;
+
+        let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+        let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+        if !self.crc != crc || self.size != size {
+            return Err(invalid("checksum mismatch"));
+        }
+
+        Ok(())
+    }
+
+    fn skip(&mut self, length: usize) -> io::Result<()> {
+        let skipped = io::copy(&mut (&mut self.input).take(length as u64), &mut io::sink())?;
+        if skipped as usize != length {
+            return Err(invalid("truncated header"));
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Read for GzDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        loop {
+            match self.state {
+                State::First | State::Next => {
+                    self.state = if self.read_header()? {
+                        State::Body
+                    } else {
+                        State::Done
+                    };
+                }
+                State::Body => {
+                    let input = self.input.fill_buf()?;
+                    let eof = input.is_empty();
+                    let result = inflate(&mut self.inflate, input, buf, MZFlush::None);
+                    self.input.consume(result.bytes_consumed);
+
+                    let written = &buf[..result.bytes_written];
+                    self.crc = crc32_update(self.crc, written);
+                    self.size = self.size.wrapping_add(written.len() as u32);
+
+                    match result.status {
+                        Ok(MZStatus::StreamEnd) => {
+                            self.read_trailer()?;
+                            self.state = State::Next;
+                        }
+                        Ok(_) => (),
+                        Err(MZError::Buf) if !eof && result.bytes_consumed > 0 => (),
+                        Err(MZError::Buf) if result.bytes_written > 0 => (),
+                        Err(MZError::Buf) => {
+                            return Err(truncated(io::ErrorKind::UnexpectedEof.into()));
+                        }
+                        Err(_) => return Err(invalid("invalid deflate stream")),
+                    }
+
+                    if !written.is_empty() {
+                        return Ok(written.len());
+                    }
+                }
+                State::Done => return Ok(0),
+            }
+        }
+    }
+}
+
+/// Decompress a gzip file, including concatenated members.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+pub(crate) fn gunzip(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut output = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut output)?;
+
+    Ok(output)
+}
+
+fn invalid(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("gzip: {message}"))
+}
+
+fn truncated(e: io::Error) -> io::Error {
+    if e.kind() == io::ErrorKind::UnexpectedEof {
+        io::Error::new(io::ErrorKind::UnexpectedEof, "gzip: truncated input")
+    } else {
+        e
+    }
+}
+
+/// Update a running CRC-32 as used by gzip, starting with `!0` and inverted at the end.
+pub(crate) fn crc32_update(mut crc: u32, data: &[u8]) -> u32 {
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    crc
+}
+
+#[cfg(test)]
+mod gzip_test {
+    use std::io::{BufReader, Read};
+
+    use crate::compression::gzip::{GzDecoder, crc32_update, gunzip};
+
+    // `printf 'hello\n' | gzip -n`
+    const HELLO: [u8; 26] = [
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xcb, 0x48, 0xcd, 0xc9, 0xc9,
+        0xe7, 0x02, 0x00, 0x20, 0x30, 0x3a, 0x36, 0x06, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn decompress() {
+        assert_eq!(gunzip(&HELLO).unwrap(), b"hello\n");
+    }
+
+    #[test]
+    fn concatenated() {
+        let data = [HELLO, HELLO].concat();
+
+        assert_eq!(gunzip(&data).unwrap(), b"hello\nhello\n");
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(gunzip(b"hello").is_err());
+
+        let mut corrupted = HELLO;
+        corrupted[20] ^= 0xff;
+        assert!(gunzip(&corrupted).is_err());
+    }
+
+    #[test]
+    fn streaming() {
+        let data = [HELLO, HELLO, HELLO].concat();
+        // A tiny buffer makes every read cross the boundaries of the input.
+        let mut decoder = GzDecoder::new(BufReader::with_capacity(3, data.as_slice()));
+        let mut output = Vec::new();
+        let mut buffer = [0; 4];
+        loop {
+            match decoder.read(&mut buffer).unwrap() {
+                0 => break,
+                read => output.extend_from_slice(&buffer[..read]),
+            }
+        }
+
+        assert_eq!(output, b"hello\nhello\nhello\n");
+    }
+
+    #[test]
+    fn truncated() {
+        assert!(gunzip(&HELLO[..HELLO.len() - 3]).is_err());
+        assert!(gunzip(&HELLO[..15]).is_err());
+    }
+
+    #[test]
+    fn checksum() {
+        assert_eq!(!crc32_update(!0, b"123456789"), 0xCBF4_3926);
+    }
+}
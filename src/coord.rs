@@ -1,9 +1,14 @@
 pub mod bbox;
 pub mod coordinates;
+pub mod dms;
 pub mod error;
 pub mod latitude;
 pub mod longitude;
 pub mod normalize;
+pub mod polygon;
+pub mod polyline;
+pub mod tile;
+pub mod well_known;
 
 #[cfg(feature = "coordinate_f32")]
 pub type CoordinateType = f32;
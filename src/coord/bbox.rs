@@ -5,7 +5,12 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
-use crate::coord::{self, CoordinateType, coordinates::Coordinates};
+use crate::coord::{
+    self, CoordinateType,
+    coordinates::{Coordinates, EARTH_RADIUS_M},
+    latitude::LATITUDE_RANGE,
+    longitude::{LONGITUDE_RANGE, Longitude},
+};
 
 /// A BBox or Bounding Box.
 ///
@@ -19,9 +24,11 @@ pub struct BBox {
 impl BBox {
     /// Construct a [`BBox`] from the south_west(lower left) and north_east(upper right) coordinate.
     ///
+    /// Use [`BBox::new_crossing`] for a [`BBox`] crossing the antimeridian.
+    ///
     /// # Error
     ///
-    /// Returns a [`coordinates::error::Error::InvalidCornerOrder`] if south_west > north_east.
+    /// Returns a [`coord::error::Error::InvalidCornerOrder`] if south_west > north_east.
     pub fn new(
         south_west: Coordinates,
         north_east: Coordinates,
@@ -40,6 +47,31 @@ impl BBox {
         }
     }
 
+    /// Construct a [`BBox`] crossing the antimeridian from the south_west(lower left) and
+    /// north_east(upper right) coordinate, see [`BBox::crosses_antimeridian`].
+    ///
+    /// # Error
+    ///
+    /// Returns a [`coord::error::Error::InvalidCornerOrder`] if south_west is not south of
+    /// north_east or its longitude is not east of the longitude of north_east.
+    pub fn new_crossing(
+        south_west: Coordinates,
+        north_east: Coordinates,
+    ) -> Result<Self, coord::error::Error> {
+        if south_west.latitude() < north_east.latitude()
+            && south_west.longitude() > north_east.longitude()
+        {
+            Ok(Self {
+                south_west,
+                north_east,
+            })
+        } else {
+            Err(coord::error::Error::InvalidCornerOrder((
+                south_west, north_east,
+            )))
+        }
+    }
+
     /// Create a new [`BBox`] without checking if south_west is south-west from north_east.
     pub fn from_unchecked(south_west: Coordinates, north_east: Coordinates) -> Self {
         Self {
@@ -60,6 +92,37 @@ impl BBox {
         }
     }
 
+    /// Construct the [`BBox`] around a circle of `radius_m` meters around `center`.
+    ///
+    /// The longitude extent grows towards the poles where meridians converge. Circles reaching
+    /// over a pole span all longitudes and the latitudes are clamped to the poles. Circles reaching
+    /// over the antimeridian give a [`BBox`] crossing it. Returns [`None`] if `radius_m` is
+    /// negative or not finite.
+    pub fn from_center_radius(center: Coordinates, radius_m: CoordinateType) -> Option<Self> {
+        if !radius_m.is_finite() || radius_m < 0.0 {
+            return None;
+        }
+
+        let delta_lat = Self::rad_to_deg(radius_m / EARTH_RADIUS_M as CoordinateType);
+        let latitude = center.latitude().value();
+        let south = (latitude - delta_lat).max(*LATITUDE_RANGE.start());
+        let north = (latitude + delta_lat).min(*LATITUDE_RANGE.end());
+
+        let cos_latitude = Self::deg_to_rad(latitude).cos();
+        let delta_lon = if south == *LATITUDE_RANGE.start() || north == *LATITUDE_RANGE.end() {
+            CoordinateType::INFINITY
+        } else {
+            delta_lat / cos_latitude
+        };
+        let longitude = center.longitude().value();
+        let (west, east) = Self::wrap_longitudes(longitude - delta_lon, longitude + delta_lon);
+
+        Some(Self::from_unchecked(
+            Coordinates::from_unchecked(south, west),
+            Coordinates::from_unchecked(north, east),
+        ))
+    }
+
     /// Return the lower left coordinate.
     pub fn south_west(&self) -> Coordinates {
         self.south_west
@@ -70,16 +133,64 @@ impl BBox {
         self.north_east
     }
 
+    /// Get a reference to the lower left coordinate.
+    pub fn south_west_ref(&self) -> &Coordinates {
+        &self.south_west
+    }
+
+    /// Get a reference to the upper right coordinate.
+    pub fn north_east_ref(&self) -> &Coordinates {
+        &self.north_east
+    }
+
+    /// Get a [`BBox`] grown by `margin` degrees on every side. A negative `margin` shrinks the
+    /// [`BBox`].
+    ///
+    /// The latitudes are clamped to the poles, the longitudes wrap across the antimeridian. A
+    /// [`BBox`] grown around the whole earth spans all longitudes. An extent shrunk by more than
+    /// its size collapses to the center.
+    pub fn expand(&self, margin: CoordinateType) -> Self {
+        let clamp =
+            |value: CoordinateType| value.clamp(*LATITUDE_RANGE.start(), *LATITUDE_RANGE.end());
+        let center = self.center();
+
+        let (south, north) = if self.delta_lat_deg() + 2.0 * margin < 0.0 {
+            (center.latitude().value(), center.latitude().value())
+        } else {
+            (
+                clamp(self.south_west.latitude().value() - margin),
+                clamp(self.north_east.latitude().value() + margin),
+            )
+        };
+        let (west, east) = if self.delta_lon_deg() + 2.0 * margin < 0.0 {
+            (center.longitude().value(), center.longitude().value())
+        } else {
+            let west = self.south_west.longitude().value();
+            Self::wrap_longitudes(west - margin, west + self.delta_lon_deg() + margin)
+        };
+
+        Self::from_unchecked(
+            Coordinates::from_unchecked(south, west),
+            Coordinates::from_unchecked(north, east),
+        )
+    }
+
     /// Get latitude degrees.
     pub fn delta_lat_deg(&self) -> CoordinateType {
         CoordinateType::from(self.north_east().latitude())
             - CoordinateType::from(self.south_west().latitude())
     }
 
-    /// Get longitude degrees.
+    /// Get longitude degrees, measured eastwards across the antimeridian if the [`BBox`] crosses it.
     pub fn delta_lon_deg(&self) -> CoordinateType {
-        CoordinateType::from(self.north_east().longitude())
-            - CoordinateType::from(self.south_west().longitude())
+        let delta = CoordinateType::from(self.north_east().longitude())
+            - CoordinateType::from(self.south_west().longitude());
+
+        if self.crosses_antimeridian() {
+            delta + 360.0
+        } else {
+            delta
+        }
     }
 
     /// Get latitude in rad.
@@ -92,14 +203,16 @@ impl BBox {
         Self::deg_to_rad(self.delta_lon_deg())
     }
 
-    /// Get latitude in m.
+    /// Get the north-south extent in m.
     pub fn height_m(&self) -> CoordinateType {
-        todo!()
+        self.delta_lat_rad() * EARTH_RADIUS_M as CoordinateType
     }
 
-    /// Get longitude in m.
+    /// Get the west-east extent in m, measured along the latitude of the center.
     pub fn width_m(&self) -> CoordinateType {
-        todo!()
+        let center_latitude = Self::deg_to_rad(self.center().latitude().value());
+
+        self.delta_lon_rad() * center_latitude.cos() * EARTH_RADIUS_M as CoordinateType
     }
 
     /// Get the corners of the [`BBox`].
@@ -126,7 +239,7 @@ impl BBox {
         self.delta_lon_deg() * self.delta_lat_deg()
     }
 
-    /// Get the [`BBox`] area in m2.
+    /// Get the [`BBox`] area in m2, approximating the earth as a sphere.
     pub fn area_m2(&self) -> CoordinateType {
         self.width_m() * self.height_m()
     }
@@ -137,10 +250,130 @@ impl BBox {
             + Coordinates::from_wrapped(self.delta_lat_deg() / 2.0, self.delta_lon_deg() / 2.0)
     }
 
+    /// Get if this [`BBox`] crosses the antimeridian, i.e. its west longitude is greater than its
+    /// east longitude.
+    pub fn crosses_antimeridian(&self) -> bool {
+        self.south_west.longitude().value() > self.north_east.longitude().value()
+    }
+
+    /// Split a [`BBox`] crossing the antimeridian into its western half ending at 180° and its
+    /// eastern half starting at -180°.
+    ///
+    /// Returns [`None`] if the [`BBox`] does not cross the antimeridian.
+    pub fn split_at_antimeridian(&self) -> Option<(Self, Self)> {
+        if !self.crosses_antimeridian() {
+            return None;
+        }
+
+        let (south, west, north, east) = self.corners();
+        Some((
+            Self::from_unchecked(
+                Coordinates::from_unchecked(south, west),
+                Coordinates::from_unchecked(north, *LONGITUDE_RANGE.end()),
+            ),
+            Self::from_unchecked(
+                Coordinates::from_unchecked(south, *LONGITUDE_RANGE.start()),
+                Coordinates::from_unchecked(north, east),
+            ),
+        ))
+    }
+
     /// Get if a [`Coordinates`] is inside the [`BBox`].
     ///
     /// This function is inclusive.
     pub fn contains(&self, p: &Coordinates) -> bool {
+        self.halves()
+            .iter()
+            .flatten()
+            .any(|half| half.contains_simple(p))
+    }
+
+    /// Get if a [`BBox`] is inside the [`BBox`].
+    ///
+    /// This function is inclusive.
+    pub fn contains_bbox(&self, other: &Self) -> bool {
+        other.halves().iter().flatten().all(|other| {
+            self.halves().iter().flatten().any(|half| {
+                half.contains_simple(&other.south_west) && half.contains_simple(&other.north_east)
+            })
+        })
+    }
+
+    /// Get if this and another [`BBox`] share at least one point.
+    ///
+    /// This function is inclusive.
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.halves().iter().flatten().any(|half| {
+            other
+                .halves()
+                .iter()
+                .flatten()
+                .any(|other| half.intersects_simple(other))
+        })
+    }
+
+    /// Get the [`BBox`] of the area shared by this and another [`BBox`].
+    ///
+    /// Where boxes crossing the antimeridian overlap on both sides of a box, e.g. 170° to -170°
+    /// and -175° to 175°, the shared area is two boxes and the smallest [`BBox`] around both is
+    /// returned.
+    ///
+    /// Returns [`None`] if the boxes do not intersect.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let mut parts: Vec<Self> = self
+            .halves()
+            .iter()
+            .flatten()
+            .flat_map(|half| {
+                other
+                    .halves()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|other| half.intersection_simple(&other))
+            })
+            .collect();
+        parts.sort_by(|a, b| {
+            a.south_west
+                .longitude()
+                .value()
+                .total_cmp(&b.south_west.longitude().value())
+        });
+
+        let (first, last) = (*parts.first()?, *parts.last()?);
+        // The parts cover the longitudes except for the gaps between them, the smallest box is
+        // the one leaving out the largest gap. Parts touching at the antimeridian leave no gap
+        // there and are joined to a box crossing it.
+        let mut gap =
+            first.south_west.longitude().value() + 360.0 - last.north_east.longitude().value();
+        let (mut west, mut east) = (first.south_west.longitude(), last.north_east.longitude());
+        for pair in parts.windows(2) {
+            let pair_gap =
+                pair[1].south_west.longitude().value() - pair[0].north_east.longitude().value();
+            if pair_gap > gap {
+                gap = pair_gap;
+                (west, east) = (
+                    pair[1].south_west.longitude(),
+                    pair[0].north_east.longitude(),
+                );
+            }
+        }
+
+        Some(Self::from_unchecked(
+            Coordinates::new(first.south_west.latitude(), west),
+            Coordinates::new(first.north_east.latitude(), east),
+        ))
+    }
+
+    /// Split this [`BBox`] into the boxes not crossing the antimeridian, itself if it does not
+    /// cross it.
+    pub(crate) fn halves(&self) -> [Option<Self>; 2] {
+        match self.split_at_antimeridian() {
+            Some((west, east)) => [Some(west), Some(east)],
+            None => [Some(*self), None],
+        }
+    }
+
+    fn contains_simple(&self, p: &Coordinates) -> bool {
         let lat = p.latitude().value();
         let lon = p.longitude().value();
 
@@ -155,14 +388,7 @@ impl BBox {
         )
     }
 
-    /// Get if a [`BBox`] is inside the [`BBox`].
-    ///
-    /// This function is inclusive.
-    pub fn contains_bbox(&self, other: &Self) -> bool {
-        self.contains(&other.south_west()) && self.contains(&other.north_east())
-    }
-
-    pub fn intersects(&self, other: &Self) -> bool {
+    fn intersects_simple(&self, other: &Self) -> bool {
         let (a_s, a_w) = (
             self.south_west.latitude().value(),
             self.south_west.longitude().value(),
@@ -183,8 +409,8 @@ impl BBox {
         Self::overlaps_1d(a_s, a_n, b_s, b_n) && Self::overlaps_1d(a_w, a_e, b_w, b_e)
     }
 
-    pub fn intersection(&self, other: &Self) -> Option<Self> {
-        if !self.intersects(other) {
+    fn intersection_simple(&self, other: &Self) -> Option<Self> {
+        if !self.intersects_simple(other) {
             return None;
         };
 
@@ -209,7 +435,158 @@ impl BBox {
             .value()
             .min(other.north_east.longitude().value());
 
-        Some(BBox::from_wrapped(sw_lat, sw_lon, ne_lat, ne_lon))
+        Some(Self::from_unchecked(
+            Coordinates::from_unchecked(sw_lat, sw_lon),
+            Coordinates::from_unchecked(ne_lat, ne_lon),
+        ))
+    }
+
+    /// Get the smallest [`BBox`] containing this and another [`BBox`].
+    ///
+    /// The longitudes are joined the shorter way around, so the union crosses the antimeridian if
+    /// that is narrower.
+    pub fn union(&self, other: &Self) -> Self {
+        let (west, east) = Self::join_longitudes(
+            (self.south_west.longitude().value(), self.delta_lon_deg()),
+            (other.south_west.longitude().value(), other.delta_lon_deg()),
+        );
+
+        Self::from_unchecked(
+            Coordinates::from_unchecked(
+                self.south_west
+                    .latitude()
+                    .value()
+                    .min(other.south_west.latitude().value()),
+                west,
+            ),
+            Coordinates::from_unchecked(
+                self.north_east
+                    .latitude()
+                    .value()
+                    .max(other.north_east.latitude().value()),
+                east,
+            ),
+        )
+    }
+
+    /// Grow this [`BBox`] to contain a [`Coordinates`].
+    ///
+    /// The [`BBox`] is grown the shorter way around, across the antimeridian if that is narrower.
+    pub fn extend(&mut self, point: &Coordinates) {
+        *self = self.union(&Self::from_unchecked(*point, *point));
+    }
+
+    /// Get the west and east longitude of the narrowest range containing two ranges given by
+    /// their west longitude and their extent eastwards.
+    fn join_longitudes(
+        a: (CoordinateType, CoordinateType),
+        b: (CoordinateType, CoordinateType),
+    ) -> (CoordinateType, CoordinateType) {
+        // The joined range starts at the west of one range and spans up to the end of the other.
+        let span_from =
+            |(west, span): (CoordinateType, CoordinateType),
+             (other_west, other_span): (CoordinateType, CoordinateType)| {
+                span.max((other_west - west).rem_euclid(360.0) + other_span)
+            };
+        let (from_a, from_b) = (span_from(a, b), span_from(b, a));
+
+        // Prefer the range not crossing the antimeridian if both are equally wide.
+        let crosses = |west: CoordinateType, span: CoordinateType| west + span > 180.0;
+        let (west, span) = if from_a < from_b || from_a == from_b && !crosses(a.0, from_a) {
+            (a.0, from_a)
+        } else {
+            (b.0, from_b)
+        };
+
+        Self::wrap_longitudes(west, west + span)
+    }
+
+    /// Wrap the west and east longitude of a range into the valid longitudes. A range spanning
+    /// all longitudes is returned as -180° to 180°.
+    fn wrap_longitudes(
+        west: CoordinateType,
+        east: CoordinateType,
+    ) -> (CoordinateType, CoordinateType) {
+        if east - west >= 360.0 {
+            return (*LONGITUDE_RANGE.start(), *LONGITUDE_RANGE.end());
+        }
+
+        // Edges already in range keep their exact value. West edges on the antimeridian are
+        // -180°, east edges 180°.
+        let west = if Longitude::is_valid(west) && west < *LONGITUDE_RANGE.end() {
+            west
+        } else {
+            Longitude::from_wrapped(west).value()
+        };
+        let east = if Longitude::is_valid(east) && east > *LONGITUDE_RANGE.start() {
+            east
+        } else {
+            -Longitude::from_wrapped(-east).value()
+        };
+
+        (west, east)
+    }
+
+    /// Split this [`BBox`] into a grid of `rows` times `cols` equally sized boxes, row by row
+    /// from the south-west. Neighbouring boxes share their edges.
+    ///
+    /// `rows` and `cols` of 0 are treated as 1. A [`BBox`] crossing the antimeridian is split along
+    /// its extent across it.
+    pub fn split(&self, rows: u32, cols: u32) -> impl Iterator<Item = Self> {
+        let (rows, cols) = (rows.max(1), cols.max(1));
+        let (south, west, north, east) = self.corners();
+        let delta_lon = self.delta_lon_deg();
+        // The last edge is taken as is so rounding does not shrink the grid.
+        let edge = move |start: CoordinateType, end: CoordinateType, count: u32, i: u32| {
+            if i == count {
+                end
+            } else {
+                start + (end - start) * i as CoordinateType / count as CoordinateType
+            }
+        };
+        // Edges past the antimeridian are wrapped, one on it is the east edge of the box west of
+        // it and the west edge of the box east of it.
+        let lon_edge = move |i: u32, is_west: bool| {
+            if i == cols {
+                return east;
+            }
+
+            let lon = west + delta_lon * i as CoordinateType / cols as CoordinateType;
+            if lon > *LONGITUDE_RANGE.end() || (is_west && lon == *LONGITUDE_RANGE.end()) {
+                lon - 360.0
+            } else {
+                lon
+            }
+        };
+
+        (0..rows).flat_map(move |row| {
+            (0..cols).map(move |col| {
+                Self::from_unchecked(
+                    Coordinates::from_unchecked(edge(south, north, rows, row), lon_edge(col, true)),
+                    Coordinates::from_unchecked(
+                        edge(south, north, rows, row + 1),
+                        lon_edge(col + 1, false),
+                    ),
+                )
+            })
+        })
+    }
+
+    /// Split this [`BBox`] into a grid of boxes with an area of at most `max_deg2`, e.g. to stay
+    /// below the maximum area of a map request. See [`BBox::split`] for the order.
+    ///
+    /// The boxes are close to square, a [`BBox`] already small enough is returned as is. Returns
+    /// [`None`] if `max_deg2` is not positive.
+    pub fn tiles_max_area(&self, max_deg2: CoordinateType) -> Option<impl Iterator<Item = Self>> {
+        if max_deg2.is_nan() || max_deg2 <= 0.0 {
+            return None;
+        }
+
+        let (delta_lat, delta_lon) = (self.delta_lat_deg(), self.delta_lon_deg());
+        let rows = (delta_lat / max_deg2.sqrt()).ceil().max(1.0);
+        let cols = (delta_lon * (delta_lat / rows) / max_deg2).ceil().max(1.0);
+
+        Some(self.split(rows as u32, cols as u32))
     }
 
     fn between_inclusive(v: CoordinateType, lo: CoordinateType, hi: CoordinateType) -> bool {
@@ -354,6 +731,72 @@ mod bbox_test {
         assert_eq!(2.5, north_east.longitude().value());
     }
 
+    #[test]
+    fn references() {
+        let bbox = get_bbox();
+
+        assert_eq!(bbox.south_west_ref(), &bbox.south_west());
+        assert_eq!(bbox.north_east_ref(), &bbox.north_east());
+    }
+
+    #[test]
+    fn expand() {
+        let bbox = get_bbox().expand(0.5);
+
+        assert_eq!(bbox, BBox::from_wrapped(0.5, 1.0, 2.5, 3.0));
+        assert_eq!(bbox.expand(-0.5), get_bbox());
+        assert_eq!(
+            BBox::from_wrapped(80.0, 170.0, 89.0, 179.0).expand(5.0),
+            BBox::from_unchecked(
+                Coordinates::from_unchecked(75.0, 165.0),
+                Coordinates::from_unchecked(90.0, -176.0)
+            )
+        );
+        assert_eq!(
+            BBox::from_wrapped(0.0, 170.0, 10.0, -170.0).expand(1.0),
+            BBox::from_wrapped(-1.0, 169.0, 11.0, -169.0)
+        );
+        assert_eq!(
+            BBox::from_wrapped(0.0, 170.0, 10.0, 175.0).expand(5.0),
+            BBox::from_unchecked(
+                Coordinates::from_unchecked(-5.0, 165.0),
+                Coordinates::from_unchecked(15.0, 180.0)
+            )
+        );
+        assert_eq!(
+            BBox::from_wrapped(0.0, -170.0, 10.0, 170.0).expand(20.0),
+            BBox::from_unchecked(
+                Coordinates::from_unchecked(-20.0, -180.0),
+                Coordinates::from_unchecked(30.0, 180.0)
+            )
+        );
+    }
+
+    #[test]
+    fn expand_shrink() {
+        let bbox = BBox::from_wrapped(1.0, 1.5, 2.0, 2.5);
+
+        assert_eq!(
+            bbox.expand(-0.25),
+            BBox::from_wrapped(1.25, 1.75, 1.75, 2.25)
+        );
+        let collapsed = bbox.expand(-1.0);
+        assert_eq!(collapsed, BBox::from_wrapped(1.5, 2.0, 1.5, 2.0));
+        assert!(!collapsed.crosses_antimeridian());
+
+        assert_eq!(
+            BBox::from_wrapped(0.0, 0.0, 10.0, 1.0).expand(-2.0),
+            BBox::from_wrapped(2.0, 0.5, 8.0, 0.5)
+        );
+        assert_eq!(
+            BBox::from_wrapped(0.0, 179.0, 1.0, -179.0).expand(-1.5),
+            BBox::from_unchecked(
+                Coordinates::from_unchecked(0.5, -180.0),
+                Coordinates::from_unchecked(0.5, -180.0)
+            )
+        );
+    }
+
     #[test]
     fn tuple() {
         let bbox = get_bbox();
@@ -382,6 +825,54 @@ mod bbox_test {
         assert_eq!(bbox.delta_lat_deg(), 1.0)
     }
 
+    #[test]
+    fn from_center_radius() {
+        let berlin = Coordinates::from_wrapped(52.52, 13.405);
+        let bbox = BBox::from_center_radius(berlin, 500.0).unwrap();
+
+        assert!((bbox.height_m() - 1000.0).abs() < 1.0);
+        assert!((bbox.width_m() - 1000.0).abs() < 1.0);
+        assert!(bbox.delta_lon_deg() > bbox.delta_lat_deg());
+        assert!(bbox.contains(&berlin));
+
+        let equator =
+            BBox::from_center_radius(Coordinates::from_wrapped(0.0, 0.0), 1000.0).unwrap();
+        assert!((equator.delta_lon_deg() - equator.delta_lat_deg()).abs() < 1e-6);
+
+        let pole =
+            BBox::from_center_radius(Coordinates::from_wrapped(89.99, 179.0), 5000.0).unwrap();
+        assert_eq!(pole.north_east().latitude().value(), 90.0);
+        assert_eq!(pole.south_west().longitude().value(), -180.0);
+        assert_eq!(pole.north_east().longitude().value(), 180.0);
+
+        let center = Coordinates::from_wrapped(0.0, 179.9);
+        let antimeridian = BBox::from_center_radius(center, 50_000.0).unwrap();
+        assert!(antimeridian.crosses_antimeridian());
+        assert!((antimeridian.south_west().longitude().value() - 179.45).abs() < 0.01);
+        assert!((antimeridian.north_east().longitude().value() + 179.65).abs() < 0.01);
+        assert!((antimeridian.width_m() - 100_000.0).abs() < 100.0);
+        assert!(antimeridian.contains(&center));
+        assert!(antimeridian.contains(&Coordinates::from_wrapped(0.0, -179.8)));
+
+        assert!(BBox::from_center_radius(center, 0.0).is_some());
+        assert!(BBox::from_center_radius(center, -1000.0).is_none());
+        assert!(BBox::from_center_radius(center, CoordinateType::NAN).is_none());
+        assert!(BBox::from_center_radius(center, CoordinateType::INFINITY).is_none());
+    }
+
+    #[test]
+    fn size_m() {
+        let berlin = BBox::from_wrapped(52.3383, 13.0884, 52.6755, 13.7611);
+        assert!((berlin.height_m() - 37_495.0).abs() < 10.0);
+        assert!((berlin.width_m() - 45_529.0).abs() < 10.0);
+        assert!((berlin.area_m2() / 1e6 - 1_707.1).abs() < 1.0);
+
+        let new_york = BBox::from_wrapped(40.4774, -74.2591, 40.9176, -73.7004);
+        assert!((new_york.height_m() - 48_948.0).abs() < 10.0);
+        assert!((new_york.width_m() - 47_101.0).abs() < 10.0);
+        assert!((new_york.area_m2() / 1e6 - 2_305.5).abs() < 1.0);
+    }
+
     #[test]
     fn area() {
         let bbox = BBox::new(
@@ -504,6 +995,231 @@ mod bbox_test {
         );
     }
 
+    #[test]
+    fn antimeridian() {
+        let bbox = BBox::new_crossing(
+            Coordinates::from_unchecked(-10.0, 170.0),
+            Coordinates::from_unchecked(10.0, -170.0),
+        )
+        .unwrap();
+
+        assert!(bbox.crosses_antimeridian());
+        assert!(!get_bbox().crosses_antimeridian());
+        assert!(
+            BBox::new_crossing(
+                Coordinates::from_unchecked(10.0, 170.0),
+                Coordinates::from_unchecked(-10.0, -170.0),
+            )
+            .is_err()
+        );
+        assert!(
+            BBox::new_crossing(
+                Coordinates::from_unchecked(-10.0, -170.0),
+                Coordinates::from_unchecked(10.0, 170.0),
+            )
+            .is_err()
+        );
+        assert!(
+            BBox::new(
+                Coordinates::from_unchecked(-10.0, 170.0),
+                Coordinates::from_unchecked(10.0, -170.0),
+            )
+            .is_err()
+        );
+        assert!(
+            BBox::new(
+                Coordinates::from_unchecked(0.0, 10.0),
+                Coordinates::from_unchecked(1.0, 5.0),
+            )
+            .is_err()
+        );
+        assert!(BBox::try_from((0.0, 10.0, 1.0, 5.0)).is_err());
+        assert_eq!(bbox.delta_lon_deg(), 20.0);
+        assert_eq!(bbox.center(), Coordinates::from_unchecked(0.0, -180.0));
+        assert_eq!(
+            BBox::from_wrapped(-10.0, 160.0, 10.0, -170.0).center(),
+            Coordinates::from_unchecked(0.0, 175.0)
+        );
+
+        assert_eq!(
+            bbox.split_at_antimeridian(),
+            Some((
+                BBox::from_unchecked(
+                    Coordinates::from_unchecked(-10.0, 170.0),
+                    Coordinates::from_unchecked(10.0, 180.0),
+                ),
+                BBox::from_wrapped(-10.0, -180.0, 10.0, -170.0),
+            ))
+        );
+        assert_eq!(get_bbox().split_at_antimeridian(), None);
+    }
+
+    #[test]
+    fn antimeridian_contains() {
+        let bbox = BBox::from_wrapped(-10.0, 170.0, 10.0, -170.0);
+
+        assert!(bbox.contains(&Coordinates::from_unchecked(0.0, 175.0)));
+        assert!(bbox.contains(&Coordinates::from_unchecked(0.0, -175.0)));
+        assert!(bbox.contains(&Coordinates::from_unchecked(0.0, 180.0)));
+        assert!(!bbox.contains(&Coordinates::from_unchecked(0.0, 0.0)));
+        assert!(!bbox.contains(&Coordinates::from_unchecked(20.0, 175.0)));
+
+        assert!(bbox.contains_bbox(&BBox::from_wrapped(-5.0, 175.0, 5.0, -175.0)));
+        assert!(bbox.contains_bbox(&BBox::from_wrapped(-5.0, 171.0, 5.0, 179.0)));
+        assert!(!bbox.contains_bbox(&BBox::from_wrapped(-5.0, -175.0, 5.0, 175.0)));
+        assert!(!BBox::from_wrapped(-5.0, -175.0, 5.0, 175.0).contains_bbox(&bbox));
+        assert!(bbox > BBox::from_wrapped(-5.0, 175.0, 5.0, -175.0));
+    }
+
+    #[test]
+    fn antimeridian_intersection() {
+        let bbox = BBox::from_wrapped(-10.0, 170.0, 10.0, -170.0);
+
+        let other = BBox::from_wrapped(0.0, -175.0, 20.0, 0.0);
+        assert!(bbox.intersects(&other));
+        assert_eq!(
+            bbox.intersection(&other),
+            Some(BBox::from_wrapped(0.0, -175.0, 10.0, -170.0))
+        );
+
+        let other = BBox::from_wrapped(0.0, 160.0, 20.0, -175.0);
+        assert!(bbox.intersects(&other));
+        assert_eq!(
+            bbox.intersection(&other),
+            Some(BBox::from_wrapped(0.0, 170.0, 10.0, -175.0))
+        );
+
+        let other = BBox::from_wrapped(-5.0, -175.0, 5.0, 175.0);
+        assert_eq!(
+            bbox.intersection(&other),
+            Some(BBox::from_wrapped(-5.0, 170.0, 5.0, -170.0))
+        );
+
+        let other = BBox::from_wrapped(-5.0, 0.0, 5.0, 160.0);
+        assert!(!bbox.intersects(&other));
+        assert!(!other.intersects(&bbox));
+        assert_eq!(bbox.intersection(&other), None);
+    }
+
+    #[test]
+    fn antimeridian_split() {
+        let bbox = BBox::from_wrapped(0.0, 170.0, 10.0, -170.0);
+        let boxes: Vec<BBox> = bbox.split(1, 4).collect();
+
+        assert_eq!(
+            boxes,
+            vec![
+                BBox::from_wrapped(0.0, 170.0, 10.0, 175.0),
+                BBox::from_unchecked(
+                    Coordinates::from_unchecked(0.0, 175.0),
+                    Coordinates::from_unchecked(10.0, 180.0),
+                ),
+                BBox::from_wrapped(0.0, -180.0, 10.0, -175.0),
+                BBox::from_wrapped(0.0, -175.0, 10.0, -170.0),
+            ]
+        );
+        assert_eq!(bbox.tiles_max_area(50.0).unwrap().count(), 4);
+    }
+
+    #[test]
+    fn split() {
+        let bbox = BBox::from_wrapped(0.0, 0.0, 10.0, 30.0);
+        let cells: Vec<BBox> = bbox.split(2, 3).collect();
+
+        assert_eq!(cells.len(), 6);
+        assert_eq!(cells[0], BBox::from_wrapped(0.0, 0.0, 5.0, 10.0));
+        assert_eq!(cells[2], BBox::from_wrapped(0.0, 20.0, 5.0, 30.0));
+        assert_eq!(cells[5], BBox::from_wrapped(5.0, 20.0, 10.0, 30.0));
+        assert!(cells.iter().all(|cell| bbox.contains_bbox(cell)));
+        assert_eq!(bbox.split(0, 0).collect::<Vec<_>>(), vec![bbox]);
+
+        let thirds = BBox::from_wrapped(0.0, 0.0, 1.0, 1.0);
+        assert_eq!(
+            thirds.split(3, 3).last().unwrap().north_east(),
+            thirds.north_east()
+        );
+    }
+
+    #[test]
+    fn tiles_max_area() {
+        let bbox = BBox::from_wrapped(50.0, 10.0, 51.0, 12.0);
+        let tiles: Vec<BBox> = bbox.tiles_max_area(0.25).unwrap().collect();
+
+        assert_eq!(tiles.len(), 8);
+        assert!(tiles.iter().all(|tile| tile.area_deg2() <= 0.25 + 1e-9));
+        let area: CoordinateType = tiles.iter().map(BBox::area_deg2).sum();
+        assert!((area - bbox.area_deg2()).abs() < 1e-9);
+
+        assert_eq!(
+            bbox.tiles_max_area(5.0).unwrap().collect::<Vec<_>>(),
+            vec![bbox]
+        );
+        assert_eq!(bbox.tiles_max_area(0.01).unwrap().count(), 200);
+        assert_eq!(
+            bbox.tiles_max_area(CoordinateType::INFINITY)
+                .unwrap()
+                .count(),
+            1
+        );
+
+        assert!(bbox.tiles_max_area(0.0).is_none());
+        assert!(bbox.tiles_max_area(-1.0).is_none());
+        assert!(bbox.tiles_max_area(CoordinateType::NAN).is_none());
+    }
+
+    #[test]
+    fn union() {
+        let bbox = BBox::from_wrapped(0.0, 0.0, 10.0, 10.0);
+        let other = BBox::from_wrapped(-5.0, 5.0, 5.0, 20.0);
+
+        assert_eq!(
+            bbox.union(&other),
+            BBox::from_wrapped(-5.0, 0.0, 10.0, 20.0)
+        );
+        assert_eq!(other.union(&bbox), bbox.union(&other));
+        assert_eq!(bbox.union(&bbox), bbox);
+    }
+
+    #[test]
+    fn antimeridian_union() {
+        let crossing = BBox::from_wrapped(-10.0, 170.0, 10.0, -170.0);
+        let other = BBox::from_wrapped(0.0, 0.0, 5.0, 10.0);
+        let union = crossing.union(&other);
+
+        assert_eq!(union, BBox::from_wrapped(-10.0, 0.0, 10.0, -170.0));
+        assert!(union.contains_bbox(&crossing));
+        assert!(union.contains_bbox(&other));
+        assert_eq!(other.union(&crossing), union);
+
+        let west = BBox::from_wrapped(0.0, 170.0, 10.0, 175.0);
+        let east = BBox::from_wrapped(0.0, -175.0, 10.0, -170.0);
+        assert_eq!(
+            west.union(&east),
+            BBox::from_wrapped(0.0, 170.0, 10.0, -170.0)
+        );
+        assert_eq!(east.union(&west), west.union(&east));
+        assert_eq!(crossing.union(&east), crossing);
+    }
+
+    #[test]
+    fn extend() {
+        let mut bbox = BBox::from_wrapped(0.0, 0.0, 10.0, 10.0);
+
+        bbox.extend(&Coordinates::from_wrapped(5.0, 5.0));
+        assert_eq!(bbox, BBox::from_wrapped(0.0, 0.0, 10.0, 10.0));
+
+        bbox.extend(&Coordinates::from_wrapped(-3.0, 12.0));
+        assert_eq!(bbox, BBox::from_wrapped(-3.0, 0.0, 10.0, 12.0));
+
+        let mut bbox = BBox::from_wrapped(0.0, 170.0, 10.0, 175.0);
+        bbox.extend(&Coordinates::from_wrapped(5.0, -175.0));
+        assert!(bbox.crosses_antimeridian());
+        assert_eq!(bbox, BBox::from_wrapped(0.0, 170.0, 10.0, -175.0));
+
+        bbox.extend(&Coordinates::from_wrapped(5.0, 0.0));
+        assert_eq!(bbox, BBox::from_wrapped(0.0, 0.0, 10.0, -175.0));
+    }
+
     #[test]
     fn intersection_no_intersection() {
         let bbox = BBox::from_wrapped(0.0, 0.0, 50.0, 50.0);
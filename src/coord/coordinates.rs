@@ -1,11 +1,17 @@
 use std::{
     fmt::Display,
     ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign},
+    str::FromStr,
 };
 
 use serde::{Deserialize, Serialize};
 
-use crate::coord::{self, CoordinateType, latitude::Latitude, longitude::Longitude};
+use crate::coord::{
+    self, CoordinateType, error::ParseError, latitude::Latitude, longitude::Longitude,
+};
+
+/// The mean radius of the earth in meters.
+pub const EARTH_RADIUS_M: f64 = 6_371_008.8;
 
 /// A single point on earth.
 ///
@@ -43,7 +49,7 @@ impl Coordinates {
     ///
     /// # Error
     ///
-    /// Returns a [`coordinates::error::Error::OutOfRange`] if the latitude or the longitude is out of range.
+    /// Returns a [`coord::error::Error::OutOfRange`] if the latitude or the longitude is out of range.
     pub fn from_value(
         latitude: CoordinateType,
         longitude: CoordinateType,
@@ -70,6 +76,72 @@ impl Coordinates {
         )
     }
 
+    /// Parse [`Coordinates`] from one of the common notations:
+    ///
+    /// - decimal degrees separated by a comma or whitespace, e.g. `48.1372, 11.5756`
+    /// - degrees, minutes and seconds with hemisphere letters, e.g. `48°8′14″N 11°34′31″E`, where
+    ///   the letters may also lead and the longitude may come first
+    /// - a `geo:` URI, e.g. `geo:48.1372,11.5756;u=35`, see <https://www.rfc-editor.org/rfc/rfc5870>
+    ///
+    /// Without hemisphere letters the latitude comes first.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`ParseError`] if `input` is in none of these notations or out of range.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        if let Some(uri) = input
+            .get(..4)
+            .filter(|scheme| scheme.eq_ignore_ascii_case("geo:"))
+            .map(|_| &input[4..])
+        {
+            let invalid = || ParseError::InvalidGeoUri(input.to_string());
+            let position = uri.split([';', '?']).next().unwrap_or_default();
+            let values = position
+                .split(',')
+                .map(|value| value.trim().parse::<f64>().map_err(|_| invalid()))
+                .collect::<Result<Vec<_>, _>>()?;
+            // An altitude may follow.
+            let [latitude, longitude, ..] = values[..] else {
+                return Err(invalid());
+            };
+            if values.len() > 3 {
+                return Err(invalid());
+            }
+
+            return Ok(Self::from_value(
+                latitude as CoordinateType,
+                longitude as CoordinateType,
+            )?);
+        }
+
+        let (first, second) =
+            split_components(input).ok_or_else(|| ParseError::InvalidFormat(input.to_string()))?;
+        let (first, first_hemisphere) = parse_component(first)?;
+        let (second, second_hemisphere) = parse_component(second)?;
+
+        let is_latitude = |hemisphere: Option<char>| matches!(hemisphere, Some('N' | 'S'));
+        let is_longitude = |hemisphere: Option<char>| matches!(hemisphere, Some('E' | 'W'));
+        let (latitude, longitude) =
+            if is_longitude(first_hemisphere) || is_latitude(second_hemisphere) {
+                if is_longitude(second_hemisphere) || is_latitude(first_hemisphere) {
+                    return Err(ParseError::AmbiguousHemisphere(input.to_string()));
+                }
+                (second, first)
+            } else {
+                (first, second)
+            };
+
+        Ok(Self::from_value(
+            latitude as CoordinateType,
+            longitude as CoordinateType,
+        )?)
+    }
+
     /// [`Latitude`] of this [`Coordinates`].
     ///
     /// [`Latitude`] is the y coordinate.
@@ -83,6 +155,160 @@ impl Coordinates {
     pub fn longitude(&self) -> Longitude {
         self.longitude
     }
+
+    /// Get the great-circle distance to `other` in meters.
+    ///
+    /// Uses the haversine formula on a sphere with the [`EARTH_RADIUS_M`], which is off by up to
+    /// 0.5% from the distance on the ellipsoid of the earth.
+    pub fn distance_m(&self, other: &Coordinates) -> CoordinateType {
+        distance_m(*self, *other) as CoordinateType
+    }
+
+    /// Get the great-circle distance to `other` in kilometers, see [`Coordinates::distance_m`].
+    pub fn distance_km(&self, other: &Coordinates) -> CoordinateType {
+        self.distance_m(other) / 1000.0
+    }
+
+    /// Get the initial bearing of the great circle to `other` in degrees clockwise from north,
+    /// from 0 up to 360.
+    ///
+    /// The bearing changes along the way, except on meridians and the equator.
+    pub fn bearing_to(&self, other: &Coordinates) -> CoordinateType {
+        let (lat1, lat2) = (
+            widen(self.latitude().value()).to_radians(),
+            widen(other.latitude().value()).to_radians(),
+        );
+        let delta_lon =
+            (widen(other.longitude().value()) - widen(self.longitude().value())).to_radians();
+
+        let y = delta_lon.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * delta_lon.cos();
+        y.atan2(x).to_degrees().rem_euclid(360.0) as CoordinateType
+    }
+
+    /// Get the point reached by following the great circle starting at `bearing_deg` degrees
+    /// clockwise from north for `distance_m` meters, on a sphere with the [`EARTH_RADIUS_M`].
+    pub fn destination(&self, bearing_deg: CoordinateType, distance_m: CoordinateType) -> Self {
+        let lat1 = widen(self.latitude().value()).to_radians();
+        let lon1 = widen(self.longitude().value()).to_radians();
+        let bearing = widen(bearing_deg).to_radians();
+        let angle = widen(distance_m) / EARTH_RADIUS_M;
+
+        let lat2 = (lat1.sin() * angle.cos() + lat1.cos() * angle.sin() * bearing.cos())
+            .clamp(-1.0, 1.0)
+            .asin();
+        let lon2 = lon1
+            + (bearing.sin() * angle.sin() * lat1.cos())
+                .atan2(angle.cos() - lat1.sin() * lat2.sin());
+
+        Self::from_wrapped(
+            lat2.to_degrees() as CoordinateType,
+            lon2.to_degrees() as CoordinateType,
+        )
+    }
+}
+
+/// Get the great-circle distance between two points in meters.
+pub(crate) fn distance_m(a: Coordinates, b: Coordinates) -> f64 {
+    let (lat1, lat2) = (
+        widen(a.latitude().value()).to_radians(),
+        widen(b.latitude().value()).to_radians(),
+    );
+    let half_delta_lat = (lat2 - lat1) / 2.0;
+    let half_delta_lon =
+        (widen(b.longitude().value()) - widen(a.longitude().value())).to_radians() / 2.0;
+
+    let h = half_delta_lat.sin().powi(2) + lat1.cos() * lat2.cos() * half_delta_lon.sin().powi(2);
+    2.0 * EARTH_RADIUS_M * h.sqrt().min(1.0).asin()
+}
+
+/// Check if `c` is a hemisphere letter.
+fn is_hemisphere(c: char) -> bool {
+    matches!(c.to_ascii_uppercase(), 'N' | 'S' | 'E' | 'W')
+}
+
+/// Split coordinates into the latitude and longitude part, in any order.
+fn split_components(input: &str) -> Option<(&str, &str)> {
+    if let Some((first, second)) = input.split_once(',') {
+        return (!second.contains(',')).then_some((first, second));
+    }
+
+    let letters: Vec<_> = input
+        .char_indices()
+        .filter(|(_, c)| is_hemisphere(*c))
+        .map(|(i, _)| i)
+        .collect();
+    let split = match letters[..] {
+        // Leading letters like `N 48° E 11°`.
+        [0, second] => second,
+        [0, ..] => return None,
+        // Trailing letters like `48° N 11° E`, or only one letter.
+        [first, ..] => first + 1,
+        // Two values separated by whitespace, e.g. `48.1 11.5` or `48° 8′ 11° 34′`.
+        [] => {
+            let tokens: Vec<_> = input.split_whitespace().collect();
+            let second = if tokens.len() == 2 {
+                tokens[1]
+            } else {
+                *tokens.iter().skip(1).find(|token| token.contains('°'))?
+            };
+            second.as_ptr() as usize - input.as_ptr() as usize
+        }
+    };
+    let (first, second) = input.split_at(split);
+
+    (!first.trim().is_empty() && !second.trim().is_empty()).then_some((first, second))
+}
+
+/// Parse a value in decimal degrees or degrees, minutes and seconds with an optional leading or
+/// trailing hemisphere letter.
+///
+/// Returns the signed value in degrees and the uppercase hemisphere letter.
+fn parse_component(component: &str) -> Result<(f64, Option<char>), ParseError> {
+    let invalid = || ParseError::InvalidDms(component.trim().to_string());
+    let mut value = component.trim();
+
+    let mut hemisphere = None;
+    if let Some(letter) = value.chars().next().filter(|c| is_hemisphere(*c)) {
+        hemisphere = Some(letter.to_ascii_uppercase());
+        value = &value[letter.len_utf8()..];
+    } else if let Some(letter) = value.chars().next_back().filter(|c| is_hemisphere(*c)) {
+        hemisphere = Some(letter.to_ascii_uppercase());
+        value = &value[..value.len() - letter.len_utf8()];
+    }
+
+    let parts = value
+        .split(|c: char| c.is_whitespace() || "°º:′'’″\"”".contains(c))
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            part.parse::<f64>()
+                .map_err(|_| ParseError::InvalidNumber(part.to_string()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (degrees, minutes, seconds) = match parts[..] {
+        [degrees] => (degrees, 0.0, 0.0),
+        [degrees, minutes] => (degrees, minutes, 0.0),
+        [degrees, minutes, seconds] => (degrees, minutes, seconds),
+        _ => return Err(invalid()),
+    };
+    if parts.len() > 1 && (degrees.fract() != 0.0 || !(0.0..60.0).contains(&minutes))
+        || parts.len() > 2 && (minutes.fract() != 0.0 || !(0.0..60.0).contains(&seconds))
+        || hemisphere.is_some() && degrees.is_sign_negative()
+    {
+        return Err(invalid());
+    }
+
+    let mut degrees = degrees.abs() + minutes / 60.0 + seconds / 3600.0;
+    if value.trim_start().starts_with('-') || matches!(hemisphere, Some('S' | 'W')) {
+        degrees = -degrees;
+    }
+
+    Ok((degrees, hemisphere))
+}
+
+pub(crate) fn widen(value: impl Into<f64>) -> f64 {
+    value.into()
 }
 
 impl From<Coordinates> for (CoordinateType, CoordinateType) {
@@ -103,6 +329,15 @@ impl TryFrom<(CoordinateType, CoordinateType)> for Coordinates {
     }
 }
 
+impl FromStr for Coordinates {
+    type Err = ParseError;
+
+    /// See [`Coordinates::parse`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
 impl PartialEq for Coordinates {
     fn eq(&self, other: &Self) -> bool {
         self.latitude == other.latitude && self.longitude == other.longitude
@@ -129,7 +364,10 @@ impl PartialOrd for Coordinates {
 
 impl Display for Coordinates {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} {}", self.latitude, self.longitude)
+        // Forward the formatter so the alternate form and the precision apply to both.
+        self.latitude.fmt(f)?;
+        f.write_str(" ")?;
+        self.longitude.fmt(f)
     }
 }
 
@@ -222,8 +460,9 @@ impl<T: Into<CoordinateType>> DivAssign<T> for Coordinates {
 }
 
 #[cfg(test)]
+#[allow(clippy::nonminimal_bool, clippy::neg_cmp_op_on_partial_ord)]
 mod coordinate_test {
-    use crate::coord::{CoordinateType, coordinates::Coordinates};
+    use crate::coord::{CoordinateType, coordinates::Coordinates, error::ParseError};
 
     #[test]
     fn latitude() {
@@ -323,7 +562,134 @@ mod coordinate_test {
         assert!(!(coord1 > coord2));
     }
 
+    #[test]
+    fn distance() {
+        let berlin = Coordinates::from_value(52.52, 13.405).unwrap();
+        let paris = Coordinates::from_value(48.8566, 2.3522).unwrap();
+
+        assert!((berlin.distance_km(&paris) - 877.5).abs() < 1.0);
+        assert_eq!(berlin.distance_m(&paris), paris.distance_m(&berlin));
+        assert_eq!(berlin.distance_m(&berlin), 0.0);
+
+        let antipode = Coordinates::from_value(-52.52, 13.405 - 180.0).unwrap();
+        assert!((berlin.distance_km(&antipode) - 20_015.1).abs() < 1.0);
+    }
+
+    #[test]
+    fn bearing_to() {
+        let origin = Coordinates::from_value(0.0, 0.0).unwrap();
+
+        assert!((origin.bearing_to(&Coordinates::from_value(1.0, 0.0).unwrap())).abs() < 1e-4);
+        assert!(
+            (origin.bearing_to(&Coordinates::from_value(0.0, 1.0).unwrap()) - 90.0).abs() < 1e-4
+        );
+        assert!(
+            (origin.bearing_to(&Coordinates::from_value(0.0, -1.0).unwrap()) - 270.0).abs() < 1e-4
+        );
+
+        let berlin = Coordinates::from_value(52.52, 13.405).unwrap();
+        let paris = Coordinates::from_value(48.8566, 2.3522).unwrap();
+        assert!((berlin.bearing_to(&paris) - 246.74).abs() < 0.01);
+    }
+
+    #[test]
+    fn destination() {
+        let berlin = Coordinates::from_value(52.52, 13.405).unwrap();
+        let paris = Coordinates::from_value(48.8566, 2.3522).unwrap();
+
+        let reached = berlin.destination(berlin.bearing_to(&paris), berlin.distance_m(&paris));
+        assert!(reached.distance_m(&paris) < 1.0);
+
+        let origin = Coordinates::from_value(0.0, 179.0).unwrap();
+        let reached = origin.destination(
+            90.0,
+            2.0 * origin.distance_m(&Coordinates::from_value(0.0, 180.0).unwrap()),
+        );
+        assert!(reached.latitude().value().abs() < 1e-4);
+        assert!((reached.longitude().value() + 179.0).abs() < 1e-4);
+        assert!(berlin.destination(45.0, 0.0).distance_m(&berlin) < 1.0);
+    }
+
     fn get_coordinate() -> Coordinates {
         Coordinates::from_value(1.0, 2.0).unwrap()
     }
+
+    #[test]
+    fn parse() {
+        let expected = Coordinates::from_value(48.137_22, 11.575_28).unwrap();
+        let assert_parsed = |input: &str| {
+            let parsed = Coordinates::parse(input).unwrap();
+            assert!(parsed.distance_m(&expected) < 1.0, "{input}: {parsed}");
+        };
+
+        assert_parsed("48.13722,11.57528");
+        assert_parsed(" 48.13722, 11.57528 ");
+        assert_parsed("48.13722 11.57528");
+        assert_parsed("48°8′14″N 11°34′31″E");
+        assert_parsed("48°8'14\"N, 11°34'31\"E");
+        assert_parsed("N 48° 8' 14\" E 11° 34' 31\"");
+        assert_parsed("11°34′31″E 48°8′14″N");
+        assert_parsed("48° 8.2333′ N 11° 34.5167′ E");
+        assert_parsed("geo:48.13722,11.57528");
+        assert_parsed("GEO:48.13722,11.57528,520;u=35");
+        assert_parsed("geo:48.13722,11.57528?z=19");
+
+        let south_west = "33°51′35″S 151°12′40″W".parse::<Coordinates>().unwrap();
+        assert!(south_west.latitude().value() < -33.85);
+        assert!(south_west.longitude().value() < -151.2);
+        let negative = Coordinates::parse("-0°30′, -0°30′").unwrap();
+        assert_eq!(negative.latitude().value(), -0.5);
+        assert_eq!(negative.longitude().value(), -0.5);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(matches!(Coordinates::parse(" "), Err(ParseError::Empty)));
+        assert!(matches!(
+            Coordinates::parse("48.1"),
+            Err(ParseError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            Coordinates::parse("48.1,11.5,3"),
+            Err(ParseError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            Coordinates::parse("48.1, abc"),
+            Err(ParseError::InvalidNumber(_))
+        ));
+        assert!(matches!(
+            Coordinates::parse("48°61′N 11°E"),
+            Err(ParseError::InvalidDms(_))
+        ));
+        assert!(matches!(
+            Coordinates::parse("-48°N 11°E"),
+            Err(ParseError::InvalidDms(_))
+        ));
+        assert!(matches!(
+            Coordinates::parse("48°N 11°S"),
+            Err(ParseError::AmbiguousHemisphere(_))
+        ));
+        assert!(matches!(
+            Coordinates::parse("geo:48.1"),
+            Err(ParseError::InvalidGeoUri(_))
+        ));
+        assert!(matches!(
+            Coordinates::parse("91, 11"),
+            Err(ParseError::OutOfRange(_))
+        ));
+    }
+
+    #[test]
+    fn display_dms() {
+        let coordinates = Coordinates::from_value(48.137_22, 11.575_28).unwrap();
+
+        assert_eq!(format!("{coordinates:#}"), "48°8′14″N 11°34′31″E");
+        assert_eq!(format!("{coordinates:#.2}"), "48°8′13.99″N 11°34′31.01″E");
+        assert!(
+            Coordinates::parse(&format!("{coordinates:#.2}"))
+                .unwrap()
+                .distance_m(&coordinates)
+                < 1.0
+        );
+    }
 }
@@ -0,0 +1,100 @@
+use std::fmt::Display;
+
+use crate::coord::{CoordinateType, coordinates::widen};
+
+/// An angle split into degrees, minutes and seconds with a hemisphere letter, e.g. `48°8′14″N`.
+///
+/// The [`Display`] precision sets the number of decimal places of the seconds, which defaults to
+/// whole seconds, e.g. `format!("{dms:.2}")` gives `48°8′13.99″N`. Decimal places past the ninth
+/// are zero.
+///
+/// See <https://en.wikipedia.org/wiki/Degree_(angle)#Subdivisions>
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dms {
+    pub degrees: u16,
+    pub minutes: u8,
+    pub seconds: CoordinateType,
+    /// One of `N`, `S`, `E` and `W`.
+    pub hemisphere: char,
+}
+
+impl Dms {
+    /// Split `value` in degrees, using `positive` as the hemisphere letter for values of 0 and more
+    /// and `negative` otherwise.
+    pub(crate) fn new(value: CoordinateType, positive: char, negative: char) -> Self {
+        let hemisphere = if value >= 0.0 { positive } else { negative };
+        let value = widen(value).abs();
+        let minutes = value.fract() * 60.0;
+
+        Self {
+            degrees: value.trunc() as u16,
+            minutes: minutes.trunc() as u8,
+            seconds: (minutes.fract() * 60.0) as CoordinateType,
+            hemisphere,
+        }
+    }
+}
+
+/// The most decimal places of the seconds computed, far below the precision of a coordinate.
+const MAX_SECONDS_DIGITS: usize = 9;
+
+impl Display for Dms {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let precision = f.precision().unwrap_or(0);
+        // Seconds have no more than 9 meaningful decimal places, further places are zero padded.
+        let digits = precision.min(MAX_SECONDS_DIGITS);
+        let scale = 10u64.pow(digits as u32);
+
+        // Round the whole angle, so rounding up carries into the minutes and degrees.
+        let total = ((f64::from(self.degrees) * 3600.0
+            + f64::from(self.minutes) * 60.0
+            + widen(self.seconds))
+            * scale as f64)
+            .round() as u64;
+        let (degrees, rest) = (total / (3600 * scale), total % (3600 * scale));
+        let (minutes, seconds) = (rest / (60 * scale), rest % (60 * scale));
+
+        write!(f, "{degrees}°{minutes}′{}", seconds / scale)?;
+        if precision > 0 {
+            write!(
+                f,
+                ".{:0digits$}{:0<2$}",
+                seconds % scale,
+                "",
+                precision - digits
+            )?;
+        }
+        write!(f, "″{}", self.hemisphere)
+    }
+}
+
+#[cfg(test)]
+mod dms_test {
+    use crate::coord::dms::Dms;
+
+    #[test]
+    fn display() {
+        let dms = Dms::new(48.137_22, 'N', 'S');
+
+        assert_eq!(dms.degrees, 48);
+        assert_eq!(dms.minutes, 8);
+        assert_eq!(dms.hemisphere, 'N');
+        assert_eq!(dms.to_string(), "48°8′14″N");
+        assert_eq!(format!("{dms:.2}"), "48°8′13.99″N");
+        assert_eq!(Dms::new(-0.5, 'E', 'W').to_string(), "0°30′0″W");
+        assert_eq!(Dms::new(11.999_99, 'E', 'W').to_string(), "12°0′0″E");
+    }
+
+    #[test]
+    fn large_precision() {
+        let dms = Dms::new(-179.5, 'E', 'W');
+
+        assert_eq!(format!("{dms:.9}"), "179°30′0.000000000″W");
+        assert_eq!(format!("{dms:.12}"), "179°30′0.000000000000″W");
+        assert_eq!(format!("{dms:.20}"), "179°30′0.00000000000000000000″W");
+        assert_eq!(
+            format!("{:.40}", Dms::new(48.137_22, 'N', 'S')).len(),
+            format!("{:.2}", Dms::new(48.137_22, 'N', 'S')).len() + 38
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use std::ops::RangeInclusive;
 
-use crate::coord::{CoordinateType, coordinates::Coordinates};
+use crate::coord::{CoordinateType, coordinates::Coordinates, polyline::Polyline};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -8,4 +8,25 @@ pub enum Error {
     OutOfRange((CoordinateType, RangeInclusive<CoordinateType>)),
     #[error("south_west must be more south-west than north_east")]
     InvalidCornerOrder((Coordinates, Coordinates)),
+    #[error("A ring must be closed and have at least four coordinates")]
+    InvalidRing(Polyline),
+}
+
+/// The error returned by [`Coordinates::parse`].
+#[derive(Debug, thiserror::Error)]
+pub enum ParseError {
+    #[error("The input is empty")]
+    Empty,
+    #[error("Expected a latitude and a longitude: {0}")]
+    InvalidFormat(String),
+    #[error("Invalid number: {0}")]
+    InvalidNumber(String),
+    #[error("Invalid degrees, minutes and seconds: {0}")]
+    InvalidDms(String),
+    #[error("Both values have a latitude or both a longitude hemisphere: {0}")]
+    AmbiguousHemisphere(String),
+    #[error("Invalid geo URI: {0}")]
+    InvalidGeoUri(String),
+    #[error(transparent)]
+    OutOfRange(#[from] Error),
 }
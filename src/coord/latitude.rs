@@ -6,10 +6,11 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
-use crate::coord::{self, CoordinateType, normalize::Normalized};
+use crate::coord::{self, CoordinateType, dms::Dms, normalize::Normalized};
 
 pub const LATITUDE_RANGE: RangeInclusive<CoordinateType> = -90.0..=90.0;
 
+#[allow(clippy::derive_ord_xor_partial_ord)]
 #[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct Latitude(CoordinateType);
@@ -19,7 +20,7 @@ impl Latitude {
     ///
     /// # Error
     ///
-    /// Returns a [`coordinates::error::Error::OutOfRange`] if the latitude provided is outside of the [`LATITUDE_RANGE`].
+    /// Returns a [`coord::error::Error::OutOfRange`] if the latitude provided is outside of the [`LATITUDE_RANGE`].
     pub fn new(latitude: CoordinateType) -> Result<Self, coord::error::Error> {
         if Self::is_valid(latitude) {
             Ok(Self(latitude))
@@ -47,6 +48,11 @@ impl Latitude {
     pub fn value(&self) -> CoordinateType {
         self.0
     }
+
+    /// Split this [`Latitude`] into degrees, minutes and seconds with a `N` or `S` hemisphere.
+    pub fn to_dms(&self) -> Dms {
+        Dms::new(self.0, 'N', 'S')
+    }
 }
 
 impl Normalized for Latitude {
@@ -55,9 +61,12 @@ impl Normalized for Latitude {
     const MAX: CoordinateType = *LATITUDE_RANGE.end();
 }
 
+/// The alternate form `{:#}` writes degrees, minutes and seconds, see [`Dms`].
 impl Display for Latitude {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.0 >= 0.0 {
+        if f.alternate() {
+            self.to_dms().fmt(f)
+        } else if self.0 >= 0.0 {
             write!(f, "{} °N", self.0)
         } else {
             write!(f, "{} °S", self.0.abs())
@@ -76,7 +85,7 @@ impl Ord for Latitude {
 impl Hash for Latitude {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         let bits = if self.0 == 0.0 {
-            0.0f64.to_bits()
+            CoordinateType::to_bits(0.0)
         } else {
             self.0.to_bits()
         };
@@ -164,6 +173,7 @@ impl Neg for Latitude {
 }
 
 #[cfg(test)]
+#[allow(clippy::nonminimal_bool, clippy::neg_cmp_op_on_partial_ord)]
 mod latitude_test {
     use crate::coord::latitude::Latitude;
 
@@ -222,4 +232,13 @@ mod latitude_test {
     fn neg() {
         assert_eq!(-Latitude::new(45.0).unwrap(), Latitude::new(-45.0).unwrap());
     }
+
+    #[test]
+    fn dms() {
+        let latitude = Latitude::new(-33.859_72).unwrap();
+
+        assert_eq!(latitude.to_dms().hemisphere, 'S');
+        assert_eq!(format!("{latitude:#}"), "33°51′35″S");
+        assert_eq!(format!("{latitude:#.1}"), "33°51′35.0″S");
+    }
 }
@@ -6,10 +6,11 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
-use crate::coord::{self, CoordinateType, normalize::Normalized};
+use crate::coord::{self, CoordinateType, dms::Dms, normalize::Normalized};
 
 pub const LONGITUDE_RANGE: RangeInclusive<CoordinateType> = -180.0..=180.0;
 
+#[allow(clippy::derive_ord_xor_partial_ord)]
 #[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct Longitude(CoordinateType);
@@ -19,7 +20,7 @@ impl Longitude {
     ///
     /// # Error
     ///
-    /// Returns a [`coordinates::error::Error::OutOfRange`] if the longitude provided is outside of the [`LONGITUDE_RANGE`].
+    /// Returns a [`coord::error::Error::OutOfRange`] if the longitude provided is outside of the [`LONGITUDE_RANGE`].
     pub fn new(longitude: CoordinateType) -> Result<Self, coord::error::Error> {
         if Self::is_valid(longitude) {
             Ok(Self(longitude))
@@ -50,6 +51,11 @@ impl Longitude {
     pub const fn value(&self) -> CoordinateType {
         self.0
     }
+
+    /// Split this [`Longitude`] into degrees, minutes and seconds with a `E` or `W` hemisphere.
+    pub fn to_dms(&self) -> Dms {
+        Dms::new(self.0, 'E', 'W')
+    }
 }
 
 impl Normalized for Longitude {
@@ -57,9 +63,12 @@ impl Normalized for Longitude {
     const MAX: CoordinateType = *LONGITUDE_RANGE.end();
 }
 
+/// The alternate form `{:#}` writes degrees, minutes and seconds, see [`Dms`].
 impl Display for Longitude {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.0 >= 0.0 {
+        if f.alternate() {
+            self.to_dms().fmt(f)
+        } else if self.0 >= 0.0 {
             write!(f, "{} °E", self.0)
         } else {
             write!(f, "{} °W", self.0.abs())
@@ -78,7 +87,7 @@ impl Ord for Longitude {
 impl Hash for Longitude {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         let bits = if self.0 == 0.0 {
-            0.0f64.to_bits()
+            CoordinateType::to_bits(0.0)
         } else {
             self.0.to_bits()
         };
@@ -166,6 +175,7 @@ impl Neg for Longitude {
 }
 
 #[cfg(test)]
+#[allow(clippy::nonminimal_bool, clippy::neg_cmp_op_on_partial_ord)]
 mod longitude_test {
     use crate::coord::{
         CoordinateType,
@@ -284,6 +294,15 @@ mod longitude_test {
         );
     }
 
+    #[test]
+    fn dms() {
+        let longitude = Longitude::new(11.575_28).unwrap();
+
+        assert_eq!(longitude.to_dms().degrees, 11);
+        assert_eq!(format!("{longitude:#}"), "11°34′31″E");
+        assert_eq!(format!("{:#}", Longitude::new(-0.25).unwrap()), "0°15′0″W");
+    }
+
     fn round(x: CoordinateType) -> CoordinateType {
         (x * 1e6).round() / 1e6
     }
@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+
+use crate::coord::{self, bbox::BBox, coordinates::Coordinates, polyline::Polyline};
+
+/// An area bounded by an exterior ring, with optional holes cut out by interior rings.
+///
+/// The first and the last [`Coordinates`](crate::coord::coordinates::Coordinates) of every ring
+/// are expected to be equal.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Polygon {
+    exterior: Polyline,
+    #[serde(default)]
+    interiors: Vec<Polyline>,
+}
+
+impl Polygon {
+    /// Construct a new [`Polygon`] from its exterior ring and its holes.
+    pub fn new(exterior: Polyline, interiors: Vec<Polyline>) -> Self {
+        Self {
+            exterior,
+            interiors,
+        }
+    }
+
+    /// Construct a new [`Polygon`] after checking that all rings are valid, see
+    /// [`Polyline::is_ring`].
+    ///
+    /// # Error
+    ///
+    /// Returns a [`coord::error::Error::InvalidRing`] with the first ring that is not closed or
+    /// has less than four [`Coordinates`].
+    pub fn try_new(
+        exterior: Polyline,
+        interiors: Vec<Polyline>,
+    ) -> Result<Self, coord::error::Error> {
+        if let Some(ring) = std::iter::once(&exterior)
+            .chain(&interiors)
+            .find(|ring| !ring.is_ring())
+        {
+            return Err(coord::error::Error::InvalidRing(ring.clone()));
+        }
+
+        Ok(Self::new(exterior, interiors))
+    }
+
+    /// Check if all rings are closed and have at least four [`Coordinates`].
+    pub fn is_valid(&self) -> bool {
+        self.exterior.is_ring() && self.interiors.iter().all(Polyline::is_ring)
+    }
+
+    /// Get the smallest [`BBox`] containing the exterior ring.
+    ///
+    /// Returns [`None`] if the exterior ring is empty.
+    pub fn bbox(&self) -> Option<BBox> {
+        self.exterior.bbox()
+    }
+
+    /// Get the exterior ring.
+    pub fn exterior(&self) -> &Polyline {
+        &self.exterior
+    }
+
+    /// Get the interior rings, the holes of this [`Polygon`].
+    pub fn interiors(&self) -> &[Polyline] {
+        &self.interiors
+    }
+
+    /// Check if [`Coordinates`] lie inside the exterior ring and outside of all holes.
+    ///
+    /// The rings are treated as planar, which is accurate enough for areas that do not span
+    /// large parts of the globe.
+    pub fn contains(&self, coordinates: &Coordinates) -> bool {
+        ring_contains(&self.exterior, coordinates)
+            && !self
+                .interiors
+                .iter()
+                .any(|interior| ring_contains(interior, coordinates))
+    }
+}
+
+/// Check if a closed ring contains [`Coordinates`] using ray casting.
+fn ring_contains(ring: &Polyline, coordinates: &Coordinates) -> bool {
+    let (x, y) = position(coordinates);
+
+    ring.coordinates().windows(2).fold(false, |inside, pair| {
+        let (x1, y1) = position(&pair[0]);
+        let (x2, y2) = position(&pair[1]);
+        if (y1 > y) != (y2 > y) && x < (x2 - x1) * (y - y1) / (y2 - y1) + x1 {
+            !inside
+        } else {
+            inside
+        }
+    })
+}
+
+/// Get the longitude and latitude as `f64`.
+fn position(coordinates: &Coordinates) -> (f64, f64) {
+    fn widen(value: impl Into<f64>) -> f64 {
+        value.into()
+    }
+
+    (
+        widen(coordinates.longitude().value()),
+        widen(coordinates.latitude().value()),
+    )
+}
+
+#[cfg(test)]
+mod polygon_test {
+    use crate::coord::{
+        bbox::BBox, coordinates::Coordinates, error::Error, polygon::Polygon, polyline::Polyline,
+    };
+
+    fn ring(corners: &[(f64, f64)]) -> Polyline {
+        corners
+            .iter()
+            .chain(&corners[..1])
+            .map(|(lat, lon)| Coordinates::from_unchecked(*lat as _, *lon as _))
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    #[test]
+    fn contains() {
+        let polygon = Polygon::new(
+            ring(&[(0.0, 0.0), (0.0, 4.0), (4.0, 4.0), (4.0, 0.0)]),
+            vec![ring(&[(1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0)])],
+        );
+        let point = |lat, lon| Coordinates::from_unchecked(lat, lon);
+
+        assert!(polygon.contains(&point(3.0, 3.0)));
+        assert!(!polygon.contains(&point(1.5, 1.5)));
+        assert!(!polygon.contains(&point(5.0, 1.0)));
+        assert!(!Polygon::default().contains(&point(0.0, 0.0)));
+    }
+
+    #[test]
+    fn validation() {
+        let exterior = ring(&[(0.0, 0.0), (0.0, 4.0), (4.0, 4.0), (4.0, 0.0)]);
+        let open = Polyline::new(exterior.coordinates()[..4].to_vec());
+
+        let polygon = Polygon::try_new(exterior.clone(), Vec::new()).unwrap();
+        assert!(polygon.is_valid());
+        assert_eq!(polygon.bbox(), Some(BBox::from_wrapped(0.0, 0.0, 4.0, 4.0)));
+
+        assert!(matches!(
+            Polygon::try_new(exterior.clone(), vec![open.clone()]),
+            Err(Error::InvalidRing(ring)) if ring == open
+        ));
+        assert!(!Polygon::new(open, Vec::new()).is_valid());
+        assert!(!Polygon::default().is_valid());
+        assert_eq!(Polygon::default().bbox(), None);
+    }
+}
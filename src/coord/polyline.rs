@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+
+use crate::coord::{CoordinateType, bbox::BBox, coordinates::Coordinates};
+
+/// An ordered list of [`Coordinates`] connected by straight lines, also known as a line string.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Polyline {
+    coordinates: Vec<Coordinates>,
+}
+
+impl Polyline {
+    /// Construct a new [`Polyline`] from its [`Coordinates`].
+    pub fn new(coordinates: Vec<Coordinates>) -> Self {
+        Self { coordinates }
+    }
+
+    /// Get the [`Coordinates`] of this [`Polyline`].
+    pub fn coordinates(&self) -> &[Coordinates] {
+        &self.coordinates
+    }
+
+    /// Take the [`Coordinates`] out of this [`Polyline`].
+    pub fn into_coordinates(self) -> Vec<Coordinates> {
+        self.coordinates
+    }
+
+    /// Check if the first and the last [`Coordinates`] are equal.
+    ///
+    /// A [`Polyline`] with less than two [`Coordinates`] is not closed.
+    pub fn is_closed(&self) -> bool {
+        self.coordinates.len() >= 2 && self.coordinates.first() == self.coordinates.last()
+    }
+
+    /// Check if this [`Polyline`] is a valid ring of a [`Polygon`](super::polygon::Polygon),
+    /// closed with at least four [`Coordinates`].
+    pub fn is_ring(&self) -> bool {
+        self.coordinates.len() >= 4 && self.is_closed()
+    }
+
+    /// Get the smallest [`BBox`] containing all [`Coordinates`].
+    ///
+    /// Returns [`None`] if this [`Polyline`] is empty.
+    pub fn bbox(&self) -> Option<BBox> {
+        let (first, rest) = self.coordinates.split_first()?;
+        let mut bbox = BBox::from_unchecked(*first, *first);
+        for coordinates in rest {
+            bbox.extend(coordinates);
+        }
+
+        Some(bbox)
+    }
+
+    /// Get the length along the great circles between the [`Coordinates`] in meters, see
+    /// [`Coordinates::distance_m`].
+    pub fn length_m(&self) -> CoordinateType {
+        self.coordinates
+            .windows(2)
+            .map(|pair| pair[0].distance_m(&pair[1]))
+            .sum()
+    }
+}
+
+impl From<Vec<Coordinates>> for Polyline {
+    fn from(coordinates: Vec<Coordinates>) -> Self {
+        Self::new(coordinates)
+    }
+}
+
+#[cfg(test)]
+mod polyline_test {
+    use crate::coord::{bbox::BBox, coordinates::Coordinates, polyline::Polyline};
+
+    fn line(points: &[(f64, f64)]) -> Polyline {
+        points
+            .iter()
+            .map(|(lat, lon)| Coordinates::from_unchecked(*lat as _, *lon as _))
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    #[test]
+    fn closed() {
+        let ring = line(&[(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (0.0, 0.0)]);
+
+        assert!(ring.is_closed());
+        assert!(ring.is_ring());
+        assert!(line(&[(0.0, 0.0), (0.0, 0.0)]).is_closed());
+        assert!(!line(&[(0.0, 0.0), (0.0, 0.0)]).is_ring());
+        assert!(!line(&[(0.0, 0.0), (0.0, 1.0), (1.0, 1.0)]).is_closed());
+        assert!(!Polyline::default().is_closed());
+    }
+
+    #[test]
+    fn bbox() {
+        let polyline = line(&[(1.0, 2.0), (-1.0, 3.0), (0.5, -4.0)]);
+
+        assert_eq!(
+            polyline.bbox(),
+            Some(BBox::from_wrapped(-1.0, -4.0, 1.0, 3.0))
+        );
+        assert_eq!(Polyline::default().bbox(), None);
+    }
+
+    #[test]
+    fn length_m() {
+        let berlin = Coordinates::from_unchecked(52.52, 13.405);
+        let paris = Coordinates::from_unchecked(48.8566, 2.3522);
+        let polyline = Polyline::new(vec![berlin, paris, berlin]);
+
+        assert!((polyline.length_m() - 2.0 * berlin.distance_m(&paris)).abs() < 1.0);
+        assert_eq!(Polyline::new(vec![berlin]).length_m(), 0.0);
+        assert_eq!(Polyline::default().length_m(), 0.0);
+    }
+}
@@ -0,0 +1,258 @@
+use std::{f64::consts::PI, fmt::Display};
+
+use crate::coord::{CoordinateType, bbox::BBox, coordinates::Coordinates};
+
+/// The latitude limit of the web mercator projection in degrees.
+const MAX_LATITUDE: f64 = 85.051_128_78;
+
+/// A web mercator tile as used by raster tile servers, addressed by `z/x/y`.
+///
+/// `x` grows from west to east and `y` from north to south, zoom level `z` has `2^z` tiles in
+/// each direction.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Slippy_map_tilenames>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Tile {
+    zoom: u8,
+    x: u32,
+    y: u32,
+}
+
+impl Tile {
+    /// The highest supported zoom level.
+    pub const MAX_ZOOM: u8 = 24;
+
+    /// Construct a new [`Tile`].
+    ///
+    /// Returns [`None`] if `zoom` is above [`Tile::MAX_ZOOM`] or `x` or `y` are outside of the
+    /// zoom level.
+    pub fn new(zoom: u8, x: u32, y: u32) -> Option<Self> {
+        if zoom > Self::MAX_ZOOM || x >= 1 << zoom || y >= 1 << zoom {
+            return None;
+        }
+
+        Some(Self { zoom, x, y })
+    }
+
+    /// Get the [`Tile`] containing [`Coordinates`] at `zoom`, at most [`Tile::MAX_ZOOM`].
+    ///
+    /// Latitudes beyond the limits of web mercator, about ±85.05°, are in the outermost tiles.
+    pub fn from_coordinates(coordinates: &Coordinates, zoom: u8) -> Self {
+        let zoom = zoom.min(Self::MAX_ZOOM);
+        let tiles = f64::from(1u32 << zoom);
+        let latitude = widen(coordinates.latitude().value())
+            .clamp(-MAX_LATITUDE, MAX_LATITUDE)
+            .to_radians();
+        let x = (widen(coordinates.longitude().value()) + 180.0) / 360.0 * tiles;
+        let y = (1.0 - latitude.tan().asinh() / PI) / 2.0 * tiles;
+
+        let max = (1u32 << zoom) - 1;
+        Self {
+            zoom,
+            x: (x as u32).min(max),
+            y: (y as u32).min(max),
+        }
+    }
+
+    /// Iterate over the tiles at `zoom` covering a [`BBox`], row by row from the north-west.
+    ///
+    /// A [`BBox`] crossing the antimeridian is covered as its western half followed by its
+    /// eastern half.
+    pub fn covering(bbox: &BBox, zoom: u8) -> impl Iterator<Item = Self> {
+        bbox.halves()
+            .into_iter()
+            .flatten()
+            .flat_map(move |half| Self::covering_half(&half, zoom))
+    }
+
+    /// Iterate over the tiles at `zoom` covering a [`BBox`] not crossing the antimeridian.
+    fn covering_half(bbox: &BBox, zoom: u8) -> impl Iterator<Item = Self> + use<> {
+        let north_west = Self::from_coordinates(
+            &Coordinates::from_unchecked(
+                bbox.north_east().latitude().value(),
+                bbox.south_west().longitude().value(),
+            ),
+            zoom,
+        );
+        let south_east = Self::from_coordinates(
+            &Coordinates::from_unchecked(
+                bbox.south_west().latitude().value(),
+                bbox.north_east().longitude().value(),
+            ),
+            zoom,
+        );
+
+        (north_west.y..=south_east.y).flat_map(move |y| {
+            (north_west.x..=south_east.x).map(move |x| Self {
+                zoom: north_west.zoom,
+                x,
+                y,
+            })
+        })
+    }
+
+    /// Get the zoom level.
+    pub fn zoom(&self) -> u8 {
+        self.zoom
+    }
+
+    /// Get the column, counted from the west.
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+
+    /// Get the row, counted from the north.
+    pub fn y(&self) -> u32 {
+        self.y
+    }
+
+    /// Get the north-west corner.
+    pub fn north_west(&self) -> Coordinates {
+        corner(self.zoom, self.x, self.y)
+    }
+
+    /// Get the area covered by this tile.
+    pub fn bbox(&self) -> BBox {
+        BBox::from_unchecked(
+            corner(self.zoom, self.x, self.y + 1),
+            corner(self.zoom, self.x + 1, self.y),
+        )
+    }
+
+    /// Get the tile one zoom level lower containing this tile.
+    ///
+    /// Returns [`None`] at zoom level 0.
+    pub fn parent(&self) -> Option<Self> {
+        Some(Self {
+            zoom: self.zoom.checked_sub(1)?,
+            x: self.x / 2,
+            y: self.y / 2,
+        })
+    }
+
+    /// Get the four tiles one zoom level higher covering this tile.
+    ///
+    /// Returns [`None`] at [`Tile::MAX_ZOOM`].
+    pub fn children(&self) -> Option<[Self; 4]> {
+        if self.zoom == Self::MAX_ZOOM {
+            return None;
+        }
+
+        let child = |dx, dy| Self {
+            zoom: self.zoom + 1,
+            x: self.x * 2 + dx,
+            y: self.y * 2 + dy,
+        };
+        Some([child(0, 0), child(1, 0), child(0, 1), child(1, 1)])
+    }
+}
+
+/// Formats as `z/x/y`, the path of the tile on a tile server.
+impl Display for Tile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}/{}", self.zoom, self.x, self.y)
+    }
+}
+
+/// Get the north-west corner of the tile `x`, `y` at `zoom`.
+fn corner(zoom: u8, x: u32, y: u32) -> Coordinates {
+    let tiles = f64::from(1u32 << zoom);
+    let longitude = f64::from(x) / tiles * 360.0 - 180.0;
+    let latitude = (PI * (1.0 - 2.0 * f64::from(y) / tiles))
+        .sinh()
+        .atan()
+        .to_degrees();
+
+    Coordinates::from_unchecked(latitude as CoordinateType, longitude as CoordinateType)
+}
+
+fn widen(value: impl Into<f64>) -> f64 {
+    value.into()
+}
+
+#[cfg(test)]
+mod tile_test {
+    use crate::coord::{bbox::BBox, coordinates::Coordinates, tile::Tile};
+
+    #[test]
+    fn new() {
+        assert!(Tile::new(0, 0, 0).is_some());
+        assert!(Tile::new(2, 3, 3).is_some());
+        assert!(Tile::new(2, 4, 0).is_none());
+        assert!(Tile::new(25, 0, 0).is_none());
+    }
+
+    #[test]
+    fn from_coordinates() {
+        let berlin = Coordinates::from_unchecked(52.52, 13.405);
+        assert_eq!(
+            Tile::from_coordinates(&berlin, 10),
+            Tile::new(10, 550, 335).unwrap()
+        );
+        assert_eq!(
+            Tile::from_coordinates(&berlin, 0),
+            Tile::new(0, 0, 0).unwrap()
+        );
+
+        let pole = Coordinates::from_unchecked(90.0, 180.0);
+        assert_eq!(
+            Tile::from_coordinates(&pole, 2),
+            Tile::new(2, 3, 0).unwrap()
+        );
+        assert_eq!(Tile::from_coordinates(&pole, 30).zoom(), Tile::MAX_ZOOM);
+    }
+
+    #[test]
+    fn bbox() {
+        let tile = Tile::new(1, 1, 0).unwrap();
+        let bbox = tile.bbox();
+        assert!((bbox.south_west().latitude().value() - 0.0).abs() < 1e-6);
+        assert!((bbox.south_west().longitude().value() - 0.0).abs() < 1e-6);
+        assert!((bbox.north_east().latitude().value() - 85.051_128).abs() < 1e-4);
+        assert!((bbox.north_east().longitude().value() - 180.0).abs() < 1e-6);
+        assert_eq!(tile.north_west().longitude().value(), 0.0);
+        assert_eq!(tile.to_string(), "1/1/0");
+    }
+
+    #[test]
+    fn hierarchy() {
+        let tile = Tile::new(3, 5, 2).unwrap();
+        let children = tile.children().unwrap();
+        assert!(children.iter().all(|child| child.parent() == Some(tile)));
+        assert_eq!(children[3], Tile::new(4, 11, 5).unwrap());
+        assert_eq!(Tile::new(0, 0, 0).unwrap().parent(), None);
+        assert!(
+            Tile::new(Tile::MAX_ZOOM, 0, 0)
+                .unwrap()
+                .children()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn covering() {
+        let bbox = BBox::from_unchecked(
+            Coordinates::from_unchecked(-10.0, -10.0),
+            Coordinates::from_unchecked(10.0, 10.0),
+        );
+        let tiles: Vec<Tile> = Tile::covering(&bbox, 1).collect();
+        assert_eq!(tiles.len(), 4);
+        assert_eq!(tiles[0], Tile::new(1, 0, 0).unwrap());
+        assert_eq!(tiles[3], Tile::new(1, 1, 1).unwrap());
+        assert_eq!(Tile::covering(&bbox, 0).count(), 1);
+
+        let crossing = BBox::from_wrapped(-10.0, 170.0, 10.0, -170.0);
+        let tiles: Vec<Tile> = Tile::covering(&crossing, 1).collect();
+        assert_eq!(
+            tiles,
+            vec![
+                Tile::new(1, 1, 0).unwrap(),
+                Tile::new(1, 1, 1).unwrap(),
+                Tile::new(1, 0, 0).unwrap(),
+                Tile::new(1, 0, 1).unwrap(),
+            ]
+        );
+        let node = Coordinates::from_unchecked(0.0, 175.0);
+        assert!(Tile::covering(&crossing, 8).any(|tile| tile == Tile::from_coordinates(&node, 8)));
+    }
+}
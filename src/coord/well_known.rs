@@ -0,0 +1,347 @@
+use std::fmt::Write;
+
+use crate::coord::{bbox::BBox, coordinates::Coordinates, polygon::Polygon, polyline::Polyline};
+
+/// The spatial reference id of WGS 84, which all OSM coordinates use.
+///
+/// See <https://epsg.io/4326>
+pub const SRID: u32 = 4326;
+
+/// The EWKB flag marking that a SRID follows the geometry type.
+const EWKB_SRID_FLAG: u32 = 0x2000_0000;
+
+/// Serialization as Well-Known Text.
+///
+/// The output can be used with PostGIS functions like `ST_GeomFromText` or `ST_GeomFromEWKT`.
+///
+/// See <https://libgeos.org/specifications/wkt/>
+pub trait ToWkt {
+    /// Serialize as WKT like `POINT(13.4 52.5)`.
+    fn to_wkt(&self) -> String;
+
+    /// Serialize as extended WKT with the [`SRID`] like `SRID=4326;POINT(13.4 52.5)`.
+    fn to_ewkt(&self) -> String {
+        format!("SRID={SRID};{}", self.to_wkt())
+    }
+}
+
+/// Serialization as Well-Known Binary in little endian byte order.
+///
+/// The output can be used with PostGIS functions like `ST_GeomFromWKB` or be bound directly to a
+/// `geometry` column as extended WKB.
+///
+/// See <https://libgeos.org/specifications/wkb/>
+pub trait ToWkb {
+    /// Serialize as WKB.
+    fn to_wkb(&self) -> Vec<u8>;
+
+    /// Serialize as extended WKB with the [`SRID`].
+    fn to_ewkb(&self) -> Vec<u8>;
+}
+
+/// The geometries that have a WKT and WKB representation.
+enum Geometry<'a> {
+    Point(Coordinates),
+    LineString(&'a [Coordinates]),
+    Polygon(Vec<&'a [Coordinates]>),
+    MultiPolygon(Vec<Vec<&'a [Coordinates]>>),
+}
+
+impl Geometry<'_> {
+    fn write_wkt(&self, wkt: &mut String) {
+        match self {
+            Self::Point(coordinates) => {
+                wkt.push_str("POINT(");
+                write_wkt_position(coordinates, wkt);
+                wkt.push(')');
+            }
+            Self::LineString([]) => wkt.push_str("LINESTRING EMPTY"),
+            Self::LineString(coordinates) => {
+                wkt.push_str("LINESTRING");
+                write_wkt_positions(coordinates, wkt);
+            }
+            Self::Polygon(rings) if rings.is_empty() || rings[0].is_empty() => {
+                wkt.push_str("POLYGON EMPTY")
+            }
+            Self::Polygon(rings) => {
+                wkt.push_str("POLYGON");
+                write_wkt_rings(rings, wkt);
+            }
+            Self::MultiPolygon(polygons) => {
+                wkt.push_str("MULTIPOLYGON(");
+                for (index, rings) in polygons.iter().enumerate() {
+                    if index > 0 {
+                        wkt.push(',');
+                    }
+                    write_wkt_rings(rings, wkt);
+                }
+                wkt.push(')');
+            }
+        }
+    }
+
+    fn write_wkb(&self, srid: Option<u32>, wkb: &mut Vec<u8>) {
+        let geometry_type: u32 = match self {
+            Self::Point(_) => 1,
+            Self::LineString(_) => 2,
+            Self::Polygon(_) => 3,
+            Self::MultiPolygon(_) => 6,
+        };
+
+        // Little endian.
+        wkb.push(1);
+        match srid {
+            Some(srid) => {
+                wkb.extend_from_slice(&(geometry_type | EWKB_SRID_FLAG).to_le_bytes());
+                wkb.extend_from_slice(&srid.to_le_bytes());
+            }
+            None => wkb.extend_from_slice(&geometry_type.to_le_bytes()),
+        }
+
+        match self {
+            Self::Point(coordinates) => write_wkb_position(coordinates, wkb),
+            Self::LineString(coordinates) => write_wkb_positions(coordinates, wkb),
+            Self::Polygon(rings) => {
+                wkb.extend_from_slice(&(rings.len() as u32).to_le_bytes());
+                for ring in rings {
+                    write_wkb_positions(ring, wkb);
+                }
+            }
+            Self::MultiPolygon(polygons) => {
+                wkb.extend_from_slice(&(polygons.len() as u32).to_le_bytes());
+                for rings in polygons {
+                    Self::Polygon(rings.clone()).write_wkb(None, wkb);
+                }
+            }
+        }
+    }
+}
+
+macro_rules! well_known {
+    ($($geometry:ty),*) => {
+        $(
+            impl ToWkt for $geometry {
+                fn to_wkt(&self) -> String {
+                    let mut wkt = String::new();
+                    self.with_geometry(&mut |geometry| geometry.write_wkt(&mut wkt));
+                    wkt
+                }
+            }
+
+            impl ToWkb for $geometry {
+                fn to_wkb(&self) -> Vec<u8> {
+                    let mut wkb = Vec::new();
+                    self.with_geometry(&mut |geometry| geometry.write_wkb(None, &mut wkb));
+                    wkb
+                }
+
+                fn to_ewkb(&self) -> Vec<u8> {
+                    let mut wkb = Vec::new();
+                    self.with_geometry(&mut |geometry| geometry.write_wkb(Some(SRID), &mut wkb));
+                    wkb
+                }
+            }
+        )*
+    };
+}
+
+well_known!(Coordinates, Polyline, Polygon, BBox);
+
+/// Types that can be viewed as a [`Geometry`].
+///
+/// The geometry is passed to a callback since a [`BBox`] has to build its ring first.
+trait AsGeometry {
+    fn with_geometry(&self, f: &mut dyn FnMut(Geometry<'_>));
+}
+
+impl AsGeometry for Coordinates {
+    fn with_geometry(&self, f: &mut dyn FnMut(Geometry<'_>)) {
+        f(Geometry::Point(*self))
+    }
+}
+
+impl AsGeometry for Polyline {
+    fn with_geometry(&self, f: &mut dyn FnMut(Geometry<'_>)) {
+        f(Geometry::LineString(self.coordinates()))
+    }
+}
+
+impl AsGeometry for Polygon {
+    fn with_geometry(&self, f: &mut dyn FnMut(Geometry<'_>)) {
+        let rings = std::iter::once(self.exterior())
+            .chain(self.interiors())
+            .map(Polyline::coordinates)
+            .collect();
+
+        f(Geometry::Polygon(rings))
+    }
+}
+
+impl AsGeometry for BBox {
+    /// A [`BBox`] is a [`Polygon`] with a counterclockwise ring starting in the south west. A
+    /// [`BBox`] crossing the antimeridian is a multi polygon of its western and eastern half.
+    fn with_geometry(&self, f: &mut dyn FnMut(Geometry<'_>)) {
+        let rings: Vec<[Coordinates; 5]> = self.halves().iter().flatten().map(ring).collect();
+
+        match rings.as_slice() {
+            [ring] => f(Geometry::Polygon(vec![ring])),
+            rings => f(Geometry::MultiPolygon(
+                rings.iter().map(|ring| vec![ring.as_slice()]).collect(),
+            )),
+        }
+    }
+}
+
+/// Get the counterclockwise ring of a [`BBox`] not crossing the antimeridian.
+fn ring(bbox: &BBox) -> [Coordinates; 5] {
+    let (south_west, north_east) = (bbox.south_west(), bbox.north_east());
+
+    [
+        south_west,
+        Coordinates::new(south_west.latitude(), north_east.longitude()),
+        north_east,
+        Coordinates::new(north_east.latitude(), south_west.longitude()),
+        south_west,
+    ]
+}
+
+/// Write the parenthesized WKT rings of a polygon.
+fn write_wkt_rings(rings: &[&[Coordinates]], wkt: &mut String) {
+    wkt.push('(');
+    for (index, ring) in rings.iter().enumerate() {
+        if index > 0 {
+            wkt.push(',');
+        }
+        write_wkt_positions(ring, wkt);
+    }
+    wkt.push(')');
+}
+
+/// Write a WKT position, which is longitude first.
+fn write_wkt_position(coordinates: &Coordinates, wkt: &mut String) {
+    let _ = write!(
+        wkt,
+        "{} {}",
+        coordinates.longitude().value(),
+        coordinates.latitude().value()
+    );
+}
+
+fn write_wkt_positions(coordinates: &[Coordinates], wkt: &mut String) {
+    wkt.push('(');
+    for (index, coordinates) in coordinates.iter().enumerate() {
+        if index > 0 {
+            wkt.push(',');
+        }
+        write_wkt_position(coordinates, wkt);
+    }
+    wkt.push(')');
+}
+
+/// Write a WKB position, which is longitude first.
+fn write_wkb_position(coordinates: &Coordinates, wkb: &mut Vec<u8>) {
+    for value in [
+        coordinates.longitude().value(),
+        coordinates.latitude().value(),
+    ] {
+        write_wkb_double(value, wkb);
+    }
+}
+
+/// Write a double, widening it if [`crate::coord::CoordinateType`] is `f32`.
+fn write_wkb_double(value: impl Into<f64>, wkb: &mut Vec<u8>) {
+    wkb.extend_from_slice(&value.into().to_le_bytes());
+}
+
+fn write_wkb_positions(coordinates: &[Coordinates], wkb: &mut Vec<u8>) {
+    wkb.extend_from_slice(&(coordinates.len() as u32).to_le_bytes());
+    for coordinates in coordinates {
+        write_wkb_position(coordinates, wkb);
+    }
+}
+
+#[cfg(test)]
+mod well_known_test {
+    use crate::coord::{
+        bbox::BBox,
+        coordinates::Coordinates,
+        polygon::Polygon,
+        polyline::Polyline,
+        well_known::{ToWkb, ToWkt},
+    };
+
+    fn ring(corners: [(f64, f64); 4]) -> Polyline {
+        corners
+            .iter()
+            .chain(&corners[..1])
+            .map(|(lat, lon)| Coordinates::from_unchecked(*lat as _, *lon as _))
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    #[test]
+    fn wkt() {
+        let point = Coordinates::from_unchecked(52.5, 13.25);
+        assert_eq!(point.to_wkt(), "POINT(13.25 52.5)");
+        assert_eq!(point.to_ewkt(), "SRID=4326;POINT(13.25 52.5)");
+
+        let line = Polyline::new(vec![point, Coordinates::from_unchecked(53.0, 14.0)]);
+        assert_eq!(line.to_wkt(), "LINESTRING(13.25 52.5,14 53)");
+        assert_eq!(Polyline::default().to_wkt(), "LINESTRING EMPTY");
+
+        let polygon = Polygon::new(
+            ring([(0.0, 0.0), (0.0, 4.0), (4.0, 4.0), (4.0, 0.0)]),
+            vec![ring([(1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0)])],
+        );
+        assert_eq!(
+            polygon.to_wkt(),
+            "POLYGON((0 0,4 0,4 4,0 4,0 0),(1 1,1 2,2 2,2 1,1 1))"
+        );
+        assert_eq!(Polygon::default().to_wkt(), "POLYGON EMPTY");
+    }
+
+    #[test]
+    fn bbox() {
+        let bbox = BBox::new(
+            Coordinates::from_unchecked(1.0, 2.0),
+            Coordinates::from_unchecked(3.0, 4.0),
+        )
+        .unwrap();
+
+        assert_eq!(bbox.to_wkt(), "POLYGON((2 1,4 1,4 3,2 3,2 1))");
+        assert_eq!(bbox.to_wkb().len(), 1 + 4 + 4 + 4 + 5 * 16);
+
+        let crossing = BBox::from_wrapped(-10.0, 170.0, 10.0, -170.0);
+        assert_eq!(
+            crossing.to_wkt(),
+            "MULTIPOLYGON(((170 -10,180 -10,180 10,170 10,170 -10)),\
+             ((-180 -10,-170 -10,-170 10,-180 10,-180 -10)))"
+        );
+        let wkb = crossing.to_ewkb();
+        assert_eq!(wkb[1..5], [6, 0, 0, 0x20]);
+        assert_eq!(wkb[9..13], [2, 0, 0, 0]);
+        assert_eq!(wkb[13..18], [1, 3, 0, 0, 0]);
+        assert_eq!(wkb.len(), 13 + 2 * (1 + 4 + 4 + 4 + 5 * 16));
+    }
+
+    #[test]
+    fn wkb() {
+        let point = Coordinates::from_unchecked(2.0, 1.0);
+
+        let mut expected = vec![1, 1, 0, 0, 0];
+        expected.extend_from_slice(&1f64.to_le_bytes());
+        expected.extend_from_slice(&2f64.to_le_bytes());
+        assert_eq!(point.to_wkb(), expected);
+
+        let mut expected = vec![1, 1, 0, 0, 0x20, 0xe6, 0x10, 0, 0];
+        expected.extend_from_slice(&1f64.to_le_bytes());
+        expected.extend_from_slice(&2f64.to_le_bytes());
+        assert_eq!(point.to_ewkb(), expected);
+
+        let line = Polyline::new(vec![point, point]);
+        let wkb = line.to_wkb();
+        assert_eq!(wkb[1..5], [2, 0, 0, 0]);
+        assert_eq!(wkb[5..9], [2, 0, 0, 0]);
+        assert_eq!(wkb.len(), 9 + 2 * 16);
+    }
+}
@@ -0,0 +1,247 @@
+use std::{
+    borrow::Borrow,
+    io::{self, Write},
+};
+
+use crate::model::element::Element;
+
+/// A column of a CSV export.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Column {
+    /// The element type like `node`.
+    Type,
+    Id,
+    /// The latitude of nodes, empty for ways and relations.
+    Lat,
+    /// The longitude of nodes, empty for ways and relations.
+    Lon,
+    Version,
+    Changeset,
+    Timestamp,
+    User,
+    Uid,
+    /// The value of the tag with the given key, empty if the element does not have it.
+    Tag(String),
+}
+
+impl Column {
+    /// Get the name of the column used in the header.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Type => "@type",
+            Self::Id => "@id",
+            Self::Lat => "@lat",
+            Self::Lon => "@lon",
+            Self::Version => "@version",
+            Self::Changeset => "@changeset",
+            Self::Timestamp => "@timestamp",
+            Self::User => "@user",
+            Self::Uid => "@uid",
+            Self::Tag(key) => key,
+        }
+    }
+
+    fn value(&self, element: &Element) -> Option<String> {
+        let metadata = element.metadata();
+
+        match (self, element) {
+            (Self::Type, _) => Some(element.element_type().as_str().to_string()),
+            (Self::Id, _) => Some(element.id().value().to_string()),
+            (Self::Lat, Element::Node(node)) => node.lat.map(|lat| lat.to_string()),
+            (Self::Lon, Element::Node(node)) => node.lon.map(|lon| lon.to_string()),
+            (Self::Lat | Self::Lon, _) => None,
+            (Self::Version, _) => metadata.version.map(|version| version.to_string()),
+            (Self::Changeset, _) => metadata
+                .changeset
+                .map(|changeset| changeset.value().to_string()),
+            (Self::Timestamp, _) => metadata.timestamp.clone(),
+            (Self::User, _) => metadata.user.clone(),
+            (Self::Uid, _) => metadata.uid.map(|uid| uid.to_string()),
+            (Self::Tag(key), _) => element.tags().get(key).cloned(),
+        }
+    }
+}
+
+/// Options for [`write_csv`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    columns: Vec<Column>,
+    delimiter: char,
+    header: bool,
+}
+
+impl CsvOptions {
+    /// Construct new [`CsvOptions`] with the type, id, latitude and longitude columns, `,` as
+    /// delimiter and a header line.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace all columns.
+    pub fn with_columns(mut self, columns: impl IntoIterator<Item = Column>) -> Self {
+        self.columns = columns.into_iter().collect();
+        self
+    }
+
+    /// Add a [`Column::Tag`] for every key.
+    pub fn with_tags<S: Into<String>>(mut self, keys: impl IntoIterator<Item = S>) -> Self {
+        self.columns
+            .extend(keys.into_iter().map(|key| Column::Tag(key.into())));
+        self
+    }
+
+    /// Set the character separating the columns, e.g. `;` or `\t`.
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Set whether the first line contains the names of the columns.
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    fn write_row<'a>(
+        &self,
+        fields: impl Iterator<Item = Option<&'a str>>,
+        writer: &mut impl Write,
+    ) -> io::Result<()> {
+        let mut line = String::new();
+        for (index, field) in fields.enumerate() {
+            if index > 0 {
+                line.push(self.delimiter);
+            }
+            if let Some(field) = field {
+                self.push_field(field, &mut line);
+            }
+        }
+        line.push_str("\r\n");
+
+        writer.write_all(line.as_bytes())
+    }
+
+    /// Quote a field if it contains the delimiter, a quote or a line break.
+    ///
+    /// See <https://datatracker.ietf.org/doc/html/rfc4180#section-2>
+    fn push_field(&self, field: &str, line: &mut String) {
+        if !field.contains([self.delimiter, '"', '\r', '\n']) {
+            line.push_str(field);
+            return;
+        }
+
+        line.push('"');
+        line.push_str(&field.replace('"', "\"\""));
+        line.push('"');
+    }
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            columns: vec![Column::Type, Column::Id, Column::Lat, Column::Lon],
+            delimiter: ',',
+            header: true,
+        }
+    }
+}
+
+/// Write `elements` as CSV with one line per element.
+///
+/// Works with owned elements as well as references.
+///
+/// # Error
+///
+/// Returns an [`io::Error`] if writing fails.
+pub fn write_csv<I>(elements: I, options: &CsvOptions, mut writer: impl Write) -> io::Result<()>
+where
+    I: IntoIterator,
+    I::Item: Borrow<Element>,
+{
+    if options.header {
+        options.write_row(
+            options.columns.iter().map(|column| Some(column.name())),
+            &mut writer,
+        )?;
+    }
+
+    for element in elements {
+        let values: Vec<Option<String>> = options
+            .columns
+            .iter()
+            .map(|column| column.value(element.borrow()))
+            .collect();
+        options.write_row(values.iter().map(Option::as_deref), &mut writer)?;
+    }
+
+    Ok(())
+}
+
+/// Convert `elements` into a CSV string.
+pub fn to_csv<I>(elements: I, options: &CsvOptions) -> String
+where
+    I: IntoIterator,
+    I::Item: Borrow<Element>,
+{
+    let mut csv = Vec::new();
+    // Writing into a `Vec` never fails.
+    let _ = write_csv(elements, options, &mut csv);
+
+    String::from_utf8(csv).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod csv_test {
+    use crate::{
+        csv::{Column, CsvOptions, to_csv},
+        model::{
+            element::{Element, Metadata, Node, Way},
+            id::{NodeId, WayId},
+            tags::Tags,
+        },
+    };
+
+    fn elements() -> Vec<Element> {
+        vec![
+            Element::Node(Node {
+                id: NodeId::new(1),
+                lat: Some(50.5),
+                lon: Some(8.25),
+                tags: Tags::from([
+                    ("amenity".to_string(), "cafe".to_string()),
+                    ("name".to_string(), "Café \"Roma\", Mitte".to_string()),
+                ]),
+                metadata: Metadata {
+                    version: Some(3),
+                    ..Default::default()
+                },
+            }),
+            Element::Way(Way {
+                id: WayId::new(2),
+                tags: Tags::from([("amenity".to_string(), "parking".to_string())]),
+                ..Default::default()
+            }),
+        ]
+    }
+
+    #[test]
+    fn default_columns() {
+        assert_eq!(
+            to_csv(elements().iter(), &CsvOptions::new().with_tags(["name"])),
+            "@type,@id,@lat,@lon,name\r\n\
+             node,1,50.5,8.25,\"Café \"\"Roma\"\", Mitte\"\r\n\
+             way,2,,,\r\n"
+        );
+    }
+
+    #[test]
+    fn custom_columns() {
+        let options = CsvOptions::new()
+            .with_columns([Column::Id, Column::Version])
+            .with_tags(["amenity"])
+            .with_delimiter(';')
+            .with_header(false);
+
+        assert_eq!(to_csv(elements(), &options), "1;3;cafe\r\n2;;parking\r\n");
+    }
+}
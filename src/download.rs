@@ -0,0 +1,228 @@
+pub mod error;
+pub(crate) mod md5;
+
+#[cfg(feature = "async")]
+pub mod download_async;
+#[cfg(feature = "blocking")]
+pub mod download_blocking;
+
+use std::{
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use reqwest::header::{ETAG, HeaderMap, LAST_MODIFIED};
+
+use crate::download::{error::Error, md5::Md5};
+
+/// Url of the newest planet file in the PBF format.
+pub const PLANET_PBF_URL: &str = "https://planet.openstreetmap.org/pbf/planet-latest.osm.pbf";
+
+/// Base url of the extracts of Geofabrik.
+pub const GEOFABRIK_URL: &str = "https://download.geofabrik.de";
+
+/// Get the url of the newest Geofabrik extract of a region in the PBF format.
+///
+/// `region` is the path of the region on the download server, e.g. `europe/germany/berlin`.
+pub fn geofabrik_url(region: &str) -> String {
+    format!(
+        "{GEOFABRIK_URL}/{}-latest.osm.pbf",
+        region.trim_matches('/')
+    )
+}
+
+/// The progress of a running download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// The amount of bytes on disk, including those of a resumed earlier attempt.
+    pub downloaded: u64,
+    /// The size of the file if the server reported it.
+    pub total: Option<u64>,
+}
+
+/// Get the path a file is downloaded to before it is complete and verified.
+fn part_path(path: &Path) -> PathBuf {
+    let mut part = path.as_os_str().to_owned();
+    part.push(".part");
+
+    PathBuf::from(part)
+}
+
+/// Get the path the validator of a partial download is stored at, used to only resume it if the
+/// file on the server did not change in between.
+fn validator_path(part: &Path) -> PathBuf {
+    let mut validator = part.as_os_str().to_owned();
+    validator.push(".validator");
+
+    PathBuf::from(validator)
+}
+
+/// Get the validator stored next to a partial download, if any.
+fn read_validator(part: &Path) -> Option<String> {
+    std::fs::read_to_string(validator_path(part))
+        .ok()
+        .filter(|validator| !validator.is_empty())
+}
+
+/// Store the validator of the response a partial download is written from: a strong `ETag`, or
+/// else the `Last-Modified` date. Weak entity tags can not be sent in `If-Range`, without a
+/// validator the next attempt starts over.
+fn write_validator(part: &Path, headers: &HeaderMap) -> std::io::Result<()> {
+    let validator = headers
+        .get(ETAG)
+        .filter(|etag| !etag.as_bytes().starts_with(b"W/"))
+        .or_else(|| headers.get(LAST_MODIFIED))
+        .and_then(|validator| validator.to_str().ok());
+
+    match validator {
+        Some(validator) => std::fs::write(validator_path(part), validator),
+        None => remove_validator(part),
+    }
+}
+
+/// Remove the validator stored next to a partial download.
+fn remove_validator(part: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(validator_path(part)) {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+/// Get the size of the file at `path`, or zero if it does not exist.
+fn existing_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map_or(0, |metadata| metadata.len())
+}
+
+/// Get the digest of a checksum file like those published next to planet files, consisting of
+/// the hex digest optionally followed by the file name.
+fn parse_md5(checksum: &str) -> Result<String, Error> {
+    checksum
+        .split_whitespace()
+        .next()
+        .filter(|digest| digest.len() == 32 && digest.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(str::to_ascii_lowercase)
+        .ok_or_else(|| Error::InvalidChecksum(checksum.trim().to_string()))
+}
+
+/// Check the MD5 digest of the file at `path`, removing the file if it does not match so the
+/// next attempt starts over.
+fn verify_md5(path: &Path, expected: &str) -> Result<(), Error> {
+    let mut md5 = Md5::new();
+    let mut file = File::open(path)?;
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        md5.update(&buffer[..read]);
+    }
+
+    let actual = md5.finalize();
+    if actual != expected {
+        std::fs::remove_file(path)?;
+        return Err(Error::ChecksumMismatch((expected.to_string(), actual)));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod download_test {
+    use std::path::Path;
+
+    use crate::download::{
+        error::Error, geofabrik_url, parse_md5, part_path, validator_path, verify_md5,
+    };
+
+    #[test]
+    fn urls() {
+        assert_eq!(
+            geofabrik_url("/europe/germany/berlin/"),
+            "https://download.geofabrik.de/europe/germany/berlin-latest.osm.pbf"
+        );
+        assert_eq!(
+            part_path(Path::new("data/berlin.osm.pbf")),
+            Path::new("data/berlin.osm.pbf.part")
+        );
+    }
+
+    #[test]
+    fn checksum_file() {
+        assert_eq!(
+            parse_md5("900150983CD24FB0D6963F7D28E17F72  planet-latest.osm.pbf\n").unwrap(),
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+        assert!(matches!(
+            parse_md5("<html>Not Found</html>"),
+            Err(Error::InvalidChecksum(_))
+        ));
+    }
+
+    #[test]
+    fn verify() {
+        let path = std::env::temp_dir().join(format!("rust-osm-md5-{}", std::process::id()));
+
+        std::fs::write(&path, b"abc").unwrap();
+        verify_md5(&path, "900150983cd24fb0d6963f7d28e17f72").unwrap();
+
+        assert!(matches!(
+            verify_md5(&path, "d41d8cd98f00b204e9800998ecf8427e"),
+            Err(Error::ChecksumMismatch(_))
+        ));
+        assert!(!path.exists());
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn resume() {
+        use std::sync::Arc;
+
+        use reqwest::{
+            StatusCode,
+            header::{ETAG, HeaderMap, IF_RANGE, RANGE},
+        };
+
+        use crate::{download::download_blocking::Downloader, transport::MockTransport};
+
+        let path = std::env::temp_dir().join(format!("rust-osm-resume-{}", std::process::id()));
+        let part = part_path(&path);
+        let download = |transport: &Arc<MockTransport>| {
+            Downloader::new()
+                .with_checksum(false)
+                .with_transport(transport.clone())
+                .download("https://example.com/file", &path, |_| {})
+                .unwrap();
+            std::fs::read(&path).unwrap()
+        };
+
+        // An unchanged file is resumed.
+        std::fs::write(&part, b"abc").unwrap();
+        std::fs::write(validator_path(&part), "\"v1\"").unwrap();
+        let transport =
+            Arc::new(MockTransport::new().with_response(StatusCode::PARTIAL_CONTENT, "def"));
+        assert_eq!(download(&transport), b"abcdef");
+        let request = &transport.requests()[0];
+        assert_eq!(request.headers[RANGE], "bytes=3-");
+        assert_eq!(request.headers[IF_RANGE], "\"v1\"");
+        assert!(!validator_path(&part).exists());
+
+        // A changed file starts over.
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, "\"v2\"".parse().unwrap());
+        std::fs::write(&part, b"abc").unwrap();
+        std::fs::write(validator_path(&part), "\"v1\"").unwrap();
+        let transport =
+            Arc::new(MockTransport::new().with_response_headers(StatusCode::OK, headers, "xyz"));
+        assert_eq!(download(&transport), b"xyz");
+
+        // A part without a validator is not resumed.
+        std::fs::write(&part, b"abc").unwrap();
+        let transport = Arc::new(MockTransport::new().with_response(StatusCode::OK, "full"));
+        assert_eq!(download(&transport), b"full");
+        assert!(!transport.requests()[0].headers.contains_key(RANGE));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
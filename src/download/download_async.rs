@@ -0,0 +1,252 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use reqwest::{
+    StatusCode,
+    header::{IF_RANGE, RANGE},
+};
+
+use crate::{
+    client_config::ClientConfig,
+    download::{
+        Progress, error::Error, existing_size, parse_md5, part_path, read_validator,
+        remove_validator, verify_md5, write_validator,
+    },
+    osm_config::OsmConfig,
+    request_limit::RequestLimit,
+    retry::RetryPolicy,
+    scheduler::{ScheduledTransport, Scheduler},
+    transport::HttpTransport,
+};
+
+/// An async downloader for planet files and extracts, e.g. of [Geofabrik](super::geofabrik_url).
+///
+/// Files are downloaded next to the target path with a `.part` suffix and only moved to the
+/// target path once they are complete and verified. An interrupted download is resumed with a
+/// HTTP range request by downloading to the same path again. The range is only used if the file
+/// on the server is unchanged, identified by its `ETag` or `Last-Modified` date, otherwise the
+/// download starts over.
+#[derive(Debug, Clone)]
+pub struct Downloader {
+    inner: Arc<Inner>,
+}
+
+/// The state shared by the clones of a [`Downloader`].
+#[derive(Debug, Clone)]
+struct Inner {
+    client: reqwest::Client,
+    verify_checksum: bool,
+    retry_policy: RetryPolicy,
+    transport: Option<Arc<dyn HttpTransport>>,
+    request_limit: Option<RequestLimit>,
+    scheduler: Option<Scheduler>,
+}
+
+impl Downloader {
+    /// Construct a new [`Downloader`] verifying the checksums of the downloaded files.
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                client: reqwest::Client::new(),
+                verify_checksum: true,
+                retry_policy: RetryPolicy::default(),
+                transport: None,
+                request_limit: None,
+                scheduler: None,
+            }),
+        }
+    }
+
+    /// Set whether the MD5 checksum published as `<url>.md5` is verified after downloading.
+    pub fn with_checksum(mut self, verify_checksum: bool) -> Self {
+        Arc::make_mut(&mut self.inner).verify_checksum = verify_checksum;
+        self
+    }
+
+    /// Construct a new [`Downloader`] with the HTTP client and retry policy of an [`OsmConfig`].
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::Request`] if the HTTP client can not be built.
+    pub fn from_config(config: &OsmConfig) -> Result<Self, Error> {
+        Ok(Self::new()
+            .with_config(config.client_config())?
+            .with_retry_policy(config.retry_policy().clone()))
+    }
+
+    /// Use an HTTP client with the given [`ClientConfig`], e.g. with custom timeouts.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::Request`] if the HTTP client can not be built.
+    pub fn with_config(mut self, config: &ClientConfig) -> Result<Self, Error> {
+        Arc::make_mut(&mut self.inner).client = config.build_async()?;
+        Ok(self)
+    }
+
+    /// Set how failed requests are retried.
+    ///
+    /// Only starting a download is retried, a download interrupted while receiving the file is
+    /// resumed by downloading again.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        Arc::make_mut(&mut self.inner).retry_policy = retry_policy;
+        self
+    }
+
+    /// Send the requests with a [`HttpTransport`] instead of the [`reqwest::Client`], e.g. a
+    /// [`MockTransport`](crate::transport::MockTransport) in tests.
+    pub fn with_transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        Arc::make_mut(&mut self.inner).transport = Some(transport);
+        self
+    }
+
+    /// Limit how many requests are in flight at once. The limit is shared by all clones of this
+    /// client and can be shared with other clients.
+    pub fn with_request_limit(mut self, request_limit: RequestLimit) -> Self {
+        Arc::make_mut(&mut self.inner).request_limit = Some(request_limit);
+        self
+    }
+
+    /// Send the requests in the slots of a [`Scheduler`] shared with other clients, queued in its
+    /// `download` lane.
+    pub fn with_scheduler(mut self, scheduler: Scheduler) -> Self {
+        Arc::make_mut(&mut self.inner).scheduler = Some(scheduler);
+        self
+    }
+
+    /// Download `url` to `path`, calling `progress` after every received chunk.
+    ///
+    /// Returns the size of the downloaded file.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error`] if the download fails or the checksum does not match. A partial
+    /// download is kept to be resumed, a download with a wrong checksum is removed.
+    pub async fn download(
+        &self,
+        url: &str,
+        path: impl AsRef<Path>,
+        mut progress: impl FnMut(Progress),
+    ) -> Result<u64, Error> {
+        let path = path.as_ref();
+        let part = part_path(path);
+        // Without a validator a changed file could not be told apart, so the part is discarded.
+        let validator = read_validator(&part);
+        let offset = validator.as_ref().map_or(0, |_| existing_size(&part));
+
+        let mut request = self.inner.client.get(url);
+        if let Some(validator) = validator.filter(|_| offset > 0) {
+            request = request
+                .header(RANGE, format!("bytes={offset}-"))
+                .header(IF_RANGE, validator);
+        }
+        let mut response = self.send(request).await?;
+
+        let status = response.status();
+        // The part is already complete if nothing is left after it.
+        if status != StatusCode::RANGE_NOT_SATISFIABLE || offset == 0 {
+            // A full response means the file changed since the part was written.
+            let append = match status {
+                StatusCode::PARTIAL_CONTENT => true,
+                StatusCode::OK => {
+                    write_validator(&part, response.headers())?;
+                    false
+                }
+                _ => return Err(Error::HttpStatus((status, response.text().await?))),
+            };
+            let mut downloaded = if append { offset } else { 0 };
+            let total = response.content_length().map(|length| length + downloaded);
+            let mut file = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .append(append)
+                .truncate(!append)
+                .open(&part)?;
+
+            while let Some(chunk) = response.chunk().await? {
+                file.write_all(&chunk)?;
+                downloaded += chunk.len() as u64;
+                progress(Progress { downloaded, total });
+            }
+            file.flush()?;
+        }
+
+        if self.inner.verify_checksum {
+            let expected = self.get_checksum(url).await?;
+            verify_md5(&part, &expected)?;
+        }
+        std::fs::rename(&part, path)?;
+        remove_validator(&part)?;
+
+        Ok(existing_size(path))
+    }
+
+    /// Download `url` into a directory, naming the file after the last segment of the url.
+    ///
+    /// Returns the path of the downloaded file.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error`] if the download fails or the checksum does not match.
+    pub async fn download_to_dir(
+        &self,
+        url: &str,
+        directory: impl AsRef<Path>,
+        progress: impl FnMut(Progress),
+    ) -> Result<PathBuf, Error> {
+        let name = url
+            .rsplit('/')
+            .next()
+            .filter(|name| !name.is_empty())
+            .unwrap_or("download");
+        let path = directory.as_ref().join(name);
+        self.download(url, &path, progress).await?;
+
+        Ok(path)
+    }
+
+    async fn get_checksum(&self, url: &str) -> Result<String, Error> {
+        let response = self
+            .send(self.inner.client.get(format!("{url}.md5")))
+            .await?;
+        let status = response.status();
+
+        if status.is_success() {
+            parse_md5(&response.text().await?)
+        } else {
+            Err(Error::HttpStatus((status, response.text().await?)))
+        }
+    }
+
+    /// Send a request with retries, leaving the status to the caller.
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, Error> {
+        let _permit = match self.inner.request_limit {
+            Some(ref request_limit) => Some(request_limit.acquire().await),
+            None => None,
+        };
+        let (client, request) = request.build_split();
+
+        self.inner
+            .retry_policy
+            .execute(
+                &ScheduledTransport::new(
+                    self.inner.transport.as_deref().unwrap_or(&client),
+                    self.inner.scheduler.as_ref(),
+                    "download",
+                ),
+                request?,
+                |_| Ok::<_, Error>(()),
+            )
+            .await
+    }
+}
+
+impl Default for Downloader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
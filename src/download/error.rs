@@ -0,0 +1,13 @@
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("The request could not be sent: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("The server responded with {}: {}", .0.0, .0.1)]
+    HttpStatus((reqwest::StatusCode, String)),
+    #[error("The file could not be written: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("The checksum file is invalid: {0}")]
+    InvalidChecksum(String),
+    #[error("The checksum {} does not match the expected {}", .0.1, .0.0)]
+    ChecksumMismatch((String, String)),
+}
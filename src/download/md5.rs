@@ -0,0 +1,134 @@
+/// An incremental MD5 hasher for verifying the checksums published next to planet files and
+/// extracts.
+///
+/// MD5 is not collision resistant, it only detects corrupted downloads.
+///
+/// See <https://datatracker.ietf.org/doc/html/rfc1321>
+#[derive(Debug, Clone)]
+pub(crate) struct Md5 {
+    state: [u32; 4],
+    buffer: Vec<u8>,
+    length: u64,
+}
+
+/// The amount of bits each word is rotated by in each round.
+const SHIFTS: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9,
+    14, 20, 5, 9, 14, 20, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 6, 10, 15,
+    21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+
+impl Md5 {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476],
+            buffer: Vec::with_capacity(64),
+            length: 0,
+        }
+    }
+
+    pub(crate) fn update(&mut self, mut data: &[u8]) {
+        self.length = self.length.wrapping_add(data.len() as u64);
+
+        if !self.buffer.is_empty() {
+            let take = (64 - self.buffer.len()).min(data.len());
+            self.buffer.extend_from_slice(&data[..take]);
+            data = &data[take..];
+            if self.buffer.len() < 64 {
+                return;
+            }
+            let block = std::mem::take(&mut self.buffer);
+            self.process(&block);
+        }
+
+        let mut blocks = data.chunks_exact(64);
+        for block in blocks.by_ref() {
+            self.process(block);
+        }
+        self.buffer.extend_from_slice(blocks.remainder());
+    }
+
+    /// Get the digest as lowercase hex string.
+    pub(crate) fn finalize(mut self) -> String {
+        let bits = self.length.wrapping_mul(8);
+        let mut padding = vec![0x80];
+        padding.resize((55usize.wrapping_sub(self.buffer.len()) % 64) + 1, 0);
+        padding.extend_from_slice(&bits.to_le_bytes());
+        // The length is already final.
+        let length = self.length;
+        self.update(&padding);
+        self.length = length;
+
+        self.state
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    fn process(&mut self, block: &[u8]) {
+        let words: Vec<u32> = block
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+            .collect();
+        let [mut a, mut b, mut c, mut d] = self.state;
+
+        for (i, shift) in SHIFTS.into_iter().enumerate() {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let constant = ((i as f64 + 1.0).sin().abs() * 4_294_967_296.0) as u32;
+            let rotated = a
+                .wrapping_add(f)
+                .wrapping_add(constant)
+                .wrapping_add(words[g])
+                .rotate_left(shift);
+
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(rotated);
+        }
+
+        for (state, value) in self.state.iter_mut().zip([a, b, c, d]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod md5_test {
+    use crate::download::md5::Md5;
+
+    fn digest(chunks: &[&[u8]]) -> String {
+        let mut md5 = Md5::new();
+        for chunk in chunks {
+            md5.update(chunk);
+        }
+
+        md5.finalize()
+    }
+
+    #[test]
+    fn digests() {
+        assert_eq!(digest(&[]), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(digest(&[b"abc"]), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(
+            digest(&[b"The quick brown fox ", b"jumps over the lazy dog"]),
+            "9e107d9d372bb6826bd81d3542a419d6"
+        );
+        assert_eq!(
+            digest(&[&[b'a'; 100], &[b'a'; 27]]),
+            digest(&[&[b'a'; 127]])
+        );
+        assert_eq!(
+            digest(&[
+                b"12345678901234567890123456789012345678901234567890123456789012345678901234567890"
+            ]),
+            "57edf4a22be3c955ac49da2e2107b67a"
+        );
+    }
+}
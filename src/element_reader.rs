@@ -0,0 +1,45 @@
+use crate::model::element::Element;
+
+/// A reader that yields the [`Element`]s of a file or response one at a time.
+///
+/// Implemented by [`crate::osm_xml::OsmXmlReader`] and, with the `pbf` feature,
+/// [`crate::pbf::PbfReader`], so processing that is generic over [`ElementReader`] runs on every
+/// input format.
+/// Elements are read lazily and no further elements are yielded after the first error.
+pub trait ElementReader: Iterator<Item = Result<Element, <Self as ElementReader>::Error>> {
+    /// The error returned if the input can not be read or is malformed.
+    type Error: std::error::Error + Send + Sync + 'static;
+}
+
+#[cfg(test)]
+mod element_reader_test {
+    use crate::{
+        element_reader::ElementReader, model::element::ElementType, osm_xml::OsmXmlReader,
+    };
+
+    fn count_tagged<R: ElementReader>(reader: R) -> Result<Vec<(ElementType, usize)>, R::Error> {
+        let mut counts = Vec::new();
+        for element in reader {
+            let element = element?;
+            if !element.tags().is_empty() {
+                counts.push((element.element_type(), element.tags().len()));
+            }
+        }
+
+        Ok(counts)
+    }
+
+    #[test]
+    fn generic_pipeline() {
+        let document = r#"<osm>
+            <node id="1" lat="1" lon="2"/>
+            <way id="2"><nd ref="1"/><tag k="highway" v="path"/></way>
+        </osm>"#;
+
+        assert_eq!(
+            count_tagged(OsmXmlReader::new(document.as_bytes())).unwrap(),
+            vec![(ElementType::Way, 1)]
+        );
+        assert!(count_tagged(OsmXmlReader::new(&b"<osm><way id=\"x\"/></osm>"[..])).is_err());
+    }
+}
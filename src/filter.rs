@@ -0,0 +1,338 @@
+pub mod error;
+pub(crate) mod regex;
+
+use std::{borrow::Borrow, str::FromStr};
+
+use crate::{
+    filter::{error::Error, regex::Regex},
+    model::{element::Element, tags::Tags},
+};
+
+/// A tag filter expression like `amenity=cafe and cuisine~"ital"`.
+///
+/// The same filter can be applied to elements from any source, e.g. Overpass results, the OSM
+/// API or local files.
+///
+/// The syntax follows the tag filters of Overpass:
+/// - `key` matches elements having the tag, `not key` elements without it
+/// - `key=value` and `key!=value` compare the value
+/// - `key~regex` and `key!~regex` match the value against a regular expression
+/// - conditions are combined with `and`, `or`, `not` and parentheses, `and` binding stronger
+///   than `or`
+///
+/// Keys and values containing other characters than letters, digits, `_`, `:`, `.` and `-` have
+/// to be quoted with `"`, which also allows escaping with `\`. As in Overpass, `!=` and `!~` also
+/// match elements without the key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    expression: Expression,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expression {
+    Has(String),
+    Equals((String, String)),
+    NotEquals((String, String)),
+    Matches((String, Regex)),
+    NotMatches((String, Regex)),
+    And(Box<(Expression, Expression)>),
+    Or(Box<(Expression, Expression)>),
+    Not(Box<Expression>),
+}
+
+impl Filter {
+    /// Parse a filter expression.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error`] if the expression or a regular expression in it is invalid.
+    pub fn parse(expression: &str) -> Result<Self, Error> {
+        let mut parser = Parser {
+            input: expression,
+            position: 0,
+        };
+        let expression = parser.or()?;
+        parser.skip_whitespace();
+        if !parser.rest().is_empty() {
+            return Err(parser.error("unexpected input"));
+        }
+
+        Ok(Self { expression })
+    }
+
+    /// Check if [`Tags`] match this filter.
+    pub fn matches_tags(&self, tags: &Tags) -> bool {
+        self.expression.matches(tags)
+    }
+
+    /// Check if the tags of an [`Element`] match this filter.
+    pub fn matches(&self, element: &Element) -> bool {
+        self.matches_tags(element.tags())
+    }
+
+    /// Keep only the elements matching this filter.
+    ///
+    /// Works with owned elements as well as references.
+    pub fn apply<'a, I>(&'a self, elements: I) -> impl Iterator<Item = I::Item> + 'a
+    where
+        I: IntoIterator + 'a,
+        I::Item: Borrow<Element>,
+    {
+        elements
+            .into_iter()
+            .filter(move |element| self.matches(element.borrow()))
+    }
+}
+
+impl FromStr for Filter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Expression {
+    fn matches(&self, tags: &Tags) -> bool {
+        match self {
+            Self::Has(key) => tags.contains_key(key),
+            Self::Equals((key, value)) => tags.get(key) == Some(value),
+            Self::NotEquals((key, value)) => tags.get(key) != Some(value),
+            Self::Matches((key, regex)) => tags.get(key).is_some_and(|value| regex.is_match(value)),
+            Self::NotMatches((key, regex)) => {
+                !tags.get(key).is_some_and(|value| regex.is_match(value))
+            }
+            Self::And(operands) => operands.0.matches(tags) && operands.1.matches(tags),
+            Self::Or(operands) => operands.0.matches(tags) || operands.1.matches(tags),
+            Self::Not(operand) => !operand.matches(tags),
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn or(&mut self) -> Result<Expression, Error> {
+        let mut expression = self.and()?;
+        while self.keyword("or") {
+            expression = Expression::Or(Box::new((expression, self.and()?)));
+        }
+
+        Ok(expression)
+    }
+
+    fn and(&mut self) -> Result<Expression, Error> {
+        let mut expression = self.unary()?;
+        while self.keyword("and") {
+            expression = Expression::And(Box::new((expression, self.unary()?)));
+        }
+
+        Ok(expression)
+    }
+
+    fn unary(&mut self) -> Result<Expression, Error> {
+        if self.keyword("not") {
+            return Ok(Expression::Not(Box::new(self.unary()?)));
+        }
+
+        self.skip_whitespace();
+        if self.eat("(") {
+            let expression = self.or()?;
+            self.skip_whitespace();
+            if !self.eat(")") {
+                return Err(self.error("expected `)`"));
+            }
+            return Ok(expression);
+        }
+
+        self.condition()
+    }
+
+    fn condition(&mut self) -> Result<Expression, Error> {
+        let key = self.string()?;
+
+        self.skip_whitespace();
+        if self.eat("!=") {
+            Ok(Expression::NotEquals((key, self.string()?)))
+        } else if self.eat("!~") {
+            Ok(Expression::NotMatches((key, self.regex()?)))
+        } else if self.eat("=") {
+            Ok(Expression::Equals((key, self.string()?)))
+        } else if self.eat("~") {
+            Ok(Expression::Matches((key, self.regex()?)))
+        } else {
+            Ok(Expression::Has(key))
+        }
+    }
+
+    fn regex(&mut self) -> Result<Regex, Error> {
+        let pattern = self.string()?;
+
+        Regex::new(&pattern).map_err(|message| Error::Regex((pattern, message)))
+    }
+
+    /// Parse a bare word or a quoted string.
+    fn string(&mut self) -> Result<String, Error> {
+        self.skip_whitespace();
+        if !self.eat("\"") {
+            let word = self.word();
+            if word.is_empty() {
+                return Err(self.error("expected a key or value"));
+            }
+            self.position += word.len();
+            return Ok(word.to_string());
+        }
+
+        let mut string = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.position += index + 1;
+                    return Ok(string);
+                }
+                '\\' => match chars.next() {
+                    // Keep escapes of the regular expression syntax.
+                    Some((_, c @ ('"' | '\\'))) => string.push(c),
+                    Some((_, c)) => {
+                        string.push('\\');
+                        string.push(c);
+                    }
+                    None => break,
+                },
+                c => string.push(c),
+            }
+        }
+
+        Err(self.error("unterminated string"))
+    }
+
+    /// Consume a keyword if it is the next word.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        self.skip_whitespace();
+        if self.word() == keyword {
+            self.position += keyword.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn word(&self) -> &'a str {
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | ':' | '.' | '-')))
+            .unwrap_or(rest.len());
+
+        &rest[..end]
+    }
+
+    fn eat(&mut self, pattern: &str) -> bool {
+        if self.rest().starts_with(pattern) {
+            self.position += pattern.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn error(&self, message: &'static str) -> Error {
+        Error::Syntax((self.position, message))
+    }
+}
+
+#[cfg(test)]
+mod filter_test {
+    use crate::{
+        filter::{Filter, error::Error},
+        model::{
+            element::{Element, Node},
+            id::NodeId,
+            tags::Tags,
+        },
+    };
+
+    fn tags(pairs: &[(&str, &str)]) -> Tags {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn node(id: i64, pairs: &[(&str, &str)]) -> Element {
+        Element::Node(Node {
+            id: NodeId::new(id),
+            tags: tags(pairs),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn conditions() {
+        let cafe = tags(&[("amenity", "cafe"), ("cuisine", "italian;coffee_shop")]);
+        let matches = |filter: &str| Filter::parse(filter).unwrap().matches_tags(&cafe);
+
+        assert!(matches("amenity"));
+        assert!(!matches("shop"));
+        assert!(matches("not shop"));
+        assert!(matches("amenity=cafe"));
+        assert!(matches("amenity = \"cafe\""));
+        assert!(!matches("amenity!=cafe"));
+        assert!(matches("shop!=bakery"));
+        assert!(matches(r#"cuisine~"ital""#));
+        assert!(matches(r#"cuisine~"^(italian|greek)(;|$)""#));
+        assert!(!matches("cuisine!~ital"));
+        assert!(matches("shop!~bakery"));
+    }
+
+    #[test]
+    fn combinations() {
+        let cafe = tags(&[("amenity", "cafe"), ("name", "Café \"Roma\"")]);
+        let matches = |filter: &str| Filter::parse(filter).unwrap().matches_tags(&cafe);
+
+        assert!(matches(
+            r#"amenity=cafe and cuisine~"ital" or name="Café \"Roma\"""#
+        ));
+        assert!(!matches(r#"amenity=cafe and (cuisine~"ital" or shop)"#));
+        assert!(matches("not (shop or amenity=bar) and amenity"));
+        assert!(matches("addr:street or amenity=cafe"));
+    }
+
+    #[test]
+    fn apply() {
+        let elements = vec![
+            node(1, &[("amenity", "cafe")]),
+            node(2, &[("amenity", "bar")]),
+            node(3, &[]),
+        ];
+        let filter: Filter = "amenity=cafe or not amenity".parse().unwrap();
+
+        let ids: Vec<_> = filter.apply(&elements).map(Element::id).collect();
+        assert_eq!(ids, vec![NodeId::new(1).into(), NodeId::new(3).into()]);
+        assert_eq!(filter.apply(elements).count(), 2);
+        assert!(filter.matches_tags(&Tags::new()));
+    }
+
+    #[test]
+    fn invalid() {
+        for filter in ["", "amenity=", "(amenity", "amenity=cafe)", "\"amenity"] {
+            assert!(
+                matches!(Filter::parse(filter), Err(Error::Syntax(_))),
+                "{filter}"
+            );
+        }
+        assert!(matches!(Filter::parse("name~\"(a\""), Err(Error::Regex(_))));
+    }
+}
@@ -0,0 +1,7 @@
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid filter at byte {}: {}", .0.0, .0.1)]
+    Syntax((usize, &'static str)),
+    #[error("Invalid regular expression `{}`: {}", .0.0, .0.1)]
+    Regex((String, &'static str)),
+}
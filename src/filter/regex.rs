@@ -0,0 +1,409 @@
+/// The maximum number of instructions of a compiled pattern, which limits nested repetitions.
+const MAX_PROGRAM_SIZE: usize = 10_000;
+
+/// A small regular expression engine for the `~` operator of
+/// [`Filter`](crate::filter::Filter)s.
+///
+/// Supports literals, `.`, character classes like `[a-z]` and `[^0-9]`, the escapes `\d`, `\w`
+/// and `\s`, the anchors `^` and `$`, groups, alternation with `|` and the quantifiers `*`, `+`,
+/// `?`, `{n}`, `{n,}` and `{n,m}`. Like Overpass, a match may start anywhere in the text.
+///
+/// Patterns are compiled to a program that is run on all possible paths at once instead of
+/// backtracking, so matching takes linear time in the length of the text even for patterns like
+/// `^(a*)*b$`.
+///
+/// See <https://swtch.com/~rsc/regexp/regexp1.html>
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Regex {
+    program: Vec<Instruction>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Char(char),
+    Any,
+    /// Inclusive character ranges and whether the class is negated.
+    Class((Vec<(char, char)>, bool)),
+    Start,
+    End,
+    Group(Vec<Vec<Node>>),
+    Repeat((Box<Node>, u32, Option<u32>)),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Instruction {
+    Char(char),
+    Any,
+    Class((Vec<(char, char)>, bool)),
+    Start,
+    End,
+    /// Continue at both targets.
+    Split((usize, usize)),
+    Jump(usize),
+    Match,
+}
+
+impl Regex {
+    /// Compile a regular expression.
+    ///
+    /// Returns a message describing the problem if the pattern is invalid.
+    pub(crate) fn new(pattern: &str) -> Result<Self, &'static str> {
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut position = 0;
+        let alternatives = parse_alternatives(&chars, &mut position)?;
+        if position < chars.len() {
+            return Err("unmatched `)`");
+        }
+
+        let mut program = Vec::new();
+        compile_alternatives(&alternatives, &mut program)?;
+        program.push(Instruction::Match);
+
+        Ok(Self { program })
+    }
+
+    /// Check if the regular expression matches anywhere in `text`.
+    pub(crate) fn is_match(&self, text: &str) -> bool {
+        let text: Vec<char> = text.chars().collect();
+        // The position each instruction was last added at, so every instruction runs at most
+        // once per position.
+        let mut added = vec![usize::MAX; self.program.len()];
+        let mut threads = Vec::new();
+
+        for position in 0..=text.len() {
+            // A match may start at every position.
+            if self.add_thread(&mut threads, &mut added, 0, &text, position) {
+                return true;
+            }
+            let Some(c) = text.get(position) else {
+                break;
+            };
+
+            let mut next = Vec::new();
+            for pc in threads.drain(..) {
+                let matched = match &self.program[pc] {
+                    Instruction::Char(expected) => expected == c,
+                    Instruction::Any => true,
+                    Instruction::Class((ranges, negated)) => {
+                        ranges.iter().any(|(start, end)| (start..=end).contains(&c)) != *negated
+                    }
+                    _ => false,
+                };
+                if matched && self.add_thread(&mut next, &mut added, pc + 1, &text, position + 1) {
+                    return true;
+                }
+            }
+            threads = next;
+        }
+
+        false
+    }
+
+    /// Follow the instructions that consume no character from `pc` and add the ones that do to
+    /// `threads`.
+    ///
+    /// Returns `true` if a match was reached.
+    fn add_thread(
+        &self,
+        threads: &mut Vec<usize>,
+        added: &mut [usize],
+        pc: usize,
+        text: &[char],
+        position: usize,
+    ) -> bool {
+        let mut stack = vec![pc];
+        while let Some(pc) = stack.pop() {
+            if added[pc] == position {
+                continue;
+            }
+            added[pc] = position;
+
+            match self.program[pc] {
+                Instruction::Match => return true,
+                Instruction::Jump(target) => stack.push(target),
+                Instruction::Split((first, second)) => stack.extend([second, first]),
+                Instruction::Start if position == 0 => stack.push(pc + 1),
+                Instruction::End if position == text.len() => stack.push(pc + 1),
+                Instruction::Start | Instruction::End => (),
+                _ => threads.push(pc),
+            }
+        }
+
+        false
+    }
+}
+
+fn compile_alternatives(
+    alternatives: &[Vec<Node>],
+    program: &mut Vec<Instruction>,
+) -> Result<(), &'static str> {
+    let mut jumps = Vec::new();
+    for (i, nodes) in alternatives.iter().enumerate() {
+        let split = program.len();
+        if i + 1 < alternatives.len() {
+            program.push(Instruction::Split((split + 1, 0)));
+        }
+        for node in nodes {
+            compile(node, program)?;
+        }
+        if i + 1 < alternatives.len() {
+            jumps.push(program.len());
+            program.push(Instruction::Jump(0));
+            program[split] = Instruction::Split((split + 1, program.len()));
+        }
+    }
+
+    let end = program.len();
+    for jump in jumps {
+        program[jump] = Instruction::Jump(end);
+    }
+
+    Ok(())
+}
+
+fn compile(node: &Node, program: &mut Vec<Instruction>) -> Result<(), &'static str> {
+    if program.len() > MAX_PROGRAM_SIZE {
+        return Err("repetition is too large");
+    }
+
+    match node {
+        Node::Char(c) => program.push(Instruction::Char(*c)),
+        Node::Any => program.push(Instruction::Any),
+        Node::Class(class) => program.push(Instruction::Class(class.clone())),
+        Node::Start => program.push(Instruction::Start),
+        Node::End => program.push(Instruction::End),
+        Node::Group(alternatives) => compile_alternatives(alternatives, program)?,
+        Node::Repeat((node, min, max)) => {
+            for _ in 0..*min {
+                compile(node, program)?;
+            }
+
+            match max {
+                None => {
+                    let split = program.len();
+                    program.push(Instruction::Split((split + 1, 0)));
+                    compile(node, program)?;
+                    program.push(Instruction::Jump(split));
+                    program[split] = Instruction::Split((split + 1, program.len()));
+                }
+                Some(max) => {
+                    let mut splits = Vec::new();
+                    for _ in *min..*max {
+                        splits.push(program.len());
+                        program.push(Instruction::Split((program.len() + 1, 0)));
+                        compile(node, program)?;
+                    }
+                    let end = program.len();
+                    for split in splits {
+                        program[split] = Instruction::Split((split + 1, end));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_alternatives(
+    chars: &[char],
+    position: &mut usize,
+) -> Result<Vec<Vec<Node>>, &'static str> {
+    let mut alternatives = vec![Vec::new()];
+
+    while let Some(&c) = chars.get(*position) {
+        *position += 1;
+        let node = match c {
+            ')' => {
+                *position -= 1;
+                break;
+            }
+            '|' => {
+                alternatives.push(Vec::new());
+                continue;
+            }
+            '(' => {
+                // Non-capturing groups behave the same since nothing is captured.
+                if chars[*position..].starts_with(&['?', ':']) {
+                    *position += 2;
+                }
+                let group = parse_alternatives(chars, position)?;
+                if chars.get(*position) != Some(&')') {
+                    return Err("unclosed `(`");
+                }
+                *position += 1;
+                Node::Group(group)
+            }
+            '[' => parse_class(chars, position)?,
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '\\' => parse_escape(chars, position)?,
+            '*' | '+' | '?' | '{' => return Err("quantifier without a preceding atom"),
+            c => Node::Char(c),
+        };
+
+        let node = parse_quantifier(chars, position, node)?;
+        if let Some(sequence) = alternatives.last_mut() {
+            sequence.push(node);
+        }
+    }
+
+    Ok(alternatives)
+}
+
+fn parse_quantifier(
+    chars: &[char],
+    position: &mut usize,
+    node: Node,
+) -> Result<Node, &'static str> {
+    let (min, max) = match chars.get(*position) {
+        Some('*') => (0, None),
+        Some('+') => (1, None),
+        Some('?') => (0, Some(1)),
+        Some('{') => {
+            let end = chars[*position..]
+                .iter()
+                .position(|c| *c == '}')
+                .ok_or("unclosed `{`")?;
+            let bounds: String = chars[*position + 1..*position + end].iter().collect();
+            *position += end;
+            let parse = |bound: &str| {
+                bound
+                    .trim()
+                    .parse::<u32>()
+                    .map_err(|_| "invalid repetition")
+            };
+
+            match bounds.split_once(',') {
+                None => (parse(&bounds)?, Some(parse(&bounds)?)),
+                Some((min, "")) => (parse(min)?, None),
+                Some((min, max)) => (parse(min)?, Some(parse(max)?)),
+            }
+        }
+        _ => return Ok(node),
+    };
+    *position += 1;
+
+    if matches!(node, Node::Start | Node::End) {
+        return Err("quantifier after an anchor");
+    }
+    if max.is_some_and(|max| max < min) {
+        return Err("invalid repetition");
+    }
+
+    Ok(Node::Repeat((Box::new(node), min, max)))
+}
+
+fn parse_class(chars: &[char], position: &mut usize) -> Result<Node, &'static str> {
+    let negated = chars.get(*position) == Some(&'^');
+    if negated {
+        *position += 1;
+    }
+
+    let mut ranges = Vec::new();
+    let mut first = true;
+    loop {
+        let c = *chars.get(*position).ok_or("unclosed `[`")?;
+        *position += 1;
+
+        // A `]` directly after the opening bracket is a literal.
+        if c == ']' && !first {
+            return Ok(Node::Class((ranges, negated)));
+        }
+        first = false;
+
+        let start = if c == '\\' {
+            match parse_escape(chars, position)? {
+                Node::Char(c) => c,
+                Node::Class((class, false)) => {
+                    ranges.extend(class);
+                    continue;
+                }
+                _ => return Err("invalid escape in a character class"),
+            }
+        } else {
+            c
+        };
+
+        if chars.get(*position) == Some(&'-') && chars.get(*position + 1).is_some_and(|c| *c != ']')
+        {
+            let end = chars[*position + 1];
+            *position += 2;
+            if end < start {
+                return Err("invalid character range");
+            }
+            ranges.push((start, end));
+        } else {
+            ranges.push((start, start));
+        }
+    }
+}
+
+fn parse_escape(chars: &[char], position: &mut usize) -> Result<Node, &'static str> {
+    let c = *chars.get(*position).ok_or("trailing `\\`")?;
+    *position += 1;
+
+    Ok(match c {
+        'd' => Node::Class((vec![('0', '9')], false)),
+        'D' => Node::Class((vec![('0', '9')], true)),
+        'w' => Node::Class((WORD.to_vec(), false)),
+        'W' => Node::Class((WORD.to_vec(), true)),
+        's' => Node::Class((SPACE.to_vec(), false)),
+        'S' => Node::Class((SPACE.to_vec(), true)),
+        'n' => Node::Char('\n'),
+        't' => Node::Char('\t'),
+        c => Node::Char(c),
+    })
+}
+
+const WORD: [(char, char); 4] = [('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')];
+const SPACE: [(char, char); 4] = [(' ', ' '), ('\t', '\t'), ('\n', '\n'), ('\r', '\r')];
+
+#[cfg(test)]
+mod regex_test {
+    use crate::filter::regex::Regex;
+
+    fn is_match(pattern: &str, text: &str) -> bool {
+        Regex::new(pattern).unwrap().is_match(text)
+    }
+
+    #[test]
+    fn matching() {
+        assert!(is_match("ital", "italian;pizza"));
+        assert!(!is_match("^ital", "pizza;italian"));
+        assert!(is_match("^(pizza|kebab)$", "kebab"));
+        assert!(!is_match("^(pizza|kebab)$", "kebabs"));
+        assert!(is_match("^[A-Z][a-z]+ ?\\d{2,3}$", "Bus 42"));
+        assert!(!is_match("^[A-Z][a-z]+ ?\\d{2,3}$", "Bus 4"));
+        assert!(is_match("^a.*b$", "a-x-b"));
+        assert!(is_match("^[^0-9]*$", "none"));
+        assert!(is_match("colou?r", "color"));
+        assert!(is_match("^$", ""));
+        assert!(is_match("^(a*)*$", "aaa"));
+        assert!(is_match("^(ab){2}c?$", "abab"));
+        assert!(is_match("^a{1,3}$", "aaa"));
+        assert!(!is_match("^a{1,3}$", "aaaa"));
+        assert!(is_match("x|^y", "ay x"));
+    }
+
+    #[test]
+    fn pathological() {
+        let regex = Regex::new("^(a*)*b$").unwrap();
+        let text = format!("{}c", "a".repeat(255));
+
+        let start = std::time::Instant::now();
+        assert!(!regex.is_match(&text));
+        assert!(regex.is_match(&format!("{}b", "a".repeat(255))));
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+
+        assert!(Regex::new("((a{100}){100}){100}").is_err());
+    }
+
+    #[test]
+    fn invalid() {
+        for pattern in ["(a", "a)", "[a", "*a", "a{2", "a{3,1}", "[z-a]", "\\"] {
+            assert!(Regex::new(pattern).is_err(), "{pattern}");
+        }
+    }
+}
@@ -0,0 +1,526 @@
+pub mod error;
+
+use std::collections::{HashMap, HashSet};
+
+use serde_json::{Map, Value, json};
+
+use crate::{
+    coord::{CoordinateType, bbox::BBox, coordinates::Coordinates},
+    geojson::error::Error,
+    model::{
+        element::{Element, Metadata, Node, Way},
+        id::NodeId,
+        tags::Tags,
+    },
+};
+
+/// How the `id` member of a feature is built.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum IdScheme {
+    /// The element type and id separated by a slash like `way/3`, as used by osmtogeojson.
+    #[default]
+    Prefixed,
+    /// The first letter of the element type followed by the id like `w3`, as used by osmium.
+    Short,
+    /// Features have no `id` member.
+    None,
+}
+
+/// How the tags of an element are mapped to the properties of a feature.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum TagMapping {
+    /// Every tag becomes a property.
+    #[default]
+    Flat,
+    /// All tags are put into an object under the `tags` property.
+    Nested,
+    /// Only the tags with the given keys are exported, as properties named by the value.
+    Select(HashMap<String, String>),
+}
+
+/// Options for [`feature_collection`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GeoJsonOptions {
+    id_scheme: IdScheme,
+    tag_mapping: TagMapping,
+    metadata: bool,
+}
+
+impl GeoJsonOptions {
+    /// Construct new [`GeoJsonOptions`] with prefixed ids, flat tags and without metadata.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the [`IdScheme`].
+    pub fn with_id_scheme(mut self, id_scheme: IdScheme) -> Self {
+        self.id_scheme = id_scheme;
+        self
+    }
+
+    /// Set the [`TagMapping`].
+    pub fn with_tag_mapping(mut self, tag_mapping: TagMapping) -> Self {
+        self.tag_mapping = tag_mapping;
+        self
+    }
+
+    /// Add the [`Metadata`] of each element as `@version`, `@changeset`, `@timestamp`, `@user`
+    /// and `@uid` properties.
+    pub fn with_metadata(mut self, metadata: bool) -> Self {
+        self.metadata = metadata;
+        self
+    }
+}
+
+/// Convert `elements` into a GeoJSON `FeatureCollection`.
+///
+/// The geometries of ways are resolved from the nodes in `elements`.
+/// Nodes become `Point`s, except untagged nodes that are part of a way.
+/// Ways become `Polygon`s if they are closed and tagged like an area and `LineString`s otherwise.
+/// Elements whose geometry can not be resolved, e.g. ways with missing nodes and relations, are
+/// skipped.
+///
+/// See <https://datatracker.ietf.org/doc/html/rfc7946>
+pub fn feature_collection(elements: &[Element], options: &GeoJsonOptions) -> Value {
+    let mut nodes = HashMap::new();
+    let mut way_nodes = HashSet::new();
+    for element in elements {
+        match element {
+            Element::Node(node) => {
+                nodes.insert(node.id, node);
+            }
+            Element::Way(way) => way_nodes.extend(way.nodes.iter().copied()),
+            Element::Relation(_) => (),
+        }
+    }
+
+    let features: Vec<Value> = elements
+        .iter()
+        .filter_map(|element| {
+            let geometry = match element {
+                Element::Node(node) if node.tags.is_empty() && way_nodes.contains(&node.id) => None,
+                Element::Node(node) => node_geometry(node),
+                Element::Way(way) => way_geometry(way, &nodes),
+                Element::Relation(_) => None,
+            }?;
+
+            Some(feature(element, geometry, options))
+        })
+        .collect();
+
+    json!({
+        "type": "FeatureCollection",
+        "features": features,
+    })
+}
+
+fn feature(element: &Element, geometry: Value, options: &GeoJsonOptions) -> Value {
+    let mut properties = tag_properties(element.tags(), &options.tag_mapping);
+    if options.metadata {
+        metadata_properties(element.metadata(), &mut properties);
+    }
+
+    let mut feature = Map::new();
+    feature.insert("type".to_string(), json!("Feature"));
+    let id = element.id();
+    let element_type = id.element_type().as_str();
+    let id = match options.id_scheme {
+        IdScheme::Prefixed => Some(format!("{element_type}/{}", id.value())),
+        IdScheme::Short => Some(format!("{}{}", &element_type[..1], id.value())),
+        IdScheme::None => None,
+    };
+    if let Some(id) = id {
+        feature.insert("id".to_string(), json!(id));
+    }
+    feature.insert("geometry".to_string(), geometry);
+    feature.insert("properties".to_string(), Value::Object(properties));
+
+    Value::Object(feature)
+}
+
+fn tag_properties(tags: &Tags, tag_mapping: &TagMapping) -> Map<String, Value> {
+    match tag_mapping {
+        TagMapping::Flat => tags
+            .iter()
+            .map(|(key, value)| (key.clone(), json!(value)))
+            .collect(),
+        TagMapping::Nested => {
+            let mut properties = Map::new();
+            properties.insert("tags".to_string(), json!(tags));
+            properties
+        }
+        TagMapping::Select(keys) => keys
+            .iter()
+            .filter_map(|(key, property)| Some((property.clone(), json!(tags.get(key)?))))
+            .collect(),
+    }
+}
+
+fn metadata_properties(metadata: &Metadata, properties: &mut Map<String, Value>) {
+    let fields = [
+        ("@version", metadata.version.map(|version| json!(version))),
+        (
+            "@changeset",
+            metadata.changeset.map(|changeset| json!(changeset)),
+        ),
+        (
+            "@timestamp",
+            metadata
+                .timestamp
+                .as_ref()
+                .map(|timestamp| json!(timestamp)),
+        ),
+        ("@user", metadata.user.as_ref().map(|user| json!(user))),
+        ("@uid", metadata.uid.map(|uid| json!(uid))),
+    ];
+
+    for (key, value) in fields {
+        if let Some(value) = value {
+            properties.insert(key.to_string(), value);
+        }
+    }
+}
+
+fn node_geometry(node: &Node) -> Option<Value> {
+    Some(json!({
+        "type": "Point",
+        "coordinates": position(&node.coordinates()?),
+    }))
+}
+
+fn way_geometry(way: &Way, nodes: &HashMap<NodeId, &Node>) -> Option<Value> {
+    let positions: Vec<Value> = way
+        .nodes
+        .iter()
+        .map(|id| Some(position(&nodes.get(id)?.coordinates()?)))
+        .collect::<Option<_>>()?;
+    if positions.len() < 2 {
+        return None;
+    }
+
+    if way.is_area() {
+        Some(json!({
+            "type": "Polygon",
+            "coordinates": [positions],
+        }))
+    } else {
+        Some(json!({
+            "type": "LineString",
+            "coordinates": positions,
+        }))
+    }
+}
+
+/// Get the GeoJSON `Point` geometry of [`Coordinates`].
+pub fn point(coordinates: &Coordinates) -> Value {
+    json!({
+        "type": "Point",
+        "coordinates": position(coordinates),
+    })
+}
+
+/// Get the GeoJSON `Polygon` geometry of a [`BBox`], counterclockwise from the south-west corner.
+///
+/// A [`BBox`] crossing the antimeridian becomes a `MultiPolygon` of its two halves, as required
+/// by RFC 7946.
+pub fn polygon(bbox: &BBox) -> Value {
+    match bbox.split_at_antimeridian() {
+        Some((west, east)) => json!({
+            "type": "MultiPolygon",
+            "coordinates": [[ring(&west)], [ring(&east)]],
+        }),
+        None => json!({
+            "type": "Polygon",
+            "coordinates": [ring(bbox)],
+        }),
+    }
+}
+
+/// Get the GeoJSON bounding box of a [`BBox`], `[west, south, east, north]`.
+///
+/// West is greater than east for a [`BBox`] crossing the antimeridian.
+///
+/// See <https://datatracker.ietf.org/doc/html/rfc7946#section-5>
+pub fn bbox_array(bbox: &BBox) -> Value {
+    let (south, west, north, east) = bbox.corners();
+    json!([west, south, east, north])
+}
+
+fn ring(bbox: &BBox) -> Vec<Value> {
+    let (south, west, north, east) = bbox.corners();
+    [
+        (west, south),
+        (east, south),
+        (east, north),
+        (west, north),
+        (west, south),
+    ]
+    .into_iter()
+    .map(|(longitude, latitude)| json!([longitude, latitude]))
+    .collect()
+}
+
+/// Get the GeoJSON position of [`Coordinates`], which is longitude first.
+fn position(coordinates: &Coordinates) -> Value {
+    json!([
+        coordinates.longitude().value(),
+        coordinates.latitude().value()
+    ])
+}
+
+/// Read [`Coordinates`] from a GeoJSON position.
+fn from_position(position: &Value) -> Result<Coordinates, Error> {
+    match position.as_array().map(Vec::as_slice) {
+        Some([longitude, latitude, ..]) => Ok(Coordinates::from_value(
+            number(latitude, "position")?,
+            number(longitude, "position")?,
+        )?),
+        _ => Err(Error::InvalidGeometry("position")),
+    }
+}
+
+fn number(value: &Value, what: &'static str) -> Result<CoordinateType, Error> {
+    value
+        .as_f64()
+        .map(|number| number as CoordinateType)
+        .ok_or(Error::InvalidGeometry(what))
+}
+
+impl From<Coordinates> for Value {
+    /// See [`point`].
+    fn from(coordinates: Coordinates) -> Self {
+        point(&coordinates)
+    }
+}
+
+impl From<BBox> for Value {
+    /// See [`polygon`].
+    fn from(bbox: BBox) -> Self {
+        polygon(&bbox)
+    }
+}
+
+impl TryFrom<&Value> for Coordinates {
+    type Error = Error;
+
+    /// Read [`Coordinates`] from a GeoJSON `Point` geometry or a position.
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        if value.is_array() {
+            return from_position(value);
+        }
+
+        match (value["type"].as_str(), value.get("coordinates")) {
+            (Some("Point"), Some(position)) => from_position(position),
+            _ => Err(Error::InvalidGeometry("Point")),
+        }
+    }
+}
+
+impl TryFrom<&Value> for BBox {
+    type Error = Error;
+
+    /// Read a [`BBox`] from a GeoJSON bounding box or the box around a `Polygon` geometry.
+    ///
+    /// Bounding boxes with elevations, `[west, south, min, east, north, max]`, are read without
+    /// them. A bounding box with west greater than east crosses the antimeridian.
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        if let Some(array) = value.as_array() {
+            let [west, south, east, north] = match array.as_slice() {
+                [west, south, east, north] | [west, south, _, east, north, _] => {
+                    [west, south, east, north]
+                }
+                _ => return Err(Error::InvalidGeometry("bounding box")),
+            }
+            .map(|value| number(value, "bounding box"));
+
+            let (south_west, north_east) = (
+                Coordinates::from_value(south?, west?)?,
+                Coordinates::from_value(north?, east?)?,
+            );
+
+            return Ok(if south_west.longitude() > north_east.longitude() {
+                Self::new_crossing(south_west, north_east)?
+            } else {
+                Self::new(south_west, north_east)?
+            });
+        }
+
+        let exterior = match (value["type"].as_str(), value["coordinates"][0].as_array()) {
+            (Some("Polygon"), Some(exterior)) => exterior,
+            _ => return Err(Error::InvalidGeometry("Polygon")),
+        };
+        let positions = exterior
+            .iter()
+            .map(from_position)
+            .collect::<Result<Vec<_>, _>>()?;
+        // GeoJSON polygons do not cross the antimeridian, so the longitudes are compared as is
+        // instead of extending the box the shorter way around.
+        let (south, west, north, east) = positions
+            .iter()
+            .map(|position| (position.latitude().value(), position.longitude().value()))
+            .fold(None, |bounds, (lat, lon)| match bounds {
+                None => Some((lat, lon, lat, lon)),
+                Some((south, west, north, east)) => {
+                    Some((lat.min(south), lon.min(west), lat.max(north), lon.max(east)))
+                }
+            })
+            .ok_or(Error::InvalidGeometry("Polygon"))?;
+
+        Ok(Self::from_unchecked(
+            Coordinates::from_unchecked(south, west),
+            Coordinates::from_unchecked(north, east),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod geojson_test {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use crate::{
+        coord::{bbox::BBox, coordinates::Coordinates},
+        geojson::{
+            GeoJsonOptions, IdScheme, TagMapping, bbox_array, error::Error, feature_collection,
+            point, polygon,
+        },
+        model::{element::Element, osm_data::OsmData},
+    };
+
+    const DATA: &str = r#"{
+        "elements": [
+            {"type": "node", "id": 1, "lat": 1.0, "lon": 2.0},
+            {"type": "node", "id": 2, "lat": 1.0, "lon": 3.0},
+            {"type": "node", "id": 3, "lat": 2.0, "lon": 3.0, "tags": {"amenity": "bench"}},
+            {"type": "node", "id": 4, "lat": 5.0, "lon": 5.0, "version": 2, "user": "a"},
+            {"type": "way", "id": 5, "nodes": [1, 2], "tags": {"highway": "path", "name": "Trail"}},
+            {"type": "way", "id": 6, "nodes": [1, 2, 3, 1], "tags": {"building": "yes"}},
+            {"type": "way", "id": 7, "nodes": [1, 8]},
+            {"type": "relation", "id": 9, "members": []}
+        ]
+    }"#;
+
+    fn elements() -> Vec<Element> {
+        OsmData::from_json(DATA).unwrap().elements
+    }
+
+    #[test]
+    fn geometries() {
+        let collection = feature_collection(&elements(), &GeoJsonOptions::new());
+        let features = collection["features"].as_array().unwrap();
+
+        let ids: Vec<&str> = features
+            .iter()
+            .map(|feature| feature["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["node/3", "node/4", "way/5", "way/6"]);
+        assert_eq!(
+            features[0]["geometry"],
+            json!({"type": "Point", "coordinates": [3.0, 2.0]})
+        );
+        assert_eq!(
+            features[2]["geometry"],
+            json!({"type": "LineString", "coordinates": [[2.0, 1.0], [3.0, 1.0]]})
+        );
+        assert_eq!(features[3]["geometry"]["type"], "Polygon");
+        assert_eq!(
+            features[3]["geometry"]["coordinates"][0][3],
+            json!([2.0, 1.0])
+        );
+        assert_eq!(features[2]["properties"]["name"], "Trail");
+    }
+
+    #[test]
+    fn options() {
+        let options = GeoJsonOptions::new()
+            .with_id_scheme(IdScheme::Short)
+            .with_tag_mapping(TagMapping::Select(HashMap::from([(
+                "name".to_string(),
+                "title".to_string(),
+            )])))
+            .with_metadata(true);
+        let collection = feature_collection(&elements(), &options);
+        let features = collection["features"].as_array().unwrap();
+
+        assert_eq!(features[1]["id"], "n4");
+        assert_eq!(
+            features[1]["properties"],
+            json!({"@version": 2, "@user": "a"})
+        );
+        assert_eq!(features[2]["properties"], json!({"title": "Trail"}));
+
+        let options = GeoJsonOptions::new()
+            .with_id_scheme(IdScheme::None)
+            .with_tag_mapping(TagMapping::Nested);
+        let collection = feature_collection(&elements(), &options);
+
+        assert!(collection["features"][0].get("id").is_none());
+        assert_eq!(
+            collection["features"][0]["properties"],
+            json!({"tags": {"amenity": "bench"}})
+        );
+    }
+
+    #[test]
+    fn coordinates() {
+        let coordinates = Coordinates::from_unchecked(52.5, 13.25);
+        let geometry = point(&coordinates);
+
+        assert_eq!(
+            geometry,
+            json!({"type": "Point", "coordinates": [13.25, 52.5]})
+        );
+        assert_eq!(serde_json::Value::from(coordinates), geometry);
+        assert_eq!(Coordinates::try_from(&geometry).unwrap(), coordinates);
+        assert_eq!(
+            Coordinates::try_from(&json!([13.25, 52.5, 34.0])).unwrap(),
+            coordinates
+        );
+        assert!(matches!(
+            Coordinates::try_from(&json!({"type": "LineString", "coordinates": [13.25, 52.5]})),
+            Err(Error::InvalidGeometry("Point"))
+        ));
+        assert!(matches!(
+            Coordinates::try_from(&json!([13.25, 95.0])),
+            Err(Error::Coordinates(_))
+        ));
+    }
+
+    #[test]
+    fn bbox() {
+        let bbox = BBox::from_wrapped(1.0, 2.0, 3.0, 4.0);
+
+        assert_eq!(bbox_array(&bbox), json!([2.0, 1.0, 4.0, 3.0]));
+        assert_eq!(BBox::try_from(&bbox_array(&bbox)).unwrap(), bbox);
+        assert_eq!(
+            BBox::try_from(&json!([2.0, 1.0, 0.0, 4.0, 3.0, 10.0])).unwrap(),
+            bbox
+        );
+        assert_eq!(
+            polygon(&bbox),
+            json!({
+                "type": "Polygon",
+                "coordinates": [[[2.0, 1.0], [4.0, 1.0], [4.0, 3.0], [2.0, 3.0], [2.0, 1.0]]],
+            })
+        );
+        assert_eq!(
+            BBox::try_from(&serde_json::Value::from(bbox)).unwrap(),
+            bbox
+        );
+        assert!(matches!(
+            BBox::try_from(&json!([2.0, 1.0, 4.0])),
+            Err(Error::InvalidGeometry("bounding box"))
+        ));
+
+        let wide = BBox::from_wrapped(1.0, -170.0, 3.0, 170.0);
+        assert_eq!(BBox::try_from(&polygon(&wide)).unwrap(), wide);
+
+        let crossing = BBox::from_wrapped(1.0, 170.0, 3.0, -170.0);
+        assert_eq!(BBox::try_from(&bbox_array(&crossing)).unwrap(), crossing);
+        let geometry = polygon(&crossing);
+        assert_eq!(geometry["type"], "MultiPolygon");
+        assert_eq!(geometry["coordinates"][0][0][1], json!([180.0, 1.0]));
+        assert_eq!(geometry["coordinates"][1][0][0], json!([-180.0, 1.0]));
+    }
+}
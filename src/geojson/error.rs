@@ -0,0 +1,9 @@
+use crate::coord;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("The GeoJSON is not a valid {0}")]
+    InvalidGeometry(&'static str),
+    #[error("The GeoJSON contains invalid coordinates: {0}")]
+    Coordinates(#[from] coord::error::Error),
+}
@@ -0,0 +1,318 @@
+use std::fmt::Write;
+
+use crate::{
+    coord::{coordinates::Coordinates, polyline::Polyline},
+    xml::{self, Event, Reader, Tag, escape},
+};
+
+/// A GPX document containing GPS tracks.
+///
+/// Waypoints and routes are skipped since neither the OSM API nor ways have a use for them.
+///
+/// See <https://www.topografix.com/GPX/1/1/>
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Gpx {
+    /// The program that created the document.
+    pub creator: Option<String>,
+    pub tracks: Vec<Track>,
+}
+
+/// A track, an ordered list of [`TrackSegment`]s.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Track {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub segments: Vec<TrackSegment>,
+}
+
+/// A continuous part of a [`Track`], e.g. between losing and regaining the GPS signal.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TrackSegment {
+    pub points: Vec<TrackPoint>,
+}
+
+/// A single recorded position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackPoint {
+    pub coordinates: Coordinates,
+    /// The elevation in meters.
+    pub elevation: Option<f64>,
+    /// The time of the recording as ISO 8601 timestamp.
+    pub time: Option<String>,
+}
+
+impl Gpx {
+    /// Parse a GPX 1.0 or 1.1 document.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`xml::Error`] if the document is malformed or a point has invalid coordinates.
+    pub fn from_xml(document: &str) -> Result<Self, xml::Error> {
+        let mut gpx = Self::default();
+        let mut reader = Reader::new(document);
+
+        while let Some(event) = reader.next() {
+            match event? {
+                Event::Start(tag) if tag.name == "gpx" => {
+                    gpx.creator = tag.attribute("creator").map(str::to_string);
+                }
+                Event::Start(tag) if tag.name == "trk" && tag.self_closing => {
+                    gpx.tracks.push(Track::default())
+                }
+                Event::Start(tag) if tag.name == "trk" => {
+                    gpx.tracks.push(Track::from_xml(&mut reader)?)
+                }
+                // Skip waypoints, routes and metadata with all their children.
+                Event::Start(tag) if tag.name != "gpx" && !tag.self_closing => {
+                    reader.skip_element()?
+                }
+                _ => (),
+            }
+        }
+
+        Ok(gpx)
+    }
+
+    /// Serialize this [`Gpx`] as GPX 1.1 document.
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = write!(
+            xml,
+            r#"<gpx version="1.1" creator="{}" xmlns="http://www.topografix.com/GPX/1/1">"#,
+            escape(self.creator.as_deref().unwrap_or("rust-osm"))
+        );
+
+        for track in self.tracks.iter() {
+            xml.push_str("<trk>");
+            if let Some(ref name) = track.name {
+                let _ = write!(xml, "<name>{}</name>", escape(name));
+            }
+            if let Some(ref description) = track.description {
+                let _ = write!(xml, "<desc>{}</desc>", escape(description));
+            }
+            for segment in track.segments.iter() {
+                xml.push_str("<trkseg>");
+                for point in segment.points.iter() {
+                    point.write_xml(&mut xml);
+                }
+                xml.push_str("</trkseg>");
+            }
+            xml.push_str("</trk>");
+        }
+        xml.push_str("</gpx>");
+
+        xml
+    }
+
+    /// Get every [`TrackSegment`] of every [`Track`] as [`Polyline`].
+    pub fn polylines(&self) -> Vec<Polyline> {
+        self.tracks
+            .iter()
+            .flat_map(|track| track.segments.iter())
+            .map(TrackSegment::polyline)
+            .collect()
+    }
+}
+
+impl Track {
+    /// Parse the children of an already consumed `trk` start tag.
+    fn from_xml(reader: &mut Reader<'_>) -> Result<Self, xml::Error> {
+        let mut track = Self::default();
+
+        while let Some(event) = reader.next() {
+            match event? {
+                Event::Start(tag) if tag.self_closing => {
+                    if tag.name == "trkseg" {
+                        track.segments.push(TrackSegment::default());
+                    }
+                }
+                Event::Start(tag) => match tag.name {
+                    "name" => track.name = Some(reader.read_text_content()?),
+                    "desc" => track.description = Some(reader.read_text_content()?),
+                    "trkseg" => track.segments.push(TrackSegment::from_xml(reader)?),
+                    _ => reader.skip_element()?,
+                },
+                Event::End(_) => return Ok(track),
+                Event::Text(_) => (),
+            }
+        }
+
+        Ok(track)
+    }
+}
+
+impl TrackSegment {
+    /// Parse the children of an already consumed `trkseg` start tag.
+    fn from_xml(reader: &mut Reader<'_>) -> Result<Self, xml::Error> {
+        let mut segment = Self::default();
+
+        while let Some(event) = reader.next() {
+            match event? {
+                Event::Start(tag) if tag.name == "trkpt" => {
+                    segment.points.push(TrackPoint::from_xml(&tag, reader)?)
+                }
+                Event::Start(tag) if !tag.self_closing => reader.skip_element()?,
+                Event::End(_) => return Ok(segment),
+                _ => (),
+            }
+        }
+
+        Ok(segment)
+    }
+
+    /// Get the [`Coordinates`] of all points as [`Polyline`].
+    pub fn polyline(&self) -> Polyline {
+        Polyline::new(self.points.iter().map(|point| point.coordinates).collect())
+    }
+}
+
+impl TrackPoint {
+    /// Construct a new [`TrackPoint`] without elevation and time.
+    pub fn new(coordinates: Coordinates) -> Self {
+        Self {
+            coordinates,
+            elevation: None,
+            time: None,
+        }
+    }
+
+    fn from_xml(tag: &Tag<'_>, reader: &mut Reader<'_>) -> Result<Self, xml::Error> {
+        let latitude = tag.parse("lat")?;
+        let longitude = tag.parse("lon")?;
+        let coordinates = Coordinates::from_value(latitude, longitude).map_err(|_| {
+            xml::Error::InvalidAttribute(("lat", format!("{latitude},{longitude}")))
+        })?;
+        let mut point = Self::new(coordinates);
+        if tag.self_closing {
+            return Ok(point);
+        }
+
+        while let Some(event) = reader.next() {
+            match event? {
+                Event::Start(tag) if tag.self_closing => (),
+                Event::Start(tag) => match tag.name {
+                    "ele" => {
+                        let elevation = reader.read_text_content()?;
+                        point.elevation = Some(
+                            elevation
+                                .parse()
+                                .map_err(|_| xml::Error::InvalidAttribute(("ele", elevation)))?,
+                        );
+                    }
+                    "time" => point.time = Some(reader.read_text_content()?),
+                    _ => reader.skip_element()?,
+                },
+                Event::End(_) => return Ok(point),
+                Event::Text(_) => (),
+            }
+        }
+
+        Ok(point)
+    }
+
+    fn write_xml(&self, xml: &mut String) {
+        let _ = write!(
+            xml,
+            r#"<trkpt lat="{}" lon="{}""#,
+            self.coordinates.latitude().value(),
+            self.coordinates.longitude().value()
+        );
+        if self.elevation.is_none() && self.time.is_none() {
+            xml.push_str("/>");
+            return;
+        }
+
+        xml.push('>');
+        if let Some(elevation) = self.elevation {
+            let _ = write!(xml, "<ele>{elevation}</ele>");
+        }
+        if let Some(ref time) = self.time {
+            let _ = write!(xml, "<time>{}</time>", escape(time));
+        }
+        xml.push_str("</trkpt>");
+    }
+}
+
+#[cfg(test)]
+mod gpx_test {
+    use crate::{
+        coord::coordinates::Coordinates,
+        gpx::{Gpx, Track, TrackPoint, TrackSegment},
+    };
+
+    const GPX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <gpx version="1.1" creator="Field &amp; Survey" xmlns="http://www.topografix.com/GPX/1/1">
+            <metadata><name>Survey</name></metadata>
+            <wpt lat="5.0" lon="6.0"><name>Start</name></wpt>
+            <trk>
+                <name>Morning</name>
+                <desc>Along the river</desc>
+                <trkseg>
+                    <trkpt lat="1.0" lon="2.0"><ele>12.5</ele><time>2025-01-01T00:00:00Z</time></trkpt>
+                    <trkpt lat="1.1" lon="2.1"><extensions><speed>3</speed></extensions></trkpt>
+                </trkseg>
+                <trkseg/>
+            </trk>
+        </gpx>"#;
+
+    #[test]
+    fn parse() {
+        let gpx = Gpx::from_xml(GPX).unwrap();
+
+        assert_eq!(gpx.creator.as_deref(), Some("Field & Survey"));
+        assert_eq!(gpx.tracks.len(), 1);
+        let track = &gpx.tracks[0];
+        assert_eq!(track.name.as_deref(), Some("Morning"));
+        assert_eq!(track.description.as_deref(), Some("Along the river"));
+        assert_eq!(track.segments.len(), 2);
+        assert_eq!(
+            track.segments[0].points[0],
+            TrackPoint {
+                coordinates: Coordinates::from_value(1.0, 2.0).unwrap(),
+                elevation: Some(12.5),
+                time: Some("2025-01-01T00:00:00Z".to_string()),
+            }
+        );
+        assert_eq!(track.segments[0].points[1].elevation, None);
+        assert!(track.segments[1].points.is_empty());
+        assert_eq!(gpx.polylines()[0].coordinates().len(), 2);
+    }
+
+    #[test]
+    fn round_trip() {
+        let gpx = Gpx {
+            creator: None,
+            tracks: vec![Track {
+                name: Some("<River>".to_string()),
+                description: None,
+                segments: vec![TrackSegment {
+                    points: vec![
+                        TrackPoint::new(Coordinates::from_value(1.0, 2.0).unwrap()),
+                        TrackPoint {
+                            coordinates: Coordinates::from_value(1.5, 2.5).unwrap(),
+                            elevation: Some(-3.0),
+                            time: Some("2025-01-01T00:00:00Z".to_string()),
+                        },
+                    ],
+                }],
+            }],
+        };
+        let xml = gpx.to_xml();
+
+        assert!(xml.contains(r#"creator="rust-osm""#));
+        assert!(xml.contains("<name>&lt;River&gt;</name>"));
+        assert_eq!(Gpx::from_xml(&xml).unwrap().tracks, gpx.tracks);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(
+            Gpx::from_xml(r#"<gpx><trk><trkseg><trkpt lat="91" lon="0"/></trkseg></trk></gpx>"#)
+                .is_err()
+        );
+        assert!(
+            Gpx::from_xml(r#"<gpx><trk><trkseg><trkpt lat="1" lon="0"><ele>high</ele></trkpt></trkseg></trk></gpx>"#)
+                .is_err()
+        );
+    }
+}
@@ -19,8 +19,54 @@ compile_error!("One of `coordinate_f32` or `coordinate_f64` must be enabled.");
 #[cfg(all(feature = "coordinate_f32", feature = "coordinate_f64"))]
 compile_error!("Features `coordinate_f32` and `coordinate_f64` can not be enabled together.");
 
+pub mod boundary;
+pub mod cleanup;
+pub mod client_config;
+pub mod clip;
 pub mod coord;
+pub mod element_reader;
+pub mod filter;
+pub mod gpx;
+pub mod model;
+pub mod multipolygon;
+pub mod osm_config;
+pub mod osm_xml;
+pub mod prelude;
 pub mod rest_methods;
+pub mod retry;
+pub mod routing;
+pub mod stats;
+pub mod xml;
 
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub mod cache;
+#[cfg(feature = "compression")]
+pub mod compression;
+#[cfg(feature = "csv")]
+pub mod csv;
+#[cfg(all(feature = "download", any(feature = "async", feature = "blocking")))]
+pub mod download;
+#[cfg(feature = "geojson")]
+pub mod geojson;
+#[cfg(feature = "opening_hours")]
+pub mod opening_hours;
+#[cfg(feature = "osm_api")]
+pub mod osm_api;
 #[cfg(feature = "overpass")]
 pub mod overpass;
+#[cfg(feature = "pbf")]
+pub mod pbf;
+#[cfg(feature = "replication")]
+pub mod replication;
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub mod request_limit;
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub mod scheduler;
+#[cfg(all(feature = "tile", any(feature = "async", feature = "blocking")))]
+pub mod tile;
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub mod transport;
+
+/// The HTTP library of the clients, so its types can be used without depending on it.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub use reqwest;
@@ -0,0 +1,10 @@
+pub mod changeset;
+pub mod element;
+#[cfg(test)]
+pub(crate) mod fixture;
+pub mod id;
+pub mod osm_change;
+pub mod osm_data;
+pub mod osm_store;
+pub mod tag_value;
+pub mod tags;
@@ -0,0 +1,273 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    coord::{CoordinateType, bbox::BBox},
+    model::{id::ChangesetId, tags::Tags},
+    xml::{self, Event, Reader, Tag},
+};
+
+/// The metadata of a changeset.
+///
+/// The same model is used for the responses of the API and the changeset dumps of the planet and
+/// the changeset replication.
+///
+/// See <https://wiki.openstreetmap.org/wiki/API_v0.6#Changesets_2>
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Changeset {
+    pub id: ChangesetId,
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<String>,
+    pub open: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_lat: Option<CoordinateType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_lon: Option<CoordinateType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_lat: Option<CoordinateType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_lon: Option<CoordinateType>,
+    #[serde(default)]
+    pub comments_count: u64,
+    #[serde(default)]
+    pub changes_count: u64,
+    #[serde(default, skip_serializing_if = "Tags::is_empty")]
+    pub tags: Tags,
+    /// The discussion, only present if it was requested.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<ChangesetComment>,
+}
+
+impl Changeset {
+    /// Get the [`BBox`] of this [`Changeset`].
+    ///
+    /// Returns [`None`] if the changeset has no changes.
+    pub fn bbox(&self) -> Option<BBox> {
+        Some(BBox::from_wrapped(
+            self.min_lat?,
+            self.min_lon?,
+            self.max_lat?,
+            self.max_lon?,
+        ))
+    }
+
+    /// Parse the changesets of a JSON document containing either a single `changeset` or a
+    /// list of `changesets`.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`serde_json::Error`] if the document is malformed.
+    pub fn from_json(document: &str) -> Result<Vec<Self>, serde_json::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Document {
+            Single { changeset: Box<Changeset> },
+            List { changesets: Vec<Changeset> },
+        }
+
+        Ok(match serde_json::from_str(document)? {
+            Document::Single { changeset } => vec![*changeset],
+            Document::List { changesets } => changesets,
+        })
+    }
+
+    /// Parse the changesets of an XML document, e.g. an API response or a changeset dump.
+    ///
+    /// Both `changes_count` of the API and `num_changes` of the dumps are understood.
+    /// Other elements than `changeset` are skipped.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`xml::Error`] if the document is malformed.
+    pub fn from_xml(document: &str) -> Result<Vec<Self>, xml::Error> {
+        let mut changesets = Vec::new();
+        let mut reader = Reader::new(document);
+
+        while let Some(event) = reader.next() {
+            if let Event::Start(tag) = event? {
+                if tag.name == "changeset" {
+                    changesets.push(read_xml_changeset(&tag, &mut reader)?);
+                } else if tag.name != "osm" && !tag.self_closing {
+                    reader.skip_element()?;
+                }
+            }
+        }
+
+        Ok(changesets)
+    }
+}
+
+/// A comment in the discussion of a [`Changeset`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangesetComment {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+    pub date: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub text: String,
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+/// Read a changeset in the XML format from its start tag, consuming all its children.
+fn read_xml_changeset(tag: &Tag<'_>, reader: &mut Reader<'_>) -> Result<Changeset, xml::Error> {
+    let changes_count = match tag.parse_optional("changes_count")? {
+        Some(changes_count) => Some(changes_count),
+        None => tag.parse_optional("num_changes")?,
+    };
+    let mut changeset = Changeset {
+        id: tag.parse("id")?,
+        created_at: tag.required("created_at")?.to_string(),
+        closed_at: tag.attribute("closed_at").map(str::to_string),
+        open: tag.parse("open")?,
+        user: tag.attribute("user").map(str::to_string),
+        uid: tag.parse_optional("uid")?,
+        min_lat: tag.parse_optional("min_lat")?,
+        min_lon: tag.parse_optional("min_lon")?,
+        max_lat: tag.parse_optional("max_lat")?,
+        max_lon: tag.parse_optional("max_lon")?,
+        comments_count: tag.parse_optional("comments_count")?.unwrap_or_default(),
+        changes_count: changes_count.unwrap_or_default(),
+        tags: Tags::new(),
+        comments: Vec::new(),
+    };
+    if tag.self_closing {
+        return Ok(changeset);
+    }
+
+    loop {
+        match reader.next() {
+            Some(Ok(Event::Start(child))) => match child.name {
+                "tag" => {
+                    changeset.tags.insert(
+                        child.required("k")?.to_string(),
+                        child.required("v")?.to_string(),
+                    );
+                    if !child.self_closing {
+                        reader.skip_element()?;
+                    }
+                }
+                // The comments are read as the children of `discussion`.
+                "discussion" => (),
+                "comment" => {
+                    let mut comment = ChangesetComment {
+                        id: child.parse_optional("id")?,
+                        visible: child.parse_optional("visible")?.unwrap_or(true),
+                        date: child.required("date")?.to_string(),
+                        uid: child.parse_optional("uid")?,
+                        user: child.attribute("user").map(str::to_string),
+                        text: String::new(),
+                    };
+                    if !child.self_closing {
+                        comment.text = read_comment_text(reader)?;
+                    }
+                    changeset.comments.push(comment);
+                }
+                _ if !child.self_closing => reader.skip_element()?,
+                _ => (),
+            },
+            Some(Ok(Event::End("changeset"))) => return Ok(changeset),
+            Some(Ok(_)) => (),
+            Some(Err(e)) => return Err(e),
+            None => return Err(xml::Error::Syntax((0, "unexpected end of document"))),
+        }
+    }
+}
+
+/// Read the `text` child of an already consumed `comment` start tag until its end tag.
+fn read_comment_text(reader: &mut Reader<'_>) -> Result<String, xml::Error> {
+    let mut text = String::new();
+    loop {
+        match reader.next() {
+            Some(Ok(Event::Start(child))) if child.name == "text" && !child.self_closing => {
+                text = reader.read_text_content()?;
+            }
+            Some(Ok(Event::Start(child))) if !child.self_closing => reader.skip_element()?,
+            Some(Ok(Event::End(_))) => return Ok(text),
+            Some(Ok(_)) => (),
+            Some(Err(e)) => return Err(e),
+            None => return Err(xml::Error::Syntax((0, "unexpected end of document"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod changeset_test {
+    use crate::model::{changeset::Changeset, id::ChangesetId};
+
+    #[test]
+    fn from_json() {
+        let single = Changeset::from_json(
+            r#"{"version": "0.6", "changeset": {"id": 12, "created_at": "2025-02-01T10:00:00Z", "open": true}}"#,
+        )
+        .unwrap();
+        let list = Changeset::from_json(
+            r#"{"changesets": [{"id": 12, "created_at": "2025-02-01T10:00:00Z", "open": true},
+                               {"id": 11, "created_at": "2025-01-01T10:00:00Z", "open": false}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].id, ChangesetId::new(12));
+        assert_eq!(list.len(), 2);
+        assert!(!list[1].open);
+    }
+
+    #[test]
+    fn from_xml_api() {
+        let changesets = Changeset::from_xml(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <osm version="0.6" generator="OpenStreetMap server">
+              <changeset id="12" created_at="2025-02-01T10:00:00Z" closed_at="2025-02-01T11:00:00Z" open="false" user="a" uid="7" min_lat="1.0" min_lon="2.0" max_lat="1.5" max_lon="2.5" comments_count="1" changes_count="3">
+                <tag k="comment" v="Fix &amp; add"/>
+                <discussion>
+                  <comment id="1" date="2025-02-02T10:00:00Z" uid="8" user="b" visible="false">
+                    <text>Why?</text>
+                  </comment>
+                </discussion>
+              </changeset>
+            </osm>"#,
+        )
+        .unwrap();
+
+        assert_eq!(changesets.len(), 1);
+        let changeset = &changesets[0];
+        assert_eq!(changeset.closed_at.as_deref(), Some("2025-02-01T11:00:00Z"));
+        assert_eq!(changeset.changes_count, 3);
+        assert_eq!(changeset.tags["comment"], "Fix & add");
+        assert!(changeset.bbox().is_some());
+        assert_eq!(changeset.comments.len(), 1);
+        assert_eq!(changeset.comments[0].text, "Why?");
+        assert!(!changeset.comments[0].visible);
+    }
+
+    #[test]
+    fn from_xml_dump() {
+        let changesets = Changeset::from_xml(
+            r#"<osm version="0.6" generator="replicate_changesets.rb">
+              <changeset id="1" created_at="2025-01-01T00:00:00Z" open="true" num_changes="5" comments_count="0" uid="1" user="c"/>
+              <changeset id="2" created_at="2025-01-01T00:01:00Z" open="false" num_changes="0" comments_count="0">
+                <tag k="created_by" v="JOSM"/>
+              </changeset>
+            </osm>"#,
+        )
+        .unwrap();
+
+        assert_eq!(changesets.len(), 2);
+        assert_eq!(changesets[0].changes_count, 5);
+        assert_eq!(changesets[1].tags["created_by"], "JOSM");
+        assert!(changesets[1].bbox().is_none());
+    }
+}
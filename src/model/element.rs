@@ -0,0 +1,538 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    coord::{CoordinateType, coordinates::Coordinates, polygon::Polygon, polyline::Polyline},
+    model::{
+        id::{ChangesetId, ElementId, NodeId, RelationId, WayId},
+        tags::Tags,
+    },
+    xml::{self, Event, Reader, Tag, escape},
+};
+
+/// Tag keys that turn a closed way into an area.
+const AREA_KEYS: [&str; 16] = [
+    "aeroway", "amenity", "boundary", "building", "craft", "historic", "landuse", "leisure",
+    "man_made", "military", "natural", "office", "place", "shop", "tourism", "water",
+];
+
+/// The type of an OSM element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElementType {
+    Node,
+    Way,
+    Relation,
+}
+
+impl ElementType {
+    /// The name of the element type as used in API paths.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Node => "node",
+            Self::Way => "way",
+            Self::Relation => "relation",
+        }
+    }
+}
+
+impl FromStr for ElementType {
+    type Err = xml::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "node" => Ok(Self::Node),
+            "way" => Ok(Self::Way),
+            "relation" => Ok(Self::Relation),
+            _ => Err(xml::Error::InvalidAttribute(("type", s.to_string()))),
+        }
+    }
+}
+
+/// A single OSM element.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Element {
+    Node(Node),
+    Way(Way),
+    Relation(Relation),
+}
+
+impl Element {
+    /// Get the [`ElementId`] of this [`Element`].
+    pub fn id(&self) -> ElementId {
+        match self {
+            Self::Node(node) => node.id.into(),
+            Self::Way(way) => way.id.into(),
+            Self::Relation(relation) => relation.id.into(),
+        }
+    }
+
+    /// Get the [`ElementType`] of this [`Element`].
+    pub fn element_type(&self) -> ElementType {
+        match self {
+            Self::Node(_) => ElementType::Node,
+            Self::Way(_) => ElementType::Way,
+            Self::Relation(_) => ElementType::Relation,
+        }
+    }
+
+    /// Get the tags of this [`Element`].
+    pub fn tags(&self) -> &Tags {
+        match self {
+            Self::Node(node) => &node.tags,
+            Self::Way(way) => &way.tags,
+            Self::Relation(relation) => &relation.tags,
+        }
+    }
+
+    /// Get the [`Metadata`] of this [`Element`].
+    pub fn metadata(&self) -> &Metadata {
+        match self {
+            Self::Node(node) => &node.metadata,
+            Self::Way(way) => &way.metadata,
+            Self::Relation(relation) => &relation.metadata,
+        }
+    }
+}
+
+impl From<Node> for Element {
+    fn from(node: Node) -> Self {
+        Self::Node(node)
+    }
+}
+
+impl From<Way> for Element {
+    fn from(way: Way) -> Self {
+        Self::Way(way)
+    }
+}
+
+impl From<Relation> for Element {
+    fn from(relation: Relation) -> Self {
+        Self::Relation(relation)
+    }
+}
+
+/// The metadata every OSM element carries.
+///
+/// All fields are optional since not every endpoint returns them.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Metadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changeset: Option<ChangesetId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visible: Option<bool>,
+}
+
+/// A single point.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Node>
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Node {
+    pub id: NodeId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lat: Option<CoordinateType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lon: Option<CoordinateType>,
+    #[serde(default, skip_serializing_if = "Tags::is_empty")]
+    pub tags: Tags,
+    #[serde(flatten)]
+    pub metadata: Metadata,
+}
+
+impl Node {
+    /// Get the [`Coordinates`] of this [`Node`].
+    ///
+    /// Returns [`None`] if the node has no valid position, e.g. because it was deleted.
+    pub fn coordinates(&self) -> Option<Coordinates> {
+        Coordinates::from_value(self.lat?, self.lon?).ok()
+    }
+}
+
+/// An ordered list of nodes.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Way>
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Way {
+    pub id: WayId,
+    #[serde(default)]
+    pub nodes: Vec<NodeId>,
+    #[serde(default, skip_serializing_if = "Tags::is_empty")]
+    pub tags: Tags,
+    #[serde(flatten)]
+    pub metadata: Metadata,
+}
+
+impl Way {
+    /// Check if the first and the last node are the same.
+    pub fn is_closed(&self) -> bool {
+        self.nodes.len() >= 2 && self.nodes.first() == self.nodes.last()
+    }
+
+    /// Check if this [`Way`] describes an area instead of a line, i.e. it is closed and tagged
+    /// like an area or with `area=yes`.
+    ///
+    /// See <https://wiki.openstreetmap.org/wiki/Key:area>
+    pub fn is_area(&self) -> bool {
+        if self.nodes.len() < 4 || !self.is_closed() {
+            return false;
+        }
+
+        match self.tags.get("area").map(String::as_str) {
+            Some("yes") => true,
+            Some("no") => false,
+            _ => {
+                self.tags
+                    .keys()
+                    .any(|key| AREA_KEYS.contains(&key.as_str()))
+                    && self.tags.get("natural").map(String::as_str) != Some("coastline")
+            }
+        }
+    }
+}
+
+/// The geometry of a [`Way`] resolved from the positions of its nodes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WayGeometry {
+    Polyline(Polyline),
+    /// The geometry of a [`Way`] that [is an area](Way::is_area).
+    Polygon(Polygon),
+}
+
+impl WayGeometry {
+    /// Get the [`WayGeometry`] of a [`Way`] from the positions of its nodes, in the order of
+    /// [`Way::nodes`].
+    pub fn new(way: &Way, coordinates: Vec<Coordinates>) -> Self {
+        let polyline = Polyline::new(coordinates);
+        if way.is_area() {
+            Self::Polygon(Polygon::new(polyline, Vec::new()))
+        } else {
+            Self::Polyline(polyline)
+        }
+    }
+
+    /// Get the [`Polyline`], the exterior ring of a [`WayGeometry::Polygon`].
+    pub fn polyline(&self) -> &Polyline {
+        match self {
+            Self::Polyline(polyline) => polyline,
+            Self::Polygon(polygon) => polygon.exterior(),
+        }
+    }
+}
+
+/// An ordered list of members.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Relation>
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Relation {
+    pub id: RelationId,
+    #[serde(default)]
+    pub members: Vec<RelationMember>,
+    #[serde(default, skip_serializing_if = "Tags::is_empty")]
+    pub tags: Tags,
+    #[serde(flatten)]
+    pub metadata: Metadata,
+}
+
+/// A member of a [`Relation`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelationMember {
+    #[serde(rename = "type")]
+    pub member_type: ElementType,
+    #[serde(rename = "ref")]
+    pub reference: i64,
+    #[serde(default)]
+    pub role: String,
+}
+
+impl RelationMember {
+    /// Construct a new [`RelationMember`] referencing the element with the given id.
+    pub fn new(id: impl Into<ElementId>, role: impl Into<String>) -> Self {
+        let id = id.into();
+
+        Self {
+            member_type: id.element_type(),
+            reference: id.value(),
+            role: role.into(),
+        }
+    }
+
+    /// Get the [`ElementId`] of the referenced element.
+    pub fn id(&self) -> ElementId {
+        ElementId::new(self.member_type, self.reference)
+    }
+}
+
+impl Metadata {
+    fn from_xml(tag: &Tag<'_>) -> Result<Self, xml::Error> {
+        Ok(Self {
+            version: tag.parse_optional("version")?,
+            changeset: tag.parse_optional("changeset")?,
+            timestamp: tag.attribute("timestamp").map(str::to_string),
+            user: tag.attribute("user").map(str::to_string),
+            uid: tag.parse_optional("uid")?,
+            visible: tag.parse_optional("visible")?,
+        })
+    }
+}
+
+/// Read an OSM element in the XML format from its start tag, consuming all its children.
+///
+/// Returns [`None`] and skips the element if `tag` is not a node, way or relation.
+pub(crate) fn read_xml_element(
+    tag: &Tag<'_>,
+    reader: &mut Reader<'_>,
+) -> Result<Option<Element>, xml::Error> {
+    let id: i64 = match tag.name {
+        "node" | "way" | "relation" => tag.parse("id")?,
+        _ => {
+            if !tag.self_closing {
+                reader.skip_element()?;
+            }
+            return Ok(None);
+        }
+    };
+    let metadata = Metadata::from_xml(tag)?;
+
+    let mut tags = Tags::new();
+    let mut nodes = Vec::new();
+    let mut members = Vec::new();
+
+    if !tag.self_closing {
+        loop {
+            match reader.next() {
+                Some(Ok(Event::Start(child))) => {
+                    match child.name {
+                        "tag" => {
+                            tags.insert(
+                                child.required("k")?.to_string(),
+                                child.required("v")?.to_string(),
+                            );
+                        }
+                        "nd" => nodes.push(child.parse("ref")?),
+                        "member" => members.push(RelationMember {
+                            member_type: child.parse("type")?,
+                            reference: child.parse("ref")?,
+                            role: child.attribute("role").unwrap_or_default().to_string(),
+                        }),
+                        _ => (),
+                    }
+                    if !child.self_closing {
+                        reader.skip_element()?;
+                    }
+                }
+                Some(Ok(Event::End(_))) => break,
+                Some(Ok(Event::Text(_))) => (),
+                Some(Err(e)) => return Err(e),
+                None => return Err(xml::Error::Syntax((0, "unexpected end of document"))),
+            }
+        }
+    }
+
+    Ok(Some(match tag.name {
+        "node" => Element::Node(Node {
+            id: NodeId::new(id),
+            lat: tag.parse_optional("lat")?,
+            lon: tag.parse_optional("lon")?,
+            tags,
+            metadata,
+        }),
+        "way" => Element::Way(Way {
+            id: WayId::new(id),
+            nodes,
+            tags,
+            metadata,
+        }),
+        _ => Element::Relation(Relation {
+            id: RelationId::new(id),
+            members,
+            tags,
+            metadata,
+        }),
+    }))
+}
+
+/// Write an OSM element in the XML format.
+///
+/// If `upload_changeset` is set, the element is written for an upload to that changeset and only
+/// the version is kept from the [`Metadata`]. Otherwise all metadata is written.
+pub(crate) fn write_xml_element(
+    element: &Element,
+    upload_changeset: Option<ChangesetId>,
+    xml: &mut String,
+) {
+    write_element(element, upload_changeset, None, xml);
+}
+
+/// Write an OSM element in the XML format with every child on its own line, indented by `indent`.
+///
+/// Elements without children are written as a single self-closing tag.
+pub(crate) fn write_indented_xml_element(element: &Element, indent: &str, xml: &mut String) {
+    write_element(element, None, Some(indent), xml);
+}
+
+fn write_element(
+    element: &Element,
+    upload_changeset: Option<ChangesetId>,
+    indent: Option<&str>,
+    xml: &mut String,
+) {
+    let name = element.element_type().as_str();
+    let id = element.id().value();
+    let metadata = element.metadata();
+
+    xml.push_str(&format!(
+        r#"{}<{name} id="{id}""#,
+        indent.unwrap_or_default()
+    ));
+    if let Some(version) = metadata.version {
+        xml.push_str(&format!(r#" version="{version}""#));
+    }
+    match upload_changeset {
+        Some(changeset) => xml.push_str(&format!(r#" changeset="{changeset}""#)),
+        None => {
+            if let Some(changeset) = metadata.changeset {
+                xml.push_str(&format!(r#" changeset="{changeset}""#));
+            }
+            if let Some(ref timestamp) = metadata.timestamp {
+                xml.push_str(&format!(r#" timestamp="{}""#, escape(timestamp)));
+            }
+            if let Some(ref user) = metadata.user {
+                xml.push_str(&format!(r#" user="{}""#, escape(user)));
+            }
+            if let Some(uid) = metadata.uid {
+                xml.push_str(&format!(r#" uid="{uid}""#));
+            }
+            if let Some(visible) = metadata.visible {
+                xml.push_str(&format!(r#" visible="{visible}""#));
+            }
+        }
+    }
+    if let Element::Node(node) = element
+        && let (Some(lat), Some(lon)) = (node.lat, node.lon)
+    {
+        xml.push_str(&format!(r#" lat="{lat}" lon="{lon}""#));
+    }
+
+    let mut children = Vec::new();
+    match element {
+        Element::Way(way) => {
+            for node in way.nodes.iter() {
+                children.push(format!(r#"<nd ref="{node}"/>"#));
+            }
+        }
+        Element::Relation(relation) => {
+            for member in relation.members.iter() {
+                children.push(format!(
+                    r#"<member type="{}" ref="{}" role="{}"/>"#,
+                    member.member_type.as_str(),
+                    member.reference,
+                    escape(&member.role)
+                ));
+            }
+        }
+        Element::Node(_) => (),
+    }
+
+    for (key, value) in element.tags() {
+        children.push(format!(
+            r#"<tag k="{}" v="{}"/>"#,
+            escape(key),
+            escape(value)
+        ));
+    }
+
+    match indent {
+        None => {
+            xml.push('>');
+            xml.push_str(&children.concat());
+            xml.push_str(&format!("</{name}>"));
+        }
+        Some(_) if children.is_empty() => xml.push_str("/>\n"),
+        Some(indent) => {
+            xml.push_str(">\n");
+            for child in children {
+                xml.push_str(&format!("{indent}  {child}\n"));
+            }
+            xml.push_str(&format!("{indent}</{name}>\n"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod element_test {
+    use crate::{
+        model::{
+            element::{
+                Element, ElementType, Metadata, Node, Relation, RelationMember, read_xml_element,
+                write_xml_element,
+            },
+            id::{ChangesetId, ElementId, NodeId, RelationId},
+        },
+        xml::{Event, Reader},
+    };
+
+    #[test]
+    fn element_type() {
+        let element: Element =
+            serde_json::from_str(r#"{"type": "way", "id": 3, "nodes": [1, 2]}"#).unwrap();
+
+        assert!(matches!(element, Element::Way(_)));
+        assert_eq!(element.element_type(), ElementType::Way);
+        assert_eq!(
+            "relation".parse::<ElementType>().unwrap(),
+            ElementType::Relation
+        );
+        assert!("area".parse::<ElementType>().is_err());
+    }
+
+    #[test]
+    fn xml_round_trip() {
+        let relation = Element::Relation(Relation {
+            id: RelationId::new(4),
+            members: vec![RelationMember::new(NodeId::new(1), "label")],
+            tags: [("type".to_string(), "boundary".to_string())].into(),
+            metadata: Metadata {
+                version: Some(2),
+                ..Default::default()
+            },
+        });
+        let mut xml = String::new();
+        write_xml_element(&relation, Some(ChangesetId::new(9)), &mut xml);
+
+        let mut reader = Reader::new(&xml);
+        let Some(Ok(Event::Start(tag))) = reader.next() else {
+            panic!("expected a start tag");
+        };
+        let Some(Element::Relation(parsed)) = read_xml_element(&tag, &mut reader).unwrap() else {
+            panic!("expected a relation");
+        };
+
+        assert_eq!(parsed.members[0].id(), ElementId::Node(NodeId::new(1)));
+        assert_eq!(parsed.members[0].role, "label");
+        assert_eq!(parsed.tags["type"], "boundary");
+        assert_eq!(parsed.metadata.changeset, Some(ChangesetId::new(9)));
+    }
+
+    #[test]
+    fn node_coordinates() {
+        let node = Node {
+            id: NodeId::new(1),
+            lat: Some(1.5),
+            lon: None,
+            ..Default::default()
+        };
+
+        assert!(node.coordinates().is_none());
+    }
+}
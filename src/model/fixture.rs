@@ -0,0 +1,37 @@
+//! Elements shared by the tests of the modules working on elements.
+
+use crate::model::{
+    element::{Element, Node, Way},
+    id::{NodeId, WayId},
+};
+
+/// Construct a [`Node`] at `lat`, `lon` without tags.
+pub(crate) fn node(id: i64, lat: f64, lon: f64) -> Element {
+    Element::Node(Node {
+        id: NodeId::new(id),
+        lat: Some(lat as _),
+        lon: Some(lon as _),
+        ..Default::default()
+    })
+}
+
+/// Construct a [`Way`] along `nodes` without tags.
+pub(crate) fn way(id: i64, nodes: &[i64]) -> Element {
+    Element::Way(Way {
+        id: WayId::new(id),
+        nodes: nodes.iter().copied().map(NodeId::new).collect(),
+        ..Default::default()
+    })
+}
+
+/// Add `tags` to `element`.
+pub(crate) fn tagged(mut element: Element, tags: &[(&str, &str)]) -> Element {
+    let element_tags = match element {
+        Element::Node(ref mut node) => &mut node.tags,
+        Element::Way(ref mut way) => &mut way.tags,
+        Element::Relation(ref mut relation) => &mut relation.tags,
+    };
+    element_tags.extend(tags.iter().copied());
+
+    element
+}
@@ -0,0 +1,173 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::element::ElementType;
+
+macro_rules! id_type {
+    ($(#[$attribute:meta])* $name:ident($inner:ty)) => {
+        $(#[$attribute])*
+        #[derive(
+            Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+        )]
+        #[serde(transparent)]
+        pub struct $name($inner);
+
+        impl $name {
+            #[doc = concat!("Construct a new [`", stringify!($name), "`].")]
+            pub const fn new(id: $inner) -> Self {
+                Self(id)
+            }
+
+            /// Get the internal id.
+            pub const fn value(&self) -> $inner {
+                self.0
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl From<$inner> for $name {
+            fn from(id: $inner) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<$name> for $inner {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = std::num::ParseIntError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                s.parse().map(Self)
+            }
+        }
+    };
+}
+
+id_type!(
+    /// The id of a [`Node`](crate::model::element::Node).
+    ///
+    /// Negative ids are placeholders for nodes that do not exist on the server yet.
+    NodeId(i64)
+);
+
+id_type!(
+    /// The id of a [`Way`](crate::model::element::Way).
+    ///
+    /// Negative ids are placeholders for ways that do not exist on the server yet.
+    WayId(i64)
+);
+
+id_type!(
+    /// The id of a [`Relation`](crate::model::element::Relation).
+    ///
+    /// Negative ids are placeholders for relations that do not exist on the server yet.
+    RelationId(i64)
+);
+
+id_type!(
+    /// The id of a changeset.
+    ///
+    /// See <https://wiki.openstreetmap.org/wiki/Changeset>
+    ChangesetId(u64)
+);
+
+/// The id of any element together with its [`ElementType`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ElementId {
+    Node(NodeId),
+    Way(WayId),
+    Relation(RelationId),
+}
+
+impl ElementId {
+    /// Construct a new [`ElementId`] from an [`ElementType`] and a raw id.
+    pub fn new(element_type: ElementType, id: i64) -> Self {
+        match element_type {
+            ElementType::Node => Self::Node(NodeId(id)),
+            ElementType::Way => Self::Way(WayId(id)),
+            ElementType::Relation => Self::Relation(RelationId(id)),
+        }
+    }
+
+    /// Get the [`ElementType`] of this [`ElementId`].
+    pub fn element_type(&self) -> ElementType {
+        match self {
+            Self::Node(_) => ElementType::Node,
+            Self::Way(_) => ElementType::Way,
+            Self::Relation(_) => ElementType::Relation,
+        }
+    }
+
+    /// Get the raw id.
+    pub fn value(&self) -> i64 {
+        match self {
+            Self::Node(id) => id.0,
+            Self::Way(id) => id.0,
+            Self::Relation(id) => id.0,
+        }
+    }
+}
+
+impl Display for ElementId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.element_type().as_str(), self.value())
+    }
+}
+
+impl From<NodeId> for ElementId {
+    fn from(id: NodeId) -> Self {
+        Self::Node(id)
+    }
+}
+
+impl From<WayId> for ElementId {
+    fn from(id: WayId) -> Self {
+        Self::Way(id)
+    }
+}
+
+impl From<RelationId> for ElementId {
+    fn from(id: RelationId) -> Self {
+        Self::Relation(id)
+    }
+}
+
+#[cfg(test)]
+mod id_test {
+    use crate::model::{
+        element::ElementType,
+        id::{ChangesetId, ElementId, NodeId, WayId},
+    };
+
+    #[test]
+    fn newtype() {
+        let id = NodeId::new(-1);
+
+        assert_eq!(id.value(), -1);
+        assert_eq!(id.to_string(), "-1");
+        assert_eq!("42".parse::<WayId>().unwrap(), WayId::new(42));
+        assert_eq!(u64::from(ChangesetId::from(7)), 7);
+        assert_eq!(serde_json::to_string(&NodeId::new(5)).unwrap(), "5");
+    }
+
+    #[test]
+    fn element_id() {
+        let id = ElementId::new(ElementType::Way, 3);
+
+        assert_eq!(id, ElementId::from(WayId::new(3)));
+        assert_eq!(id.element_type(), ElementType::Way);
+        assert_eq!(id.value(), 3);
+        assert_eq!(id.to_string(), "way 3");
+        assert_ne!(id, ElementId::from(NodeId::new(3)));
+    }
+}
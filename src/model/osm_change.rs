@@ -0,0 +1,571 @@
+use std::collections::HashMap;
+
+use crate::{
+    model::{
+        element::{Element, ElementType, read_xml_element, write_xml_element},
+        id::{ChangesetId, ElementId},
+    },
+    xml::{self, Event, Reader},
+};
+
+/// A set of changes in the osmChange format.
+///
+/// The same model is used for uploads to the OSM API and for replication diffs.
+///
+/// See <https://wiki.openstreetmap.org/wiki/OsmChange>
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct OsmChange {
+    pub create: Vec<Element>,
+    pub modify: Vec<Element>,
+    pub delete: Vec<Element>,
+}
+
+impl OsmChange {
+    /// Construct a new empty [`OsmChange`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the creation of an element, which needs a negative placeholder id.
+    pub fn with_create(mut self, element: impl Into<Element>) -> Self {
+        self.create.push(element.into());
+        self
+    }
+
+    /// Add the modification of an element, which needs the version it is based on.
+    pub fn with_modify(mut self, element: impl Into<Element>) -> Self {
+        self.modify.push(element.into());
+        self
+    }
+
+    /// Add the deletion of an element, which needs the version it is based on.
+    pub fn with_delete(mut self, element: impl Into<Element>) -> Self {
+        self.delete.push(element.into());
+        self
+    }
+
+    /// Parse an osmChange XML document.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`xml::Error`] if the document is malformed.
+    pub fn from_xml(document: &str) -> Result<Self, xml::Error> {
+        let mut change = Self::default();
+        let mut reader = Reader::new(document);
+        let mut action: Option<Action> = None;
+
+        while let Some(event) = reader.next() {
+            match event? {
+                Event::Start(tag) => match (tag.name, action) {
+                    ("osmChange", _) => (),
+                    ("create", None) => action = Some(Action::Create),
+                    ("modify", None) => action = Some(Action::Modify),
+                    ("delete", None) => action = Some(Action::Delete),
+                    (_, Some(current)) => {
+                        if let Some(element) = read_xml_element(&tag, &mut reader)? {
+                            change.elements_mut(current).push(element);
+                        }
+                    }
+                    (_, None) => {
+                        if !tag.self_closing {
+                            reader.skip_element()?;
+                        }
+                    }
+                },
+                Event::End("create" | "modify" | "delete") => action = None,
+                Event::End(_) | Event::Text(_) => (),
+            }
+        }
+
+        Ok(change)
+    }
+
+    /// Read and parse an osmChange file like a replication diff, which may be gzip compressed.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`crate::osm_xml::error::Error`] if the file can not be read or is malformed.
+    #[cfg(feature = "compression")]
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, crate::osm_xml::error::Error> {
+        use std::io::Read;
+
+        let mut document = String::new();
+        crate::compression::open(path)?.read_to_string(&mut document)?;
+
+        Ok(Self::from_xml(&document)?)
+    }
+
+    /// Serialize this [`OsmChange`] into an osmChange XML document.
+    ///
+    /// If `changeset` is set, all elements are assigned to that changeset as required for uploads
+    /// and only their versions are kept from the metadata.
+    pub fn to_xml(&self, changeset: Option<ChangesetId>) -> String {
+        let mut xml = String::from(r#"<osmChange version="0.6" generator="rust-osm">"#);
+
+        for (action, elements) in [
+            (Action::Create, &self.create),
+            (Action::Modify, &self.modify),
+            (Action::Delete, &self.delete),
+        ] {
+            let name = action.as_str();
+            if elements.is_empty() {
+                continue;
+            }
+
+            xml.push_str(&format!("<{name}>"));
+            for element in elements.iter() {
+                write_xml_element(element, changeset, &mut xml);
+            }
+            xml.push_str(&format!("</{name}>"));
+        }
+        xml.push_str("</osmChange>");
+
+        xml
+    }
+
+    /// Split this [`OsmChange`] into chunks of at most `max_elements` elements.
+    ///
+    /// Elements are ordered so that every chunk only references elements that exist on the server
+    /// or are created in the same or an earlier chunk: created nodes come before ways and relations,
+    /// deleted relations and ways before nodes.
+    pub fn split(&self, max_elements: usize) -> Vec<Self> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        if max_elements == 0 || self.len() <= max_elements {
+            return vec![self.clone()];
+        }
+
+        let mut create = self.create.clone();
+        create.sort_by_key(|element| type_rank(element.element_type()));
+        let mut delete = self.delete.clone();
+        delete.sort_by_key(|element| std::cmp::Reverse(type_rank(element.element_type())));
+
+        let ordered = create
+            .into_iter()
+            .map(|element| (Action::Create, element))
+            .chain(
+                self.modify
+                    .iter()
+                    .cloned()
+                    .map(|element| (Action::Modify, element)),
+            )
+            .chain(delete.into_iter().map(|element| (Action::Delete, element)));
+
+        let mut chunks: Vec<Self> = Vec::new();
+        for (index, (action, element)) in ordered.enumerate() {
+            if index % max_elements == 0 {
+                chunks.push(Self::default());
+            }
+            if let Some(chunk) = chunks.last_mut() {
+                chunk.elements_mut(action).push(element);
+            }
+        }
+
+        chunks
+    }
+
+    /// Replace references to placeholder ids with the ids assigned by the server.
+    ///
+    /// `ids` maps the old ids to the new ids, e.g. the id map of the diff result of an upload.
+    pub fn remap_ids(&mut self, ids: &HashMap<ElementId, ElementId>) {
+        let elements = self
+            .create
+            .iter_mut()
+            .chain(self.modify.iter_mut())
+            .chain(self.delete.iter_mut());
+
+        for element in elements {
+            match element {
+                Element::Way(way) => {
+                    for node in way.nodes.iter_mut() {
+                        if let Some(ElementId::Node(id)) = ids.get(&ElementId::Node(*node)) {
+                            *node = *id;
+                        }
+                    }
+                }
+                Element::Relation(relation) => {
+                    for member in relation.members.iter_mut() {
+                        if let Some(id) = ids.get(&member.id()) {
+                            member.reference = id.value();
+                        }
+                    }
+                }
+                Element::Node(_) => (),
+            }
+        }
+    }
+
+    /// Get the total amount of changed elements.
+    pub fn len(&self) -> usize {
+        self.create.len() + self.modify.len() + self.delete.len()
+    }
+
+    /// Check if this [`OsmChange`] contains no changes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Add an element changed by `action`.
+    pub fn push(&mut self, action: Action, element: impl Into<Element>) {
+        self.elements_mut(action).push(element.into());
+    }
+
+    /// Iterate over the changes, the created elements first, then the modified and the deleted
+    /// ones.
+    pub fn actions(&self) -> impl Iterator<Item = (Action, &Element)> {
+        [
+            (Action::Create, &self.create),
+            (Action::Modify, &self.modify),
+            (Action::Delete, &self.delete),
+        ]
+        .into_iter()
+        .flat_map(|(action, elements)| elements.iter().map(move |element| (action, element)))
+    }
+
+    /// Take the changes out of this [`OsmChange`] in the order of [`OsmChange::actions`].
+    pub fn into_actions(self) -> impl Iterator<Item = (Action, Element)> {
+        [
+            (Action::Create, self.create),
+            (Action::Modify, self.modify),
+            (Action::Delete, self.delete),
+        ]
+        .into_iter()
+        .flat_map(|(action, elements)| elements.into_iter().map(move |element| (action, element)))
+    }
+
+    fn elements_mut(&mut self, action: Action) -> &mut Vec<Element> {
+        match action {
+            Action::Create => &mut self.create,
+            Action::Modify => &mut self.modify,
+            Action::Delete => &mut self.delete,
+        }
+    }
+}
+
+/// The order in which element types have to be created.
+pub(crate) fn type_rank(element_type: ElementType) -> u8 {
+    match element_type {
+        ElementType::Node => 0,
+        ElementType::Way => 1,
+        ElementType::Relation => 2,
+    }
+}
+
+/// How an element is changed by an [`OsmChange`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Create,
+    Modify,
+    Delete,
+}
+
+impl Action {
+    /// The name of the action as used in osmChange documents.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Modify => "modify",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+impl<E: Into<Element>> FromIterator<(Action, E)> for OsmChange {
+    fn from_iter<T: IntoIterator<Item = (Action, E)>>(iter: T) -> Self {
+        let mut change = Self::new();
+        for (action, element) in iter {
+            change.push(action, element);
+        }
+
+        change
+    }
+}
+
+#[cfg(test)]
+mod osm_change_test {
+    use std::collections::HashMap;
+
+    use crate::model::{
+        element::{Element, ElementType, Metadata, Node, Relation, RelationMember, Way},
+        id::{ChangesetId, ElementId, NodeId, RelationId, WayId},
+        osm_change::{Action, OsmChange},
+        tags::Tags,
+    };
+
+    const CHANGE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <osmChange version="0.6" generator="openstreetmap-cgimap">
+            <create>
+                <node id="1" changeset="5" timestamp="2025-01-01T00:00:00Z" version="1" visible="true" user="a" uid="2" lat="1.5" lon="2.5">
+                    <tag k="amenity" v="cafe"/>
+                    <tag k="name" v="Tom &amp; Jerry"/>
+                </node>
+            </create>
+            <modify>
+                <way id="3" changeset="5" version="2" visible="true">
+                    <nd ref="1"/>
+                    <nd ref="4"/>
+                    <tag k="highway" v="service"/>
+                </way>
+                <relation id="6" changeset="5" version="7" visible="true">
+                    <member type="way" ref="3" role="outer"/>
+                </relation>
+            </modify>
+            <delete>
+                <node id="8" changeset="5" version="3" visible="false"/>
+            </delete>
+        </osmChange>"#;
+
+    #[test]
+    fn builder() {
+        let node = Node {
+            id: NodeId::new(-1),
+            lat: Some(1.0),
+            lon: Some(2.0),
+            ..Default::default()
+        };
+        let way = Way {
+            id: WayId::new(3),
+            nodes: vec![NodeId::new(-1), NodeId::new(4)],
+            metadata: Metadata {
+                version: Some(2),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let change = OsmChange::new()
+            .with_create(node.clone())
+            .with_modify(way)
+            .with_delete(Element::Relation(Relation {
+                id: RelationId::new(6),
+                ..Default::default()
+            }));
+
+        assert_eq!(change.len(), 3);
+        assert_eq!(change.create, vec![Element::Node(node)]);
+        assert_eq!(change.modify[0].id(), WayId::new(3).into());
+        assert_eq!(change.delete[0].id(), RelationId::new(6).into());
+    }
+
+    #[test]
+    fn actions() {
+        let change = OsmChange::from_xml(CHANGE).unwrap();
+        let actions: Vec<(Action, ElementId)> = change
+            .actions()
+            .map(|(action, element)| (action, element.id()))
+            .collect();
+
+        assert_eq!(
+            actions,
+            vec![
+                (Action::Create, NodeId::new(1).into()),
+                (Action::Modify, WayId::new(3).into()),
+                (Action::Modify, RelationId::new(6).into()),
+                (Action::Delete, NodeId::new(8).into()),
+            ]
+        );
+        assert_eq!(change.clone().into_actions().collect::<OsmChange>(), change);
+        assert_eq!(OsmChange::from_xml(&change.to_xml(None)).unwrap(), change);
+    }
+
+    #[test]
+    fn parse() {
+        let change = OsmChange::from_xml(CHANGE).unwrap();
+
+        assert_eq!(change.len(), 4);
+        assert_eq!(change.create.len(), 1);
+        assert_eq!(change.modify.len(), 2);
+        assert_eq!(change.delete.len(), 1);
+    }
+
+    #[test]
+    fn parse_node() {
+        let change = OsmChange::from_xml(CHANGE).unwrap();
+        let Element::Node(ref node) = change.create[0] else {
+            panic!("expected a node");
+        };
+
+        assert_eq!(node.id, NodeId::new(1));
+        assert_eq!(node.lat, Some(1.5));
+        assert_eq!(node.lon, Some(2.5));
+        assert_eq!(node.tags["name"], "Tom & Jerry");
+        assert_eq!(node.metadata.uid, Some(2));
+        assert_eq!(node.metadata.visible, Some(true));
+    }
+
+    #[test]
+    fn parse_way_and_relation() {
+        let change = OsmChange::from_xml(CHANGE).unwrap();
+        let Element::Way(ref way) = change.modify[0] else {
+            panic!("expected a way");
+        };
+        let Element::Relation(ref relation) = change.modify[1] else {
+            panic!("expected a relation");
+        };
+
+        assert_eq!(way.nodes, vec![NodeId::new(1), NodeId::new(4)]);
+        assert_eq!(way.metadata.version, Some(2));
+        assert_eq!(relation.members[0].member_type, ElementType::Way);
+        assert_eq!(relation.members[0].reference, 3);
+        assert_eq!(relation.members[0].role, "outer");
+    }
+
+    #[test]
+    fn parse_deleted() {
+        let change = OsmChange::from_xml(CHANGE).unwrap();
+
+        assert_eq!(change.delete[0].id(), NodeId::new(8).into());
+        assert_eq!(change.delete[0].element_type(), ElementType::Node);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(
+            OsmChange::from_xml(r#"<osmChange><create><node id="x"/></create></osmChange>"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn to_xml() {
+        let change = OsmChange {
+            create: vec![Element::Node(Node {
+                id: NodeId::new(-1),
+                lat: Some(1.5),
+                lon: Some(2.5),
+                tags: Tags::from([("name".to_string(), "A & B".to_string())]),
+                ..Default::default()
+            })],
+            modify: vec![],
+            delete: vec![Element::Way(Way {
+                id: WayId::new(3),
+                nodes: vec![NodeId::new(1), NodeId::new(2)],
+                metadata: Metadata {
+                    version: Some(4),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })],
+        };
+
+        assert_eq!(
+            change.to_xml(Some(ChangesetId::new(9))),
+            concat!(
+                r#"<osmChange version="0.6" generator="rust-osm">"#,
+                r#"<create><node id="-1" changeset="9" lat="1.5" lon="2.5"><tag k="name" v="A &amp; B"/></node></create>"#,
+                r#"<delete><way id="3" version="4" changeset="9"><nd ref="1"/><nd ref="2"/></way></delete>"#,
+                "</osmChange>"
+            )
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let change = OsmChange::from_xml(CHANGE).unwrap();
+
+        assert_eq!(OsmChange::from_xml(&change.to_xml(None)).unwrap(), change);
+    }
+
+    #[test]
+    fn split() {
+        let change = OsmChange {
+            create: vec![
+                Element::Relation(Relation {
+                    id: RelationId::new(-4),
+                    ..Default::default()
+                }),
+                Element::Way(Way {
+                    id: WayId::new(-3),
+                    ..Default::default()
+                }),
+                Element::Node(Node {
+                    id: NodeId::new(-1),
+                    ..Default::default()
+                }),
+                Element::Node(Node {
+                    id: NodeId::new(-2),
+                    ..Default::default()
+                }),
+            ],
+            modify: vec![],
+            delete: vec![
+                Element::Node(Node {
+                    id: NodeId::new(5),
+                    ..Default::default()
+                }),
+                Element::Way(Way {
+                    id: WayId::new(6),
+                    ..Default::default()
+                }),
+            ],
+        };
+        let chunks = change.split(4);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(
+            chunks[0].create.iter().map(Element::id).collect::<Vec<_>>(),
+            vec![
+                NodeId::new(-1).into(),
+                NodeId::new(-2).into(),
+                WayId::new(-3).into(),
+                RelationId::new(-4).into()
+            ]
+        );
+        assert!(chunks[1].create.is_empty());
+        assert_eq!(
+            chunks[1].delete.iter().map(Element::id).collect::<Vec<_>>(),
+            vec![
+                ElementId::from(WayId::new(6)),
+                ElementId::from(NodeId::new(5))
+            ]
+        );
+    }
+
+    #[test]
+    fn split_small() {
+        let change = OsmChange::from_xml(CHANGE).unwrap();
+
+        assert_eq!(change.split(10), vec![change.clone()]);
+        assert!(OsmChange::default().split(10).is_empty());
+    }
+
+    #[test]
+    fn remap_ids() {
+        let mut change = OsmChange {
+            create: vec![
+                Element::Way(Way {
+                    id: WayId::new(-3),
+                    nodes: vec![NodeId::new(-1), NodeId::new(7), NodeId::new(-2)],
+                    ..Default::default()
+                }),
+                Element::Relation(Relation {
+                    id: RelationId::new(-4),
+                    members: vec![RelationMember::new(WayId::new(-3), "")],
+                    ..Default::default()
+                }),
+            ],
+            modify: vec![],
+            delete: vec![],
+        };
+        let ids = HashMap::from([
+            (NodeId::new(-1).into(), NodeId::new(100).into()),
+            (NodeId::new(-2).into(), NodeId::new(101).into()),
+            (WayId::new(-3).into(), WayId::new(200).into()),
+        ]);
+        change.remap_ids(&ids);
+
+        let Element::Way(ref way) = change.create[0] else {
+            panic!("expected a way");
+        };
+        let Element::Relation(ref relation) = change.create[1] else {
+            panic!("expected a relation");
+        };
+
+        assert_eq!(way.id, WayId::new(-3));
+        assert_eq!(
+            way.nodes,
+            vec![NodeId::new(100), NodeId::new(7), NodeId::new(101)]
+        );
+        assert_eq!(relation.members[0].id(), WayId::new(200).into());
+    }
+}
@@ -0,0 +1,733 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::{Read, Write},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    coord::{CoordinateType, bbox::BBox, coordinates::Coordinates},
+    element_reader::ElementReader,
+    model::{
+        element::{
+            Element, Node, Relation, Way, WayGeometry, read_xml_element,
+            write_indented_xml_element, write_xml_element,
+        },
+        id::{ElementId, NodeId, RelationId, WayId},
+    },
+    xml::{self, Event, Reader, escape},
+};
+
+/// A set of OSM elements as returned by the OSM API or Overpass in the OSM JSON or XML format.
+///
+/// The document level fields of both sources are kept, so documents can be written back without
+/// losing information.
+///
+/// See <https://wiki.openstreetmap.org/wiki/OSM_JSON> and <https://wiki.openstreetmap.org/wiki/OSM_XML>
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OsmData {
+    /// Overpass writes the version as a number, it is read as a string either way.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_version",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generator: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copyright: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribution: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// The area that was requested, only returned by the OSM API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bounds: Option<Bounds>,
+    /// The state of the database, only returned by Overpass.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub osm3s: Option<Osm3s>,
+    #[serde(default)]
+    pub elements: Vec<Element>,
+    /// A message of the server, only returned by Overpass, e.g. that the query timed out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remark: Option<String>,
+}
+
+pub(crate) fn deserialize_version<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Version {
+        String(String),
+        Number(serde_json::Number),
+    }
+
+    Ok(
+        Option::<Version>::deserialize(deserializer)?.map(|version| match version {
+            Version::String(version) => version,
+            Version::Number(version) => version.to_string(),
+        }),
+    )
+}
+
+/// The bounds of an OSM document.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bounds {
+    pub minlat: CoordinateType,
+    pub minlon: CoordinateType,
+    pub maxlat: CoordinateType,
+    pub maxlon: CoordinateType,
+}
+
+impl Bounds {
+    /// Convert these [`Bounds`] into a [`BBox`].
+    pub fn bbox(&self) -> BBox {
+        BBox::from_wrapped(self.minlat, self.minlon, self.maxlat, self.maxlon)
+    }
+}
+
+/// The state of the Overpass database a result was computed from.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Osm3s {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_osm_base: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_areas_base: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copyright: Option<String>,
+}
+
+impl OsmData {
+    /// Parse an OSM JSON document.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`serde_json::Error`] if the document is malformed.
+    pub fn from_json(document: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(document)
+    }
+
+    /// Parse an OSM JSON document from a reader, e.g. a file.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`serde_json::Error`] if the document can not be read or is malformed.
+    pub fn from_json_reader(reader: impl Read) -> Result<Self, serde_json::Error> {
+        serde_json::from_reader(reader)
+    }
+
+    /// Serialize this [`OsmData`] into an OSM JSON document.
+    ///
+    /// Parsing the document with [`Self::from_json`] results in an equal [`OsmData`].
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Serialize this [`OsmData`] into an OSM JSON document and write it to `writer`.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`serde_json::Error`] if the document can not be written.
+    pub fn to_json_writer(&self, writer: impl Write) -> Result<(), serde_json::Error> {
+        serde_json::to_writer(writer, self)
+    }
+
+    /// Parse an OSM XML document into the same model as [`Self::from_json`].
+    ///
+    /// Other elements than nodes, ways, relations and `bounds` are skipped.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`xml::Error`] if the document is malformed.
+    pub fn from_xml(document: &str) -> Result<Self, xml::Error> {
+        let mut data = Self::default();
+        let mut reader = Reader::new(document);
+
+        while let Some(event) = reader.next() {
+            if let Event::Start(tag) = event? {
+                if tag.name == "osm" {
+                    data.version = tag.attribute("version").map(str::to_string);
+                    data.generator = tag.attribute("generator").map(str::to_string);
+                    data.copyright = tag.attribute("copyright").map(str::to_string);
+                    data.attribution = tag.attribute("attribution").map(str::to_string);
+                    data.license = tag.attribute("license").map(str::to_string);
+                } else if tag.name == "bounds" {
+                    data.bounds = Some(Bounds {
+                        minlat: tag.parse("minlat")?,
+                        minlon: tag.parse("minlon")?,
+                        maxlat: tag.parse("maxlat")?,
+                        maxlon: tag.parse("maxlon")?,
+                    });
+                    if !tag.self_closing {
+                        reader.skip_element()?;
+                    }
+                } else if tag.name == "meta" {
+                    let osm3s = data.osm3s.get_or_insert_default();
+                    osm3s.timestamp_osm_base = tag.attribute("osm_base").map(str::to_string);
+                    osm3s.timestamp_areas_base = tag.attribute("areas").map(str::to_string);
+                    if !tag.self_closing {
+                        reader.skip_element()?;
+                    }
+                } else if tag.name == "note" && !tag.self_closing {
+                    data.osm3s.get_or_insert_default().copyright =
+                        Some(reader.read_text_content()?);
+                } else if tag.name == "remark" && !tag.self_closing {
+                    data.remark = Some(reader.read_text_content()?);
+                } else if let Some(element) = read_xml_element(&tag, &mut reader)? {
+                    data.elements.push(element);
+                }
+            }
+        }
+
+        Ok(data)
+    }
+
+    /// Collect all elements of an [`ElementReader`], e.g. a `.osm.pbf` extract, into an
+    /// [`OsmData`], so files can be processed like API responses.
+    ///
+    /// The document level fields are left empty.
+    ///
+    /// # Error
+    ///
+    /// Returns the first error of `reader`.
+    pub fn from_reader<R: ElementReader>(reader: R) -> Result<Self, R::Error> {
+        Ok(Self {
+            elements: reader.collect::<Result<_, _>>()?,
+            ..Self::default()
+        })
+    }
+
+    /// Serialize this [`OsmData`] into an OSM XML document.
+    pub fn to_xml(&self) -> String {
+        let mut xml = self.xml_start_tag();
+        for line in self.xml_header() {
+            xml.push_str(&line);
+        }
+        for element in self.elements.iter() {
+            write_xml_element(element, None, &mut xml);
+        }
+        xml.push_str("</osm>");
+
+        xml
+    }
+
+    /// Serialize this [`OsmData`] into a canonical OSM XML document, which can be opened in JOSM.
+    ///
+    /// Elements are sorted by type and id and written one child per line with their attributes
+    /// in a fixed order and their tags sorted by key. Two exports of the same data are therefore
+    /// identical and line based diffs between exports only show actual changes.
+    pub fn to_canonical_xml(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&self.xml_start_tag());
+        xml.push('\n');
+        for line in self.xml_header() {
+            xml.push_str(&format!("  {line}\n"));
+        }
+
+        let mut elements: Vec<&Element> = self.elements.iter().collect();
+        elements.sort_by_key(|element| element.id());
+        for element in elements {
+            write_indented_xml_element(element, "  ", &mut xml);
+        }
+        xml.push_str("</osm>\n");
+
+        xml
+    }
+
+    fn xml_start_tag(&self) -> String {
+        let mut xml = format!(
+            r#"<osm version="{}" generator="{}""#,
+            escape(self.version.as_deref().unwrap_or("0.6")),
+            escape(self.generator.as_deref().unwrap_or("rust-osm"))
+        );
+        for (name, value) in [
+            ("copyright", &self.copyright),
+            ("attribution", &self.attribution),
+            ("license", &self.license),
+        ] {
+            if let Some(value) = value {
+                xml.push_str(&format!(r#" {name}="{}""#, escape(value)));
+            }
+        }
+        xml.push('>');
+
+        xml
+    }
+
+    /// Get the children of the `osm` element before the elements, the bounds of the OSM API or
+    /// the note, meta and remark of Overpass.
+    fn xml_header(&self) -> Vec<String> {
+        let mut header = Vec::new();
+        if let Some(bounds) = self.bounds {
+            header.push(format!(
+                r#"<bounds minlat="{}" minlon="{}" maxlat="{}" maxlon="{}"/>"#,
+                bounds.minlat, bounds.minlon, bounds.maxlat, bounds.maxlon
+            ));
+        }
+        if let Some(ref osm3s) = self.osm3s {
+            if let Some(ref copyright) = osm3s.copyright {
+                header.push(format!("<note>{}</note>", escape(copyright)));
+            }
+            let mut meta = String::from("<meta");
+            for (name, value) in [
+                ("osm_base", &osm3s.timestamp_osm_base),
+                ("areas", &osm3s.timestamp_areas_base),
+            ] {
+                if let Some(value) = value {
+                    meta.push_str(&format!(r#" {name}="{}""#, escape(value)));
+                }
+            }
+            meta.push_str("/>");
+            header.push(meta);
+        }
+        if let Some(ref remark) = self.remark {
+            header.push(format!("<remark>{}</remark>", escape(remark)));
+        }
+
+        header
+    }
+
+    /// Serialize this [`OsmData`] as a GeoJSON `FeatureCollection`.
+    ///
+    /// See [`crate::geojson::feature_collection`]
+    #[cfg(feature = "geojson")]
+    pub fn to_geojson(&self, options: &crate::geojson::GeoJsonOptions) -> String {
+        crate::geojson::feature_collection(&self.elements, options).to_string()
+    }
+
+    /// Iterate over all [`Node`]s.
+    pub fn nodes(&self) -> impl Iterator<Item = &Node> {
+        self.elements.iter().filter_map(|element| match element {
+            Element::Node(node) => Some(node),
+            _ => None,
+        })
+    }
+
+    /// Iterate over all [`Way`]s.
+    pub fn ways(&self) -> impl Iterator<Item = &Way> {
+        self.elements.iter().filter_map(|element| match element {
+            Element::Way(way) => Some(way),
+            _ => None,
+        })
+    }
+
+    /// Iterate over all [`Relation`]s.
+    pub fn relations(&self) -> impl Iterator<Item = &Relation> {
+        self.elements.iter().filter_map(|element| match element {
+            Element::Relation(relation) => Some(relation),
+            _ => None,
+        })
+    }
+
+    /// Take all [`Node`]s out of this [`OsmData`].
+    pub fn into_nodes(self) -> Vec<Node> {
+        self.elements
+            .into_iter()
+            .filter_map(|element| match element {
+                Element::Node(node) => Some(node),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Take all [`Way`]s out of this [`OsmData`].
+    pub fn into_ways(self) -> Vec<Way> {
+        self.elements
+            .into_iter()
+            .filter_map(|element| match element {
+                Element::Way(way) => Some(way),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Take all [`Relation`]s out of this [`OsmData`].
+    pub fn into_relations(self) -> Vec<Relation> {
+        self.elements
+            .into_iter()
+            .filter_map(|element| match element {
+                Element::Relation(relation) => Some(relation),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Append the elements of `other` that are not in this [`OsmData`] yet, e.g. to combine the
+    /// responses for neighbouring areas. The document level fields are kept.
+    pub fn merge(&mut self, other: OsmData) {
+        let ids: HashSet<ElementId> = self.elements.iter().map(Element::id).collect();
+
+        self.elements.extend(
+            other
+                .elements
+                .into_iter()
+                .filter(|element| !ids.contains(&element.id())),
+        );
+    }
+
+    /// Find the [`Node`] with the given id.
+    pub fn node(&self, id: NodeId) -> Option<&Node> {
+        self.nodes().find(|node| node.id == id)
+    }
+
+    /// Find the [`Way`] with the given id.
+    pub fn way(&self, id: WayId) -> Option<&Way> {
+        self.ways().find(|way| way.id == id)
+    }
+
+    /// Find the [`Relation`] with the given id.
+    pub fn relation(&self, id: RelationId) -> Option<&Relation> {
+        self.relations().find(|relation| relation.id == id)
+    }
+
+    /// Resolve the node references of a [`Way`] into [`Coordinates`].
+    ///
+    /// Returns [`None`] if a referenced node is missing or has no position.
+    pub fn way_coordinates(&self, way: &Way) -> Option<Vec<Coordinates>> {
+        let nodes: HashMap<NodeId, &Node> = self.nodes().map(|node| (node.id, node)).collect();
+
+        way.nodes
+            .iter()
+            .map(|id| nodes.get(id).and_then(|node| node.coordinates()))
+            .collect()
+    }
+
+    /// Resolve the geometries of all [`Way`]s from the [`Node`]s in this [`OsmData`], see
+    /// [`resolve_geometries`].
+    pub fn resolve_geometries(&self) -> HashMap<WayId, WayGeometry> {
+        resolve_geometries(&self.elements)
+    }
+}
+
+/// Resolve the geometry of every [`Way`] in `elements` from the positions of the [`Node`]s in
+/// `elements`, e.g. of an Overpass query ending with `(._;>;); out body;`.
+///
+/// Ways with a missing node or a node without a position are left out.
+pub fn resolve_geometries(elements: &[Element]) -> HashMap<WayId, WayGeometry> {
+    let nodes: HashMap<NodeId, Coordinates> = elements
+        .iter()
+        .filter_map(|element| match element {
+            Element::Node(node) => Some((node.id, node.coordinates()?)),
+            _ => None,
+        })
+        .collect();
+
+    elements
+        .iter()
+        .filter_map(|element| match element {
+            Element::Way(way) => {
+                let coordinates = way
+                    .nodes
+                    .iter()
+                    .map(|id| nodes.get(id).copied())
+                    .collect::<Option<_>>()?;
+                Some((way.id, WayGeometry::new(way, coordinates)))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod osm_data_test {
+    use crate::{
+        coord::{bbox::BBox, coordinates::Coordinates},
+        model::{
+            element::{Element, Node, WayGeometry},
+            id::{NodeId, WayId},
+            osm_data::OsmData,
+        },
+        osm_xml::OsmXmlReader,
+    };
+
+    const WAY_FULL: &str = r#"{
+        "version": "0.6",
+        "generator": "openstreetmap-cgimap",
+        "bounds": {"minlat": 1.0, "minlon": 2.0, "maxlat": 3.0, "maxlon": 4.0},
+        "elements": [
+            {"type": "node", "id": 1, "lat": 1.0, "lon": 2.0, "version": 2, "changeset": 10, "user": "a", "uid": 5},
+            {"type": "node", "id": 2, "lat": 1.5, "lon": 2.5, "version": 1},
+            {"type": "way", "id": 3, "nodes": [1, 2], "tags": {"highway": "residential"}, "version": 4}
+        ]
+    }"#;
+
+    #[test]
+    fn parse() {
+        let data: OsmData = serde_json::from_str(WAY_FULL).unwrap();
+
+        assert_eq!(data.elements.len(), 3);
+        assert_eq!(data.nodes().count(), 2);
+        assert_eq!(data.ways().count(), 1);
+        assert_eq!(data.relations().count(), 0);
+    }
+
+    #[test]
+    fn metadata() {
+        let data: OsmData = serde_json::from_str(WAY_FULL).unwrap();
+        let node = data.node(NodeId::new(1)).unwrap();
+
+        assert_eq!(node.metadata.version, Some(2));
+        assert_eq!(node.metadata.changeset.map(u64::from), Some(10));
+        assert_eq!(node.metadata.user.as_deref(), Some("a"));
+        assert_eq!(
+            data.way(WayId::new(3)).unwrap().tags["highway"],
+            "residential"
+        );
+    }
+
+    #[test]
+    fn way_coordinates() {
+        let data: OsmData = serde_json::from_str(WAY_FULL).unwrap();
+        let way = data.way(WayId::new(3)).unwrap();
+
+        assert_eq!(
+            data.way_coordinates(way).unwrap(),
+            vec![
+                Coordinates::from_value(1.0, 2.0).unwrap(),
+                Coordinates::from_value(1.5, 2.5).unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn way_coordinates_missing_node() {
+        let mut data: OsmData = serde_json::from_str(WAY_FULL).unwrap();
+        data.elements
+            .retain(|element| element.id() != NodeId::new(2).into());
+        let way = data.way(WayId::new(3)).unwrap().clone();
+
+        assert!(data.way_coordinates(&way).is_none());
+    }
+
+    #[test]
+    fn resolve_geometries() {
+        let data = OsmData::from_json(
+            r#"{"elements": [
+                {"type": "node", "id": 1, "lat": 0.0, "lon": 0.0},
+                {"type": "node", "id": 2, "lat": 0.0, "lon": 1.0},
+                {"type": "node", "id": 3, "lat": 1.0, "lon": 1.0},
+                {"type": "way", "id": 4, "nodes": [1, 2]},
+                {"type": "way", "id": 5, "nodes": [1, 2, 3, 1], "tags": {"building": "yes"}},
+                {"type": "way", "id": 6, "nodes": [1, 7]}
+            ]}"#,
+        )
+        .unwrap();
+        let geometries = data.resolve_geometries();
+
+        assert_eq!(geometries.len(), 2);
+        assert!(matches!(
+            &geometries[&WayId::new(4)],
+            WayGeometry::Polyline(polyline) if polyline.coordinates() == [
+                Coordinates::from_unchecked(0.0, 0.0),
+                Coordinates::from_unchecked(0.0, 1.0),
+            ]
+        ));
+        assert!(matches!(
+            &geometries[&WayId::new(5)],
+            WayGeometry::Polygon(polygon) if polygon.is_valid()
+        ));
+        assert_eq!(geometries[&WayId::new(5)].polyline().coordinates().len(), 4);
+    }
+
+    #[test]
+    fn into_nodes() {
+        let data: OsmData = serde_json::from_str(WAY_FULL).unwrap();
+        let nodes = data.into_nodes();
+
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].id, NodeId::new(1));
+        assert_eq!(nodes[1].id, NodeId::new(2));
+    }
+
+    #[test]
+    fn json_and_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <osm version="0.6" generator="openstreetmap-cgimap">
+                <bounds minlat="1" minlon="2" maxlat="3" maxlon="4"/>
+                <node id="1" lat="1.0" lon="2.0" version="2" changeset="10" user="a" uid="5"/>
+                <node id="2" lat="1.5" lon="2.5" version="1"/>
+                <way id="3" version="4">
+                    <nd ref="1"/>
+                    <nd ref="2"/>
+                    <tag k="highway" v="residential"/>
+                </way>
+            </osm>"#;
+
+        assert_eq!(
+            OsmData::from_xml(xml).unwrap(),
+            OsmData::from_json(WAY_FULL).unwrap()
+        );
+    }
+
+    #[test]
+    fn overpass_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <osm version="0.6" generator="Overpass API 0.7.62.1 084b4234">
+                <note>The data included in this document is from www.openstreetmap.org.</note>
+                <meta osm_base="2025-01-01T00:00:00Z" areas="2024-12-31T00:00:00Z"/>
+                <node id="1" lat="1.0" lon="2.0"/>
+                <remark> runtime error: Query timed out </remark>
+            </osm>"#;
+        let data = OsmData::from_xml(xml).unwrap();
+
+        let osm3s = data.osm3s.as_ref().unwrap();
+        assert_eq!(
+            osm3s.timestamp_osm_base.as_deref(),
+            Some("2025-01-01T00:00:00Z")
+        );
+        assert_eq!(
+            osm3s.timestamp_areas_base.as_deref(),
+            Some("2024-12-31T00:00:00Z")
+        );
+        assert!(osm3s.copyright.as_ref().unwrap().starts_with("The data"));
+        assert_eq!(
+            data.remark.as_deref(),
+            Some("runtime error: Query timed out")
+        );
+        assert_eq!(data.elements.len(), 1);
+
+        assert_eq!(OsmData::from_xml(&data.to_xml()).unwrap(), data);
+        assert_eq!(OsmData::from_xml(&data.to_canonical_xml()).unwrap(), data);
+    }
+
+    #[test]
+    fn bounds() {
+        let data = OsmData::from_json(WAY_FULL).unwrap();
+
+        assert_eq!(
+            data.bounds.unwrap().bbox(),
+            BBox::from_wrapped(1.0, 2.0, 3.0, 4.0)
+        );
+    }
+
+    #[test]
+    fn merge() {
+        let mut data = OsmData::from_json(WAY_FULL).unwrap();
+        let mut other = OsmData::from_json(WAY_FULL).unwrap();
+        other.elements.remove(0);
+        other.elements.push(
+            Node {
+                id: NodeId::new(4),
+                ..Default::default()
+            }
+            .into(),
+        );
+        data.merge(other);
+
+        assert_eq!(data.elements.len(), 4);
+        assert_eq!(data.elements[3].id(), NodeId::new(4).into());
+        assert_eq!(data.generator.as_deref(), Some("openstreetmap-cgimap"));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let overpass = r#"{
+            "version": 0.6,
+            "generator": "Overpass API 0.7.62.1 084b4234",
+            "osm3s": {
+                "timestamp_osm_base": "2025-01-01T00:00:00Z",
+                "copyright": "The data included in this document is from www.openstreetmap.org. The data is made available under ODbL."
+            },
+            "elements": [
+                {"type": "node", "id": 1, "lat": 1.0, "lon": 2.0, "timestamp": "2025-01-01T00:00:00Z", "version": 2, "changeset": 10, "user": "a", "uid": 5, "tags": {"amenity": "cafe"}},
+                {"type": "relation", "id": 6, "members": [{"type": "node", "ref": 1, "role": "label"}], "tags": {"type": "multipolygon"}}
+            ]
+        }"#;
+        let data = OsmData::from_json(overpass).unwrap();
+
+        assert_eq!(data.version.as_deref(), Some("0.6"));
+        assert_eq!(OsmData::from_json(&data.to_json()).unwrap(), data);
+
+        let written = OsmData::from_json(WAY_FULL).unwrap().to_json();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&written).unwrap(),
+            serde_json::from_str::<serde_json::Value>(WAY_FULL).unwrap()
+        );
+    }
+
+    #[test]
+    fn json_reader_and_writer() {
+        let data = OsmData::from_json(WAY_FULL).unwrap();
+        let mut written = Vec::new();
+        data.to_json_writer(&mut written).unwrap();
+
+        assert_eq!(OsmData::from_json_reader(written.as_slice()).unwrap(), data);
+    }
+
+    #[test]
+    fn xml_round_trip() {
+        let data = OsmData::from_json(WAY_FULL).unwrap();
+
+        assert_eq!(OsmData::from_xml(&data.to_xml()).unwrap(), data);
+
+        let mut multi_line = data.clone();
+        let Element::Way(ref mut way) = multi_line.elements[2] else {
+            panic!("expected the way");
+        };
+        way.tags
+            .insert("note".to_string(), "first\r\nsecond\tthird".to_string());
+        let xml = multi_line.to_xml();
+        assert!(xml.contains("first&#13;&#10;second&#9;third"));
+        assert_eq!(OsmData::from_xml(&xml).unwrap(), multi_line);
+        assert_eq!(
+            OsmData::from_xml(&multi_line.to_canonical_xml()).unwrap(),
+            multi_line
+        );
+    }
+
+    #[test]
+    fn canonical_xml() {
+        let document = r#"<osm version="0.6" generator="test">
+            <way id="3"><tag k="name" v="B"/><nd ref="2"/><tag k="highway" v="path"/><nd ref="1"/></way>
+            <node id="2" lat="1.5" lon="2.5" version="1"/>
+            <relation id="4"><member type="way" ref="3" role=""/></relation>
+            <node id="1" lat="1" lon="2"><tag k="amenity" v="bench"/></node>
+        </osm>"#;
+        let data = OsmData::from_xml(document).unwrap();
+
+        assert_eq!(
+            data.to_canonical_xml(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<osm version="0.6" generator="test">
+  <node id="1" lat="1" lon="2">
+    <tag k="amenity" v="bench"/>
+  </node>
+  <node id="2" version="1" lat="1.5" lon="2.5"/>
+  <way id="3">
+    <nd ref="2"/>
+    <nd ref="1"/>
+    <tag k="highway" v="path"/>
+    <tag k="name" v="B"/>
+  </way>
+  <relation id="4">
+    <member type="way" ref="3" role=""/>
+  </relation>
+</osm>
+"#
+        );
+        assert_eq!(
+            OsmData::from_xml(&data.to_canonical_xml())
+                .unwrap()
+                .elements
+                .len(),
+            4
+        );
+    }
+
+    #[test]
+    fn from_reader() {
+        let document = r#"<osm version="0.6">
+  <node id="1" lat="1" lon="2"/>
+  <way id="2"><nd ref="1"/></way>
+</osm>"#;
+        let data = OsmData::from_reader(OsmXmlReader::new(document.as_bytes())).unwrap();
+
+        assert_eq!(data.elements.len(), 2);
+        assert_eq!(data.way(WayId::new(2)).unwrap().nodes, vec![NodeId::new(1)]);
+        assert_eq!(data.version, None);
+
+        assert!(OsmData::from_reader(OsmXmlReader::new("<osm><node".as_bytes())).is_err());
+    }
+}
@@ -0,0 +1,569 @@
+pub mod error;
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    coord::{bbox::BBox, coordinates::Coordinates},
+    model::{
+        element::{Element, Node, Relation, Way},
+        id::{ElementId, NodeId, RelationId, WayId},
+        osm_change::{OsmChange, type_rank},
+        osm_data::OsmData,
+        osm_store::error::Error,
+    },
+};
+
+/// A grid cell of the spatial index as (latitude, longitude) indexes.
+type Cell = (i32, i32);
+
+/// An in-memory store of OSM elements with O(1) lookup by id.
+///
+/// Besides the elements, the store keeps reverse indexes from nodes to the ways using them and
+/// from elements to the relations they are members of. An optional grid based spatial index
+/// speeds up [`OsmStore::nodes_in`].
+#[derive(Debug, Default, Clone)]
+pub struct OsmStore {
+    nodes: HashMap<NodeId, Node>,
+    ways: HashMap<WayId, Way>,
+    relations: HashMap<RelationId, Relation>,
+    node_ways: HashMap<NodeId, HashSet<WayId>>,
+    member_relations: HashMap<ElementId, HashSet<RelationId>>,
+    spatial_index: Option<SpatialIndex>,
+}
+
+#[derive(Debug, Clone)]
+struct SpatialIndex {
+    /// The size of a cell in degrees.
+    cell_size: f64,
+    cells: HashMap<Cell, HashSet<NodeId>>,
+}
+
+impl OsmStore {
+    /// Construct a new empty [`OsmStore`] without spatial index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maintain a spatial index of the nodes with grid cells of `cell_size` degrees.
+    ///
+    /// Already stored nodes are indexed immediately.
+    pub fn with_spatial_index(mut self, cell_size: f64) -> Self {
+        let mut index = SpatialIndex {
+            cell_size,
+            cells: HashMap::new(),
+        };
+        for node in self.nodes.values() {
+            index.insert(node);
+        }
+        self.spatial_index = Some(index);
+
+        self
+    }
+
+    /// Insert an [`Element`], replacing and returning the stored element with the same id.
+    pub fn insert(&mut self, element: Element) -> Option<Element> {
+        let replaced = self.remove(element.id());
+
+        match element {
+            Element::Node(node) => {
+                if let Some(ref mut index) = self.spatial_index {
+                    index.insert(&node);
+                }
+                self.nodes.insert(node.id, node);
+            }
+            Element::Way(way) => {
+                for node in way.nodes.iter() {
+                    self.node_ways.entry(*node).or_default().insert(way.id);
+                }
+                self.ways.insert(way.id, way);
+            }
+            Element::Relation(relation) => {
+                for member in relation.members.iter() {
+                    self.member_relations
+                        .entry(member.id())
+                        .or_default()
+                        .insert(relation.id);
+                }
+                self.relations.insert(relation.id, relation);
+            }
+        }
+
+        replaced
+    }
+
+    /// Remove and return the [`Element`] with the given id.
+    ///
+    /// Elements referencing it are kept, so the store may contain dangling references afterwards.
+    pub fn remove(&mut self, id: impl Into<ElementId>) -> Option<Element> {
+        match id.into() {
+            ElementId::Node(id) => {
+                let node = self.nodes.remove(&id)?;
+                if let Some(ref mut index) = self.spatial_index {
+                    index.remove(&node);
+                }
+                Some(Element::Node(node))
+            }
+            ElementId::Way(id) => {
+                let way = self.ways.remove(&id)?;
+                for node in way.nodes.iter() {
+                    remove_reference(&mut self.node_ways, *node, way.id);
+                }
+                Some(Element::Way(way))
+            }
+            ElementId::Relation(id) => {
+                let relation = self.relations.remove(&id)?;
+                for member in relation.members.iter() {
+                    remove_reference(&mut self.member_relations, member.id(), relation.id);
+                }
+                Some(Element::Relation(relation))
+            }
+        }
+    }
+
+    /// Apply an [`OsmChange`], e.g. a replication diff or staged edits.
+    ///
+    /// Created and modified elements are inserted, deleted elements are removed. An element may
+    /// appear multiple times, e.g. with every version of a minutely diff, and is applied in the
+    /// order create, modify, delete.
+    /// Changes with the same version as the stored element are accepted, so staged edits based
+    /// on the stored version can be applied.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::VersionConflict`] without changing the store if a change is older than
+    /// the stored element.
+    pub fn apply(&mut self, change: &OsmChange) -> Result<(), Error> {
+        let mut create: Vec<&Element> = change.create.iter().collect();
+        create.sort_by_key(|element| type_rank(element.element_type()));
+        let mut delete: Vec<&Element> = change.delete.iter().collect();
+        delete.sort_by_key(|element| std::cmp::Reverse(type_rank(element.element_type())));
+        let ordered = || {
+            create
+                .iter()
+                .copied()
+                .chain(change.modify.iter())
+                .map(|element| (element, false))
+                .chain(delete.iter().map(|element| (*element, true)))
+        };
+
+        // Check all versions first, so a conflict leaves the store untouched.
+        let mut versions: HashMap<ElementId, Option<u64>> = HashMap::new();
+        for (element, _) in ordered() {
+            let id = element.id();
+            let current = *versions.entry(id).or_insert_with(|| self.version(id));
+            match (current, element.metadata().version) {
+                (Some(current), Some(version)) if version < current => {
+                    return Err(Error::VersionConflict((id, version, current)));
+                }
+                (_, Some(version)) => {
+                    versions.insert(id, Some(version));
+                }
+                _ => (),
+            }
+        }
+
+        for (element, deleted) in ordered() {
+            if deleted {
+                self.remove(element.id());
+            } else {
+                self.insert(element.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check if an element with the given id is stored.
+    pub fn contains(&self, id: impl Into<ElementId>) -> bool {
+        match id.into() {
+            ElementId::Node(id) => self.nodes.contains_key(&id),
+            ElementId::Way(id) => self.ways.contains_key(&id),
+            ElementId::Relation(id) => self.relations.contains_key(&id),
+        }
+    }
+
+    /// Get the version of the stored element with the given id.
+    fn version(&self, id: ElementId) -> Option<u64> {
+        match id {
+            ElementId::Node(id) => self.nodes.get(&id)?.metadata.version,
+            ElementId::Way(id) => self.ways.get(&id)?.metadata.version,
+            ElementId::Relation(id) => self.relations.get(&id)?.metadata.version,
+        }
+    }
+
+    /// Get the [`Node`] with the given id.
+    pub fn node(&self, id: NodeId) -> Option<&Node> {
+        self.nodes.get(&id)
+    }
+
+    /// Get the [`Way`] with the given id.
+    pub fn way(&self, id: WayId) -> Option<&Way> {
+        self.ways.get(&id)
+    }
+
+    /// Get the [`Relation`] with the given id.
+    pub fn relation(&self, id: RelationId) -> Option<&Relation> {
+        self.relations.get(&id)
+    }
+
+    /// Iterate over all [`Node`]s in no particular order.
+    pub fn nodes(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.values()
+    }
+
+    /// Iterate over all [`Way`]s in no particular order.
+    pub fn ways(&self) -> impl Iterator<Item = &Way> {
+        self.ways.values()
+    }
+
+    /// Iterate over all [`Relation`]s in no particular order.
+    pub fn relations(&self) -> impl Iterator<Item = &Relation> {
+        self.relations.values()
+    }
+
+    /// Get the amount of stored elements.
+    pub fn len(&self) -> usize {
+        self.nodes.len() + self.ways.len() + self.relations.len()
+    }
+
+    /// Check if no elements are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over the stored [`Way`]s using the given node.
+    pub fn ways_of_node(&self, id: NodeId) -> impl Iterator<Item = &Way> {
+        self.node_ways
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .filter_map(|way| self.ways.get(way))
+    }
+
+    /// Iterate over the stored [`Relation`]s the given element is a member of.
+    pub fn relations_of(&self, id: impl Into<ElementId>) -> impl Iterator<Item = &Relation> {
+        self.member_relations
+            .get(&id.into())
+            .into_iter()
+            .flatten()
+            .filter_map(|relation| self.relations.get(relation))
+    }
+
+    /// Get all [`Node`]s inside a [`BBox`].
+    ///
+    /// Uses the spatial index if enabled and scans all nodes otherwise.
+    pub fn nodes_in(&self, bbox: &BBox) -> Vec<&Node> {
+        let inside = |node: &&Node| {
+            node.coordinates()
+                .is_some_and(|coordinates| bbox.contains(&coordinates))
+        };
+
+        match self.spatial_index {
+            Some(ref index) => index
+                .candidates(bbox)
+                .filter_map(|id| self.nodes.get(id))
+                .filter(inside)
+                .collect(),
+            None => self.nodes.values().filter(inside).collect(),
+        }
+    }
+
+    /// Resolve the node references of a [`Way`] into [`Coordinates`].
+    ///
+    /// Returns [`None`] if a referenced node is missing or has no position.
+    pub fn way_coordinates(&self, way: &Way) -> Option<Vec<Coordinates>> {
+        way.nodes
+            .iter()
+            .map(|id| self.nodes.get(id).and_then(Node::coordinates))
+            .collect()
+    }
+}
+
+impl Extend<Element> for OsmStore {
+    fn extend<T: IntoIterator<Item = Element>>(&mut self, iter: T) {
+        for element in iter {
+            self.insert(element);
+        }
+    }
+}
+
+impl FromIterator<Element> for OsmStore {
+    fn from_iter<T: IntoIterator<Item = Element>>(iter: T) -> Self {
+        let mut store = Self::new();
+        store.extend(iter);
+        store
+    }
+}
+
+impl From<OsmData> for OsmStore {
+    fn from(data: OsmData) -> Self {
+        data.elements.into_iter().collect()
+    }
+}
+
+impl SpatialIndex {
+    fn insert(&mut self, node: &Node) {
+        if let Some(cell) = self.cell(node) {
+            self.cells.entry(cell).or_default().insert(node.id);
+        }
+    }
+
+    fn remove(&mut self, node: &Node) {
+        if let Some(cell) = self.cell(node) {
+            remove_reference(&mut self.cells, cell, node.id);
+        }
+    }
+
+    fn cell(&self, node: &Node) -> Option<Cell> {
+        let coordinates = node.coordinates()?;
+
+        Some(self.cell_of(
+            coordinates.latitude().value(),
+            coordinates.longitude().value(),
+        ))
+    }
+
+    fn cell_of(&self, latitude: impl Into<f64>, longitude: impl Into<f64>) -> Cell {
+        (
+            (latitude.into() / self.cell_size).floor() as i32,
+            (longitude.into() / self.cell_size).floor() as i32,
+        )
+    }
+
+    /// Iterate over the nodes in all cells overlapping a [`BBox`].
+    fn candidates(&self, bbox: &BBox) -> impl Iterator<Item = &NodeId> {
+        bbox.halves()
+            .into_iter()
+            .flatten()
+            .flat_map(|half| self.candidates_in_half(&half))
+    }
+
+    /// Iterate over the nodes in all cells overlapping a [`BBox`] not crossing the antimeridian.
+    fn candidates_in_half(&self, bbox: &BBox) -> impl Iterator<Item = &NodeId> + use<'_> {
+        let (south_west, north_east) = (bbox.south_west(), bbox.north_east());
+        let (south, west) = self.cell_of(
+            south_west.latitude().value(),
+            south_west.longitude().value(),
+        );
+        let (north, east) = self.cell_of(
+            north_east.latitude().value(),
+            north_east.longitude().value(),
+        );
+
+        (south..=north)
+            .flat_map(move |latitude| (west..=east).map(move |longitude| (latitude, longitude)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+    }
+}
+
+/// Remove `reference` from the set at `key`, removing the set once it is empty.
+fn remove_reference<K, V>(index: &mut HashMap<K, HashSet<V>>, key: K, reference: V)
+where
+    K: std::hash::Hash + Eq,
+    V: std::hash::Hash + Eq,
+{
+    if let Some(references) = index.get_mut(&key) {
+        references.remove(&reference);
+        if references.is_empty() {
+            index.remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod osm_store_test {
+    use crate::{
+        coord::{bbox::BBox, coordinates::Coordinates},
+        model::{
+            element::{Element, Metadata, Relation, RelationMember},
+            fixture::{node, way},
+            id::{NodeId, RelationId, WayId},
+            osm_change::OsmChange,
+            osm_store::{OsmStore, error::Error},
+        },
+    };
+
+    fn versioned(mut element: Element, version: u64) -> Element {
+        let metadata = Metadata {
+            version: Some(version),
+            ..Default::default()
+        };
+        match element {
+            Element::Node(ref mut node) => node.metadata = metadata,
+            Element::Way(ref mut way) => way.metadata = metadata,
+            Element::Relation(ref mut relation) => relation.metadata = metadata,
+        }
+        element
+    }
+
+    fn store() -> OsmStore {
+        [
+            node(1, 1.0, 1.0),
+            node(2, 1.5, 1.5),
+            node(3, 5.0, 5.0),
+            way(10, &[1, 2]),
+            way(11, &[2, 3]),
+            Element::Relation(Relation {
+                id: RelationId::new(20),
+                members: vec![
+                    RelationMember::new(WayId::new(10), "outer"),
+                    RelationMember::new(NodeId::new(3), "label"),
+                ],
+                ..Default::default()
+            }),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn bbox() -> BBox {
+        BBox::new(
+            Coordinates::from_unchecked(0.0, 0.0),
+            Coordinates::from_unchecked(2.0, 2.0),
+        )
+        .unwrap()
+    }
+
+    fn sorted<T: Ord>(mut values: Vec<T>) -> Vec<T> {
+        values.sort();
+        values
+    }
+
+    #[test]
+    fn lookup() {
+        let store = store();
+
+        assert_eq!(store.len(), 6);
+        assert!(store.contains(WayId::new(10)));
+        assert!(!store.contains(NodeId::new(10)));
+        assert_eq!(store.node(NodeId::new(2)).unwrap().lat, Some(1.5));
+        assert_eq!(
+            store.way_coordinates(store.way(WayId::new(10)).unwrap()),
+            Some(vec![
+                Coordinates::from_unchecked(1.0, 1.0),
+                Coordinates::from_unchecked(1.5, 1.5)
+            ])
+        );
+    }
+
+    #[test]
+    fn reverse_indexes() {
+        let mut store = store();
+
+        assert_eq!(
+            sorted(
+                store
+                    .ways_of_node(NodeId::new(2))
+                    .map(|way| way.id)
+                    .collect()
+            ),
+            vec![WayId::new(10), WayId::new(11)]
+        );
+        assert_eq!(store.relations_of(NodeId::new(3)).count(), 1);
+        assert_eq!(store.relations_of(WayId::new(11)).count(), 0);
+
+        store.insert(way(10, &[1, 3]));
+        assert_eq!(
+            sorted(
+                store
+                    .ways_of_node(NodeId::new(3))
+                    .map(|way| way.id)
+                    .collect()
+            ),
+            vec![WayId::new(10), WayId::new(11)]
+        );
+        assert_eq!(store.ways_of_node(NodeId::new(2)).count(), 1);
+
+        assert!(store.remove(RelationId::new(20)).is_some());
+        assert_eq!(store.relations_of(WayId::new(10)).count(), 0);
+        assert!(store.remove(RelationId::new(20)).is_none());
+    }
+
+    #[test]
+    fn spatial_index() {
+        let plain = store();
+        let mut indexed = store().with_spatial_index(0.5);
+
+        let ids =
+            |store: &OsmStore| sorted(store.nodes_in(&bbox()).iter().map(|node| node.id).collect());
+        assert_eq!(ids(&plain), vec![NodeId::new(1), NodeId::new(2)]);
+        assert_eq!(ids(&indexed), ids(&plain));
+
+        indexed.insert(node(2, 4.0, 4.0));
+        indexed.insert(node(4, 0.5, 0.5));
+        assert_eq!(ids(&indexed), vec![NodeId::new(1), NodeId::new(4)]);
+
+        let crossing = BBox::from_wrapped(-10.0, 170.0, 10.0, -170.0);
+        for mut store in [OsmStore::new(), OsmStore::new().with_spatial_index(0.5)] {
+            store.insert(node(5, 0.0, 175.0));
+            store.insert(node(6, 0.0, -175.0));
+            store.insert(node(7, 0.0, 0.0));
+            assert_eq!(
+                sorted(
+                    store
+                        .nodes_in(&crossing)
+                        .iter()
+                        .map(|node| node.id)
+                        .collect()
+                ),
+                vec![NodeId::new(5), NodeId::new(6)]
+            );
+        }
+    }
+
+    #[test]
+    fn apply() {
+        let mut store: OsmStore = [
+            versioned(node(1, 1.0, 1.0), 2),
+            versioned(node(2, 1.5, 1.5), 1),
+            versioned(way(10, &[1, 2]), 1),
+        ]
+        .into_iter()
+        .collect();
+
+        let change = OsmChange {
+            create: vec![
+                versioned(way(11, &[3, 2]), 1),
+                versioned(node(3, 2.0, 2.0), 1),
+            ],
+            modify: vec![
+                versioned(node(1, 1.1, 1.1), 3),
+                versioned(node(1, 1.2, 1.2), 4),
+                versioned(way(10, &[1, 3]), 1),
+            ],
+            delete: vec![versioned(node(2, 1.5, 1.5), 2)],
+        };
+        store.apply(&change).unwrap();
+
+        assert_eq!(store.len(), 4);
+        assert_eq!(store.node(NodeId::new(1)).unwrap().lat, Some(1.2));
+        assert!(!store.contains(NodeId::new(2)));
+        assert_eq!(store.ways_of_node(NodeId::new(3)).count(), 2);
+
+        // Applying the stored versions again is harmless.
+        let change = OsmChange {
+            modify: vec![versioned(node(1, 1.2, 1.2), 4)],
+            ..Default::default()
+        };
+        store.apply(&change).unwrap();
+        assert_eq!(store.len(), 4);
+    }
+
+    #[test]
+    fn apply_conflict() {
+        let mut store: OsmStore = [versioned(node(1, 1.0, 1.0), 5)].into_iter().collect();
+        let change = OsmChange {
+            create: vec![versioned(node(2, 2.0, 2.0), 1)],
+            modify: vec![versioned(node(1, 3.0, 3.0), 4)],
+            delete: Vec::new(),
+        };
+
+        assert!(matches!(
+            store.apply(&change),
+            Err(Error::VersionConflict((_, 4, 5)))
+        ));
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.node(NodeId::new(1)).unwrap().lat, Some(1.0));
+    }
+}
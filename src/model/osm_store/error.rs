@@ -0,0 +1,7 @@
+use crate::model::id::ElementId;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("The change to {} has version {} but version {} is already stored", .0.0, .0.1, .0.2)]
+    VersionConflict((ElementId, u64, u64)),
+}
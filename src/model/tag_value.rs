@@ -0,0 +1,201 @@
+/// Kilometers per mile.
+const KM_PER_MILE: f64 = 1.609_344;
+/// Kilometers per nautical mile.
+const KM_PER_NAUTICAL_MILE: f64 = 1.852;
+/// Meters per foot.
+const M_PER_FOOT: f64 = 0.3048;
+/// Meters per inch.
+const M_PER_INCH: f64 = 0.0254;
+
+/// The value of a `maxspeed` tag.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Key:maxspeed>
+#[derive(Debug, Clone, PartialEq)]
+pub enum MaxSpeed {
+    /// A numeric limit in km/h, converted from mph or knots if necessary.
+    Limit(f64),
+    /// Walking speed, e.g. in living streets.
+    Walk,
+    /// No speed limit.
+    None,
+    /// A variable limit shown by signals.
+    Signals,
+    /// A limit implied by a zone like `DE:urban`, which has to be looked up per country.
+    Implicit(String),
+}
+
+impl MaxSpeed {
+    /// Parse a `maxspeed` value like `50`, `30 mph`, `walk` or `DE:urban`.
+    ///
+    /// Returns [`None`](Option::None) if the value does not follow any convention.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+
+        match value {
+            "walk" => return Some(Self::Walk),
+            "none" => return Some(Self::None),
+            "signals" => return Some(Self::Signals),
+            _ => (),
+        }
+        if let Some((country, zone)) = value.split_once(':')
+            && country.len() == 2
+            && country.chars().all(|c| c.is_ascii_uppercase())
+            && !zone.is_empty()
+        {
+            return Some(Self::Implicit(value.to_string()));
+        }
+
+        let (number, unit) = split_unit(value);
+        let number = parse_number(number)?;
+        let factor = match unit {
+            "" | "km/h" | "kmh" | "kph" => 1.0,
+            "mph" => KM_PER_MILE,
+            "knots" => KM_PER_NAUTICAL_MILE,
+            _ => return Option::None,
+        };
+
+        Some(Self::Limit(number * factor))
+    }
+}
+
+/// Parse a yes/no value like `yes`, `no`, `true`, `false`, `1` or `0`.
+///
+/// Returns [`None`] for every other value, e.g. `designated` or `permissive`.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Key:access>
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim() {
+        "yes" | "true" | "1" => Some(true),
+        "no" | "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parse a dimension like `height`, `width` or `maxheight` into meters.
+///
+/// Supports plain numbers in meters, the units `m`, `km`, `cm`, `mm`, `mi`, `nmi`, `ft` and
+/// `in`, and the imperial notation `3'6"`.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Map_features/Units>
+pub fn parse_length(value: &str) -> Option<f64> {
+    let value = value.trim();
+
+    if let Some((feet, inches)) = value.split_once('\'') {
+        let feet = parse_number(feet)?;
+        let inches = match inches.trim() {
+            "" => 0.0,
+            inches => parse_number(inches.strip_suffix('"')?)?,
+        };
+
+        return Some(feet * M_PER_FOOT + inches * M_PER_INCH);
+    }
+
+    let (number, unit) = split_unit(value);
+    let number = parse_number(number)?;
+    let factor = match unit {
+        "" | "m" => 1.0,
+        "km" => 1000.0,
+        "cm" => 0.01,
+        "mm" => 0.001,
+        "mi" => KM_PER_MILE * 1000.0,
+        "nmi" => KM_PER_NAUTICAL_MILE * 1000.0,
+        "ft" => M_PER_FOOT,
+        "in" => M_PER_INCH,
+        _ => return None,
+    };
+
+    Some(number * factor)
+}
+
+/// Split a value with multiple entries like `pizza;burger` into its entries.
+///
+/// Entries are trimmed and empty entries are skipped.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Semi-colon_value_separator>
+pub fn parse_list(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+}
+
+/// Parse a `layer` value, which is a small integer.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Key:layer>
+pub fn parse_layer(value: &str) -> Option<i8> {
+    value.trim().parse().ok()
+}
+
+/// Split a value like `30 mph` or `4m` into the number and the unit.
+fn split_unit(value: &str) -> (&str, &str) {
+    let end = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+        .unwrap_or(value.len());
+
+    (&value[..end], value[end..].trim())
+}
+
+fn parse_number(value: &str) -> Option<f64> {
+    let number: f64 = value.trim().parse().ok()?;
+
+    number.is_finite().then_some(number)
+}
+
+#[cfg(test)]
+mod tag_value_test {
+    use crate::model::tag_value::{MaxSpeed, parse_bool, parse_layer, parse_length, parse_list};
+
+    fn assert_close(value: Option<f64>, expected: f64) {
+        let value = value.unwrap();
+        assert!((value - expected).abs() < 1e-9, "{value} != {expected}");
+    }
+
+    #[test]
+    fn max_speed() {
+        assert_eq!(MaxSpeed::parse("50"), Some(MaxSpeed::Limit(50.0)));
+        assert_eq!(MaxSpeed::parse("50 km/h"), Some(MaxSpeed::Limit(50.0)));
+        match MaxSpeed::parse("30 mph") {
+            Some(MaxSpeed::Limit(speed)) => assert_close(Some(speed), 48.28032),
+            speed => panic!("expected a limit, got {speed:?}"),
+        }
+        assert_eq!(MaxSpeed::parse("walk"), Some(MaxSpeed::Walk));
+        assert_eq!(MaxSpeed::parse("none"), Some(MaxSpeed::None));
+        assert_eq!(
+            MaxSpeed::parse("DE:urban"),
+            Some(MaxSpeed::Implicit("DE:urban".to_string()))
+        );
+        assert_eq!(MaxSpeed::parse("fast"), None);
+        assert_eq!(MaxSpeed::parse("50 furlongs"), None);
+    }
+
+    #[test]
+    fn bool() {
+        assert_eq!(parse_bool("yes"), Some(true));
+        assert_eq!(parse_bool("1"), Some(true));
+        assert_eq!(parse_bool("false"), Some(false));
+        assert_eq!(parse_bool("designated"), None);
+    }
+
+    #[test]
+    fn length() {
+        assert_close(parse_length("4"), 4.0);
+        assert_close(parse_length("4 m"), 4.0);
+        assert_close(parse_length("2.5km"), 2500.0);
+        assert_close(parse_length("3'6\""), 1.0668);
+        assert_close(parse_length("12'"), 3.6576);
+        assert_close(parse_length("10 ft"), 3.048);
+        assert_eq!(parse_length("high"), None);
+        assert_eq!(parse_length("3'6"), None);
+    }
+
+    #[test]
+    fn list_and_layer() {
+        assert_eq!(
+            parse_list("pizza; burger;;").collect::<Vec<_>>(),
+            vec!["pizza", "burger"]
+        );
+        assert_eq!(parse_layer("-1"), Some(-1));
+        assert_eq!(parse_layer("+2"), Some(2));
+        assert_eq!(parse_layer("bridge"), None);
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    collections::BTreeMap,
+    ops::{Deref, DerefMut},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::tag_value::{self, MaxSpeed};
+
+/// The key value pairs describing an element or changeset, ordered by key.
+///
+/// Dereferences to the underlying [`BTreeMap`], so all map methods are available. In OSM JSON the
+/// tags are an object, in OSM XML a list of `<tag k="" v=""/>` elements.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Tags>
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Tags(BTreeMap<String, String>);
+
+impl Tags {
+    /// Construct new empty [`Tags`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check if there are no tags.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Get the underlying map.
+    pub fn into_inner(self) -> BTreeMap<String, String> {
+        self.0
+    }
+}
+
+impl Deref for Tags {
+    type Target = BTreeMap<String, String>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Tags {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<BTreeMap<String, String>> for Tags {
+    fn from(tags: BTreeMap<String, String>) -> Self {
+        Self(tags)
+    }
+}
+
+impl<K: Into<String>, V: Into<String>, const N: usize> From<[(K, V); N]> for Tags {
+    fn from(tags: [(K, V); N]) -> Self {
+        tags.into_iter().collect()
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for Tags {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        Self(
+            iter.into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        )
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> Extend<(K, V)> for Tags {
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        self.0.extend(
+            iter.into_iter()
+                .map(|(key, value)| (key.into(), value.into())),
+        );
+    }
+}
+
+impl IntoIterator for Tags {
+    type Item = (String, String);
+    type IntoIter = std::collections::btree_map::IntoIter<String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Tags {
+    type Item = (&'a String, &'a String);
+    type IntoIter = std::collections::btree_map::Iter<'a, String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// The `addr:*` tags of an element.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Key:addr:*>
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Address<'a> {
+    pub housenumber: Option<&'a str>,
+    pub street: Option<&'a str>,
+    /// Used instead of `street` for addresses without a street, e.g. in villages.
+    pub place: Option<&'a str>,
+    pub postcode: Option<&'a str>,
+    pub city: Option<&'a str>,
+    pub country: Option<&'a str>,
+}
+
+/// Typed access to the values of [`Tags`] that follow common OSM conventions.
+///
+/// All methods return [`None`] if the key is missing or the value does not follow the convention.
+pub trait TagValues {
+    /// Get the raw value of a tag.
+    fn value(&self, key: &str) -> Option<&str>;
+
+    /// Get a yes/no value, see [`tag_value::parse_bool`].
+    fn get_bool(&self, key: &str) -> Option<bool> {
+        tag_value::parse_bool(self.value(key)?)
+    }
+
+    /// Get a dimension like `height` in meters, see [`tag_value::parse_length`].
+    fn get_length(&self, key: &str) -> Option<f64> {
+        tag_value::parse_length(self.value(key)?)
+    }
+
+    /// Get the `maxspeed`, see [`MaxSpeed::parse`].
+    fn max_speed(&self) -> Option<MaxSpeed> {
+        MaxSpeed::parse(self.value("maxspeed")?)
+    }
+
+    /// Get the entries of a semicolon separated value, see [`tag_value::parse_list`].
+    ///
+    /// Returns an empty [`Vec`] if the key is missing.
+    fn get_list(&self, key: &str) -> Vec<&str> {
+        self.value(key)
+            .map(|value| tag_value::parse_list(value).collect())
+            .unwrap_or_default()
+    }
+
+    /// Parse a value with its [`FromStr`] implementation, e.g. `get_parsed::<u32>("lanes")`.
+    fn get_parsed<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.value(key)?.trim().parse().ok()
+    }
+
+    /// Check if the tag `key` has exactly `value`.
+    fn has(&self, key: &str, value: &str) -> bool {
+        self.value(key) == Some(value)
+    }
+
+    /// Check if the value of the tag `key` is one of `values`.
+    fn is_one_of(&self, key: &str, values: &[&str]) -> bool {
+        self.value(key).is_some_and(|value| values.contains(&value))
+    }
+
+    /// Get the name in the language `lang` like `de`, falling back to `name`.
+    fn name(&self, lang: &str) -> Option<&str> {
+        self.value(&format!("name:{lang}"))
+            .or_else(|| self.value("name"))
+    }
+
+    /// Get the [`Address`], or [`None`] if there are no `addr:*` tags.
+    fn addr(&self) -> Option<Address<'_>> {
+        let address = Address {
+            housenumber: self.value("addr:housenumber"),
+            street: self.value("addr:street"),
+            place: self.value("addr:place"),
+            postcode: self.value("addr:postcode"),
+            city: self.value("addr:city"),
+            country: self.value("addr:country"),
+        };
+
+        (address != Address::default()).then_some(address)
+    }
+
+    /// Get the `layer`, which defaults to 0 if the tag is missing.
+    fn layer(&self) -> Option<i8> {
+        match self.value("layer") {
+            Some(value) => tag_value::parse_layer(value),
+            None => Some(0),
+        }
+    }
+}
+
+impl TagValues for Tags {
+    fn value(&self, key: &str) -> Option<&str> {
+        self.get(key).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tags_test {
+    use crate::model::{
+        tag_value::MaxSpeed,
+        tags::{TagValues, Tags},
+    };
+
+    #[test]
+    fn typed_values() {
+        let tags = Tags::from([
+            ("oneway".to_string(), "yes".to_string()),
+            ("maxheight".to_string(), "3.5 m".to_string()),
+            ("maxspeed".to_string(), "walk".to_string()),
+            ("cuisine".to_string(), "pizza;pasta".to_string()),
+        ]);
+
+        assert_eq!(tags.get_bool("oneway"), Some(true));
+        assert_eq!(tags.get_bool("lit"), None);
+        assert_eq!(tags.get_length("maxheight"), Some(3.5));
+        assert_eq!(tags.max_speed(), Some(MaxSpeed::Walk));
+        assert_eq!(tags.get_list("cuisine"), vec!["pizza", "pasta"]);
+        assert!(tags.get_list("shop").is_empty());
+        assert_eq!(tags.layer(), Some(0));
+    }
+
+    #[test]
+    fn accessors() {
+        let tags = Tags::from([
+            ("lanes", " 2"),
+            ("highway", "residential"),
+            ("name", "Marienplatz"),
+            ("name:en", "Mary's Square"),
+            ("addr:street", "Marienplatz"),
+            ("addr:housenumber", "8"),
+        ]);
+
+        assert_eq!(tags.get_parsed::<u32>("lanes"), Some(2));
+        assert_eq!(tags.get_parsed::<u32>("highway"), None);
+        assert!(tags.has("highway", "residential"));
+        assert!(!tags.has("highway", "primary"));
+        assert!(tags.is_one_of("highway", &["primary", "residential"]));
+        assert!(!tags.is_one_of("railway", &["rail"]));
+        assert_eq!(tags.name("en"), Some("Mary's Square"));
+        assert_eq!(tags.name("fr"), Some("Marienplatz"));
+
+        let address = tags.addr().unwrap();
+        assert_eq!(address.street, Some("Marienplatz"));
+        assert_eq!(address.housenumber, Some("8"));
+        assert_eq!(address.city, None);
+        assert_eq!(Tags::new().addr(), None);
+    }
+
+    #[test]
+    fn serde() {
+        let tags: Tags = serde_json::from_str(r#"{"name":"B","amenity":"bench"}"#).unwrap();
+
+        assert_eq!(
+            tags.keys().map(String::as_str).collect::<Vec<_>>(),
+            vec!["amenity", "name"]
+        );
+        assert_eq!(
+            serde_json::to_string(&tags).unwrap(),
+            r#"{"amenity":"bench","name":"B"}"#
+        );
+    }
+}
@@ -0,0 +1,435 @@
+pub mod error;
+
+use std::collections::HashMap;
+
+use crate::{
+    coord::{
+        coordinates::{Coordinates, distance_m},
+        polygon::Polygon,
+        polyline::Polyline,
+    },
+    model::{
+        element::{Element, ElementType, Relation},
+        id::{NodeId, RelationId, WayId},
+    },
+    multipolygon::error::Error,
+};
+
+/// A node of a ring together with its position.
+type Vertex = (NodeId, Coordinates);
+
+/// Assembles `multipolygon` and `boundary` relations into [`Polygon`]s with holes.
+///
+/// The nodes, ways and relations can come from any source, e.g. an
+/// [`ElementReader`](crate::element_reader::ElementReader) or an API response. Every relation is
+/// assembled on its own, so a broken relation does not affect the others.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Relation:multipolygon>
+#[derive(Debug, Default, Clone)]
+pub struct Assembler {
+    nodes: HashMap<NodeId, Coordinates>,
+    ways: HashMap<WayId, Vec<NodeId>>,
+    relations: Vec<Relation>,
+    gap_tolerance: f64,
+}
+
+impl Assembler {
+    /// Construct a new empty [`Assembler`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Join ring segments whose ends are up to `meters` apart instead of failing with
+    /// [`Error::OpenRing`].
+    ///
+    /// Defaults to 0, so only segments sharing a node or a position are joined.
+    pub fn with_gap_tolerance(mut self, meters: f64) -> Self {
+        self.gap_tolerance = meters;
+        self
+    }
+
+    /// Add an [`Element`].
+    ///
+    /// Nodes without a position and relations that are neither a `multipolygon` nor a `boundary`
+    /// are ignored.
+    pub fn add(&mut self, element: Element) {
+        match element {
+            Element::Node(node) => {
+                if let Some(coordinates) = node.coordinates() {
+                    self.nodes.insert(node.id, coordinates);
+                }
+            }
+            Element::Way(way) => {
+                self.ways.insert(way.id, way.nodes);
+            }
+            Element::Relation(relation) => {
+                if matches!(
+                    relation.tags.get("type").map(String::as_str),
+                    Some("multipolygon" | "boundary")
+                ) {
+                    self.relations.push(relation);
+                }
+            }
+        }
+    }
+
+    /// Get the added relations in the order they were added.
+    pub fn relations(&self) -> &[Relation] {
+        &self.relations
+    }
+
+    /// Assemble every added relation in the order they were added.
+    pub fn assemble_all(&self) -> Vec<(RelationId, Result<Vec<Polygon>, Error>)> {
+        self.relations
+            .iter()
+            .map(|relation| (relation.id, self.assemble(relation)))
+            .collect()
+    }
+
+    /// Assemble a single relation from its `outer` and `inner` way members.
+    ///
+    /// Members without a role count as `outer`, other roles like `admin_centre` or `subarea` are
+    /// ignored. The exterior rings are counterclockwise and the interior rings clockwise.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error`] if a member is missing, a ring can not be closed or an inner ring is
+    /// not inside any outer ring.
+    pub fn assemble(&self, relation: &Relation) -> Result<Vec<Polygon>, Error> {
+        let mut outer = Vec::new();
+        let mut inner = Vec::new();
+        for member in relation.members.iter() {
+            if member.member_type != ElementType::Way {
+                continue;
+            }
+            match member.role.as_str() {
+                "outer" | "" => outer.push(self.segment(WayId::new(member.reference))?),
+                "inner" => inner.push(self.segment(WayId::new(member.reference))?),
+                _ => (),
+            }
+        }
+
+        let outer = self.rings(outer)?;
+        if outer.is_empty() {
+            return Err(Error::NoOuterRing);
+        }
+        let inner = self.rings(inner)?;
+
+        let mut polygons: Vec<(Vec<Coordinates>, Vec<Polyline>)> = outer
+            .iter()
+            .map(|ring| (oriented(ring, true), Vec::new()))
+            .collect();
+        for ring in inner.iter() {
+            // The smallest outer ring containing the inner ring, in case outer rings are nested.
+            let (index, _) = outer
+                .iter()
+                .enumerate()
+                .filter(|(_, outer)| contains_ring(outer, ring))
+                .map(|(index, outer)| (index, signed_area(outer).abs()))
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                .ok_or(Error::UnassignedInner(ring[0].0))?;
+
+            polygons[index].1.push(oriented(ring, false).into());
+        }
+
+        Ok(polygons
+            .into_iter()
+            .map(|(exterior, interiors)| Polygon::new(exterior.into(), interiors))
+            .collect())
+    }
+
+    /// Resolve the positions of the nodes of a way.
+    fn segment(&self, id: WayId) -> Result<Vec<Vertex>, Error> {
+        self.ways
+            .get(&id)
+            .ok_or(Error::MissingWay(id))?
+            .iter()
+            .map(|node| {
+                self.nodes
+                    .get(node)
+                    .map(|coordinates| (*node, *coordinates))
+                    .ok_or(Error::MissingNode((id, *node)))
+            })
+            .collect()
+    }
+
+    /// Join segments into closed rings.
+    fn rings(&self, segments: Vec<Vec<Vertex>>) -> Result<Vec<Vec<Vertex>>, Error> {
+        let mut segments: Vec<Vec<Vertex>> = segments
+            .into_iter()
+            .filter(|segment| !segment.is_empty())
+            .rev()
+            .collect();
+        let mut rings = Vec::new();
+
+        while let Some(mut ring) = segments.pop() {
+            loop {
+                let (first, last) = (ring[0], ring[ring.len() - 1]);
+                if ring.len() > 1 && first.0 == last.0 {
+                    break;
+                }
+
+                // Prefer segments sharing the node over segments within the gap tolerance.
+                if let Some((index, reverse)) = find_segment(&segments, |vertex| vertex.0 == last.0)
+                {
+                    let segment = take_segment(&mut segments, index, reverse);
+                    ring.extend(segment.into_iter().skip(1));
+                } else if let Some((index, reverse)) = find_segment(&segments, |vertex| {
+                    distance_m(vertex.1, last.1) <= self.gap_tolerance
+                }) {
+                    let segment = take_segment(&mut segments, index, reverse);
+                    ring.extend(segment);
+                } else if ring.len() > 2 && distance_m(first.1, last.1) <= self.gap_tolerance {
+                    ring.push(first);
+                } else {
+                    return Err(Error::OpenRing(first.0));
+                }
+            }
+
+            if ring.len() < 4 {
+                return Err(Error::DegenerateRing(ring[0].0));
+            }
+            rings.push(ring);
+        }
+
+        Ok(rings)
+    }
+}
+
+impl Extend<Element> for Assembler {
+    fn extend<T: IntoIterator<Item = Element>>(&mut self, iter: T) {
+        for element in iter {
+            self.add(element);
+        }
+    }
+}
+
+impl FromIterator<Element> for Assembler {
+    fn from_iter<T: IntoIterator<Item = Element>>(iter: T) -> Self {
+        let mut assembler = Self::new();
+        assembler.extend(iter);
+        assembler
+    }
+}
+
+/// Find the segment with a first or last vertex matching `predicate`.
+///
+/// Returns its index and whether it has to be reversed.
+fn find_segment(
+    segments: &[Vec<Vertex>],
+    predicate: impl Fn(&Vertex) -> bool,
+) -> Option<(usize, bool)> {
+    segments
+        .iter()
+        .enumerate()
+        .rev()
+        .find_map(|(index, segment)| {
+            if predicate(&segment[0]) {
+                Some((index, false))
+            } else if predicate(&segment[segment.len() - 1]) {
+                Some((index, true))
+            } else {
+                None
+            }
+        })
+}
+
+fn take_segment(segments: &mut Vec<Vec<Vertex>>, index: usize, reverse: bool) -> Vec<Vertex> {
+    let mut segment = segments.remove(index);
+    if reverse {
+        segment.reverse();
+    }
+    segment
+}
+
+/// Get the coordinates of a ring, counterclockwise or clockwise.
+fn oriented(ring: &[Vertex], counterclockwise: bool) -> Vec<Coordinates> {
+    let mut coordinates: Vec<Coordinates> =
+        ring.iter().map(|(_, coordinates)| *coordinates).collect();
+    if (signed_area(ring) > 0.0) != counterclockwise {
+        coordinates.reverse();
+    }
+    coordinates
+}
+
+/// Get the planar area of a closed ring in square degrees, positive if it is counterclockwise.
+fn signed_area(ring: &[Vertex]) -> f64 {
+    ring.windows(2)
+        .map(|pair| {
+            let (x1, y1) = position(pair[0].1);
+            let (x2, y2) = position(pair[1].1);
+            x1 * y2 - x2 * y1
+        })
+        .sum::<f64>()
+        / 2.0
+}
+
+/// Check if `inner` lies inside `outer`.
+///
+/// Vertices shared with `outer` are ignored, since inner rings may touch their outer ring.
+fn contains_ring(outer: &[Vertex], inner: &[Vertex]) -> bool {
+    let mut vertices = inner
+        .iter()
+        .filter(|vertex| !outer.iter().any(|other| other.0 == vertex.0))
+        .peekable();
+
+    vertices.peek().is_some() && vertices.all(|vertex| contains_point(outer, vertex.1))
+}
+
+/// Check if a closed ring contains a point using ray casting.
+fn contains_point(ring: &[Vertex], point: Coordinates) -> bool {
+    let (x, y) = position(point);
+
+    ring.windows(2).fold(false, |inside, pair| {
+        let (x1, y1) = position(pair[0].1);
+        let (x2, y2) = position(pair[1].1);
+        if (y1 > y) != (y2 > y) && x < (x2 - x1) * (y - y1) / (y2 - y1) + x1 {
+            !inside
+        } else {
+            inside
+        }
+    })
+}
+
+/// Get the longitude and latitude as `f64`.
+fn position(coordinates: Coordinates) -> (f64, f64) {
+    (
+        to_f64(coordinates.longitude().value()),
+        to_f64(coordinates.latitude().value()),
+    )
+}
+
+/// Widen a value if [`crate::coord::CoordinateType`] is `f32`.
+fn to_f64(value: impl Into<f64>) -> f64 {
+    value.into()
+}
+
+#[cfg(test)]
+mod multipolygon_test {
+    use crate::{
+        model::{
+            element::{Element, Relation, RelationMember},
+            fixture::{node, way},
+            id::{NodeId, RelationId, WayId},
+            tags::Tags,
+        },
+        multipolygon::{Assembler, error::Error, signed_area},
+    };
+
+    fn relation(id: i64, members: &[(i64, &str)]) -> Relation {
+        Relation {
+            id: RelationId::new(id),
+            members: members
+                .iter()
+                .map(|(way, role)| RelationMember::new(WayId::new(*way), *role))
+                .collect(),
+            tags: Tags::from([("type".to_string(), "multipolygon".to_string())]),
+            ..Default::default()
+        }
+    }
+
+    /// A 4x4 square split into two ways with a 1x1 hole, plus a separate island.
+    fn elements() -> Vec<Element> {
+        vec![
+            node(1, 0.0, 0.0),
+            node(2, 0.0, 4.0),
+            node(3, 4.0, 4.0),
+            node(4, 4.0, 0.0),
+            node(5, 1.0, 1.0),
+            node(6, 2.0, 1.0),
+            node(7, 2.0, 2.0),
+            node(8, 1.0, 2.0),
+            node(9, 10.0, 10.0),
+            node(10, 10.0, 11.0),
+            node(11, 11.0, 11.0),
+            way(1, &[1, 2, 3]),
+            way(2, &[1, 4, 3]),
+            way(3, &[5, 6, 7, 8, 5]),
+            way(4, &[9, 10, 11, 9]),
+        ]
+    }
+
+    fn ring_area(polygon: &crate::coord::polygon::Polygon, interior: Option<usize>) -> f64 {
+        let ring = match interior {
+            Some(index) => &polygon.interiors()[index],
+            None => polygon.exterior(),
+        };
+        let vertices: Vec<_> = ring
+            .coordinates()
+            .iter()
+            .map(|coordinates| (NodeId::new(0), *coordinates))
+            .collect();
+        signed_area(&vertices)
+    }
+
+    #[test]
+    fn assemble() {
+        let mut assembler: Assembler = elements().into_iter().collect();
+        let relation = relation(1, &[(1, "outer"), (3, "inner"), (2, "outer"), (4, "")]);
+        assembler.add(Element::Relation(relation.clone()));
+
+        let polygons = assembler.assemble(&relation).unwrap();
+        assert_eq!(polygons.len(), 2);
+        assert_eq!(polygons[0].exterior().coordinates().len(), 5);
+        assert_eq!(polygons[0].interiors().len(), 1);
+        assert!(polygons[1].interiors().is_empty());
+
+        assert_eq!(ring_area(&polygons[0], None), 16.0);
+        assert_eq!(ring_area(&polygons[0], Some(0)), -1.0);
+        assert!(ring_area(&polygons[1], None) > 0.0);
+
+        let all = assembler.assemble_all();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].0, RelationId::new(1));
+    }
+
+    #[test]
+    fn gap_tolerance() {
+        let mut elements = elements();
+        // Way 2 ends about 11 m short of node 3.
+        elements.push(node(12, 3.9999, 4.0));
+        elements.push(way(2, &[1, 4, 12]));
+        let relation = relation(1, &[(1, "outer"), (2, "outer")]);
+
+        let assembler: Assembler = elements.clone().into_iter().collect();
+        assert!(matches!(
+            assembler.assemble(&relation),
+            Err(Error::OpenRing(_))
+        ));
+
+        let mut assembler = Assembler::new().with_gap_tolerance(20.0);
+        assembler.extend(elements);
+        let polygons = assembler.assemble(&relation).unwrap();
+        assert_eq!(polygons[0].exterior().coordinates().len(), 6);
+    }
+
+    #[test]
+    fn errors() {
+        let assembler: Assembler = elements().into_iter().collect();
+
+        assert!(matches!(
+            assembler.assemble(&relation(1, &[(5, "outer")])),
+            Err(Error::MissingWay(_))
+        ));
+        assert!(matches!(
+            assembler.assemble(&relation(1, &[(3, "inner")])),
+            Err(Error::NoOuterRing)
+        ));
+        assert!(matches!(
+            assembler.assemble(&relation(1, &[(4, "outer"), (3, "inner")])),
+            Err(Error::UnassignedInner(_))
+        ));
+
+        let mut assembler = assembler;
+        assembler.add(way(6, &[1, 2, 1]));
+        assembler.add(way(7, &[1, 99, 2]));
+        assert!(matches!(
+            assembler.assemble(&relation(1, &[(6, "outer")])),
+            Err(Error::DegenerateRing(_))
+        ));
+        assert!(matches!(
+            assembler.assemble(&relation(1, &[(7, "outer")])),
+            Err(Error::MissingNode(_))
+        ));
+    }
+}
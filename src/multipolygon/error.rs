@@ -0,0 +1,17 @@
+use crate::model::id::{NodeId, WayId};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("The member way {0} is missing")]
+    MissingWay(WayId),
+    #[error("The node {} of way {} is missing", .0.1, .0.0)]
+    MissingNode((WayId, NodeId)),
+    #[error("The ring starting at node {0} can not be closed")]
+    OpenRing(NodeId),
+    #[error("The ring starting at node {0} has less than three distinct nodes")]
+    DegenerateRing(NodeId),
+    #[error("The relation has no outer ring")]
+    NoOuterRing,
+    #[error("The inner ring starting at node {0} is not inside an outer ring")]
+    UnassignedInner(NodeId),
+}
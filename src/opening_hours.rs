@@ -0,0 +1,792 @@
+pub mod error;
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::opening_hours::error::Error;
+
+const MINUTES_PER_DAY: u16 = 24 * 60;
+/// The time ranges of a rule without times.
+const FULL_DAY: [(u16, u16); 1] = [(0, MINUTES_PER_DAY)];
+/// The amount of days [`OpeningHours::next_change`] looks ahead.
+const LOOKAHEAD_DAYS: i64 = 2 * 366;
+
+const WEEKDAYS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+/// Variable times that need the position of the sun.
+const EVENTS: [&str; 4] = ["sunrise", "sunset", "dawn", "dusk"];
+
+/// The state of a feature at a point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RuleState {
+    Open,
+    Closed,
+    Unknown,
+}
+
+/// A date and time in the local time zone of the feature.
+///
+/// The order is chronological.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LocalDateTime {
+    year: i32,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+}
+
+impl LocalDateTime {
+    /// Construct a new [`LocalDateTime`].
+    ///
+    /// Returns [`None`] if the date does not exist or the time is out of range.
+    pub fn new(year: i32, month: u8, day: u8, hour: u8, minute: u8) -> Option<Self> {
+        let valid = (1..=12).contains(&month)
+            && day >= 1
+            && day <= days_in_month(year, month)
+            && hour < 24
+            && minute < 60;
+
+        valid.then_some(Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+        })
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    /// Get the day of the week, starting with 0 for Monday.
+    pub fn weekday(&self) -> u8 {
+        weekday(self.days())
+    }
+
+    /// Get the amount of days since 1970-01-01.
+    fn days(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day)
+    }
+
+    fn minute_of_day(&self) -> u16 {
+        self.hour as u16 * 60 + self.minute as u16
+    }
+
+    fn from_days(days: i64, minute_of_day: u16) -> Self {
+        let (year, month, day) = civil_from_days(days);
+
+        Self {
+            year,
+            month,
+            day,
+            hour: (minute_of_day / 60) as u8,
+            minute: (minute_of_day % 60) as u8,
+        }
+    }
+}
+
+impl Display for LocalDateTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute
+        )
+    }
+}
+
+/// A parsed `opening_hours` value that can be evaluated at any [`LocalDateTime`].
+///
+/// Supported are `24/7`, month and date ranges like `Jan-Mar` or `Dec 24-26`, weekday ranges and
+/// lists like `Mo-Fr,Su`, time ranges including ones past midnight like `22:00-02:00`, the
+/// modifiers `open`, `closed`, `off` and `unknown`, comments and the rule separators `;`, `,`
+/// and `||`.
+/// Public and school holidays (`PH`, `SH`) are accepted but never match since they depend on the
+/// region. Variable times like `sunset`, week numbers, years, nth weekdays and open ends are
+/// reported as [`Error::Unsupported`].
+///
+/// See <https://wiki.openstreetmap.org/wiki/Key:opening_hours/specification>
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpeningHours {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    separator: Separator,
+    dates: Vec<DateRange>,
+    /// The matching weekdays, [`None`] if the rule has no weekday selector.
+    weekdays: Option<[bool; 7]>,
+    /// Minutes since the start of the day. Ranges past midnight end after [`MINUTES_PER_DAY`].
+    times: Vec<(u16, u16)>,
+    state: RuleState,
+}
+
+/// How a rule is combined with the rules before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Separator {
+    /// `;`, the rule replaces the earlier rules on the days it matches.
+    Normal,
+    /// `,`, the rule is added to the earlier rules.
+    Additional,
+    /// `||`, the rule only applies on days no earlier rule matches.
+    Fallback,
+}
+
+/// An inclusive range of (month, day) pairs, which wraps around the end of the year if the start
+/// is after the end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DateRange {
+    start: (u8, u8),
+    end: (u8, u8),
+}
+
+impl OpeningHours {
+    /// Parse an `opening_hours` value like `Mo-Fr 08:00-18:00; Sa 10:00-14:00`.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error`] if the value is malformed or uses an unsupported feature.
+    pub fn parse(value: &str) -> Result<Self, Error> {
+        Parser {
+            input: value,
+            position: 0,
+        }
+        .parse()
+    }
+
+    /// Get the [`RuleState`] at `at`.
+    pub fn state(&self, at: LocalDateTime) -> RuleState {
+        self.state_at(at.days(), at.minute_of_day())
+    }
+
+    /// Check if the feature is open at `at`.
+    ///
+    /// [`RuleState::Unknown`] counts as closed.
+    pub fn is_open(&self, at: LocalDateTime) -> bool {
+        self.state(at) == RuleState::Open
+    }
+
+    /// Get the next point in time after `at` at which the [`RuleState`] changes.
+    ///
+    /// Returns [`None`] if the state does not change within the next two years, e.g. for `24/7`.
+    pub fn next_change(&self, at: LocalDateTime) -> Option<LocalDateTime> {
+        let initial = self.state(at);
+        let first_day = at.days();
+
+        for days in first_day..first_day + LOOKAHEAD_DAYS {
+            let mut boundaries = vec![0];
+            for (start, end, _) in self.schedule(days) {
+                boundaries.extend([start, end].into_iter().filter(|m| *m < MINUTES_PER_DAY));
+            }
+            for (start, end, _) in self.schedule(days - 1) {
+                boundaries.extend(
+                    [start, end]
+                        .into_iter()
+                        .filter(|m| *m > MINUTES_PER_DAY && *m < 2 * MINUTES_PER_DAY)
+                        .map(|m| m - MINUTES_PER_DAY),
+                );
+            }
+            boundaries.sort_unstable();
+            boundaries.dedup();
+
+            for minute in boundaries {
+                if (days > first_day || minute > at.minute_of_day())
+                    && self.state_at(days, minute) != initial
+                {
+                    return Some(LocalDateTime::from_days(days, minute));
+                }
+            }
+        }
+
+        None
+    }
+
+    fn state_at(&self, days: i64, minute: u16) -> RuleState {
+        let contains = |minute: u16| {
+            move |(start, end, _): &&(u16, u16, RuleState)| *start <= minute && minute < *end
+        };
+
+        // Later rules take precedence over earlier ones.
+        if let Some((_, _, state)) = self.schedule(days).iter().rev().find(contains(minute)) {
+            return *state;
+        }
+        // Ranges past midnight of the day before.
+        if let Some((_, _, state)) = self
+            .schedule(days - 1)
+            .iter()
+            .rev()
+            .find(contains(minute + MINUTES_PER_DAY))
+        {
+            return *state;
+        }
+
+        RuleState::Closed
+    }
+
+    /// Get the open and unknown time ranges of a day, in order of precedence.
+    fn schedule(&self, days: i64) -> Vec<(u16, u16, RuleState)> {
+        let (_, month, day) = civil_from_days(days);
+        let weekday = weekday(days);
+        let mut schedule: Vec<(u16, u16, RuleState)> = Vec::new();
+        let mut matched = false;
+
+        for rule in self.rules.iter() {
+            if (rule.separator == Separator::Fallback && matched)
+                || !rule.matches(month, day, weekday)
+            {
+                continue;
+            }
+            matched = true;
+
+            let times = if rule.times.is_empty() {
+                &FULL_DAY[..]
+            } else {
+                &rule.times
+            };
+            if rule.state == RuleState::Closed {
+                for (closed_start, closed_end) in times {
+                    schedule = schedule
+                        .into_iter()
+                        .flat_map(|(start, end, state)| {
+                            [
+                                (start, end.min(*closed_start), state),
+                                (start.max(*closed_end), end, state),
+                            ]
+                        })
+                        .filter(|(start, end, _)| start < end)
+                        .collect();
+                }
+            } else {
+                if rule.separator != Separator::Additional {
+                    schedule.clear();
+                }
+                schedule.extend(times.iter().map(|(start, end)| (*start, *end, rule.state)));
+            }
+        }
+
+        schedule
+    }
+}
+
+impl FromStr for OpeningHours {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Rule {
+    fn matches(&self, month: u8, day: u8, weekday: u8) -> bool {
+        let date_matches =
+            self.dates.is_empty() || self.dates.iter().any(|range| range.contains((month, day)));
+        let weekday_matches = self
+            .weekdays
+            .is_none_or(|weekdays| weekdays[weekday as usize]);
+
+        date_matches && weekday_matches
+    }
+}
+
+impl DateRange {
+    fn contains(&self, date: (u8, u8)) -> bool {
+        if self.start <= self.end {
+            self.start <= date && date <= self.end
+        } else {
+            date >= self.start || date <= self.end
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn parse(mut self) -> Result<OpeningHours, Error> {
+        let mut rules = Vec::new();
+        let mut separator = Separator::Normal;
+
+        loop {
+            self.skip_whitespace();
+            if self.rest().is_empty() && !rules.is_empty() {
+                // A trailing `;` is common.
+                break;
+            }
+            rules.push(self.rule(separator)?);
+
+            self.skip_whitespace();
+            separator = if self.eat("||") {
+                Separator::Fallback
+            } else if self.eat(";") {
+                Separator::Normal
+            } else if self.eat(",") {
+                Separator::Additional
+            } else if self.rest().is_empty() {
+                break;
+            } else {
+                return Err(self.error("expected a rule separator"));
+            };
+        }
+
+        Ok(OpeningHours { rules })
+    }
+
+    fn rule(&mut self, separator: Separator) -> Result<Rule, Error> {
+        let start = self.position;
+        let mut rule = Rule {
+            separator,
+            dates: Vec::new(),
+            weekdays: None,
+            times: Vec::new(),
+            state: RuleState::Open,
+        };
+
+        if self.eat("24/7") {
+            rule.times = FULL_DAY.to_vec();
+        } else {
+            let digits = self
+                .rest()
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(self.rest().len());
+            if digits == 4 {
+                return Err(Error::Unsupported("year".to_string()));
+            }
+            if MONTHS.contains(&self.word()) {
+                rule.dates = self.dates()?;
+                self.skip_whitespace();
+            }
+            if is_weekday(self.word()) {
+                rule.weekdays = Some(self.weekdays()?);
+                self.skip_whitespace();
+            }
+            match self.word() {
+                "week" => return Err(Error::Unsupported("week".to_string())),
+                word if EVENTS.contains(&word) => return Err(Error::Unsupported(word.to_string())),
+                _ => (),
+            }
+            if self.rest().starts_with(|c: char| c.is_ascii_digit()) {
+                rule.times = self.times()?;
+            }
+        }
+
+        self.skip_whitespace();
+        let state = match self.word() {
+            "open" => Some(RuleState::Open),
+            "closed" | "off" => Some(RuleState::Closed),
+            "unknown" => Some(RuleState::Unknown),
+            _ => None,
+        };
+        if let Some(state) = state {
+            self.position += self.word().len();
+            rule.state = state;
+            self.skip_whitespace();
+        }
+
+        let mut comment = false;
+        if self.eat("\"") {
+            match self.rest().find('"') {
+                Some(end) => self.position += end + 1,
+                None => return Err(self.error("unterminated comment")),
+            }
+            comment = true;
+        }
+
+        if self.position == start || (comment && self.position == start + 1) {
+            return Err(self.error("expected a rule"));
+        }
+
+        Ok(rule)
+    }
+
+    fn dates(&mut self) -> Result<Vec<DateRange>, Error> {
+        let mut dates = Vec::new();
+
+        loop {
+            let (start_month, start_day) = self.month_day()?;
+            let (end_month, end_day) = if self.eat("-") {
+                if MONTHS.contains(&self.word()) {
+                    self.month_day()?
+                } else if start_day.is_some() {
+                    (start_month, Some(self.day()?))
+                } else {
+                    return Err(self.error("expected a month"));
+                }
+            } else {
+                (start_month, start_day)
+            };
+
+            dates.push(DateRange {
+                start: (start_month, start_day.unwrap_or(1)),
+                end: (end_month, end_day.unwrap_or(31)),
+            });
+
+            if !self.continue_list(|parser| MONTHS.contains(&parser.word())) {
+                return Ok(dates);
+            }
+        }
+    }
+
+    /// Parse a month with an optional day like `Dec` or `Dec 24`.
+    fn month_day(&mut self) -> Result<(u8, Option<u8>), Error> {
+        let month = MONTHS
+            .iter()
+            .position(|month| *month == self.word())
+            .ok_or_else(|| self.error("expected a month"))?;
+        self.position += 3;
+
+        // A day is followed by anything but `:`, which would make it a time.
+        let rest = self.rest().trim_start();
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let day = if digits > 0 && !rest[digits..].starts_with(':') {
+            self.skip_whitespace();
+            Some(self.day()?)
+        } else {
+            None
+        };
+
+        Ok((month as u8 + 1, day))
+    }
+
+    fn day(&mut self) -> Result<u8, Error> {
+        match self.number() {
+            Some(day @ 1..=31) => Ok(day as u8),
+            _ => Err(self.error("expected a day")),
+        }
+    }
+
+    fn weekdays(&mut self) -> Result<[bool; 7], Error> {
+        let mut weekdays = [false; 7];
+
+        loop {
+            let word = self.word();
+            self.position += word.len();
+
+            // Holidays depend on the region and never match.
+            if !matches!(word, "PH" | "SH") {
+                let start = weekday_index(word).ok_or_else(|| self.error("expected a weekday"))?;
+                if self.rest().starts_with('[') {
+                    return Err(Error::Unsupported("nth weekday".to_string()));
+                }
+
+                let end = if self.eat("-") {
+                    let word = self.word();
+                    self.position += word.len();
+                    weekday_index(word).ok_or_else(|| self.error("expected a weekday"))?
+                } else {
+                    start
+                };
+
+                let mut day = start;
+                loop {
+                    weekdays[day] = true;
+                    if day == end {
+                        break;
+                    }
+                    day = (day + 1) % 7;
+                }
+            }
+
+            if !self.continue_list(|parser| is_weekday(parser.word())) {
+                return Ok(weekdays);
+            }
+        }
+    }
+
+    fn times(&mut self) -> Result<Vec<(u16, u16)>, Error> {
+        let mut times = Vec::new();
+
+        loop {
+            let start = self.time()?;
+            if !self.eat("-") {
+                return Err(self.error("expected `-`"));
+            }
+            let mut end = self.time()?;
+            if self.rest().starts_with('+') {
+                return Err(Error::Unsupported("open end".to_string()));
+            }
+
+            if end <= start {
+                end += MINUTES_PER_DAY;
+            }
+            times.push((start, end));
+
+            if !self.continue_list(|parser| parser.rest().starts_with(|c: char| c.is_ascii_digit()))
+            {
+                return Ok(times);
+            }
+        }
+    }
+
+    /// Parse a time like `08:30` into minutes since the start of the day.
+    fn time(&mut self) -> Result<u16, Error> {
+        if EVENTS.contains(&self.word()) {
+            return Err(Error::Unsupported(self.word().to_string()));
+        }
+
+        let hour = self.number().ok_or_else(|| self.error("expected a time"))?;
+        if !self.eat(":") {
+            return Err(self.error("expected `:`"));
+        }
+        let minute = self
+            .number()
+            .ok_or_else(|| self.error("expected minutes"))?;
+
+        if minute >= 60 || hour > 24 || (hour == 24 && minute > 0) {
+            return Err(self.error("time is out of range"));
+        }
+
+        Ok(hour * 60 + minute)
+    }
+
+    /// Consume a `,` if it is followed by another list item instead of a new rule.
+    fn continue_list(&mut self, is_item: impl Fn(&Self) -> bool) -> bool {
+        let position = self.position;
+        if self.eat(",") {
+            self.skip_whitespace();
+            if is_item(self) {
+                return true;
+            }
+        }
+
+        self.position = position;
+        false
+    }
+
+    fn number(&mut self) -> Option<u16> {
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let number = rest[..end].parse().ok()?;
+        self.position += end;
+
+        Some(number)
+    }
+
+    /// Get the alphabetic word at the current position without consuming it.
+    fn word(&self) -> &'a str {
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+
+        &rest[..end]
+    }
+
+    fn eat(&mut self, pattern: &str) -> bool {
+        if self.rest().starts_with(pattern) {
+            self.position += pattern.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn error(&self, message: &'static str) -> Error {
+        Error::Syntax((self.position, message))
+    }
+}
+
+fn is_weekday(word: &str) -> bool {
+    matches!(word, "PH" | "SH") || weekday_index(word).is_some()
+}
+
+fn weekday_index(word: &str) -> Option<usize> {
+    WEEKDAYS.iter().position(|weekday| *weekday == word)
+}
+
+/// Get the day of the week of the days since 1970-01-01, starting with 0 for Monday.
+fn weekday(days: i64) -> u8 {
+    // 1970-01-01 was a Thursday.
+    (days + 3).rem_euclid(7) as u8
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// See <https://howardhinnant.github.io/date_algorithms.html>
+fn days_from_civil(year: i32, month: u8, day: u8) -> i64 {
+    let year = year as i64 - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// See <https://howardhinnant.github.io/date_algorithms.html>
+fn civil_from_days(days: i64) -> (i32, u8, u8) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = era * 400 + year_of_era + i64::from(month <= 2);
+
+    (year as i32, month as u8, day as u8)
+}
+
+#[cfg(test)]
+mod opening_hours_test {
+    use crate::opening_hours::{
+        LocalDateTime, OpeningHours, RuleState, civil_from_days, days_from_civil, error::Error,
+    };
+
+    /// 2025-01-06 is a Monday.
+    fn at(day: u8, hour: u8, minute: u8) -> LocalDateTime {
+        LocalDateTime::new(2025, 1, day, hour, minute).unwrap()
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(civil_from_days(days_from_civil(2024, 2, 29)), (2024, 2, 29));
+        assert_eq!(at(6, 0, 0).weekday(), 0);
+        assert_eq!(at(12, 0, 0).weekday(), 6);
+        assert!(LocalDateTime::new(2025, 2, 29, 0, 0).is_none());
+    }
+
+    #[test]
+    fn weekdays_and_times() {
+        let hours = OpeningHours::parse("Mo-Fr 08:00-12:00,13:00-18:00; Sa 10:00-14:00").unwrap();
+
+        assert!(hours.is_open(at(6, 8, 0)));
+        assert!(!hours.is_open(at(6, 12, 30)));
+        assert!(!hours.is_open(at(6, 18, 0)));
+        assert!(hours.is_open(at(11, 10, 0)));
+        assert!(!hours.is_open(at(12, 10, 0)));
+
+        assert_eq!(hours.next_change(at(6, 9, 0)), Some(at(6, 12, 0)));
+        assert_eq!(hours.next_change(at(6, 19, 0)), Some(at(7, 8, 0)));
+        assert_eq!(hours.next_change(at(11, 15, 0)), Some(at(13, 8, 0)));
+    }
+
+    #[test]
+    fn past_midnight() {
+        let hours = OpeningHours::parse("Fr,Sa 22:00-3:00").unwrap();
+
+        assert!(hours.is_open(at(10, 23, 0)));
+        assert!(hours.is_open(at(11, 2, 59)));
+        assert!(!hours.is_open(at(11, 3, 0)));
+        assert!(hours.is_open(at(12, 1, 0)));
+        assert!(!hours.is_open(at(13, 1, 0)));
+        assert_eq!(hours.next_change(at(11, 1, 0)), Some(at(11, 3, 0)));
+    }
+
+    #[test]
+    fn overrides() {
+        let hours = OpeningHours::parse(
+            r#"24/7; Su off; Jan 01 closed "New Year"; We 12:00-14:00 unknown"#,
+        )
+        .unwrap();
+
+        assert!(hours.is_open(at(6, 3, 0)));
+        assert!(!hours.is_open(at(12, 3, 0)));
+        assert!(!hours.is_open(LocalDateTime::new(2026, 1, 1, 12, 0).unwrap()));
+        assert_eq!(hours.state(at(8, 13, 0)), RuleState::Unknown);
+        assert_eq!(hours.state(at(8, 15, 0)), RuleState::Closed);
+        assert_eq!(
+            OpeningHours::parse("24/7")
+                .unwrap()
+                .next_change(at(6, 0, 0)),
+            None
+        );
+
+        let hours = OpeningHours::parse("Mo-Sa 08:00-18:00, We 12:00-14:00 off").unwrap();
+        assert!(hours.is_open(at(8, 11, 0)));
+        assert!(!hours.is_open(at(8, 13, 0)));
+        assert!(hours.is_open(at(9, 13, 0)));
+    }
+
+    #[test]
+    fn months_and_fallback() {
+        let hours = OpeningHours::parse("Dec 24-Jan 06 10:00-12:00 || Mo-Fr 09:00-17:00").unwrap();
+
+        assert!(hours.is_open(at(2, 11, 0)));
+        assert!(!hours.is_open(at(2, 15, 0)));
+        assert!(hours.is_open(at(7, 15, 0)));
+
+        let hours = OpeningHours::parse("Apr-Sep Mo-Su 08:00-20:00; Oct-Mar off").unwrap();
+        assert!(!hours.is_open(at(6, 10, 0)));
+        assert!(hours.is_open(LocalDateTime::new(2025, 6, 1, 10, 0).unwrap()));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(OpeningHours::parse(""), Err(Error::Syntax(_))));
+        assert!(matches!(
+            OpeningHours::parse("Mo-Fr 08:00"),
+            Err(Error::Syntax(_))
+        ));
+        assert!(matches!(
+            OpeningHours::parse("Mo-Fr 25:00-26:00"),
+            Err(Error::Syntax(_))
+        ));
+        assert!(matches!(
+            OpeningHours::parse("Mo-Fr sunrise-sunset"),
+            Err(Error::Unsupported(_))
+        ));
+        assert!(matches!(
+            OpeningHours::parse("2025 Mo 10:00-12:00"),
+            Err(Error::Unsupported(_))
+        ));
+        assert!(matches!(
+            OpeningHours::parse("Mo[1] 10:00-12:00"),
+            Err(Error::Unsupported(_))
+        ));
+        assert!(matches!(
+            OpeningHours::parse("Mo-Fr 08:00-12:00 foo"),
+            Err(Error::Syntax(_))
+        ));
+    }
+}
@@ -0,0 +1,7 @@
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid opening hours at byte {}: {}", .0.0, .0.1)]
+    Syntax((usize, &'static str)),
+    #[error("The opening hours use the unsupported feature `{0}`")]
+    Unsupported(String),
+}
@@ -0,0 +1,196 @@
+pub mod auth;
+pub mod capabilities;
+pub mod changeset;
+pub mod conflict;
+pub mod credentials;
+pub mod diff_result;
+pub mod edit_session;
+pub mod error;
+pub mod notes;
+pub mod permissions;
+pub mod rate_limit;
+pub mod recovery;
+pub mod traces;
+#[cfg(any(feature = "async", feature = "blocking", test))]
+pub mod trackpoints;
+pub mod user;
+pub mod user_block;
+pub mod validation;
+pub mod versions;
+
+#[cfg(feature = "async")]
+pub mod osm_api_async;
+#[cfg(feature = "blocking")]
+pub mod osm_api_blocking;
+
+/// The API version implemented by this crate.
+pub const API_VERSION: &str = "0.6";
+
+/// Base url of the production OSM API v0.6.
+pub const OSM_API_URL: &str = "https://api.openstreetmap.org/api/0.6";
+
+/// Base url of the OSM development API v0.6.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Sandbox_for_editing>
+pub const OSM_DEV_API_URL: &str = "https://master.apis.dev.openstreetmap.org/api/0.6";
+
+#[cfg(all(test, feature = "blocking"))]
+mod osm_api_test {
+    use std::sync::Arc;
+
+    use reqwest::StatusCode;
+
+    use crate::{
+        coord::{bbox::BBox, coordinates::Coordinates},
+        model::{
+            id::{ChangesetId, ElementId, NodeId, WayId},
+            tags::Tags,
+        },
+        osm_api::{edit_session::EditSession, error::Error, osm_api_blocking::OsmApiClient},
+        retry::RetryPolicy,
+        transport::MockTransport,
+    };
+
+    const CAPABILITIES: &str = r#"{
+        "version": "0.6",
+        "api": {
+            "version": {"minimum": "0.6", "maximum": "0.6"},
+            "area": {"maximum": 1000},
+            "note_area": {"maximum": 1000},
+            "tracepoints": {"per_page": 5000},
+            "waynodes": {"maximum": 2000},
+            "relationmembers": {"maximum": 32000},
+            "changesets": {"maximum_elements": 1, "default_query_limit": 100, "maximum_query_limit": 100},
+            "notes": {"default_query_limit": 100, "maximum_query_limit": 10000},
+            "timeout": {"seconds": 300},
+            "status": {"database": "online", "api": "online", "gpx": "online"}
+        },
+        "policy": {"imagery": {"blacklist": []}}
+    }"#;
+
+    fn note(id: u64, lon: f64) -> String {
+        format!(
+            r#"{{"type": "Feature", "geometry": {{"type": "Point", "coordinates": [{lon}, 0.0]}},
+                "properties": {{"id": {id}, "date_created": "2025-01-01 10:00:00 UTC",
+                "status": "open", "comments": []}}}}"#
+        )
+    }
+
+    #[test]
+    fn antimeridian() {
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response(StatusCode::OK, CAPABILITIES)
+                .with_response(
+                    StatusCode::OK,
+                    r#"{"elements": [{"type": "node", "id": 1, "lat": 0.0, "lon": 175.0},
+                        {"type": "way", "id": 3, "nodes": [1, 2]}]}"#,
+                )
+                .with_response(
+                    StatusCode::OK,
+                    r#"{"elements": [{"type": "node", "id": 2, "lat": 0.0, "lon": -175.0},
+                        {"type": "way", "id": 3, "nodes": [1, 2]}]}"#,
+                )
+                .with_response(
+                    StatusCode::OK,
+                    format!(
+                        r#"{{"type": "FeatureCollection", "features": [{}]}}"#,
+                        note(1, 175.0)
+                    ),
+                )
+                .with_response(
+                    StatusCode::OK,
+                    format!(
+                        r#"{{"type": "FeatureCollection", "features": [{}]}}"#,
+                        note(2, -175.0)
+                    ),
+                )
+                .with_response(
+                    StatusCode::OK,
+                    r#"<gpx><trk><trkseg><trkpt lat="0" lon="175"/></trkseg></trk></gpx>"#,
+                )
+                .with_response(
+                    StatusCode::OK,
+                    r#"<gpx><trk><trkseg><trkpt lat="0" lon="-175"/></trkseg></trk></gpx>"#,
+                ),
+        );
+        let client =
+            OsmApiClient::new("https://osm.example/api/0.6").with_transport(transport.clone());
+        let bbox = BBox::from_wrapped(-10.0, 170.0, 10.0, -170.0);
+
+        let data = client.get_map(&bbox).unwrap();
+        assert_eq!(
+            data.elements
+                .iter()
+                .map(|element| element.id())
+                .collect::<Vec<_>>(),
+            vec![
+                ElementId::from(NodeId::new(1)),
+                WayId::new(3).into(),
+                NodeId::new(2).into()
+            ]
+        );
+        assert_eq!(data.bounds.unwrap().bbox(), bbox);
+
+        let notes = client.get_notes(&bbox, None, None).unwrap();
+        assert_eq!(
+            notes.iter().map(|note| note.id).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+
+        assert_eq!(client.get_trackpoints(&bbox, 0).unwrap().len(), 2);
+
+        let urls: Vec<String> = transport
+            .requests()
+            .into_iter()
+            .map(|request| request.url.replace("%2C", ","))
+            .collect();
+        assert_eq!(urls.len(), 7);
+        for pair in urls[1..].chunks(2) {
+            assert!(pair[0].contains("bbox=170,-10,180,10"), "{}", pair[0]);
+            assert!(pair[1].contains("bbox=-180,-10,-170,10"), "{}", pair[1]);
+        }
+    }
+
+    #[test]
+    fn partial_upload() {
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response(StatusCode::OK, CAPABILITIES)
+                .with_response(StatusCode::OK, "100")
+                .with_response(
+                    StatusCode::OK,
+                    r#"<diffResult><node old_id="-1" new_id="7" new_version="1"/></diffResult>"#,
+                )
+                .with_response(StatusCode::OK, "")
+                .with_response(StatusCode::OK, "101")
+                .with_response(StatusCode::BAD_REQUEST, "invalid")
+                .with_response(StatusCode::OK, ""),
+        );
+        let client = OsmApiClient::new("https://osm.example/api/0.6")
+            .with_retry_policy(RetryPolicy::disabled())
+            .with_transport(transport.clone());
+        let mut session = EditSession::new(Tags::from([("comment", "import")]));
+        session.create_node(Coordinates::from_unchecked(1.0, 2.0), Tags::new());
+        session.create_node(Coordinates::from_unchecked(1.0, 3.0), Tags::new());
+
+        let Err(Error::PartialUpload(partial)) = client.upload_session(&session) else {
+            panic!("expected a partial upload");
+        };
+        let (result, error) = *partial;
+        assert_eq!(result.changesets, vec![ChangesetId::new(100)]);
+        assert_eq!(
+            result.diff.id_map()[&NodeId::new(-1).into()],
+            NodeId::new(7).into()
+        );
+        assert!(matches!(
+            error,
+            Error::HttpStatus((StatusCode::BAD_REQUEST, _))
+        ));
+        assert!(
+            transport.requests()[6]
+                .url
+                .ends_with("/changeset/101/close")
+        );
+    }
+}
@@ -0,0 +1,495 @@
+mod sha256;
+
+use std::{
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::osm_api::{auth::sha256::sha256, credentials::Credentials, error::Error};
+
+/// The authorization endpoint of openstreetmap.org.
+pub const OSM_AUTHORIZE_URL: &str = "https://www.openstreetmap.org/oauth2/authorize";
+
+/// The token endpoint of openstreetmap.org.
+pub const OSM_TOKEN_URL: &str = "https://www.openstreetmap.org/oauth2/token";
+
+/// The authorization endpoint of the development server, see
+/// [`OSM_DEV_API_URL`](crate::osm_api::OSM_DEV_API_URL).
+pub const OSM_DEV_AUTHORIZE_URL: &str =
+    "https://master.apis.dev.openstreetmap.org/oauth2/authorize";
+
+/// The token endpoint of the development server.
+pub const OSM_DEV_TOKEN_URL: &str = "https://master.apis.dev.openstreetmap.org/oauth2/token";
+
+/// The redirect uri of applications without a web server, e.g. command line tools. The
+/// authorization code is shown to the user to be pasted into the application.
+pub const OOB_REDIRECT_URI: &str = "urn:ietf:wg:oauth:2.0:oob";
+
+/// The scopes an application can request.
+///
+/// See <https://wiki.openstreetmap.org/wiki/OAuth#OAuth_2.0>
+pub mod scope {
+    pub const READ_PREFS: &str = "read_prefs";
+    pub const WRITE_PREFS: &str = "write_prefs";
+    pub const WRITE_DIARY: &str = "write_diary";
+    pub const WRITE_API: &str = "write_api";
+    pub const READ_GPX: &str = "read_gpx";
+    pub const WRITE_GPX: &str = "write_gpx";
+    pub const WRITE_NOTES: &str = "write_notes";
+    pub const WRITE_REDACTIONS: &str = "write_redactions";
+}
+
+/// An application registered for OAuth 2.0 on an OSM server, running the authorization code
+/// flow with PKCE.
+///
+/// 1. Send the user to [`OAuth2App::authorization_url`] with a new [`Pkce`] and state.
+/// 2. The server redirects to the redirect uri with the `code` and the state.
+/// 3. Exchange the code for a [`Token`] with the same [`Pkce`], e.g. with
+///    [`OAuth2App::exchange_code`].
+///
+/// See <https://wiki.openstreetmap.org/wiki/OAuth>
+#[derive(Clone, PartialEq, Eq)]
+pub struct OAuth2App {
+    client_id: String,
+    client_secret: Option<String>,
+    redirect_uri: String,
+    authorize_url: String,
+    token_url: String,
+}
+
+impl OAuth2App {
+    /// Construct a new [`OAuth2App`] for openstreetmap.org from the client id and redirect uri
+    /// of its registration.
+    pub fn new(client_id: impl Into<String>, redirect_uri: impl Into<String>) -> Self {
+        Self {
+            client_id: client_id.into(),
+            client_secret: None,
+            redirect_uri: redirect_uri.into(),
+            authorize_url: OSM_AUTHORIZE_URL.to_string(),
+            token_url: OSM_TOKEN_URL.to_string(),
+        }
+    }
+
+    /// Set the client secret of a confidential application.
+    ///
+    /// Applications which can not keep a secret, e.g. desktop applications, rely on [`Pkce`]
+    /// alone.
+    pub fn with_client_secret(mut self, client_secret: impl Into<String>) -> Self {
+        self.client_secret = Some(client_secret.into());
+        self
+    }
+
+    /// Use the endpoints of another server, e.g. [`OSM_DEV_AUTHORIZE_URL`] and
+    /// [`OSM_DEV_TOKEN_URL`].
+    pub fn with_endpoints(
+        mut self,
+        authorize_url: impl Into<String>,
+        token_url: impl Into<String>,
+    ) -> Self {
+        self.authorize_url = authorize_url.into();
+        self.token_url = token_url.into();
+        self
+    }
+
+    /// Get the client id.
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    /// Get the redirect uri.
+    pub fn redirect_uri(&self) -> &str {
+        &self.redirect_uri
+    }
+
+    /// Get the url the user authorizes the application at.
+    ///
+    /// `state` is returned unchanged with the code and should be checked against the value sent
+    /// to prevent cross-site request forgery.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::InvalidUrl`] if the authorization endpoint is not a valid url.
+    pub fn authorization_url(
+        &self,
+        scopes: &[&str],
+        state: &str,
+        pkce: &Pkce,
+    ) -> Result<reqwest::Url, Error> {
+        let mut url = reqwest::Url::parse(&self.authorize_url)
+            .map_err(|error| Error::InvalidUrl((self.authorize_url.clone(), error.to_string())))?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.client_id)
+            .append_pair("redirect_uri", &self.redirect_uri)
+            .append_pair("scope", &scopes.join(" "))
+            .append_pair("state", state)
+            .append_pair("code_challenge", pkce.challenge())
+            .append_pair("code_challenge_method", "S256");
+
+        Ok(url)
+    }
+
+    /// Exchange an authorization code for a [`Token`].
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::HttpStatus`] if the server rejects the code, e.g. because it was
+    /// already used or the [`Pkce`] does not match.
+    #[cfg(feature = "async")]
+    pub async fn exchange_code(
+        &self,
+        client: &reqwest::Client,
+        code: &str,
+        pkce: &Pkce,
+    ) -> Result<Token, Error> {
+        let response = client
+            .post(&self.token_url)
+            .form(&self.token_form(code, pkce))
+            .send()
+            .await?;
+        let status = response.status();
+
+        if status.is_success() {
+            Ok(serde_json::from_str(&response.text().await?)?)
+        } else {
+            Err(Error::HttpStatus((status, response.text().await?)))
+        }
+    }
+
+    /// Exchange an authorization code for a [`Token`] with a blocking client.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::HttpStatus`] if the server rejects the code, e.g. because it was
+    /// already used or the [`Pkce`] does not match.
+    #[cfg(feature = "blocking")]
+    pub fn exchange_code_blocking(
+        &self,
+        client: &reqwest::blocking::Client,
+        code: &str,
+        pkce: &Pkce,
+    ) -> Result<Token, Error> {
+        let response = client
+            .post(&self.token_url)
+            .form(&self.token_form(code, pkce))
+            .send()?;
+        let status = response.status();
+
+        if status.is_success() {
+            Ok(serde_json::from_str(&response.text()?)?)
+        } else {
+            Err(Error::HttpStatus((status, response.text()?)))
+        }
+    }
+
+    /// Get the form of the token request for an authorization code.
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    fn token_form<'a>(&'a self, code: &'a str, pkce: &'a Pkce) -> Vec<(&'static str, &'a str)> {
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.redirect_uri.as_str()),
+            ("client_id", self.client_id.as_str()),
+            ("code_verifier", pkce.verifier()),
+        ];
+        if let Some(ref client_secret) = self.client_secret {
+            form.push(("client_secret", client_secret));
+        }
+
+        form
+    }
+}
+
+impl fmt::Debug for OAuth2App {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OAuth2App")
+            .field("client_id", &self.client_id)
+            .field(
+                "client_secret",
+                &self.client_secret.as_ref().map(|_| "[redacted]"),
+            )
+            .field("redirect_uri", &self.redirect_uri)
+            .field("authorize_url", &self.authorize_url)
+            .field("token_url", &self.token_url)
+            .finish()
+    }
+}
+
+/// A PKCE code verifier and its `S256` code challenge, binding the token request to the
+/// authorization request.
+///
+/// A new [`Pkce`] is used for every authorization.
+///
+/// See <https://datatracker.ietf.org/doc/html/rfc7636>
+#[derive(Clone, PartialEq, Eq)]
+pub struct Pkce {
+    verifier: String,
+    challenge: String,
+}
+
+impl Pkce {
+    /// Construct a new [`Pkce`] with a code verifier of 32 random bytes from the operating system.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::Random`] if the operating system can not supply random bytes.
+    pub fn new() -> Result<Self, Error> {
+        let mut bytes = [0; 32];
+        getrandom::fill(&mut bytes).map_err(Error::Random)?;
+
+        Self::from_verifier(base64_url(&bytes))
+    }
+
+    /// Construct a new [`Pkce`] from a code verifier of 43 to 128 characters from `A-Z`, `a-z`,
+    /// `0-9` and `-._~`.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::InvalidVerifier`] if the verifier has another length or character.
+    pub fn from_verifier(verifier: impl Into<String>) -> Result<Self, Error> {
+        let verifier = verifier.into();
+        let valid = (43..=128).contains(&verifier.len())
+            && verifier
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || b"-._~".contains(&byte));
+        if !valid {
+            return Err(Error::InvalidVerifier);
+        }
+
+        let challenge = base64_url(&sha256(verifier.as_bytes()));
+        Ok(Self {
+            verifier,
+            challenge,
+        })
+    }
+
+    /// Get the code verifier sent with the token request.
+    pub fn verifier(&self) -> &str {
+        &self.verifier
+    }
+
+    /// Get the code challenge sent with the authorization request.
+    pub fn challenge(&self) -> &str {
+        &self.challenge
+    }
+}
+
+impl fmt::Debug for Pkce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pkce")
+            .field("verifier", &"[redacted]")
+            .field("challenge", &self.challenge)
+            .finish()
+    }
+}
+
+/// The response of the token endpoint.
+///
+/// Tokens of openstreetmap.org do not expire and come without a refresh token.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Token {
+    pub access_token: String,
+    pub token_type: String,
+    /// The granted scopes, separated by spaces.
+    #[serde(default)]
+    pub scope: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_in: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+}
+
+impl Token {
+    /// Check if a scope was granted.
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scope
+            .split_whitespace()
+            .any(|granted| granted == scope)
+    }
+
+    /// Get [`Credentials`] sending the access token.
+    pub fn credentials(&self) -> Credentials {
+        Credentials::AccessToken(self.access_token.clone())
+    }
+}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Token")
+            .field("access_token", &"[redacted]")
+            .field("token_type", &self.token_type)
+            .field("scope", &self.scope)
+            .field("created_at", &self.created_at)
+            .field("expires_in", &self.expires_in)
+            .field(
+                "refresh_token",
+                &self.refresh_token.as_ref().map(|_| "[redacted]"),
+            )
+            .finish()
+    }
+}
+
+/// A source of access tokens, asked for the current token before every request so tokens can be
+/// refreshed outside of the client.
+pub trait TokenProvider: fmt::Debug + Send + Sync {
+    /// Get the current access token, or [`None`] to send the request without one.
+    fn access_token(&self) -> Option<String>;
+}
+
+/// A [`TokenProvider`] holding an access token which can be replaced at any time, e.g. after
+/// refreshing it. Clones share the token.
+#[derive(Clone, Default)]
+pub struct SharedToken {
+    access_token: Arc<RwLock<Option<String>>>,
+}
+
+impl SharedToken {
+    /// Construct a new [`SharedToken`] holding an access token.
+    pub fn new(access_token: impl Into<String>) -> Self {
+        Self {
+            access_token: Arc::new(RwLock::new(Some(access_token.into()))),
+        }
+    }
+
+    /// Replace the access token.
+    pub fn set(&self, access_token: impl Into<String>) {
+        *self
+            .access_token
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(access_token.into());
+    }
+
+    /// Remove the access token, requests are sent without one afterwards.
+    pub fn clear(&self) {
+        *self
+            .access_token
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+    }
+}
+
+impl TokenProvider for SharedToken {
+    fn access_token(&self) -> Option<String> {
+        self.access_token
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
+
+impl fmt::Debug for SharedToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedToken")
+            .field("access_token", &"[redacted]")
+            .finish()
+    }
+}
+
+/// Encode bytes as unpadded base64url.
+fn base64_url(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, byte)| {
+            bits | u32::from(*byte) << (16 - 8 * i)
+        });
+        for i in 0..=chunk.len() {
+            encoded.push(char::from(ALPHABET[(bits >> (18 - 6 * i) & 0x3f) as usize]));
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod auth_test {
+    use crate::osm_api::{
+        auth::{
+            OAuth2App, OSM_DEV_AUTHORIZE_URL, OSM_DEV_TOKEN_URL, Pkce, SharedToken, Token,
+            TokenProvider, base64_url, scope,
+        },
+        error::Error,
+    };
+
+    #[test]
+    fn base64() {
+        assert_eq!(base64_url(b""), "");
+        assert_eq!(base64_url(b"f"), "Zg");
+        assert_eq!(base64_url(b"fo"), "Zm8");
+        assert_eq!(base64_url(b"foo"), "Zm9v");
+        assert_eq!(base64_url(&[0xfb, 0xff]), "-_8");
+    }
+
+    #[test]
+    fn pkce() {
+        // The example of RFC 7636, appendix B.
+        let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk").unwrap();
+        assert_eq!(
+            pkce.challenge(),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        assert!(!format!("{pkce:?}").contains(pkce.verifier()));
+
+        let random = Pkce::new().unwrap();
+        assert_eq!(random.verifier().len(), 43);
+        assert_ne!(random, Pkce::new().unwrap());
+
+        assert!(Pkce::from_verifier("a".repeat(43)).is_ok());
+        assert!(Pkce::from_verifier("-._~".repeat(32)).is_ok());
+        assert!(matches!(
+            Pkce::from_verifier("a".repeat(42)),
+            Err(Error::InvalidVerifier)
+        ));
+        assert!(Pkce::from_verifier("a".repeat(129)).is_err());
+        assert!(Pkce::from_verifier(format!("{}+", "a".repeat(42))).is_err());
+        assert!(Pkce::from_verifier(format!("{}ä", "a".repeat(42))).is_err());
+    }
+
+    #[test]
+    fn authorization_url() {
+        let app = OAuth2App::new("client", "http://127.0.0.1:8080/callback")
+            .with_endpoints(OSM_DEV_AUTHORIZE_URL, OSM_DEV_TOKEN_URL);
+        let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk").unwrap();
+        let url = app
+            .authorization_url(&[scope::READ_PREFS, scope::WRITE_API], "xyz", &pkce)
+            .unwrap();
+
+        assert_eq!(url.host_str(), Some("master.apis.dev.openstreetmap.org"));
+        assert_eq!(
+            url.query(),
+            Some(
+                "response_type=code&client_id=client&redirect_uri=http%3A%2F%2F127.0.0.1%3A8080%2Fcallback&scope=read_prefs+write_api&state=xyz&code_challenge=E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM&code_challenge_method=S256"
+            )
+        );
+    }
+
+    #[test]
+    fn token() {
+        let token: Token = serde_json::from_str(
+            r#"{"access_token": "secret", "token_type": "Bearer", "scope": "read_prefs write_api", "created_at": 1700000000}"#,
+        )
+        .unwrap();
+
+        assert!(token.has_scope(scope::WRITE_API));
+        assert!(!token.has_scope(scope::WRITE_NOTES));
+        assert!(!format!("{token:?}").contains("secret"));
+    }
+
+    #[test]
+    fn shared_token() {
+        let token = SharedToken::new("a");
+        let provider = token.clone();
+        assert_eq!(provider.access_token().as_deref(), Some("a"));
+
+        token.set("b");
+        assert_eq!(provider.access_token().as_deref(), Some("b"));
+
+        token.clear();
+        assert_eq!(provider.access_token(), None);
+    }
+}
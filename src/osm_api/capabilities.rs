@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    coord::{CoordinateType, bbox::BBox},
+    osm_api::error::Error,
+};
+
+/// The limits and policies of an OSM API instance.
+///
+/// See <https://wiki.openstreetmap.org/wiki/API_v0.6#Capabilities:_GET_/api/capabilities>
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub api: ApiLimits,
+    #[serde(default)]
+    pub policy: Policy,
+}
+
+impl Capabilities {
+    /// Check that the area of a [`BBox`] does not exceed the maximum area of a map request.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`Error::AreaTooLarge`] if the area is too large.
+    pub fn validate_area(&self, bbox: &BBox) -> Result<(), Error> {
+        let area = bbox.area_deg2();
+        let maximum = self.api.area.maximum;
+
+        if area <= maximum {
+            Ok(())
+        } else {
+            Err(Error::AreaTooLarge((area, maximum)))
+        }
+    }
+
+    /// Check that the area of a [`BBox`] does not exceed the maximum area of a notes request.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`Error::AreaTooLarge`] if the area is too large.
+    pub fn validate_note_area(&self, bbox: &BBox) -> Result<(), Error> {
+        let area = bbox.area_deg2();
+
+        match self.api.note_area {
+            Some(ref limit) if area > limit.maximum => {
+                Err(Error::AreaTooLarge((area, limit.maximum)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check that a changeset with `count` elements can be uploaded.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`Error::TooManyElements`] if the changeset would contain too many elements.
+    pub fn validate_changeset_elements(&self, count: usize) -> Result<(), Error> {
+        Self::validate_count(count, self.api.changesets.maximum_elements)
+    }
+
+    /// Check that a way with `count` nodes can be uploaded.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`Error::TooManyElements`] if the way would contain too many nodes.
+    pub fn validate_way_nodes(&self, count: usize) -> Result<(), Error> {
+        Self::validate_count(count, self.api.waynodes.maximum)
+    }
+
+    /// Check that a relation with `count` members can be uploaded.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`Error::TooManyElements`] if the relation would contain too many members.
+    pub fn validate_relation_members(&self, count: usize) -> Result<(), Error> {
+        match self.api.relationmembers {
+            Some(ref limit) => Self::validate_count(count, limit.maximum),
+            None => Ok(()),
+        }
+    }
+
+    fn validate_count(count: usize, maximum: u64) -> Result<(), Error> {
+        if count as u64 <= maximum {
+            Ok(())
+        } else {
+            Err(Error::TooManyElements((count, maximum)))
+        }
+    }
+}
+
+/// The limits of the API.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiLimits {
+    pub version: VersionRange,
+    pub area: Maximum<CoordinateType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note_area: Option<Maximum<CoordinateType>>,
+    pub tracepoints: Tracepoints,
+    pub waynodes: Maximum<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relationmembers: Option<Maximum<u64>>,
+    pub changesets: ChangesetLimits,
+    pub timeout: Timeout,
+    pub status: Status,
+}
+
+/// The supported API versions.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionRange {
+    pub minimum: String,
+    pub maximum: String,
+}
+
+/// A single upper limit.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Maximum<T> {
+    pub maximum: T,
+}
+
+/// The amount of GPS points returned per page.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tracepoints {
+    pub per_page: u64,
+}
+
+/// The limits of changesets.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangesetLimits {
+    pub maximum_elements: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_query_limit: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maximum_query_limit: Option<u64>,
+}
+
+/// The request timeout of the server.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Timeout {
+    pub seconds: u64,
+}
+
+/// The availability of the API services.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Status {
+    pub database: String,
+    pub api: String,
+    pub gpx: String,
+}
+
+/// The usage policies of the API.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Policy {
+    #[serde(default)]
+    pub imagery: ImageryPolicy,
+}
+
+/// The imagery that must not be used for mapping.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageryPolicy {
+    #[serde(default)]
+    pub blacklist: Vec<BlacklistEntry>,
+}
+
+/// A regular expression matching forbidden imagery urls.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlacklistEntry {
+    pub regex: String,
+}
+
+#[cfg(test)]
+mod capabilities_test {
+    use crate::{
+        coord::bbox::BBox,
+        osm_api::{capabilities::Capabilities, error::Error},
+    };
+
+    const CAPABILITIES: &str = r#"{
+        "version": "0.6",
+        "generator": "OpenStreetMap server",
+        "api": {
+            "version": {"minimum": "0.6", "maximum": "0.6"},
+            "area": {"maximum": 0.25},
+            "note_area": {"maximum": 25},
+            "tracepoints": {"per_page": 5000},
+            "waynodes": {"maximum": 2000},
+            "relationmembers": {"maximum": 32000},
+            "changesets": {"maximum_elements": 10000, "default_query_limit": 100, "maximum_query_limit": 100},
+            "notes": {"default_query_limit": 100, "maximum_query_limit": 10000},
+            "timeout": {"seconds": 300},
+            "status": {"database": "online", "api": "online", "gpx": "online"}
+        },
+        "policy": {"imagery": {"blacklist": [{"regex": ".*\\.google(apis)?\\..*/.*"}]}}
+    }"#;
+
+    #[test]
+    fn parse() {
+        let capabilities: Capabilities = serde_json::from_str(CAPABILITIES).unwrap();
+
+        assert_eq!(capabilities.api.area.maximum, 0.25);
+        assert_eq!(capabilities.api.waynodes.maximum, 2000);
+        assert_eq!(capabilities.api.changesets.maximum_elements, 10000);
+        assert_eq!(capabilities.api.timeout.seconds, 300);
+        assert_eq!(capabilities.api.status.api, "online");
+        assert_eq!(capabilities.policy.imagery.blacklist.len(), 1);
+    }
+
+    #[test]
+    fn validate_area() {
+        let capabilities: Capabilities = serde_json::from_str(CAPABILITIES).unwrap();
+
+        assert!(
+            capabilities
+                .validate_area(&BBox::from_wrapped(0.0, 0.0, 0.5, 0.5))
+                .is_ok()
+        );
+        assert!(matches!(
+            capabilities.validate_area(&BBox::from_wrapped(0.0, 0.0, 1.0, 1.0)),
+            Err(Error::AreaTooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn validate_note_area() {
+        let capabilities: Capabilities = serde_json::from_str(CAPABILITIES).unwrap();
+
+        assert!(
+            capabilities
+                .validate_note_area(&BBox::from_wrapped(0.0, 0.0, 5.0, 5.0))
+                .is_ok()
+        );
+        assert!(
+            capabilities
+                .validate_note_area(&BBox::from_wrapped(0.0, 0.0, 6.0, 6.0))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn validate_counts() {
+        let capabilities: Capabilities = serde_json::from_str(CAPABILITIES).unwrap();
+
+        assert!(capabilities.validate_changeset_elements(10000).is_ok());
+        assert!(capabilities.validate_changeset_elements(10001).is_err());
+        assert!(capabilities.validate_way_nodes(2001).is_err());
+        assert!(capabilities.validate_relation_members(32000).is_ok());
+    }
+}
@@ -0,0 +1,388 @@
+#[cfg(any(feature = "async", feature = "blocking", test))]
+use serde::{Deserialize, Serialize};
+
+use crate::coord::bbox::BBox;
+pub use crate::model::changeset::{Changeset, ChangesetComment};
+#[cfg(any(feature = "async", feature = "blocking", test))]
+use crate::{model::tags::Tags, xml::escape};
+
+/// A single changeset as returned by the API.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ChangesetResponse {
+    pub(crate) changeset: Changeset,
+}
+
+/// A list of changesets as returned by the API.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Changesets {
+    #[serde(default)]
+    pub(crate) changesets: Vec<Changeset>,
+}
+
+/// The user a [`ChangesetQuery`] is filtered by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangesetUser {
+    Id(u64),
+    DisplayName(String),
+}
+
+/// The state a [`ChangesetQuery`] is filtered by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangesetState {
+    Open,
+    Closed,
+}
+
+/// The number of changesets returned by the API if no limit is set.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+const DEFAULT_LIMIT: usize = 100;
+
+/// A filter for querying changesets.
+///
+/// Timestamps are ISO 8601 strings, e.g. `2025-01-31T12:00:00Z`.
+///
+/// See <https://wiki.openstreetmap.org/wiki/API_v0.6#Query:_GET_/api/0.6/changesets>
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ChangesetQuery {
+    bbox: Option<BBox>,
+    user: Option<ChangesetUser>,
+    closed_after: Option<String>,
+    created_before: Option<String>,
+    state: Option<ChangesetState>,
+    limit: Option<u32>,
+}
+
+impl ChangesetQuery {
+    /// Construct a new [`ChangesetQuery`] without any filters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only return changesets intersecting the [`BBox`].
+    ///
+    /// A [`BBox`] crossing the antimeridian is queried as its two halves and the results are
+    /// merged.
+    pub fn bbox(mut self, bbox: BBox) -> Self {
+        self.bbox = Some(bbox);
+        self
+    }
+
+    /// Only return changesets of the given user.
+    pub fn user(mut self, user: ChangesetUser) -> Self {
+        self.user = Some(user);
+        self
+    }
+
+    /// Only return changesets closed after `closed_after` and, if set, created before `created_before`.
+    pub fn time_range(
+        mut self,
+        closed_after: impl Into<String>,
+        created_before: Option<String>,
+    ) -> Self {
+        self.closed_after = Some(closed_after.into());
+        self.created_before = created_before;
+        self
+    }
+
+    /// Only return changesets in the given state.
+    pub fn state(mut self, state: ChangesetState) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Limit the amount of returned changesets.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Build the query for the page following `page`.
+    ///
+    /// The API returns changesets ordered by creation time, newest first. The next page contains
+    /// all changesets created before the oldest changeset of `page`.
+    /// Returns [`None`] if `page` is empty.
+    pub fn next_page(&self, page: &[Changeset]) -> Option<Self> {
+        let oldest = page.iter().map(|changeset| &changeset.created_at).min()?;
+
+        let mut next = self.clone();
+        next.closed_after = Some(
+            self.closed_after
+                .clone()
+                .unwrap_or_else(|| "1970-01-01T00:00:00Z".to_string()),
+        );
+        next.created_before = Some(oldest.clone());
+
+        Some(next)
+    }
+
+    /// Get the query parameters of every request sent for this query.
+    ///
+    /// The API rejects a bbox crossing the antimeridian, so its halves are requested separately,
+    /// see [`ChangesetQuery::merge`].
+    #[cfg(any(feature = "async", feature = "blocking", test))]
+    pub(crate) fn requests(&self) -> Vec<Vec<(&'static str, String)>> {
+        match self.bbox.and_then(|bbox| bbox.split_at_antimeridian()) {
+            Some((west, east)) => [west, east]
+                .into_iter()
+                .map(|bbox| self.clone().bbox(bbox).query_pairs())
+                .collect(),
+            None => vec![self.query_pairs()],
+        }
+    }
+
+    /// Merge the changesets returned for the [`ChangesetQuery::requests`] into one page, newest
+    /// first and at most as long as a single response.
+    #[cfg(any(feature = "async", feature = "blocking", test))]
+    pub(crate) fn merge(&self, mut pages: Vec<Vec<Changeset>>) -> Vec<Changeset> {
+        if pages.len() == 1 {
+            return pages.remove(0);
+        }
+
+        let mut changesets: Vec<Changeset> = pages.into_iter().flatten().collect();
+        changesets.sort_by(|a, b| {
+            b.created_at
+                .cmp(&a.created_at)
+                .then_with(|| b.id.cmp(&a.id))
+        });
+        // Changesets intersecting both halves are returned twice.
+        changesets.dedup_by_key(|changeset| changeset.id);
+        changesets.truncate(self.limit.map_or(DEFAULT_LIMIT, |limit| limit as usize));
+
+        changesets
+    }
+
+    /// Get the query parameters.
+    #[cfg(any(feature = "async", feature = "blocking", test))]
+    pub(crate) fn query_pairs(&self) -> Vec<(&'static str, String)> {
+        let mut pairs = Vec::new();
+
+        if let Some(bbox) = self.bbox {
+            let (south, west, north, east) = bbox.corners();
+            pairs.push(("bbox", format!("{west},{south},{east},{north}")));
+        }
+
+        match self.user {
+            Some(ChangesetUser::Id(uid)) => pairs.push(("user", uid.to_string())),
+            Some(ChangesetUser::DisplayName(ref name)) => {
+                pairs.push(("display_name", name.clone()))
+            }
+            None => (),
+        }
+
+        match (&self.closed_after, &self.created_before) {
+            (Some(closed_after), Some(created_before)) => {
+                pairs.push(("time", format!("{closed_after},{created_before}")))
+            }
+            (Some(closed_after), None) => pairs.push(("time", closed_after.clone())),
+            _ => (),
+        }
+
+        match self.state {
+            Some(ChangesetState::Open) => pairs.push(("open", "true".to_string())),
+            Some(ChangesetState::Closed) => pairs.push(("closed", "true".to_string())),
+            None => (),
+        }
+
+        if let Some(limit) = self.limit {
+            pairs.push(("limit", limit.to_string()));
+        }
+
+        pairs
+    }
+}
+
+/// Build the XML payload used to create or update a changeset.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+pub(crate) fn changeset_xml(tags: &Tags) -> String {
+    let mut xml = String::from("<osm><changeset>");
+    for (key, value) in tags {
+        xml.push_str(&format!(
+            r#"<tag k="{}" v="{}"/>"#,
+            escape(key),
+            escape(value)
+        ));
+    }
+    xml.push_str("</changeset></osm>");
+
+    xml
+}
+
+#[cfg(test)]
+mod changeset_test {
+    use crate::{
+        coord::bbox::BBox,
+        model::{id::ChangesetId, tags::Tags},
+        osm_api::changeset::{
+            Changeset, ChangesetQuery, ChangesetResponse, ChangesetState, ChangesetUser,
+            Changesets, changeset_xml,
+        },
+    };
+
+    const CHANGESETS: &str = r#"{
+        "version": "0.6",
+        "changesets": [
+            {"id": 12, "created_at": "2025-02-01T10:00:00Z", "open": true, "comments_count": 1, "changes_count": 3,
+             "min_lat": 1.0, "min_lon": 2.0, "max_lat": 1.5, "max_lon": 2.5, "uid": 7, "user": "a", "tags": {"comment": "b"}},
+            {"id": 11, "created_at": "2025-01-01T10:00:00Z", "closed_at": "2025-01-01T11:00:00Z", "open": false}
+        ]
+    }"#;
+
+    #[test]
+    fn id() {
+        let id = ChangesetId::new(42);
+
+        assert_eq!(id.value(), 42);
+        assert_eq!(id.to_string(), "42");
+    }
+
+    #[test]
+    fn xml() {
+        let tags = Tags::from([
+            ("created_by".to_string(), "rust-osm".to_string()),
+            ("comment".to_string(), "Fix \"names\" & <tags>".to_string()),
+        ]);
+
+        assert_eq!(
+            changeset_xml(&tags),
+            r#"<osm><changeset><tag k="comment" v="Fix &quot;names&quot; &amp; &lt;tags&gt;"/><tag k="created_by" v="rust-osm"/></changeset></osm>"#
+        );
+    }
+
+    #[test]
+    fn xml_empty() {
+        assert_eq!(
+            changeset_xml(&Tags::new()),
+            "<osm><changeset></changeset></osm>"
+        );
+    }
+
+    #[test]
+    fn parse() {
+        let changesets: Changesets = serde_json::from_str(CHANGESETS).unwrap();
+        let changesets = changesets.changesets;
+
+        assert_eq!(changesets.len(), 2);
+        assert_eq!(changesets[0].id, ChangesetId::new(12));
+        assert_eq!(changesets[0].tags["comment"], "b");
+        assert_eq!(
+            changesets[0].bbox().unwrap(),
+            BBox::from_wrapped(1.0, 2.0, 1.5, 2.5)
+        );
+        assert!(changesets[1].bbox().is_none());
+    }
+
+    #[test]
+    fn discussion() {
+        let response: ChangesetResponse = serde_json::from_str(
+            r#"{
+                "version": "0.6",
+                "changeset": {
+                    "id": 12, "created_at": "2025-02-01T10:00:00Z", "open": false, "comments_count": 2,
+                    "comments": [
+                        {"id": 1, "visible": true, "date": "2025-02-02T10:00:00Z", "uid": 7, "user": "a", "text": "Why?"},
+                        {"id": 2, "date": "2025-02-03T10:00:00Z", "uid": 8, "user": "b", "text": "Because."}
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+        let comments = response.changeset.comments;
+
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].user.as_deref(), Some("a"));
+        assert_eq!(comments[1].text, "Because.");
+        assert!(comments[1].visible);
+    }
+
+    #[test]
+    fn query_pairs() {
+        let query = ChangesetQuery::new()
+            .bbox(BBox::from_wrapped(1.0, 2.0, 3.0, 4.0))
+            .user(ChangesetUser::DisplayName("a b".to_string()))
+            .time_range("2025-01-01T00:00:00Z", None)
+            .state(ChangesetState::Closed)
+            .limit(10);
+
+        assert_eq!(
+            query.query_pairs(),
+            vec![
+                ("bbox", "2,1,4,3".to_string()),
+                ("display_name", "a b".to_string()),
+                ("time", "2025-01-01T00:00:00Z".to_string()),
+                ("closed", "true".to_string()),
+                ("limit", "10".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn antimeridian() {
+        let query = ChangesetQuery::new()
+            .bbox(BBox::from_wrapped(1.0, 170.0, 3.0, -170.0))
+            .limit(2);
+
+        assert_eq!(
+            query.requests(),
+            vec![
+                vec![
+                    ("bbox", "170,1,180,3".to_string()),
+                    ("limit", "2".to_string())
+                ],
+                vec![
+                    ("bbox", "-180,1,-170,3".to_string()),
+                    ("limit", "2".to_string())
+                ],
+            ]
+        );
+        assert_eq!(
+            ChangesetQuery::new().limit(2).requests(),
+            vec![ChangesetQuery::new().limit(2).query_pairs()]
+        );
+
+        let changesets: Vec<Changeset> = serde_json::from_str::<Changesets>(CHANGESETS)
+            .unwrap()
+            .changesets;
+        let merged = query.merge(vec![
+            vec![changesets[1].clone()],
+            vec![changesets[0].clone(), changesets[1].clone()],
+        ]);
+
+        assert_eq!(merged, changesets);
+        assert_eq!(
+            ChangesetQuery::new()
+                .bbox(BBox::from_wrapped(1.0, 170.0, 3.0, -170.0))
+                .limit(1)
+                .merge(vec![
+                    vec![changesets[1].clone()],
+                    vec![changesets[0].clone()]
+                ]),
+            vec![changesets[0].clone()]
+        );
+        assert_eq!(query.merge(vec![changesets.clone()]), changesets);
+    }
+
+    #[test]
+    fn next_page() {
+        let changesets: Vec<Changeset> = serde_json::from_str::<Changesets>(CHANGESETS)
+            .unwrap()
+            .changesets;
+        let next = ChangesetQuery::new()
+            .user(ChangesetUser::Id(7))
+            .next_page(&changesets)
+            .unwrap();
+
+        assert_eq!(
+            next.query_pairs(),
+            vec![
+                ("user", "7".to_string()),
+                (
+                    "time",
+                    "1970-01-01T00:00:00Z,2025-01-01T10:00:00Z".to_string()
+                ),
+            ]
+        );
+        assert!(ChangesetQuery::new().next_page(&[]).is_none());
+    }
+}
@@ -0,0 +1,342 @@
+use std::fmt::Display;
+
+use crate::model::id::ElementId;
+#[cfg(any(feature = "async", feature = "blocking", test))]
+use crate::{
+    model::{element::ElementType, id::ChangesetId},
+    osm_api::error::Error,
+};
+
+/// The server had a different version of an element than the one provided.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionConflict {
+    pub element: ElementId,
+    pub provided: u64,
+    pub current: u64,
+}
+
+impl Display for VersionConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "provided version {} of {}, but the server has version {}",
+            self.provided, self.element, self.current
+        )
+    }
+}
+
+/// An edit violated the referential integrity of the data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreconditionFailed {
+    /// The element can not be deleted because it is still referenced by other elements.
+    StillUsed {
+        element: ElementId,
+        used_by: Vec<ElementId>,
+    },
+    /// The element references elements that do not exist or are not visible.
+    MissingReferences {
+        element: ElementId,
+        missing: Vec<ElementId>,
+    },
+    /// Any other precondition failure with the message of the server.
+    Other(String),
+}
+
+impl Display for PreconditionFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::StillUsed { element, used_by } => {
+                write!(f, "{element} is still used by {}", format_elements(used_by))
+            }
+            Self::MissingReferences { element, missing } => {
+                write!(
+                    f,
+                    "{element} references missing {}",
+                    format_elements(missing)
+                )
+            }
+            Self::Other(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+/// Map an unsuccessful response to an [`Error`], parsing edit conflicts into typed variants.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+pub(crate) fn error_from_status(status: reqwest::StatusCode, message: String) -> Error {
+    match status {
+        reqwest::StatusCode::CONFLICT => {
+            if let Some(conflict) = parse_version_conflict(&message) {
+                Error::VersionConflict(conflict)
+            } else if let Some(closed) = parse_changeset_closed(&message) {
+                Error::ChangesetClosed(closed)
+            } else {
+                Error::Conflict(message)
+            }
+        }
+        reqwest::StatusCode::GONE => Error::Gone(message),
+        reqwest::StatusCode::PRECONDITION_FAILED => {
+            Error::PreconditionFailed(parse_precondition_failed(&message))
+        }
+        _ => Error::HttpStatus((status, message)),
+    }
+}
+
+/// Parse `Version mismatch: Provided 1, server had: 2 of Node 1234`.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+fn parse_version_conflict(message: &str) -> Option<VersionConflict> {
+    let rest = message.trim().strip_prefix("Version mismatch: Provided ")?;
+    let (provided, rest) = rest.split_once(", server had: ")?;
+    let (current, rest) = rest.split_once(" of ")?;
+
+    Some(VersionConflict {
+        element: parse_element(rest.trim_end_matches('.'))?,
+        provided: provided.trim().parse().ok()?,
+        current: current.trim().parse().ok()?,
+    })
+}
+
+/// Parse `The changeset 123 was closed at 2025-01-01 00:00:00 UTC`.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+fn parse_changeset_closed(message: &str) -> Option<(ChangesetId, String)> {
+    let rest = message.trim().strip_prefix("The changeset ")?;
+    let (id, closed_at) = rest.split_once(" was closed at ")?;
+
+    Some((
+        ChangesetId::new(id.trim().parse().ok()?),
+        closed_at.trim().to_string(),
+    ))
+}
+
+/// Parse the known referential integrity messages, e.g.
+/// `Precondition failed: Node 5 is still used by ways 1,2.` or
+/// `Precondition failed: Way 4 requires the nodes with id in (1,2), which either do not exist, or are not visible.`
+#[cfg(any(feature = "async", feature = "blocking", test))]
+fn parse_precondition_failed(message: &str) -> PreconditionFailed {
+    let trimmed = message.trim();
+    let rest = trimmed
+        .strip_prefix("Precondition failed: ")
+        .unwrap_or(trimmed)
+        .trim_end_matches('.');
+
+    parse_still_used(rest)
+        .or_else(|| parse_missing_references(rest))
+        .unwrap_or_else(|| PreconditionFailed::Other(message.to_string()))
+}
+
+#[cfg(any(feature = "async", feature = "blocking", test))]
+fn parse_still_used(message: &str) -> Option<PreconditionFailed> {
+    let (element, used_by) = match message.split_once(" is still used by ") {
+        Some(split) => split,
+        // `The relation 1 is used in relation 2`
+        None => {
+            let (element, used_by) = message.split_once(" is used in ")?;
+            (element.strip_prefix("The ")?, used_by)
+        }
+    };
+
+    Some(PreconditionFailed::StillUsed {
+        element: parse_element(element)?,
+        used_by: parse_typed_ids(used_by)?,
+    })
+}
+
+#[cfg(any(feature = "async", feature = "blocking", test))]
+fn parse_missing_references(message: &str) -> Option<PreconditionFailed> {
+    let (element, rest) = message.split_once(" requires the ")?;
+    let (missing_type, rest) = rest.split_once(" with id in ")?;
+    let missing_type = parse_element_type(missing_type)?;
+    let ids = rest.strip_prefix('(')?.split_once(')')?.0;
+
+    Some(PreconditionFailed::MissingReferences {
+        element: parse_element(element)?,
+        missing: parse_ids(ids)?
+            .into_iter()
+            .map(|id| ElementId::new(missing_type, id))
+            .collect(),
+    })
+}
+
+/// Parse `Node 5`.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+fn parse_element(element: &str) -> Option<ElementId> {
+    let (element_type, id) = element.trim().split_once(' ')?;
+
+    Some(ElementId::new(
+        parse_element_type(element_type)?,
+        id.trim().parse().ok()?,
+    ))
+}
+
+/// Parse `ways 1,2` or `relation 2`.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+fn parse_typed_ids(elements: &str) -> Option<Vec<ElementId>> {
+    let (element_type, ids) = elements.trim().split_once(' ')?;
+    let element_type = parse_element_type(element_type)?;
+
+    Some(
+        parse_ids(ids)?
+            .into_iter()
+            .map(|id| ElementId::new(element_type, id))
+            .collect(),
+    )
+}
+
+#[cfg(any(feature = "async", feature = "blocking", test))]
+fn parse_ids(ids: &str) -> Option<Vec<i64>> {
+    ids.split(',').map(|id| id.trim().parse().ok()).collect()
+}
+
+/// Parse singular or plural element type names in any case.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+fn parse_element_type(element_type: &str) -> Option<ElementType> {
+    let element_type = element_type.trim().to_ascii_lowercase();
+
+    element_type
+        .strip_suffix('s')
+        .unwrap_or(&element_type)
+        .parse()
+        .ok()
+}
+
+fn format_elements(elements: &[ElementId]) -> String {
+    elements
+        .iter()
+        .map(ElementId::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod conflict_test {
+    use crate::{
+        model::id::{ChangesetId, ElementId, NodeId, RelationId, WayId},
+        osm_api::{
+            conflict::{PreconditionFailed, VersionConflict, error_from_status},
+            error::Error,
+        },
+    };
+
+    #[test]
+    fn version_conflict() {
+        let error = error_from_status(
+            reqwest::StatusCode::CONFLICT,
+            "Version mismatch: Provided 1, server had: 2 of Node 1234".to_string(),
+        );
+
+        assert!(matches!(
+            error,
+            Error::VersionConflict(VersionConflict {
+                element: ElementId::Node(id),
+                provided: 1,
+                current: 2
+            }) if id == NodeId::new(1234)
+        ));
+    }
+
+    #[test]
+    fn changeset_closed() {
+        let error = error_from_status(
+            reqwest::StatusCode::CONFLICT,
+            "The changeset 123 was closed at 2025-01-01 00:00:00 UTC".to_string(),
+        );
+
+        match error {
+            Error::ChangesetClosed((id, closed_at)) => {
+                assert_eq!(id, ChangesetId::new(123));
+                assert_eq!(closed_at, "2025-01-01 00:00:00 UTC");
+            }
+            _ => panic!("expected a closed changeset"),
+        }
+    }
+
+    #[test]
+    fn conflict_other() {
+        let error = error_from_status(reqwest::StatusCode::CONFLICT, "Something".to_string());
+
+        assert!(matches!(error, Error::Conflict(_)));
+    }
+
+    #[test]
+    fn gone() {
+        let error = error_from_status(reqwest::StatusCode::GONE, String::new());
+
+        assert!(matches!(error, Error::Gone(_)));
+    }
+
+    #[test]
+    fn still_used() {
+        let error = error_from_status(
+            reqwest::StatusCode::PRECONDITION_FAILED,
+            "Precondition failed: Node 5 is still used by ways 1,2.".to_string(),
+        );
+
+        match error {
+            Error::PreconditionFailed(precondition) => assert_eq!(
+                precondition,
+                PreconditionFailed::StillUsed {
+                    element: NodeId::new(5).into(),
+                    used_by: vec![WayId::new(1).into(), WayId::new(2).into()]
+                }
+            ),
+            _ => panic!("expected a failed precondition"),
+        }
+    }
+
+    #[test]
+    fn used_in_relation() {
+        let error = error_from_status(
+            reqwest::StatusCode::PRECONDITION_FAILED,
+            "Precondition failed: The relation 1 is used in relation 2.".to_string(),
+        );
+
+        match error {
+            Error::PreconditionFailed(precondition) => assert_eq!(
+                precondition,
+                PreconditionFailed::StillUsed {
+                    element: RelationId::new(1).into(),
+                    used_by: vec![RelationId::new(2).into()]
+                }
+            ),
+            _ => panic!("expected a failed precondition"),
+        }
+    }
+
+    #[test]
+    fn missing_references() {
+        let error = error_from_status(
+            reqwest::StatusCode::PRECONDITION_FAILED,
+            "Precondition failed: Way 4 requires the nodes with id in (1,2), which either do not exist, or are not visible.".to_string(),
+        );
+
+        match error {
+            Error::PreconditionFailed(precondition) => assert_eq!(
+                precondition,
+                PreconditionFailed::MissingReferences {
+                    element: WayId::new(4).into(),
+                    missing: vec![NodeId::new(1).into(), NodeId::new(2).into()]
+                }
+            ),
+            _ => panic!("expected a failed precondition"),
+        }
+    }
+
+    #[test]
+    fn precondition_other() {
+        let error = error_from_status(
+            reqwest::StatusCode::PRECONDITION_FAILED,
+            "Something else".to_string(),
+        );
+
+        assert!(matches!(
+            error,
+            Error::PreconditionFailed(PreconditionFailed::Other(_))
+        ));
+    }
+
+    #[test]
+    fn other_status() {
+        let error = error_from_status(reqwest::StatusCode::NOT_FOUND, String::new());
+
+        assert!(matches!(error, Error::HttpStatus(_)));
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use crate::{
+    model::{element::ElementType, id::ElementId},
+    xml::{self, Event, Reader},
+};
+
+/// The result of uploading an [`OsmChange`].
+///
+/// See <https://wiki.openstreetmap.org/wiki/API_v0.6#Diff_upload:_POST_/api/0.6/changeset/#id/upload>
+///
+/// [`OsmChange`]: crate::model::osm_change::OsmChange
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DiffResult {
+    pub entries: Vec<DiffEntry>,
+}
+
+/// The new id and version of a single uploaded element.
+///
+/// Both are [`None`] if the element was deleted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffEntry {
+    pub old_id: ElementId,
+    pub new_id: Option<ElementId>,
+    pub new_version: Option<u64>,
+}
+
+impl DiffResult {
+    /// Parse a diffResult XML document.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`xml::Error`] if the document is malformed.
+    pub fn from_xml(document: &str) -> Result<Self, xml::Error> {
+        let mut entries = Vec::new();
+
+        for event in Reader::new(document) {
+            if let Event::Start(tag) = event? {
+                if tag.name == "diffResult" {
+                    continue;
+                }
+
+                let element_type: ElementType = tag.name.parse()?;
+                entries.push(DiffEntry {
+                    old_id: ElementId::new(element_type, tag.parse("old_id")?),
+                    new_id: tag
+                        .parse_optional("new_id")?
+                        .map(|id| ElementId::new(element_type, id)),
+                    new_version: tag.parse_optional("new_version")?,
+                });
+            }
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Get the new ids of all elements whose id changed, e.g. created elements with placeholder ids.
+    pub fn id_map(&self) -> HashMap<ElementId, ElementId> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match entry.new_id {
+                Some(new_id) if new_id != entry.old_id => Some((entry.old_id, new_id)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Append the entries of another [`DiffResult`].
+    pub fn extend(&mut self, other: DiffResult) {
+        self.entries.extend(other.entries);
+    }
+}
+
+#[cfg(test)]
+mod diff_result_test {
+    use crate::{
+        model::id::{ElementId, NodeId},
+        osm_api::diff_result::{DiffEntry, DiffResult},
+    };
+
+    const DIFF: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <diffResult version="0.6" generator="OpenStreetMap server">
+            <node old_id="-1" new_id="100" new_version="1"/>
+            <way old_id="3" new_id="3" new_version="5"/>
+            <node old_id="8"/>
+        </diffResult>"#;
+
+    #[test]
+    fn parse() {
+        let diff = DiffResult::from_xml(DIFF).unwrap();
+
+        assert_eq!(diff.entries.len(), 3);
+        assert_eq!(
+            diff.entries[0],
+            DiffEntry {
+                old_id: NodeId::new(-1).into(),
+                new_id: Some(NodeId::new(100).into()),
+                new_version: Some(1)
+            }
+        );
+        assert_eq!(diff.entries[2].new_id, None);
+    }
+
+    #[test]
+    fn id_map() {
+        let ids = DiffResult::from_xml(DIFF).unwrap().id_map();
+
+        assert_eq!(ids.len(), 1);
+        assert_eq!(
+            ids[&ElementId::from(NodeId::new(-1))],
+            ElementId::from(NodeId::new(100))
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(DiffResult::from_xml(r#"<diffResult><area old_id="1"/></diffResult>"#).is_err());
+    }
+}
@@ -0,0 +1,308 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    coord::{CoordinateType, bbox::BBox, coordinates::Coordinates},
+    model::{
+        element::{Element, Node, Relation, RelationMember, Way},
+        id::{ChangesetId, NodeId, RelationId, WayId},
+        osm_change::OsmChange,
+        tags::Tags,
+    },
+    osm_api::{
+        diff_result::DiffResult,
+        validation::{UploadValidator, ValidationIssue},
+    },
+};
+
+/// A set of staged edits that is uploaded with one set of changeset tags.
+///
+/// New elements use negative placeholder ids which are replaced by the ids assigned by the server.
+/// If the edit exceeds the element limit of the server it is split across multiple changesets
+/// which all carry the same tags.
+#[derive(Debug, Default, Clone)]
+pub struct EditSession {
+    tags: Tags,
+    change: OsmChange,
+    /// The positions of moved nodes before they were moved.
+    original_positions: HashMap<NodeId, Coordinates>,
+    /// The lowest placeholder id in use.
+    last_placeholder: i64,
+    validators: Vec<Arc<dyn UploadValidator>>,
+}
+
+impl EditSession {
+    /// Construct a new [`EditSession`] with the tags of its changesets.
+    pub fn new(tags: Tags) -> Self {
+        Self {
+            tags,
+            ..Default::default()
+        }
+    }
+
+    /// Get the changeset tags.
+    pub fn tags(&self) -> &Tags {
+        &self.tags
+    }
+
+    /// Set a changeset tag.
+    pub fn set_tag(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.tags.insert(key.into(), value.into());
+    }
+
+    /// Stage the creation of an element.
+    ///
+    /// New elements must have a negative placeholder id.
+    pub fn create(&mut self, element: Element) {
+        self.last_placeholder = self.last_placeholder.min(element.id().value());
+        self.change.create.push(element);
+    }
+
+    /// Stage the creation of a node and return its placeholder id.
+    pub fn create_node(&mut self, coordinates: Coordinates, tags: Tags) -> NodeId {
+        let id = NodeId::new(self.next_placeholder());
+        let (lat, lon) = coordinates.into();
+        self.create(Element::Node(Node {
+            id,
+            lat: Some(lat),
+            lon: Some(lon),
+            tags,
+            ..Default::default()
+        }));
+
+        id
+    }
+
+    /// Stage the creation of a way through the given nodes and return its placeholder id.
+    ///
+    /// `nodes` may contain placeholder ids of nodes created in this session.
+    pub fn create_way(&mut self, nodes: &[NodeId], tags: Tags) -> WayId {
+        let id = WayId::new(self.next_placeholder());
+        self.create(Element::Way(Way {
+            id,
+            nodes: nodes.to_vec(),
+            tags,
+            ..Default::default()
+        }));
+
+        id
+    }
+
+    /// Stage the creation of a relation and return its placeholder id.
+    ///
+    /// `members` may reference placeholder ids of elements created in this session.
+    pub fn create_relation(&mut self, members: Vec<RelationMember>, tags: Tags) -> RelationId {
+        let id = RelationId::new(self.next_placeholder());
+        self.create(Element::Relation(Relation {
+            id,
+            members,
+            tags,
+            ..Default::default()
+        }));
+
+        id
+    }
+
+    /// Stage the modification of an element.
+    pub fn modify(&mut self, element: Element) {
+        self.change.modify.push(element);
+    }
+
+    /// Stage moving a node to new [`Coordinates`].
+    ///
+    /// The previous position of the node is remembered for [`Self::bbox`].
+    pub fn move_node(&mut self, mut node: Node, coordinates: Coordinates) {
+        if let Some(original) = node.coordinates() {
+            self.original_positions.entry(node.id).or_insert(original);
+        }
+
+        let (lat, lon) = coordinates.into();
+        node.lat = Some(lat);
+        node.lon = Some(lon);
+        self.change.modify.push(Element::Node(node));
+    }
+
+    /// Stage the deletion of an element.
+    pub fn delete(&mut self, element: Element) {
+        self.change.delete.push(element);
+    }
+
+    /// Get the staged changes.
+    pub fn change(&self) -> &OsmChange {
+        &self.change
+    }
+
+    /// Compute the [`BBox`] of all touched positions, including the old positions of moved nodes.
+    ///
+    /// Only node positions known to the session are considered, ways and relations contribute
+    /// through their staged nodes. Returns [`None`] if no position is known.
+    pub fn bbox(&self) -> Option<BBox> {
+        let positions = self
+            .change
+            .create
+            .iter()
+            .chain(self.change.modify.iter())
+            .chain(self.change.delete.iter())
+            .filter_map(|element| match element {
+                Element::Node(node) => node.coordinates(),
+                _ => None,
+            })
+            .chain(self.original_positions.values().copied())
+            .map(<(CoordinateType, CoordinateType)>::from);
+
+        let mut positions = positions.peekable();
+        let (first_lat, first_lon) = *positions.peek()?;
+        let (south, west, north, east) = positions.fold(
+            (first_lat, first_lon, first_lat, first_lon),
+            |(south, west, north, east), (lat, lon)| {
+                (south.min(lat), west.min(lon), north.max(lat), east.max(lon))
+            },
+        );
+
+        Some(BBox::from_wrapped(south, west, north, east))
+    }
+
+    /// Add an [`UploadValidator`] that runs before the session is uploaded.
+    pub fn add_validator(&mut self, validator: impl UploadValidator + 'static) {
+        self.validators.push(Arc::new(validator));
+    }
+
+    /// Run all validators and collect their issues.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        self.validators
+            .iter()
+            .flat_map(|validator| validator.validate(self))
+            .collect()
+    }
+
+    fn next_placeholder(&self) -> i64 {
+        self.last_placeholder - 1
+    }
+
+    /// Get the amount of staged elements.
+    pub fn len(&self) -> usize {
+        self.change.len()
+    }
+
+    /// Check if nothing is staged.
+    pub fn is_empty(&self) -> bool {
+        self.change.is_empty()
+    }
+}
+
+/// The result of uploading an [`EditSession`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct UploadResult {
+    /// The changesets the edit was uploaded in, in upload order.
+    pub changesets: Vec<ChangesetId>,
+    /// The combined diff of all changesets.
+    pub diff: DiffResult,
+    /// The non-blocking issues found by the validators.
+    pub warnings: Vec<ValidationIssue>,
+}
+
+#[cfg(test)]
+mod edit_session_test {
+    use crate::{
+        coord::{bbox::BBox, coordinates::Coordinates},
+        model::{
+            element::{Element, Node, RelationMember},
+            id::{NodeId, RelationId, WayId},
+            tags::Tags,
+        },
+        osm_api::{
+            edit_session::EditSession,
+            validation::{MissingComment, UntaggedNodes},
+        },
+    };
+
+    #[test]
+    fn stage() {
+        let mut session = EditSession::new(Tags::from([("comment".to_string(), "a".to_string())]));
+        session.set_tag("created_by", "rust-osm");
+        session.create(Element::Node(Node {
+            id: NodeId::new(-1),
+            ..Default::default()
+        }));
+        session.delete(Element::Node(Node {
+            id: NodeId::new(2),
+            ..Default::default()
+        }));
+
+        assert_eq!(session.len(), 2);
+        assert_eq!(session.tags().len(), 2);
+        assert_eq!(session.change().create[0].id(), NodeId::new(-1).into());
+        assert!(EditSession::default().is_empty());
+    }
+
+    #[test]
+    fn bbox() {
+        let mut session = EditSession::default();
+        assert!(session.bbox().is_none());
+
+        session.create(Element::Node(Node {
+            id: NodeId::new(-1),
+            lat: Some(1.0),
+            lon: Some(2.0),
+            ..Default::default()
+        }));
+        session.move_node(
+            Node {
+                id: NodeId::new(5),
+                lat: Some(3.0),
+                lon: Some(1.0),
+                ..Default::default()
+            },
+            Coordinates::from_wrapped(0.5, 4.0),
+        );
+
+        assert_eq!(session.bbox(), Some(BBox::from_wrapped(0.5, 1.0, 3.0, 4.0)));
+        assert_eq!(session.change().modify[0].id(), NodeId::new(5).into());
+    }
+
+    #[test]
+    fn create_helpers() {
+        let mut session = EditSession::default();
+        session.create(Element::Node(Node {
+            id: NodeId::new(-1),
+            ..Default::default()
+        }));
+
+        let a = session.create_node(Coordinates::from_wrapped(1.0, 2.0), Tags::new());
+        let b = session.create_node(Coordinates::from_wrapped(1.5, 2.5), Tags::new());
+        let way = session.create_way(
+            &[a, b],
+            Tags::from([("highway".to_string(), "path".to_string())]),
+        );
+        let relation = session.create_relation(vec![RelationMember::new(way, "")], Tags::new());
+
+        assert_eq!(
+            (a, b, way, relation),
+            (
+                NodeId::new(-2),
+                NodeId::new(-3),
+                WayId::new(-4),
+                RelationId::new(-5)
+            )
+        );
+        assert_eq!(session.len(), 5);
+        match &session.change().create[3] {
+            Element::Way(created) => assert_eq!(created.nodes, vec![a, b]),
+            _ => panic!("expected a way"),
+        }
+        assert_eq!(session.bbox(), Some(BBox::from_wrapped(1.0, 2.0, 1.5, 2.5)));
+    }
+
+    #[test]
+    fn validate() {
+        let mut session = EditSession::default();
+        session.add_validator(MissingComment);
+        session.add_validator(UntaggedNodes);
+        session.create_node(Coordinates::from_wrapped(1.0, 2.0), Tags::new());
+
+        let issues = session.validate();
+
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].is_blocking());
+        assert!(!issues[1].is_blocking());
+    }
+}
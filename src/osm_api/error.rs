@@ -0,0 +1,68 @@
+use crate::{
+    coord::CoordinateType,
+    model::id::ChangesetId,
+    osm_api::{
+        conflict::{PreconditionFailed, VersionConflict},
+        edit_session::UploadResult,
+        validation::ValidationIssue,
+    },
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("The request could not be sent: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("The response could not be parsed: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("The response could not be parsed: {0}")]
+    Xml(#[from] crate::xml::Error),
+    #[error("The server responded with {}: {}", .0.0, .0.1)]
+    HttpStatus((reqwest::StatusCode, String)),
+    #[error("Version conflict: {0}")]
+    VersionConflict(VersionConflict),
+    #[error("The changeset {} was closed at {}", .0.0, .0.1)]
+    ChangesetClosed((ChangesetId, String)),
+    #[error("Conflict: {0}")]
+    Conflict(String),
+    #[error("The element has been deleted: {0}")]
+    Gone(String),
+    #[error("Precondition failed: {0}")]
+    PreconditionFailed(PreconditionFailed),
+    #[error("Basic auth is not permitted for {0}")]
+    BasicAuthNotAllowed(String),
+    #[error("The upload is blocked by {} validation issues", .0.len())]
+    Validation(Vec<ValidationIssue>),
+    #[error("The server does not support API version {}, only {:?}", .0.0, .0.1)]
+    UnsupportedVersion((&'static str, Vec<String>)),
+    #[error("The server rate limited the request with {}, retry after {:?}", .0.0, .0.1)]
+    RateLimited((reqwest::StatusCode, Option<std::time::Duration>)),
+    #[error("Requests are suspended for {0:?} after repeated rate limiting")]
+    CircuitOpen(std::time::Duration),
+    #[error("The url {} is not valid: {}", .0.0, .0.1)]
+    InvalidUrl((String, String)),
+    #[error("The response is not valid: {0}")]
+    InvalidResponse(String),
+    #[error("The area of {} deg2 exceeds the maximum of {} deg2", .0.0, .0.1)]
+    AreaTooLarge((CoordinateType, CoordinateType)),
+    #[error("{} elements exceed the maximum of {}", .0.0, .0.1)]
+    TooManyElements((usize, u64)),
+    #[error("A PKCE code verifier must have 43 to 128 characters from A-Z, a-z, 0-9 and -._~")]
+    InvalidVerifier,
+    #[error("The operating system could not supply random bytes: {0}")]
+    Random(getrandom::Error),
+    /// The [`UploadResult`] of the changesets applied before the upload failed.
+    #[error("The upload failed after {} changesets were applied: {}", .0.0.changesets.len(), .0.1)]
+    PartialUpload(Box<(UploadResult, Error)>),
+}
+
+impl Error {
+    /// Attach the changesets applied before `error` to it, if there are any.
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    pub(crate) fn partial_upload(result: UploadResult, error: Error) -> Self {
+        if result.changesets.is_empty() {
+            error
+        } else {
+            Self::PartialUpload(Box::new((result, error)))
+        }
+    }
+}
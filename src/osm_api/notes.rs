@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+
+use crate::coord::{self, CoordinateType, coordinates::Coordinates};
+
+/// A note on the map pointing out a problem or missing data.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Notes>
+///
+/// Serializes into a GeoJSON feature in the format of the API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "NoteFeature", into = "NoteFeature")]
+pub struct Note {
+    pub id: u64,
+    pub coordinates: Coordinates,
+    pub status: NoteStatus,
+    pub date_created: String,
+    pub date_closed: Option<String>,
+    pub comments: Vec<NoteComment>,
+}
+
+impl Note {
+    /// Convert this [`Note`] into a GeoJSON feature with the status and comments as properties.
+    pub fn to_geojson(&self) -> serde_json::Value {
+        serde_json::json!(NoteFeature::from(self.clone()))
+    }
+}
+
+/// Convert [`Note`]s into a GeoJSON feature collection.
+pub fn notes_to_geojson(notes: &[Note]) -> serde_json::Value {
+    serde_json::json!({
+        "type": "FeatureCollection",
+        "features": notes.iter().map(Note::to_geojson).collect::<Vec<_>>(),
+    })
+}
+
+/// The status of a [`Note`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteStatus {
+    Open,
+    Closed,
+    Hidden,
+}
+
+/// A single comment in the thread of a [`Note`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoteComment {
+    pub date: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    pub action: NoteAction,
+    #[serde(default)]
+    pub text: String,
+}
+
+/// The action a [`NoteComment`] performed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteAction {
+    Opened,
+    Commented,
+    Closed,
+    Reopened,
+    Hidden,
+}
+
+/// A list of [`Note`]s as returned by the API.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+#[derive(Debug, Deserialize)]
+pub(crate) struct NoteCollection {
+    #[serde(default)]
+    pub(crate) features: Vec<Note>,
+}
+
+/// A [`Note`] as GeoJSON feature as returned by the API.
+#[derive(Debug, Serialize, Deserialize)]
+struct NoteFeature {
+    #[serde(rename = "type", default)]
+    feature_type: FeatureType,
+    geometry: NoteGeometry,
+    properties: NoteProperties,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+enum FeatureType {
+    #[default]
+    Feature,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+enum GeometryType {
+    #[default]
+    Point,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NoteGeometry {
+    #[serde(rename = "type", default)]
+    geometry_type: GeometryType,
+    /// Longitude and latitude.
+    coordinates: (CoordinateType, CoordinateType),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NoteProperties {
+    id: u64,
+    status: NoteStatus,
+    date_created: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    closed_at: Option<String>,
+    #[serde(default)]
+    comments: Vec<NoteComment>,
+}
+
+impl From<Note> for NoteFeature {
+    fn from(note: Note) -> Self {
+        Self {
+            feature_type: FeatureType::Feature,
+            geometry: NoteGeometry {
+                geometry_type: GeometryType::Point,
+                coordinates: (
+                    note.coordinates.longitude().value(),
+                    note.coordinates.latitude().value(),
+                ),
+            },
+            properties: NoteProperties {
+                id: note.id,
+                status: note.status,
+                date_created: note.date_created,
+                closed_at: note.date_closed,
+                comments: note.comments,
+            },
+        }
+    }
+}
+
+impl TryFrom<NoteFeature> for Note {
+    type Error = coord::error::Error;
+
+    fn try_from(feature: NoteFeature) -> Result<Self, Self::Error> {
+        let (longitude, latitude) = feature.geometry.coordinates;
+
+        Ok(Self {
+            id: feature.properties.id,
+            coordinates: Coordinates::from_value(latitude, longitude)?,
+            status: feature.properties.status,
+            date_created: feature.properties.date_created,
+            date_closed: feature.properties.closed_at,
+            comments: feature.properties.comments,
+        })
+    }
+}
+
+#[cfg(test)]
+mod notes_test {
+    use crate::{
+        coord::coordinates::Coordinates,
+        osm_api::notes::{Note, NoteAction, NoteCollection, NoteStatus, notes_to_geojson},
+    };
+
+    const NOTE: &str = r#"{
+        "type": "Feature",
+        "geometry": {"type": "Point", "coordinates": [2.5, 1.5]},
+        "properties": {
+            "id": 42,
+            "url": "https://api.openstreetmap.org/api/0.6/notes/42.json",
+            "date_created": "2025-01-01 10:00:00 UTC",
+            "status": "closed",
+            "closed_at": "2025-01-02 10:00:00 UTC",
+            "comments": [
+                {"date": "2025-01-01 10:00:00 UTC", "action": "opened", "text": "Missing cafe", "html": "<p>Missing cafe</p>"},
+                {"date": "2025-01-02 10:00:00 UTC", "uid": 7, "user": "a", "action": "closed", "text": "", "html": ""}
+            ]
+        }
+    }"#;
+
+    #[test]
+    fn parse() {
+        let note: Note = serde_json::from_str(NOTE).unwrap();
+
+        assert_eq!(note.id, 42);
+        assert_eq!(note.coordinates, Coordinates::from_value(1.5, 2.5).unwrap());
+        assert_eq!(note.status, NoteStatus::Closed);
+        assert_eq!(note.date_closed.as_deref(), Some("2025-01-02 10:00:00 UTC"));
+    }
+
+    #[test]
+    fn parse_comments() {
+        let note: Note = serde_json::from_str(NOTE).unwrap();
+
+        assert_eq!(note.comments.len(), 2);
+        assert_eq!(note.comments[0].action, NoteAction::Opened);
+        assert_eq!(note.comments[0].user, None);
+        assert_eq!(note.comments[1].uid, Some(7));
+    }
+
+    #[test]
+    fn parse_collection() {
+        let collection =
+            format!(r#"{{"type": "FeatureCollection", "features": [{NOTE}, {NOTE}]}}"#);
+        let notes: NoteCollection = serde_json::from_str(&collection).unwrap();
+
+        assert_eq!(notes.features.len(), 2);
+    }
+
+    #[test]
+    fn parse_invalid_coordinates() {
+        let note = NOTE.replace("[2.5, 1.5]", "[2.5, 100.0]");
+
+        assert!(serde_json::from_str::<Note>(&note).is_err());
+    }
+
+    #[test]
+    fn geojson() {
+        let note: Note = serde_json::from_str(NOTE).unwrap();
+        let feature = note.to_geojson();
+
+        assert_eq!(feature["type"], "Feature");
+        assert_eq!(feature["geometry"]["type"], "Point");
+        assert_eq!(feature["geometry"]["coordinates"][0], 2.5);
+        assert_eq!(feature["properties"]["status"], "closed");
+        assert_eq!(feature["properties"]["comments"][1]["user"], "a");
+        assert_eq!(serde_json::from_value::<Note>(feature).unwrap(), note);
+    }
+
+    #[test]
+    fn geojson_collection() {
+        let note: Note = serde_json::from_str(NOTE).unwrap();
+        let collection = notes_to_geojson(&[note.clone(), note]);
+
+        assert_eq!(collection["type"], "FeatureCollection");
+        assert_eq!(collection["features"].as_array().unwrap().len(), 2);
+    }
+}
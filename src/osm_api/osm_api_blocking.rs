@@ -0,0 +1,1084 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::{Arc, OnceLock},
+    time::Instant,
+};
+
+use serde::de::DeserializeOwned;
+
+use crate::{
+    client_config::{ClientConfig, redact_url},
+    coord::{bbox::BBox, coordinates::Coordinates},
+    gpx::Gpx,
+    model::{
+        element::{ElementType, Node, Relation, Way},
+        id::{ChangesetId, ElementId, NodeId, RelationId, WayId},
+        osm_change::OsmChange,
+        osm_data::{Bounds, OsmData},
+        tags::Tags,
+    },
+    osm_api::{
+        API_VERSION, OSM_API_URL,
+        auth::TokenProvider,
+        capabilities::Capabilities,
+        changeset::{Changeset, ChangesetQuery, ChangesetResponse, Changesets, changeset_xml},
+        conflict::error_from_status,
+        credentials::{Credentials, is_production_api},
+        diff_result::DiffResult,
+        edit_session::{EditSession, UploadResult},
+        error::Error,
+        notes::{Note, NoteCollection},
+        permissions::Permissions,
+        rate_limit::{CircuitBreaker, RateLimitPolicy},
+        recovery::reconcile,
+        traces::{Trace, TraceUpload, parse_traces},
+        trackpoints::parse_trackpoints,
+        user::{User, UserResponse, UsersResponse},
+        user_block::{UserBlock, UserBlockResponse, UserBlocksResponse},
+        versions::{ApiVersions, VersionsResponse},
+    },
+    osm_config::OsmConfig,
+    request_limit::BlockingRequestLimit,
+    retry::{RetryPolicy, is_rate_limited, retry_after},
+    scheduler::{BlockingScheduledTransport, BlockingScheduler},
+    transport::BlockingHttpTransport,
+};
+
+#[cfg(feature = "redaction")]
+use crate::model::element::Element;
+
+/// A blocking client for the OSM API v0.6.
+///
+/// See <https://wiki.openstreetmap.org/wiki/API_v0.6>
+#[derive(Clone)]
+pub struct OsmApiClient {
+    inner: Arc<Inner>,
+}
+
+/// The state shared by the clones of an [`OsmApiClient`].
+#[derive(Clone)]
+struct Inner {
+    base_url: String,
+    client: reqwest::blocking::Client,
+    credentials: Option<Credentials>,
+    capabilities: OnceLock<Capabilities>,
+    retry_policy: RetryPolicy,
+    transport: Option<Arc<dyn BlockingHttpTransport>>,
+    request_limit: Option<BlockingRequestLimit>,
+    scheduler: Option<BlockingScheduler>,
+    rate_limit_policy: RateLimitPolicy,
+    circuit_breaker: Arc<CircuitBreaker>,
+}
+
+impl fmt::Debug for OsmApiClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OsmApiClient")
+            .field("base_url", &redact_url(&self.inner.base_url))
+            .field("client", &self.inner.client)
+            .field("credentials", &self.inner.credentials)
+            .field("capabilities", &self.inner.capabilities)
+            .field("retry_policy", &self.inner.retry_policy)
+            .field("transport", &self.inner.transport)
+            .field("request_limit", &self.inner.request_limit)
+            .field("scheduler", &self.inner.scheduler)
+            .field("rate_limit_policy", &self.inner.rate_limit_policy)
+            .field("circuit_breaker", &self.inner.circuit_breaker)
+            .finish()
+    }
+}
+
+impl OsmApiClient {
+    /// Construct a new [`OsmApiClient`] from the base url of the API, e.g. [`OSM_API_URL`].
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                base_url: base_url.into(),
+                client: reqwest::blocking::Client::new(),
+                credentials: None,
+                capabilities: OnceLock::new(),
+                retry_policy: RetryPolicy::default(),
+                transport: None,
+                request_limit: None,
+                scheduler: None,
+                rate_limit_policy: RateLimitPolicy::default(),
+                circuit_breaker: Arc::new(CircuitBreaker::default()),
+            }),
+        }
+    }
+
+    /// Authenticate all requests with an OAuth 2.0 access token.
+    ///
+    /// An access token is required for all write operations.
+    pub fn with_access_token(mut self, access_token: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.inner).credentials =
+            Some(Credentials::AccessToken(access_token.into()));
+        self
+    }
+
+    /// Authenticate all requests with the access tokens of a [`TokenProvider`], asked before
+    /// every request so the token can be refreshed while the client is in use.
+    pub fn with_token_provider(mut self, token_provider: Arc<dyn TokenProvider>) -> Self {
+        Arc::make_mut(&mut self.inner).credentials =
+            Some(Credentials::TokenProvider(token_provider));
+        self
+    }
+
+    /// Set how failed requests are retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        Arc::make_mut(&mut self.inner).retry_policy = retry_policy;
+        self
+    }
+
+    /// Send the requests with a [`BlockingHttpTransport`] instead of the [`reqwest::blocking::Client`], e.g. a
+    /// [`MockTransport`](crate::transport::MockTransport) in tests.
+    pub fn with_transport(mut self, transport: Arc<dyn BlockingHttpTransport>) -> Self {
+        Arc::make_mut(&mut self.inner).transport = Some(transport);
+        self
+    }
+
+    /// Limit how many requests are in flight at once. The limit is shared by all clones of this
+    /// client and can be shared with other clients.
+    pub fn with_request_limit(mut self, request_limit: BlockingRequestLimit) -> Self {
+        Arc::make_mut(&mut self.inner).request_limit = Some(request_limit);
+        self
+    }
+
+    /// Send the requests in the slots of a [`BlockingScheduler`] shared with other clients, queued in its
+    /// `osm_api` lane.
+    pub fn with_scheduler(mut self, scheduler: BlockingScheduler) -> Self {
+        Arc::make_mut(&mut self.inner).scheduler = Some(scheduler);
+        self
+    }
+
+    /// Set when requests rejected by the rate limits of the server open the circuit breaker.
+    ///
+    /// Clones of this client share the state of the circuit breaker.
+    pub fn with_rate_limit_policy(mut self, rate_limit_policy: RateLimitPolicy) -> Self {
+        Arc::make_mut(&mut self.inner).rate_limit_policy = rate_limit_policy;
+        self
+    }
+
+    /// Authenticate all requests with HTTP Basic auth.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`Error::BasicAuthNotAllowed`] if the client targets a host of the production API
+    /// on openstreetmap.org, which only accepts OAuth 2.0 access tokens.
+    pub fn with_basic_auth(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Result<Self, Error> {
+        if is_production_api(&self.inner.base_url) {
+            return Err(Error::BasicAuthNotAllowed(self.inner.base_url.clone()));
+        }
+
+        Arc::make_mut(&mut self.inner).credentials = Some(Credentials::Basic {
+            username: username.into(),
+            password: password.into(),
+        });
+        Ok(self)
+    }
+
+    /// Construct a new [`OsmApiClient`] with the url, HTTP client, retry policy and credentials
+    /// of an [`OsmConfig`].
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::Request`] if the HTTP client can not be built and a
+    /// [`Error::BasicAuthNotAllowed`] for Basic auth against the production API.
+    pub fn from_config(config: &OsmConfig) -> Result<Self, Error> {
+        let client = Self::new(config.osm_api_url())
+            .with_config(config.client_config())?
+            .with_retry_policy(config.retry_policy().clone());
+
+        match config.credentials() {
+            Some(Credentials::AccessToken(access_token)) => {
+                Ok(client.with_access_token(access_token.clone()))
+            }
+            Some(Credentials::Basic { username, password }) => {
+                client.with_basic_auth(username.clone(), password.clone())
+            }
+            Some(Credentials::TokenProvider(token_provider)) => {
+                Ok(client.with_token_provider(token_provider.clone()))
+            }
+            None => Ok(client),
+        }
+    }
+
+    /// Use an HTTP client with the given [`ClientConfig`], e.g. with custom timeouts.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::Request`] if the HTTP client can not be built.
+    pub fn with_config(mut self, config: &ClientConfig) -> Result<Self, Error> {
+        Arc::make_mut(&mut self.inner).client = config.build_blocking()?;
+        Ok(self)
+    }
+
+    /// Get the base url of the API.
+    pub fn base_url(&self) -> &str {
+        &self.inner.base_url
+    }
+
+    /// Fetch the [`Capabilities`] of the API.
+    ///
+    /// The result is cached and used to validate requests before they are sent.
+    pub fn capabilities(&self) -> Result<Capabilities, Error> {
+        if let Some(capabilities) = self.inner.capabilities.get() {
+            return Ok(capabilities.clone());
+        }
+
+        let capabilities: Capabilities = self.get_json("/capabilities.json")?;
+
+        Ok(self.inner.capabilities.get_or_init(|| capabilities).clone())
+    }
+
+    /// Fetch the API versions supported by the server.
+    ///
+    /// The versions endpoint lives next to the versioned base url, e.g. `/api/versions`.
+    pub fn versions(&self) -> Result<ApiVersions, Error> {
+        let base_url = self.inner.base_url.trim_end_matches('/');
+        let root = base_url.rsplit_once('/').map_or(base_url, |(root, _)| root);
+        let response: VersionsResponse =
+            self.send_json(self.inner.client.get(format!("{root}/versions.json")))?;
+
+        Ok(response.api)
+    }
+
+    /// Check that the server supports the API version implemented by this crate.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`Error::UnsupportedVersion`] if the version is not supported.
+    pub fn check_version(&self) -> Result<(), Error> {
+        let versions = self.versions()?;
+
+        if versions.is_supported() {
+            Ok(())
+        } else {
+            Err(Error::UnsupportedVersion((API_VERSION, versions.versions)))
+        }
+    }
+
+    /// Fetch the [`Permissions`] granted to the credentials of this client.
+    pub fn permissions(&self) -> Result<Permissions, Error> {
+        self.get_json("/permissions.json")
+    }
+
+    /// Fetch all elements inside a [`BBox`].
+    ///
+    /// A [`BBox`] crossing the antimeridian sends one request per half.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`Error::AreaTooLarge`] if the [`BBox`] exceeds the maximum area of the API.
+    pub fn get_map(&self, bbox: &BBox) -> Result<OsmData, Error> {
+        self.capabilities()?.validate_area(bbox)?;
+
+        // The API rejects a bbox crossing the antimeridian, its halves are fetched separately.
+        let mut data: Option<OsmData> = None;
+        for half in bbox.halves().into_iter().flatten() {
+            let (south, west, north, east) = half.corners();
+            let half: OsmData =
+                self.get_json(&format!("/map.json?bbox={west},{south},{east},{north}"))?;
+            match data {
+                Some(ref mut data) => data.merge(half),
+                None => data = Some(half),
+            }
+        }
+
+        let mut data = data.unwrap_or_default();
+        if bbox.crosses_antimeridian() {
+            let (minlat, minlon, maxlat, maxlon) = bbox.corners();
+            data.bounds = Some(Bounds {
+                minlat,
+                minlon,
+                maxlat,
+                maxlon,
+            });
+        }
+
+        Ok(data)
+    }
+
+    /// Fetch the current version of a node.
+    pub fn get_node(&self, id: NodeId) -> Result<Node, Error> {
+        self.get_json::<OsmData>(&format!("/node/{id}.json"))?
+            .into_nodes()
+            .pop()
+            .ok_or_else(|| Error::InvalidResponse(format!("node {id} missing in response")))
+    }
+
+    /// Fetch the current version of a way.
+    pub fn get_way(&self, id: WayId) -> Result<Way, Error> {
+        self.get_json::<OsmData>(&format!("/way/{id}.json"))?
+            .into_ways()
+            .pop()
+            .ok_or_else(|| Error::InvalidResponse(format!("way {id} missing in response")))
+    }
+
+    /// Fetch the current version of a relation.
+    pub fn get_relation(&self, id: RelationId) -> Result<Relation, Error> {
+        self.get_json::<OsmData>(&format!("/relation/{id}.json"))?
+            .into_relations()
+            .pop()
+            .ok_or_else(|| Error::InvalidResponse(format!("relation {id} missing in response")))
+    }
+
+    /// Fetch the current versions of multiple elements.
+    ///
+    /// One request is sent per element type in `ids`.
+    pub fn get_elements(&self, ids: &[ElementId]) -> Result<OsmData, Error> {
+        let mut data = OsmData::default();
+
+        for element_type in [ElementType::Node, ElementType::Way, ElementType::Relation] {
+            let ids: Vec<String> = ids
+                .iter()
+                .filter(|id| id.element_type() == element_type)
+                .map(|id| id.value().to_string())
+                .collect();
+            if ids.is_empty() {
+                continue;
+            }
+
+            let name = element_type.as_str();
+            let fetched: OsmData =
+                self.get_json(&format!("/{name}s.json?{name}s={}", ids.join(",")))?;
+            data.elements.extend(fetched.elements);
+        }
+
+        Ok(data)
+    }
+
+    /// Fetch a way together with all nodes it references.
+    pub fn get_way_full(&self, id: WayId) -> Result<OsmData, Error> {
+        self.get_json(&format!("/way/{id}/full.json"))
+    }
+
+    /// Fetch a relation together with all its members and the nodes of member ways.
+    pub fn get_relation_full(&self, id: RelationId) -> Result<OsmData, Error> {
+        self.get_json(&format!("/relation/{id}/full.json"))
+    }
+
+    /// Fetch all versions of a node in chronological order.
+    pub fn get_node_history(&self, id: NodeId) -> Result<Vec<Node>, Error> {
+        let mut nodes = self
+            .get_json::<OsmData>(&format!("/node/{id}/history.json"))?
+            .into_nodes();
+        nodes.sort_by_key(|node| node.metadata.version);
+
+        Ok(nodes)
+    }
+
+    /// Fetch all versions of a way in chronological order.
+    pub fn get_way_history(&self, id: WayId) -> Result<Vec<Way>, Error> {
+        let mut ways = self
+            .get_json::<OsmData>(&format!("/way/{id}/history.json"))?
+            .into_ways();
+        ways.sort_by_key(|way| way.metadata.version);
+
+        Ok(ways)
+    }
+
+    /// Fetch all versions of a relation in chronological order.
+    pub fn get_relation_history(&self, id: RelationId) -> Result<Vec<Relation>, Error> {
+        let mut relations = self
+            .get_json::<OsmData>(&format!("/relation/{id}/history.json"))?
+            .into_relations();
+        relations.sort_by_key(|relation| relation.metadata.version);
+
+        Ok(relations)
+    }
+
+    /// Fetch a specific version of a node, including deleted versions.
+    ///
+    /// Versions hidden by a redaction are returned as [`Error::HttpStatus`] with `403 Forbidden`.
+    pub fn get_node_version(&self, id: NodeId, version: u64) -> Result<Node, Error> {
+        self.get_json::<OsmData>(&format!("/node/{id}/{version}.json"))?
+            .into_nodes()
+            .pop()
+            .ok_or_else(|| {
+                Error::InvalidResponse(format!("node {id} version {version} missing in response"))
+            })
+    }
+
+    /// Fetch a specific version of a way, including deleted versions.
+    ///
+    /// Versions hidden by a redaction are returned as [`Error::HttpStatus`] with `403 Forbidden`.
+    pub fn get_way_version(&self, id: WayId, version: u64) -> Result<Way, Error> {
+        self.get_json::<OsmData>(&format!("/way/{id}/{version}.json"))?
+            .into_ways()
+            .pop()
+            .ok_or_else(|| {
+                Error::InvalidResponse(format!("way {id} version {version} missing in response"))
+            })
+    }
+
+    /// Fetch a specific version of a relation, including deleted versions.
+    ///
+    /// Versions hidden by a redaction are returned as [`Error::HttpStatus`] with `403 Forbidden`.
+    pub fn get_relation_version(&self, id: RelationId, version: u64) -> Result<Relation, Error> {
+        self.get_json::<OsmData>(&format!("/relation/{id}/{version}.json"))?
+            .into_relations()
+            .pop()
+            .ok_or_else(|| {
+                Error::InvalidResponse(format!(
+                    "relation {id} version {version} missing in response"
+                ))
+            })
+    }
+
+    /// Fetch all ways that reference the given node.
+    pub fn get_ways_for_node(&self, id: NodeId) -> Result<Vec<Way>, Error> {
+        Ok(self
+            .get_json::<OsmData>(&format!("/node/{id}/ways.json"))?
+            .into_ways())
+    }
+
+    /// Fetch all relations that have the given element as a member.
+    pub fn get_relations_for_element(&self, element: ElementId) -> Result<Vec<Relation>, Error> {
+        Ok(self
+            .get_json::<OsmData>(&format!(
+                "/{}/{}/relations.json",
+                element.element_type().as_str(),
+                element.value()
+            ))?
+            .into_relations())
+    }
+
+    /// Open a new changeset with the given tags.
+    ///
+    /// Requires credentials, see [`Self::with_access_token`] and [`Self::with_basic_auth`].
+    pub fn create_changeset(&self, tags: &Tags) -> Result<ChangesetId, Error> {
+        let response = self.send(
+            self.request(reqwest::Method::PUT, "/changeset/create")
+                .header(reqwest::header::CONTENT_TYPE, "text/xml")
+                .body(changeset_xml(tags)),
+        )?;
+        let body = response.text()?;
+
+        body.trim()
+            .parse::<u64>()
+            .map(ChangesetId::new)
+            .map_err(|_| Error::InvalidResponse(body))
+    }
+
+    /// Replace the tags of an open changeset.
+    ///
+    /// Requires credentials, see [`Self::with_access_token`] and [`Self::with_basic_auth`].
+    pub fn update_changeset(&self, id: ChangesetId, tags: &Tags) -> Result<(), Error> {
+        self.send(
+            self.request(reqwest::Method::PUT, &format!("/changeset/{id}"))
+                .header(reqwest::header::CONTENT_TYPE, "text/xml")
+                .body(changeset_xml(tags)),
+        )?;
+
+        Ok(())
+    }
+
+    /// Close an open changeset.
+    ///
+    /// Requires credentials, see [`Self::with_access_token`] and [`Self::with_basic_auth`].
+    pub fn close_changeset(&self, id: ChangesetId) -> Result<(), Error> {
+        self.send(self.request(reqwest::Method::PUT, &format!("/changeset/{id}/close")))?;
+
+        Ok(())
+    }
+
+    /// Fetch the metadata of a changeset.
+    ///
+    /// If `include_discussion` is set, [`Changeset::comments`] contains the discussion.
+    pub fn get_changeset(
+        &self,
+        id: ChangesetId,
+        include_discussion: bool,
+    ) -> Result<Changeset, Error> {
+        let mut request = self.request(reqwest::Method::GET, &format!("/changeset/{id}.json"));
+        if include_discussion {
+            request = request.query(&[("include_discussion", "true")]);
+        }
+
+        Ok(self.send_json::<ChangesetResponse>(request)?.changeset)
+    }
+
+    /// Query changesets matching a [`ChangesetQuery`].
+    ///
+    /// A [`BBox`] crossing the antimeridian sends one request per half. Use
+    /// [`ChangesetQuery::next_page`] to fetch further pages.
+    pub fn query_changesets(&self, query: &ChangesetQuery) -> Result<Vec<Changeset>, Error> {
+        let mut pages = Vec::new();
+        for pairs in query.requests() {
+            let changesets: Changesets = self.send_json(
+                self.request(reqwest::Method::GET, "/changesets.json")
+                    .query(&pairs),
+            )?;
+            pages.push(changesets.changesets);
+        }
+
+        Ok(query.merge(pages))
+    }
+
+    /// Fetch all changes made in a changeset.
+    pub fn get_changeset_download(&self, id: ChangesetId) -> Result<OsmChange, Error> {
+        let response =
+            self.send(self.request(reqwest::Method::GET, &format!("/changeset/{id}/download")))?;
+
+        Ok(OsmChange::from_xml(&response.text()?)?)
+    }
+
+    /// Upload changes to an open changeset.
+    ///
+    /// Requires credentials, see [`Self::with_access_token`] and [`Self::with_basic_auth`].
+    pub fn upload_changeset(
+        &self,
+        id: ChangesetId,
+        change: &OsmChange,
+    ) -> Result<DiffResult, Error> {
+        let response = self.send(
+            self.request(reqwest::Method::POST, &format!("/changeset/{id}/upload"))
+                .header(reqwest::header::CONTENT_TYPE, "text/xml")
+                .body(change.to_xml(Some(id))),
+        )?;
+
+        Ok(DiffResult::from_xml(&response.text()?)?)
+    }
+
+    /// Resume an upload to an open changeset that failed without a response.
+    ///
+    /// The changeset is downloaded to determine which parts of `change` were already applied,
+    /// only the remaining changes are uploaded. This prevents creating elements twice.
+    /// The returned [`DiffResult`] covers all of `change`.
+    ///
+    /// Requires credentials, see [`Self::with_access_token`] and [`Self::with_basic_auth`].
+    pub fn resume_upload(&self, id: ChangesetId, change: &OsmChange) -> Result<DiffResult, Error> {
+        let applied = self.get_changeset_download(id)?;
+        let mut reconciliation = reconcile(change, &applied);
+
+        if !reconciliation.remaining.is_empty() {
+            let diff = self.upload_changeset(id, &reconciliation.remaining)?;
+            reconciliation.applied.extend(diff);
+        }
+
+        Ok(reconciliation.applied)
+    }
+
+    /// Upload an [`EditSession`], opening and closing as many changesets as required.
+    ///
+    /// The edit is split by the element limit of the server. Placeholder ids of elements created in
+    /// an earlier changeset are replaced by their new ids before the next changeset is uploaded.
+    /// The validators of the session run first, see [`EditSession::add_validator`].
+    /// Uploads that fail without a response are resumed, see [`Self::resume_upload`].
+    /// Every changeset is closed, even if its upload failed.
+    ///
+    /// Requires credentials, see [`Self::with_access_token`] and [`Self::with_basic_auth`].
+    ///
+    /// # Error
+    ///
+    /// Returns a [`Error::PartialUpload`] with the changesets applied so far if the upload fails
+    /// after the first changeset was applied, so they are not uploaded again.
+    pub fn upload_session(&self, session: &EditSession) -> Result<UploadResult, Error> {
+        let (blocking, warnings): (Vec<_>, Vec<_>) = session
+            .validate()
+            .into_iter()
+            .partition(|issue| issue.is_blocking());
+        if !blocking.is_empty() {
+            return Err(Error::Validation(blocking));
+        }
+
+        let maximum = self.capabilities()?.api.changesets.maximum_elements;
+        let chunks = session
+            .change()
+            .split(usize::try_from(maximum).unwrap_or(usize::MAX));
+
+        let mut result = UploadResult {
+            warnings,
+            ..Default::default()
+        };
+        let mut ids = HashMap::new();
+        for mut chunk in chunks {
+            chunk.remap_ids(&ids);
+
+            let id = match self.create_changeset(session.tags()) {
+                Ok(id) => id,
+                Err(error) => return Err(Error::partial_upload(result, error)),
+            };
+
+            let upload = match self.upload_changeset(id, &chunk) {
+                // The upload may have been applied even though the response got lost.
+                Err(Error::Request(_)) => self.resume_upload(id, &chunk),
+                upload => upload,
+            };
+            let close = self.close_changeset(id);
+            let diff = match upload {
+                Ok(diff) => diff,
+                Err(error) => return Err(Error::partial_upload(result, error)),
+            };
+
+            result.changesets.push(id);
+            ids.extend(diff.id_map());
+            result.diff.extend(diff);
+            if let Err(error) = close {
+                return Err(Error::partial_upload(result, error));
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Add a comment to a closed changeset.
+    ///
+    /// Requires credentials, see [`Self::with_access_token`] and [`Self::with_basic_auth`].
+    pub fn comment_changeset(&self, id: ChangesetId, text: &str) -> Result<(), Error> {
+        self.send(
+            self.request(reqwest::Method::POST, &format!("/changeset/{id}/comment"))
+                .form(&[("text", text)]),
+        )?;
+
+        Ok(())
+    }
+
+    /// Subscribe to the discussion of a changeset.
+    ///
+    /// Requires credentials, see [`Self::with_access_token`] and [`Self::with_basic_auth`].
+    pub fn subscribe_changeset(&self, id: ChangesetId) -> Result<(), Error> {
+        self.send(self.request(reqwest::Method::POST, &format!("/changeset/{id}/subscribe")))?;
+
+        Ok(())
+    }
+
+    /// Unsubscribe from the discussion of a changeset.
+    ///
+    /// Requires credentials, see [`Self::with_access_token`] and [`Self::with_basic_auth`].
+    pub fn unsubscribe_changeset(&self, id: ChangesetId) -> Result<(), Error> {
+        self.send(self.request(
+            reqwest::Method::POST,
+            &format!("/changeset/{id}/unsubscribe"),
+        ))?;
+
+        Ok(())
+    }
+
+    /// Fetch a single note.
+    pub fn get_note(&self, id: u64) -> Result<Note, Error> {
+        self.get_json(&format!("/notes/{id}.json"))
+    }
+
+    /// Fetch the notes inside a [`BBox`].
+    ///
+    /// `limit` restricts the amount of notes returned. Closed notes are included if they were
+    /// closed within the last `closed_days` days, `-1` includes all closed notes. A [`BBox`]
+    /// crossing the antimeridian sends one request per half.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`Error::AreaTooLarge`] if the [`BBox`] exceeds the maximum notes area of the API.
+    pub fn get_notes(
+        &self,
+        bbox: &BBox,
+        limit: Option<u32>,
+        closed_days: Option<i64>,
+    ) -> Result<Vec<Note>, Error> {
+        self.capabilities()?.validate_note_area(bbox)?;
+
+        // The API rejects a bbox crossing the antimeridian, its halves are fetched separately.
+        let mut notes: Vec<Note> = Vec::new();
+        let mut ids = HashSet::new();
+        for half in bbox.halves().into_iter().flatten() {
+            let (south, west, north, east) = half.corners();
+            let mut query = vec![("bbox", format!("{west},{south},{east},{north}"))];
+            if let Some(limit) = limit {
+                query.push(("limit", limit.to_string()));
+            }
+            if let Some(closed_days) = closed_days {
+                query.push(("closed", closed_days.to_string()));
+            }
+
+            let half: NoteCollection = self.send_json(
+                self.request(reqwest::Method::GET, "/notes.json")
+                    .query(&query),
+            )?;
+            notes.extend(half.features.into_iter().filter(|note| ids.insert(note.id)));
+        }
+        if let Some(limit) = limit {
+            notes.truncate(limit as usize);
+        }
+
+        Ok(notes)
+    }
+
+    /// Search all notes for the given text.
+    pub fn search_notes(&self, query: &str) -> Result<Vec<Note>, Error> {
+        let notes: NoteCollection = self.send_json(
+            self.request(reqwest::Method::GET, "/notes/search.json")
+                .query(&[("q", query)]),
+        )?;
+
+        Ok(notes.features)
+    }
+
+    /// Open a new note at the given [`Coordinates`].
+    ///
+    /// Notes can be created anonymously.
+    pub fn create_note(&self, coordinates: Coordinates, text: &str) -> Result<Note, Error> {
+        let (latitude, longitude) = coordinates.into();
+
+        self.send_json(self.request(reqwest::Method::POST, "/notes.json").query(&[
+            ("lat", latitude.to_string().as_str()),
+            ("lon", longitude.to_string().as_str()),
+            ("text", text),
+        ]))
+    }
+
+    /// Add a comment to an open note.
+    ///
+    /// Requires credentials, see [`Self::with_access_token`] and [`Self::with_basic_auth`].
+    pub fn comment_note(&self, id: u64, text: &str) -> Result<Note, Error> {
+        self.send_json(
+            self.request(reqwest::Method::POST, &format!("/notes/{id}/comment.json"))
+                .query(&[("text", text)]),
+        )
+    }
+
+    /// Close an open note with an optional comment.
+    ///
+    /// Requires credentials, see [`Self::with_access_token`] and [`Self::with_basic_auth`].
+    pub fn close_note(&self, id: u64, text: Option<&str>) -> Result<Note, Error> {
+        let mut request = self.request(reqwest::Method::POST, &format!("/notes/{id}/close.json"));
+        if let Some(text) = text {
+            request = request.query(&[("text", text)]);
+        }
+
+        self.send_json(request)
+    }
+
+    /// Reopen a closed note with an optional comment.
+    ///
+    /// Requires credentials, see [`Self::with_access_token`] and [`Self::with_basic_auth`].
+    pub fn reopen_note(&self, id: u64, text: Option<&str>) -> Result<Note, Error> {
+        let mut request = self.request(reqwest::Method::POST, &format!("/notes/{id}/reopen.json"));
+        if let Some(text) = text {
+            request = request.query(&[("text", text)]);
+        }
+
+        self.send_json(request)
+    }
+
+    /// Upload a GPS trace and return its id.
+    ///
+    /// The points are imported by the server in the background, see [`Trace::pending`].
+    /// Requires credentials, see [`Self::with_access_token`] and [`Self::with_basic_auth`].
+    pub fn upload_trace(&self, trace: &TraceUpload) -> Result<u64, Error> {
+        let (content_type, body) = trace.multipart();
+        let response = self.send(
+            self.request(reqwest::Method::POST, "/gpx")
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(body),
+        )?;
+        let body = response.text()?;
+
+        body.trim()
+            .parse()
+            .map_err(|_| Error::InvalidResponse(body))
+    }
+
+    /// Fetch the metadata of a GPS trace.
+    ///
+    /// Private traces of other users can not be fetched.
+    pub fn get_trace(&self, id: u64) -> Result<Trace, Error> {
+        let response = self.send(self.request(reqwest::Method::GET, &format!("/gpx/{id}")))?;
+        let body = response.text()?;
+
+        parse_traces(&body)?
+            .into_iter()
+            .next()
+            .ok_or(Error::InvalidResponse(body))
+    }
+
+    /// Fetch the metadata of all GPS traces of the authenticated user.
+    ///
+    /// Requires credentials, see [`Self::with_access_token`] and [`Self::with_basic_auth`].
+    pub fn get_user_traces(&self) -> Result<Vec<Trace>, Error> {
+        let response = self.send(self.request(reqwest::Method::GET, "/user/gpx_files"))?;
+
+        Ok(parse_traces(&response.text()?)?)
+    }
+
+    /// Download the file of a GPS trace as it was uploaded.
+    pub fn get_trace_data(&self, id: u64) -> Result<Vec<u8>, Error> {
+        let response = self.send(self.request(reqwest::Method::GET, &format!("/gpx/{id}/data")))?;
+
+        Ok(response.bytes()?.to_vec())
+    }
+
+    /// Download a GPS trace converted to GPX by the server and parse it.
+    pub fn get_trace_gpx(&self, id: u64) -> Result<Gpx, Error> {
+        let response =
+            self.send(self.request(reqwest::Method::GET, &format!("/gpx/{id}/data.gpx")))?;
+
+        Ok(Gpx::from_xml(&response.text()?)?)
+    }
+
+    /// Delete a GPS trace of the authenticated user.
+    ///
+    /// Requires credentials, see [`Self::with_access_token`] and [`Self::with_basic_auth`].
+    pub fn delete_trace(&self, id: u64) -> Result<(), Error> {
+        self.send(self.request(reqwest::Method::DELETE, &format!("/gpx/{id}")))?;
+
+        Ok(())
+    }
+
+    /// Fetch one page of public GPS points inside a [`BBox`].
+    ///
+    /// Every track segment is returned as its own sequence of [`Coordinates`]. Pages start at 0.
+    /// A [`BBox`] crossing the antimeridian sends one request per half, the page holds the points
+    /// of both.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`Error::AreaTooLarge`] if the [`BBox`] exceeds the maximum area of the API.
+    pub fn get_trackpoints(&self, bbox: &BBox, page: u32) -> Result<Vec<Vec<Coordinates>>, Error> {
+        self.capabilities()?.validate_area(bbox)?;
+
+        // The API rejects a bbox crossing the antimeridian, the page of each half is fetched
+        // separately.
+        let mut segments = Vec::new();
+        for half in bbox.halves().into_iter().flatten() {
+            let (south, west, north, east) = half.corners();
+            let response =
+                self.send(self.request(reqwest::Method::GET, "/trackpoints").query(&[
+                    ("bbox", format!("{west},{south},{east},{north}")),
+                    ("page", page.to_string()),
+                ]))?;
+            segments.extend(parse_trackpoints(&response.text()?)?);
+        }
+
+        Ok(segments)
+    }
+
+    /// Fetch all public GPS points inside a [`BBox`] by iterating over all pages.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`Error::AreaTooLarge`] if the [`BBox`] exceeds the maximum area of the API.
+    pub fn get_all_trackpoints(&self, bbox: &BBox) -> Result<Vec<Vec<Coordinates>>, Error> {
+        let per_page = self.capabilities()?.api.tracepoints.per_page;
+        let mut segments = Vec::new();
+
+        for page in 0.. {
+            let page_segments = self.get_trackpoints(bbox, page)?;
+            let points: usize = page_segments.iter().map(Vec::len).sum();
+            segments.extend(page_segments);
+
+            if (points as u64) < per_page || points == 0 {
+                break;
+            }
+        }
+
+        Ok(segments)
+    }
+
+    /// Fetch the public account information of a user.
+    pub fn get_user(&self, uid: u64) -> Result<User, Error> {
+        Ok(self
+            .get_json::<UserResponse>(&format!("/user/{uid}.json"))?
+            .user)
+    }
+
+    /// Fetch the public account information of multiple users.
+    pub fn get_users(&self, uids: &[u64]) -> Result<Vec<User>, Error> {
+        let uids = uids
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        let users: UsersResponse = self.send_json(
+            self.request(reqwest::Method::GET, "/users.json")
+                .query(&[("users", uids)]),
+        )?;
+
+        Ok(users.users.into_iter().map(|user| user.user).collect())
+    }
+
+    /// Fetch the account information of the authenticated user.
+    ///
+    /// Requires credentials, see [`Self::with_access_token`] and [`Self::with_basic_auth`].
+    pub fn get_current_user(&self) -> Result<User, Error> {
+        Ok(self.get_json::<UserResponse>("/user/details.json")?.user)
+    }
+
+    /// Fetch a user block.
+    pub fn get_user_block(&self, id: u64) -> Result<UserBlock, Error> {
+        Ok(self
+            .get_json::<UserBlockResponse>(&format!("/user_blocks/{id}.json"))?
+            .user_block)
+    }
+
+    /// Fetch the active blocks of the authenticated user.
+    ///
+    /// Uploads are rejected while a block is active, so editors can check this before uploading.
+    ///
+    /// Requires credentials, see [`Self::with_access_token`] and [`Self::with_basic_auth`].
+    pub fn get_active_blocks(&self) -> Result<Vec<UserBlock>, Error> {
+        Ok(self
+            .get_json::<UserBlocksResponse>("/user/blocks/active.json")?
+            .user_blocks)
+    }
+
+    /// Hide a version of an element behind a redaction.
+    ///
+    /// The current version of an element can not be redacted.
+    /// Requires moderator permissions and credentials, see [`Self::with_access_token`].
+    #[cfg(feature = "redaction")]
+    pub fn redact_element_version(
+        &self,
+        element: ElementId,
+        version: u64,
+        redaction_id: u64,
+    ) -> Result<(), Error> {
+        self.send(
+            self.request(
+                reqwest::Method::POST,
+                &format!(
+                    "/{}/{}/{version}/redact",
+                    element.element_type().as_str(),
+                    element.value()
+                ),
+            )
+            .query(&[("redaction", redaction_id)]),
+        )?;
+
+        Ok(())
+    }
+
+    /// Remove the redaction of a version of an element.
+    ///
+    /// Requires moderator permissions and credentials, see [`Self::with_access_token`].
+    #[cfg(feature = "redaction")]
+    pub fn unredact_element_version(&self, element: ElementId, version: u64) -> Result<(), Error> {
+        self.send(self.request(
+            reqwest::Method::POST,
+            &format!(
+                "/{}/{}/{version}/redact",
+                element.element_type().as_str(),
+                element.value()
+            ),
+        ))?;
+
+        Ok(())
+    }
+
+    /// Fetch a redacted version of an element.
+    ///
+    /// Requires moderator permissions and credentials, see [`Self::with_access_token`].
+    #[cfg(feature = "redaction")]
+    pub fn get_redacted_version(&self, element: ElementId, version: u64) -> Result<Element, Error> {
+        self.send_json::<OsmData>(
+            self.request(
+                reqwest::Method::GET,
+                &format!(
+                    "/{}/{}/{version}.json",
+                    element.element_type().as_str(),
+                    element.value()
+                ),
+            )
+            .query(&[("show_redactions", "true")]),
+        )?
+        .elements
+        .pop()
+        .ok_or_else(|| {
+            Error::InvalidResponse(format!("{element} version {version} missing in response"))
+        })
+    }
+
+    fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T, Error> {
+        self.send_json(self.request(reqwest::Method::GET, path))
+    }
+
+    fn send_json<T: DeserializeOwned>(
+        &self,
+        request: reqwest::blocking::RequestBuilder,
+    ) -> Result<T, Error> {
+        let response = self.send(request.header(reqwest::header::ACCEPT, "application/json"))?;
+
+        Ok(serde_json::from_str(&response.text()?)?)
+    }
+
+    /// Send a request, retrying it according to the [`RetryPolicy`] and tracking rate limited
+    /// responses in the circuit breaker.
+    fn send(
+        &self,
+        request: reqwest::blocking::RequestBuilder,
+    ) -> Result<reqwest::blocking::Response, Error> {
+        self.inner
+            .circuit_breaker
+            .check(Instant::now())
+            .map_err(Error::CircuitOpen)?;
+
+        let _permit = self
+            .inner
+            .request_limit
+            .as_ref()
+            .map(BlockingRequestLimit::acquire);
+        let (client, request) = request.build_split();
+        let response = self.inner.retry_policy.execute_blocking(
+            &BlockingScheduledTransport::new(
+                self.inner.transport.as_deref().unwrap_or(&client),
+                self.inner.scheduler.as_ref(),
+                "osm_api",
+            ),
+            request?,
+            |status| self.record_status(status),
+        )?;
+        let status = response.status();
+
+        if status.is_success() {
+            Ok(response)
+        } else if is_rate_limited(status) {
+            Err(Error::RateLimited((
+                status,
+                retry_after(response.headers()),
+            )))
+        } else {
+            Err(error_from_status(status, response.text()?))
+        }
+    }
+
+    /// Record a response in the circuit breaker, failing once it opens so no more retries are
+    /// sent.
+    fn record_status(&self, status: reqwest::StatusCode) -> Result<(), Error> {
+        if !is_rate_limited(status) {
+            self.inner.circuit_breaker.record_success();
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        self.inner
+            .circuit_breaker
+            .record_failure(&self.inner.rate_limit_policy, now);
+        self.inner
+            .circuit_breaker
+            .check(now)
+            .map_err(Error::CircuitOpen)
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::blocking::RequestBuilder {
+        let request = self.inner.client.request(method, self.url(path));
+
+        match self.inner.credentials {
+            Some(ref credentials) => credentials.apply_blocking(request),
+            None => request,
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.inner.base_url, path)
+    }
+}
+
+impl Default for OsmApiClient {
+    fn default() -> Self {
+        Self::new(OSM_API_URL)
+    }
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+/// The permissions granted to the credentials of a client.
+///
+/// Requests without credentials are granted no permissions.
+///
+/// See <https://wiki.openstreetmap.org/wiki/API_v0.6#Retrieving_permissions:_GET_/api/0.6/permissions>
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Permissions {
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+impl Permissions {
+    /// Check if a permission is granted, e.g. `allow_write_api`.
+    pub fn has(&self, permission: &str) -> bool {
+        self.permissions.iter().any(|granted| granted == permission)
+    }
+
+    /// Check if the map data can be edited.
+    pub fn can_write_api(&self) -> bool {
+        self.has("allow_write_api")
+    }
+
+    /// Check if notes can be created and commented.
+    pub fn can_write_notes(&self) -> bool {
+        self.has("allow_write_notes")
+    }
+}
+
+#[cfg(test)]
+mod permissions_test {
+    use crate::osm_api::permissions::Permissions;
+
+    #[test]
+    fn parse() {
+        let permissions: Permissions = serde_json::from_str(
+            r#"{"version": "0.6", "generator": "OpenStreetMap server", "permissions": ["allow_read_prefs", "allow_write_api"]}"#,
+        )
+        .unwrap();
+
+        assert!(permissions.has("allow_read_prefs"));
+        assert!(permissions.can_write_api());
+        assert!(!permissions.can_write_notes());
+    }
+
+    #[test]
+    fn anonymous() {
+        let permissions: Permissions =
+            serde_json::from_str(r#"{"version": "0.6", "permissions": []}"#).unwrap();
+
+        assert!(!permissions.has("allow_read_prefs"));
+    }
+}
@@ -0,0 +1,148 @@
+use std::time::Duration;
+#[cfg(any(feature = "async", feature = "blocking", test))]
+use std::{sync::Mutex, time::Instant};
+
+/// How the OSM API client reacts to its requests being rejected by the rate limits of the server.
+///
+/// Responses with `429 Too Many Requests` or `509 Bandwidth Limit Exceeded` are retried according
+/// to the [`RetryPolicy`](crate::retry::RetryPolicy) of the client. After `failure_threshold`
+/// consecutive rate limited responses the circuit breaker opens and all requests fail
+/// immediately until the cooldown passed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitPolicy {
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl RateLimitPolicy {
+    /// Construct the default [`RateLimitPolicy`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Never open the circuit breaker.
+    pub fn disabled() -> Self {
+        Self {
+            failure_threshold: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Open the circuit breaker for `cooldown` after `failure_threshold` consecutive rate limited
+    /// responses. A `failure_threshold` of `0` disables the circuit breaker.
+    pub fn circuit_breaker(mut self, failure_threshold: u32, cooldown: Duration) -> Self {
+        self.failure_threshold = failure_threshold;
+        self.cooldown = cooldown;
+        self
+    }
+}
+
+impl Default for RateLimitPolicy {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Tracks consecutive rate limited responses and rejects requests while open.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+#[derive(Debug, Default)]
+pub(crate) struct CircuitBreaker {
+    state: Mutex<BreakerState>,
+}
+
+#[cfg(any(feature = "async", feature = "blocking", test))]
+#[derive(Debug, Default)]
+struct BreakerState {
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+#[cfg(any(feature = "async", feature = "blocking", test))]
+impl CircuitBreaker {
+    /// Check if a request may be sent at `now`.
+    ///
+    /// Returns the remaining cooldown if the breaker is open.
+    pub(crate) fn check(&self, now: Instant) -> Result<(), Duration> {
+        let mut state = self.lock();
+
+        match state.open_until {
+            Some(open_until) if open_until > now => Err(open_until - now),
+            Some(_) => {
+                // Half open: let requests through, the next failure opens the breaker again.
+                state.open_until = None;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Record a response that was not rate limited.
+    pub(crate) fn record_success(&self) {
+        let mut state = self.lock();
+        state.failures = 0;
+        state.open_until = None;
+    }
+
+    /// Record a rate limited response at `now`.
+    pub(crate) fn record_failure(&self, policy: &RateLimitPolicy, now: Instant) {
+        let mut state = self.lock();
+        state.failures = state.failures.saturating_add(1);
+
+        if policy.failure_threshold > 0 && state.failures >= policy.failure_threshold {
+            state.open_until = Some(now + policy.cooldown);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerState> {
+        // The state stays consistent even if a holder panicked.
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod rate_limit_test {
+    use std::time::{Duration, Instant};
+
+    use crate::osm_api::rate_limit::{CircuitBreaker, RateLimitPolicy};
+
+    #[test]
+    fn circuit_breaker() {
+        let policy = RateLimitPolicy::new().circuit_breaker(2, Duration::from_secs(10));
+        let breaker = CircuitBreaker::default();
+        let now = Instant::now();
+
+        breaker.record_failure(&policy, now);
+        assert!(breaker.check(now).is_ok());
+
+        breaker.record_failure(&policy, now);
+        assert_eq!(
+            breaker.check(now + Duration::from_secs(4)),
+            Err(Duration::from_secs(6))
+        );
+
+        assert!(breaker.check(now + Duration::from_secs(10)).is_ok());
+        breaker.record_failure(&policy, now + Duration::from_secs(10));
+        assert!(breaker.check(now + Duration::from_secs(11)).is_err());
+
+        breaker.record_success();
+        assert!(breaker.check(now + Duration::from_secs(11)).is_ok());
+    }
+
+    #[test]
+    fn disabled() {
+        let policy = RateLimitPolicy::disabled();
+        let breaker = CircuitBreaker::default();
+        let now = Instant::now();
+
+        for _ in 0..10 {
+            breaker.record_failure(&policy, now);
+        }
+
+        assert!(breaker.check(now).is_ok());
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+
+use crate::{
+    coord::{CoordinateType, coordinates::widen},
+    model::{
+        element::Element,
+        id::ElementId,
+        osm_change::{OsmChange, type_rank},
+    },
+    osm_api::diff_result::{DiffEntry, DiffResult},
+};
+
+/// The result of comparing a pending upload with the changes already applied to a changeset.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Reconciliation {
+    /// The changes that still have to be uploaded, with ids of recovered elements replaced.
+    pub remaining: OsmChange,
+    /// The diff of the changes that were already applied.
+    pub applied: DiffResult,
+}
+
+/// Determine which parts of `pending` are already contained in `applied`, the download of the
+/// changeset `pending` was uploaded to.
+///
+/// Modifications and deletions are matched by id and version. Created elements are matched by
+/// their content since their final ids are unknown: nodes by position and tags, ways by tags and
+/// nodes, relations by tags and members. Every applied element is matched at most once.
+pub fn reconcile(pending: &OsmChange, applied: &OsmChange) -> Reconciliation {
+    let mut reconciliation = Reconciliation::default();
+    let mut ids: HashMap<ElementId, ElementId> = HashMap::new();
+    let mut used = vec![false; applied.create.len()];
+
+    // Match nodes first so ways and relations can be compared with remapped references.
+    let mut create = pending.create.clone();
+    create.sort_by_key(|element| type_rank(element.element_type()));
+
+    for element in create {
+        let mut candidate = OsmChange {
+            create: vec![element.clone()],
+            ..Default::default()
+        };
+        candidate.remap_ids(&ids);
+        let candidate = candidate.create.remove(0);
+
+        let found = (0..applied.create.len())
+            .find(|&index| !used[index] && same_content(&candidate, &applied.create[index]));
+
+        match found {
+            Some(index) => {
+                used[index] = true;
+                let other = &applied.create[index];
+                ids.insert(element.id(), other.id());
+                reconciliation.applied.entries.push(DiffEntry {
+                    old_id: element.id(),
+                    new_id: Some(other.id()),
+                    new_version: other.metadata().version,
+                });
+            }
+            None => reconciliation.remaining.create.push(element),
+        }
+    }
+
+    for element in pending.modify.iter() {
+        let expected = element.metadata().version.map(|version| version + 1);
+        let found = applied
+            .modify
+            .iter()
+            .find(|other| other.id() == element.id() && other.metadata().version == expected);
+
+        match found {
+            Some(other) => reconciliation.applied.entries.push(DiffEntry {
+                old_id: element.id(),
+                new_id: Some(other.id()),
+                new_version: other.metadata().version,
+            }),
+            None => reconciliation.remaining.modify.push(element.clone()),
+        }
+    }
+
+    for element in pending.delete.iter() {
+        let found = applied
+            .delete
+            .iter()
+            .any(|other| other.id() == element.id());
+
+        if found {
+            reconciliation.applied.entries.push(DiffEntry {
+                old_id: element.id(),
+                new_id: None,
+                new_version: None,
+            });
+        } else {
+            reconciliation.remaining.delete.push(element.clone());
+        }
+    }
+
+    reconciliation.remaining.remap_ids(&ids);
+
+    reconciliation
+}
+
+fn same_content(pending: &Element, applied: &Element) -> bool {
+    if pending.tags() != applied.tags() {
+        return false;
+    }
+
+    match (pending, applied) {
+        (Element::Node(pending), Element::Node(applied)) => {
+            osm_precision(pending.lat) == osm_precision(applied.lat)
+                && osm_precision(pending.lon) == osm_precision(applied.lon)
+        }
+        (Element::Way(pending), Element::Way(applied)) => pending.nodes == applied.nodes,
+        (Element::Relation(pending), Element::Relation(applied)) => {
+            pending.members == applied.members
+        }
+        _ => false,
+    }
+}
+
+/// Round a coordinate to the 7 decimal places the OSM database stores.
+fn osm_precision(value: Option<CoordinateType>) -> Option<i64> {
+    value.map(|value| (widen(value) * 1e7).round() as i64)
+}
+
+#[cfg(test)]
+mod recovery_test {
+    use crate::{
+        model::{
+            element::{Element, Metadata, Node, Way},
+            id::{ElementId, NodeId, WayId},
+            osm_change::OsmChange,
+            tags::Tags,
+        },
+        osm_api::recovery::reconcile,
+    };
+
+    fn node(id: i64, lat: f64, version: Option<u64>) -> Element {
+        Element::Node(Node {
+            id: NodeId::new(id),
+            lat: Some(lat as _),
+            lon: Some(1.0),
+            tags: Tags::new(),
+            metadata: Metadata {
+                version,
+                ..Default::default()
+            },
+        })
+    }
+
+    #[test]
+    fn partially_applied() {
+        let pending = OsmChange {
+            create: vec![
+                Element::Way(Way {
+                    id: WayId::new(-3),
+                    nodes: vec![NodeId::new(-1), NodeId::new(-2)],
+                    ..Default::default()
+                }),
+                node(-1, 1.0, None),
+                node(-2, 2.0, None),
+            ],
+            modify: vec![node(5, 3.0, Some(2)), node(6, 4.0, Some(1))],
+            delete: vec![node(7, 0.0, Some(1))],
+        };
+        let applied = OsmChange {
+            create: vec![node(100, 1.0, Some(1)), node(101, 2.0, Some(1))],
+            modify: vec![node(5, 3.0, Some(3))],
+            delete: vec![node(7, 0.0, Some(2))],
+        };
+        let reconciliation = reconcile(&pending, &applied);
+
+        let Element::Way(ref way) = reconciliation.remaining.create[0] else {
+            panic!("expected a way");
+        };
+        assert_eq!(reconciliation.remaining.len(), 2);
+        assert_eq!(way.nodes, vec![NodeId::new(100), NodeId::new(101)]);
+        assert_eq!(
+            reconciliation.remaining.modify[0].id(),
+            NodeId::new(6).into()
+        );
+
+        let ids = reconciliation.applied.id_map();
+        assert_eq!(
+            ids[&ElementId::from(NodeId::new(-1))],
+            NodeId::new(100).into()
+        );
+        assert_eq!(
+            ids[&ElementId::from(NodeId::new(-2))],
+            NodeId::new(101).into()
+        );
+        assert_eq!(reconciliation.applied.entries.len(), 4);
+    }
+
+    #[test]
+    fn rounded_coordinates() {
+        let pending = OsmChange {
+            create: vec![node(-1, 51.123_456_789, None)],
+            modify: vec![],
+            delete: vec![],
+        };
+        let applied = OsmChange {
+            create: vec![node(100, 51.123_456_8, Some(1))],
+            modify: vec![],
+            delete: vec![],
+        };
+        let reconciliation = reconcile(&pending, &applied);
+
+        assert!(reconciliation.remaining.is_empty());
+        assert_eq!(
+            reconciliation.applied.id_map()[&ElementId::from(NodeId::new(-1))],
+            NodeId::new(100).into()
+        );
+    }
+
+    #[test]
+    fn nothing_applied() {
+        let pending = OsmChange {
+            create: vec![node(-1, 1.0, None)],
+            modify: vec![],
+            delete: vec![],
+        };
+        let reconciliation = reconcile(&pending, &OsmChange::default());
+
+        assert_eq!(reconciliation.remaining, pending);
+        assert!(reconciliation.applied.entries.is_empty());
+    }
+
+    #[test]
+    fn identical_nodes_match_once() {
+        let pending = OsmChange {
+            create: vec![node(-1, 1.0, None), node(-2, 1.0, None)],
+            modify: vec![],
+            delete: vec![],
+        };
+        let applied = OsmChange {
+            create: vec![node(100, 1.0, Some(1))],
+            modify: vec![],
+            delete: vec![],
+        };
+        let reconciliation = reconcile(&pending, &applied);
+
+        assert_eq!(reconciliation.remaining.len(), 1);
+        assert_eq!(
+            reconciliation.remaining.create[0].id(),
+            NodeId::new(-2).into()
+        );
+    }
+}
@@ -0,0 +1,303 @@
+#[cfg(any(feature = "async", feature = "blocking", test))]
+use std::collections::hash_map::DefaultHasher;
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+#[cfg(any(feature = "async", feature = "blocking", test))]
+use crate::xml::{Event, Reader};
+use crate::{
+    coord::{CoordinateType, coordinates::Coordinates},
+    gpx::Gpx,
+    xml,
+};
+
+/// Who can see a GPS trace and its points.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Visibility_of_GPS_traces>
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Visibility {
+    /// Only the points are public, anonymous and unordered.
+    #[default]
+    Private,
+    /// The trace is listed publicly, the points are public but anonymous and unordered.
+    Public,
+    /// Only the points are public, anonymous but ordered with timestamps.
+    Trackable,
+    /// The trace is listed publicly, the points are public and ordered with timestamps.
+    Identifiable,
+}
+
+impl Visibility {
+    /// The name of the visibility as used by the API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Private => "private",
+            Self::Public => "public",
+            Self::Trackable => "trackable",
+            Self::Identifiable => "identifiable",
+        }
+    }
+}
+
+impl FromStr for Visibility {
+    type Err = xml::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "private" => Ok(Self::Private),
+            "public" => Ok(Self::Public),
+            "trackable" => Ok(Self::Trackable),
+            "identifiable" => Ok(Self::Identifiable),
+            _ => Err(xml::Error::InvalidAttribute(("visibility", s.to_string()))),
+        }
+    }
+}
+
+impl fmt::Display for Visibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A GPS trace to upload, e.g. with `OsmApiClient::upload_trace`.
+///
+/// See <https://wiki.openstreetmap.org/wiki/API_v0.6#Create:_POST_/api/0.6/gpx>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceUpload {
+    file_name: String,
+    data: Vec<u8>,
+    description: String,
+    tags: Vec<String>,
+    visibility: Visibility,
+}
+
+impl TraceUpload {
+    /// Construct a new private [`TraceUpload`] from the content of a file.
+    ///
+    /// Besides GPX the server accepts compressed GPX files and archives of them, recognized by
+    /// the extension of `file_name`.
+    pub fn new(
+        file_name: impl Into<String>,
+        data: impl Into<Vec<u8>>,
+        description: impl Into<String>,
+    ) -> Self {
+        Self {
+            file_name: file_name.into(),
+            data: data.into(),
+            description: description.into(),
+            tags: Vec::new(),
+            visibility: Visibility::default(),
+        }
+    }
+
+    /// Construct a new private [`TraceUpload`] of a [`Gpx`] document.
+    pub fn from_gpx(
+        file_name: impl Into<String>,
+        gpx: &Gpx,
+        description: impl Into<String>,
+    ) -> Self {
+        Self::new(file_name, gpx.to_xml(), description)
+    }
+
+    /// Set the tags of the trace.
+    pub fn with_tags<S: Into<String>>(mut self, tags: impl IntoIterator<Item = S>) -> Self {
+        self.tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the [`Visibility`] of the trace.
+    pub fn with_visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    /// Get the `multipart/form-data` body of the upload and its content type.
+    #[cfg(any(feature = "async", feature = "blocking", test))]
+    pub(crate) fn multipart(&self) -> (String, Vec<u8>) {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        let boundary = format!("rust-osm-{:016x}", hasher.finish());
+
+        let mut body = Vec::new();
+        for (name, value) in [
+            ("description", self.description.as_str()),
+            ("tags", &self.tags.join(",")),
+            ("visibility", self.visibility.as_str()),
+        ] {
+            body.extend_from_slice(
+                format!(
+                    "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+                )
+                .as_bytes(),
+            );
+        }
+        body.extend_from_slice(
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: application/octet-stream\r\n\r\n",
+                self.file_name.replace(['"', '\r', '\n'], "_")
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(&self.data);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+        (format!("multipart/form-data; boundary={boundary}"), body)
+    }
+}
+
+impl Hash for TraceUpload {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.file_name.hash(state);
+        self.data.hash(state);
+        self.description.hash(state);
+        self.tags.hash(state);
+        self.visibility.hash(state);
+    }
+}
+
+/// The metadata of an uploaded GPS trace.
+///
+/// See <https://wiki.openstreetmap.org/wiki/API_v0.6#Download_Metadata:_GET_/api/0.6/gpx/#id/details>
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Trace {
+    pub id: u64,
+    /// The file name of the upload.
+    pub name: String,
+    pub user: Option<String>,
+    pub uid: Option<u64>,
+    pub visibility: Visibility,
+    /// Whether the server has not imported the points yet.
+    pub pending: bool,
+    pub timestamp: Option<String>,
+    /// The latitude of the first point, missing while pending.
+    pub lat: Option<CoordinateType>,
+    /// The longitude of the first point, missing while pending.
+    pub lon: Option<CoordinateType>,
+    pub description: String,
+    pub tags: Vec<String>,
+}
+
+impl Trace {
+    /// Get the [`Coordinates`] of the first point.
+    pub fn coordinates(&self) -> Option<Coordinates> {
+        Coordinates::from_value(self.lat?, self.lon?).ok()
+    }
+}
+
+/// Parse the `gpx_file` elements of a trace metadata document.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+pub(crate) fn parse_traces(document: &str) -> Result<Vec<Trace>, xml::Error> {
+    let mut traces = Vec::new();
+    let mut reader = Reader::new(document);
+
+    while let Some(event) = reader.next() {
+        let Event::Start(tag) = event? else {
+            continue;
+        };
+        if tag.name != "gpx_file" {
+            continue;
+        }
+
+        let mut trace = Trace {
+            id: tag.parse("id")?,
+            name: tag.attribute("name").unwrap_or_default().to_string(),
+            user: tag.attribute("user").map(str::to_string),
+            uid: tag.parse_optional("uid")?,
+            visibility: tag.parse_optional("visibility")?.unwrap_or_default(),
+            pending: tag.parse_optional("pending")?.unwrap_or_default(),
+            timestamp: tag.attribute("timestamp").map(str::to_string),
+            lat: tag.parse_optional("lat")?,
+            lon: tag.parse_optional("lon")?,
+            ..Default::default()
+        };
+        if !tag.self_closing {
+            while let Some(event) = reader.next() {
+                match event? {
+                    Event::Start(child) if child.name == "description" && !child.self_closing => {
+                        trace.description = reader.read_text_content()?
+                    }
+                    Event::Start(child) if child.name == "tag" && !child.self_closing => {
+                        trace.tags.push(reader.read_text_content()?)
+                    }
+                    Event::Start(child) if !child.self_closing => reader.skip_element()?,
+                    Event::End(_) => break,
+                    _ => (),
+                }
+            }
+        }
+        traces.push(trace);
+    }
+
+    Ok(traces)
+}
+
+#[cfg(test)]
+mod traces_test {
+    use crate::{
+        coord::coordinates::Coordinates,
+        gpx::{Gpx, Track, TrackPoint, TrackSegment},
+        osm_api::traces::{TraceUpload, Visibility, parse_traces},
+    };
+
+    const TRACES: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <osm version="0.6" generator="OpenStreetMap server">
+            <gpx_file id="836619" name="track.gpx" uid="1234" user="mapper" visibility="trackable" pending="false" timestamp="2025-01-01T00:00:00Z" lat="49.0" lon="8.0">
+                <description>Morning ride</description>
+                <tag>bike</tag>
+                <tag>commute</tag>
+            </gpx_file>
+            <gpx_file id="836620" name="new.gpx" uid="1234" user="mapper" visibility="private" pending="true" timestamp="2025-01-02T00:00:00Z">
+                <description></description>
+            </gpx_file>
+        </osm>"#;
+
+    #[test]
+    fn parse() {
+        let traces = parse_traces(TRACES).unwrap();
+
+        assert_eq!(traces.len(), 2);
+        assert_eq!(traces[0].id, 836619);
+        assert_eq!(traces[0].visibility, Visibility::Trackable);
+        assert_eq!(traces[0].description, "Morning ride");
+        assert_eq!(traces[0].tags, vec!["bike", "commute"]);
+        assert_eq!(
+            traces[0].coordinates(),
+            Some(Coordinates::from_value(49.0, 8.0).unwrap())
+        );
+        assert!(traces[1].pending);
+        assert!(traces[1].coordinates().is_none());
+        assert!(traces[1].tags.is_empty());
+    }
+
+    #[test]
+    fn multipart() {
+        let gpx = Gpx {
+            tracks: vec![Track {
+                segments: vec![TrackSegment {
+                    points: vec![TrackPoint::new(Coordinates::from_value(1.0, 2.0).unwrap())],
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let upload = TraceUpload::from_gpx("ride.gpx", &gpx, "Morning ride")
+            .with_tags(["bike", "commute"])
+            .with_visibility(Visibility::Identifiable);
+        let (content_type, body) = upload.multipart();
+        let body = String::from_utf8(body).unwrap();
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+
+        assert_eq!(upload.multipart().0, content_type);
+        assert!(body.starts_with(&format!("--{boundary}\r\n")));
+        assert!(body.ends_with(&format!("\r\n--{boundary}--\r\n")));
+        assert!(body.contains("name=\"tags\"\r\n\r\nbike,commute\r\n"));
+        assert!(body.contains("name=\"visibility\"\r\n\r\nidentifiable\r\n"));
+        assert!(body.contains("name=\"file\"; filename=\"ride.gpx\""));
+        assert!(body.contains(&gpx.to_xml()));
+    }
+}
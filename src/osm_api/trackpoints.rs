@@ -0,0 +1,65 @@
+use crate::{
+    coord::{coordinates::Coordinates, polyline::Polyline},
+    gpx::Gpx,
+    xml,
+};
+
+/// Parse the GPX document returned by the trackpoints endpoint into track segments.
+///
+/// Every track segment is returned as its own sequence of [`Coordinates`].
+pub(crate) fn parse_trackpoints(document: &str) -> Result<Vec<Vec<Coordinates>>, xml::Error> {
+    Ok(Gpx::from_xml(document)?
+        .polylines()
+        .into_iter()
+        .map(Polyline::into_coordinates)
+        .collect())
+}
+
+#[cfg(test)]
+mod trackpoints_test {
+    use crate::{coord::coordinates::Coordinates, osm_api::trackpoints::parse_trackpoints};
+
+    const GPX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <gpx version="1.0" creator="OpenStreetMap.org" xmlns="http://www.topografix.com/GPX/1/0">
+            <trk>
+                <name>20250101.gpx</name>
+                <trkseg>
+                    <trkpt lat="1.0" lon="2.0"><time>2025-01-01T00:00:00Z</time></trkpt>
+                    <trkpt lat="1.1" lon="2.1"><time>2025-01-01T00:00:01Z</time></trkpt>
+                </trkseg>
+            </trk>
+            <trk>
+                <trkseg>
+                    <trkpt lat="3.0" lon="4.0"/>
+                </trkseg>
+            </trk>
+        </gpx>"#;
+
+    #[test]
+    fn parse() {
+        let segments = parse_trackpoints(GPX).unwrap();
+
+        assert_eq!(segments.len(), 2);
+        assert_eq!(
+            segments[0],
+            vec![
+                Coordinates::from_value(1.0, 2.0).unwrap(),
+                Coordinates::from_value(1.1, 2.1).unwrap()
+            ]
+        );
+        assert_eq!(
+            segments[1],
+            vec![Coordinates::from_value(3.0, 4.0).unwrap()]
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(
+            parse_trackpoints(
+                r#"<gpx><trk><trkseg><trkpt lat="91" lon="0"/></trkseg></trk></gpx>"#
+            )
+            .is_err()
+        );
+    }
+}
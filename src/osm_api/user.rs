@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+/// The public account information of a user.
+///
+/// See <https://wiki.openstreetmap.org/wiki/API_v0.6#Details_of_a_user:_GET_/api/0.6/user/#id>
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct User {
+    pub id: u64,
+    pub display_name: String,
+    pub account_created: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contributor_terms: Option<ContributorTerms>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub img: Option<Image>,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    #[serde(default)]
+    pub changesets: Count,
+    #[serde(default)]
+    pub traces: Count,
+    #[serde(default)]
+    pub blocks: Blocks,
+}
+
+impl User {
+    /// Check if the user has the given role, e.g. `moderator`.
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+}
+
+/// Whether the user agreed to the contributor terms.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContributorTerms {
+    pub agreed: bool,
+}
+
+/// The profile image of a user.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Image {
+    pub href: String,
+}
+
+/// The amount of something a user owns.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Count {
+    pub count: u64,
+}
+
+/// The blocks a user received.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Blocks {
+    #[serde(default)]
+    pub received: BlockCount,
+}
+
+/// The amount of received and currently active blocks.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockCount {
+    pub count: u64,
+    pub active: u64,
+}
+
+/// A single [`User`] as returned by the API.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+#[derive(Debug, Deserialize)]
+pub(crate) struct UserResponse {
+    pub(crate) user: User,
+}
+
+/// A list of [`User`]s as returned by the API.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+#[derive(Debug, Deserialize)]
+pub(crate) struct UsersResponse {
+    #[serde(default)]
+    pub(crate) users: Vec<UserResponse>,
+}
+
+#[cfg(test)]
+mod user_test {
+    use crate::osm_api::user::{UserResponse, UsersResponse};
+
+    const USER: &str = r#"{
+        "user": {
+            "id": 7,
+            "display_name": "mapper",
+            "account_created": "2020-01-01T00:00:00Z",
+            "description": "",
+            "contributor_terms": {"agreed": true},
+            "img": {"href": "https://example.com/a.png"},
+            "roles": ["moderator"],
+            "changesets": {"count": 42},
+            "traces": {"count": 3},
+            "blocks": {"received": {"count": 1, "active": 0}}
+        }
+    }"#;
+
+    #[test]
+    fn parse() {
+        let user = serde_json::from_str::<UserResponse>(USER).unwrap().user;
+
+        assert_eq!(user.id, 7);
+        assert_eq!(user.display_name, "mapper");
+        assert_eq!(user.changesets.count, 42);
+        assert_eq!(user.blocks.received.count, 1);
+        assert!(user.has_role("moderator"));
+        assert!(!user.has_role("administrator"));
+    }
+
+    #[test]
+    fn parse_users() {
+        let users = format!(r#"{{"version": "0.6", "users": [{USER}, {USER}]}}"#);
+        let users: UsersResponse = serde_json::from_str(&users).unwrap();
+
+        assert_eq!(users.users.len(), 2);
+    }
+}
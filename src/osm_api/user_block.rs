@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+/// A block that prevents a user from editing.
+///
+/// See <https://wiki.openstreetmap.org/wiki/API_v0.6#User_Blocks>
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserBlock {
+    pub id: u64,
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    pub ends_at: String,
+    /// The blocked user has to read the block before it ends.
+    #[serde(default)]
+    pub needs_view: bool,
+    pub user: BlockUser,
+    pub creator: BlockUser,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revoker: Option<BlockUser>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl UserBlock {
+    /// Check if the block was revoked by a moderator.
+    pub fn is_revoked(&self) -> bool {
+        self.revoker.is_some()
+    }
+}
+
+/// A user involved in a [`UserBlock`].
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockUser {
+    pub uid: u64,
+    pub user: String,
+}
+
+/// A single [`UserBlock`] as returned by the API.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+#[derive(Debug, Deserialize)]
+pub(crate) struct UserBlockResponse {
+    pub(crate) user_block: UserBlock,
+}
+
+/// A list of [`UserBlock`]s as returned by the API.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+#[derive(Debug, Deserialize)]
+pub(crate) struct UserBlocksResponse {
+    #[serde(default)]
+    pub(crate) user_blocks: Vec<UserBlock>,
+}
+
+#[cfg(test)]
+mod user_block_test {
+    use crate::osm_api::user_block::{UserBlockResponse, UserBlocksResponse};
+
+    #[test]
+    fn parse() {
+        let response: UserBlockResponse = serde_json::from_str(
+            r#"{
+                "version": "0.6",
+                "user_block": {
+                    "id": 5, "created_at": "2025-01-01T00:00:00Z", "updated_at": "2025-01-01T00:00:00Z",
+                    "ends_at": "2025-01-02T00:00:00Z", "needs_view": true,
+                    "user": {"uid": 1, "user": "a"}, "creator": {"uid": 2, "user": "b"},
+                    "reason": "Please read the guidelines"
+                }
+            }"#,
+        )
+        .unwrap();
+        let block = response.user_block;
+
+        assert_eq!(block.id, 5);
+        assert!(block.needs_view);
+        assert_eq!(block.user.user, "a");
+        assert_eq!(block.creator.uid, 2);
+        assert!(!block.is_revoked());
+    }
+
+    #[test]
+    fn parse_list() {
+        let response: UserBlocksResponse = serde_json::from_str(
+            r#"{"user_blocks": [{
+                "id": 5, "created_at": "2025-01-01T00:00:00Z", "ends_at": "2025-01-02T00:00:00Z",
+                "user": {"uid": 1, "user": "a"}, "creator": {"uid": 2, "user": "b"},
+                "revoker": {"uid": 3, "user": "c"}
+            }]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(response.user_blocks.len(), 1);
+        assert!(response.user_blocks[0].is_revoked());
+        assert!(
+            serde_json::from_str::<UserBlocksResponse>("{}")
+                .unwrap()
+                .user_blocks
+                .is_empty()
+        );
+    }
+}
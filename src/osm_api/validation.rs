@@ -0,0 +1,285 @@
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{
+    coord::CoordinateType,
+    model::{
+        element::{Element, Way},
+        id::{ElementId, NodeId},
+    },
+    osm_api::edit_session::EditSession,
+};
+
+/// How severe a [`ValidationIssue`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The upload is allowed but the issue should be reviewed.
+    Warning,
+    /// The upload is blocked.
+    Error,
+}
+
+/// A problem found by an [`UploadValidator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    /// The element the issue was found on, [`None`] for issues of the whole edit.
+    pub element: Option<ElementId>,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    /// Construct a new [`ValidationIssue`] with [`Severity::Warning`].
+    pub fn warning(element: Option<ElementId>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            element,
+            message: message.into(),
+        }
+    }
+
+    /// Construct a new [`ValidationIssue`] with [`Severity::Error`].
+    pub fn error(element: Option<ElementId>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            element,
+            message: message.into(),
+        }
+    }
+
+    /// Check if this issue blocks the upload.
+    pub fn is_blocking(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.element {
+            Some(element) => write!(f, "{element}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// A check that runs on an [`EditSession`] before it is uploaded.
+///
+/// Validators are added with [`EditSession::add_validator`].
+pub trait UploadValidator: std::fmt::Debug + Send + Sync {
+    /// Check the staged edits and return all found issues.
+    fn validate(&self, session: &EditSession) -> Vec<ValidationIssue>;
+}
+
+/// A position as longitude and latitude.
+type Point = (CoordinateType, CoordinateType);
+
+/// Warns about created nodes without tags that are not part of any way or relation.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct UntaggedNodes;
+
+impl UploadValidator for UntaggedNodes {
+    fn validate(&self, session: &EditSession) -> Vec<ValidationIssue> {
+        let change = session.change();
+        let mut referenced = Vec::new();
+        for element in change.create.iter().chain(change.modify.iter()) {
+            match element {
+                Element::Way(way) => referenced.extend(way.nodes.iter().copied()),
+                Element::Relation(relation) => {
+                    referenced.extend(relation.members.iter().filter_map(
+                        |member| match member.id() {
+                            ElementId::Node(id) => Some(id),
+                            _ => None,
+                        },
+                    ))
+                }
+                Element::Node(_) => (),
+            }
+        }
+
+        change
+            .create
+            .iter()
+            .filter_map(|element| match element {
+                Element::Node(node) if node.tags.is_empty() && !referenced.contains(&node.id) => {
+                    Some(ValidationIssue::warning(
+                        Some(node.id.into()),
+                        "untagged node is not part of a way or relation",
+                    ))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Blocks ways that cross themselves.
+///
+/// Only ways whose node positions are all staged in the session are checked.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SelfIntersectingWays;
+
+impl UploadValidator for SelfIntersectingWays {
+    fn validate(&self, session: &EditSession) -> Vec<ValidationIssue> {
+        let change = session.change();
+        let positions: HashMap<NodeId, Point> = change
+            .create
+            .iter()
+            .chain(change.modify.iter())
+            .filter_map(|element| match element {
+                Element::Node(node) => Some((node.id, (node.lon?, node.lat?))),
+                _ => None,
+            })
+            .collect();
+
+        change
+            .create
+            .iter()
+            .chain(change.modify.iter())
+            .filter_map(|element| match element {
+                Element::Way(way) if is_self_intersecting(way, &positions) => Some(
+                    ValidationIssue::error(Some(way.id.into()), "way intersects itself"),
+                ),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Blocks uploads without a `comment` changeset tag.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MissingComment;
+
+impl UploadValidator for MissingComment {
+    fn validate(&self, session: &EditSession) -> Vec<ValidationIssue> {
+        match session.tags().get("comment") {
+            Some(comment) if !comment.trim().is_empty() => Vec::new(),
+            _ => vec![ValidationIssue::error(None, "the changeset has no comment")],
+        }
+    }
+}
+
+/// Check if any two non-adjacent segments of a way cross each other.
+fn is_self_intersecting(way: &Way, positions: &HashMap<NodeId, Point>) -> bool {
+    let Some(points) = way
+        .nodes
+        .iter()
+        .map(|id| positions.get(id).copied())
+        .collect::<Option<Vec<_>>>()
+    else {
+        return false;
+    };
+    if points.len() < 4 {
+        return false;
+    }
+
+    let closed = way.nodes.first() == way.nodes.last();
+    let segments = points.len() - 1;
+    for a in 0..segments {
+        for b in a + 2..segments {
+            // The first and last segment of a closed way share a node.
+            if closed && a == 0 && b == segments - 1 {
+                continue;
+            }
+
+            if segments_intersect(points[a], points[a + 1], points[b], points[b + 1]) {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+fn segments_intersect(p1: Point, p2: Point, q1: Point, q2: Point) -> bool {
+    let d1 = orientation(q1, q2, p1);
+    let d2 = orientation(q1, q2, p2);
+    let d3 = orientation(p1, p2, q1);
+    let d4 = orientation(p1, p2, q2);
+
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+    {
+        return true;
+    }
+
+    (d1 == 0.0 && on_segment(q1, q2, p1))
+        || (d2 == 0.0 && on_segment(q1, q2, p2))
+        || (d3 == 0.0 && on_segment(p1, p2, q1))
+        || (d4 == 0.0 && on_segment(p1, p2, q2))
+}
+
+fn orientation(a: Point, b: Point, c: Point) -> CoordinateType {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+fn on_segment(a: Point, b: Point, p: Point) -> bool {
+    p.0 >= a.0.min(b.0) && p.0 <= a.0.max(b.0) && p.1 >= a.1.min(b.1) && p.1 <= a.1.max(b.1)
+}
+
+#[cfg(test)]
+mod validation_test {
+    use crate::{
+        coord::coordinates::Coordinates,
+        model::{id::WayId, tags::Tags},
+        osm_api::{
+            edit_session::EditSession,
+            validation::{
+                MissingComment, SelfIntersectingWays, Severity, UntaggedNodes, UploadValidator,
+            },
+        },
+    };
+
+    fn square(session: &mut EditSession, crossed: bool) -> WayId {
+        let corners = if crossed {
+            [(0.0, 0.0), (1.0, 1.0), (0.0, 1.0), (1.0, 0.0)]
+        } else {
+            [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)]
+        };
+        let nodes: Vec<_> = corners
+            .iter()
+            .map(|(lat, lon)| {
+                session.create_node(Coordinates::from_wrapped(*lat, *lon), Tags::new())
+            })
+            .collect();
+
+        session.create_way(
+            &[nodes[0], nodes[1], nodes[2], nodes[3], nodes[0]],
+            Tags::from([("area".to_string(), "yes".to_string())]),
+        )
+    }
+
+    #[test]
+    fn untagged_nodes() {
+        let mut session = EditSession::default();
+        square(&mut session, false);
+        let lonely = session.create_node(Coordinates::from_wrapped(5.0, 5.0), Tags::new());
+
+        let issues = UntaggedNodes.validate(&session);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].element, Some(lonely.into()));
+        assert_eq!(issues[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn self_intersecting() {
+        let mut session = EditSession::default();
+        square(&mut session, false);
+        assert!(SelfIntersectingWays.validate(&session).is_empty());
+
+        let way = square(&mut session, true);
+        let issues = SelfIntersectingWays.validate(&session);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].element, Some(way.into()));
+        assert!(issues[0].is_blocking());
+    }
+
+    #[test]
+    fn missing_comment() {
+        let mut session = EditSession::default();
+        assert!(MissingComment.validate(&session)[0].is_blocking());
+
+        session.set_tag("comment", "Add a park");
+        assert!(MissingComment.validate(&session).is_empty());
+    }
+}
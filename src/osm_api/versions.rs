@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+use crate::osm_api::API_VERSION;
+
+/// The API versions supported by a server.
+///
+/// See <https://wiki.openstreetmap.org/wiki/API_v0.6#Available_API_versions:_GET_/api/versions>
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiVersions {
+    #[serde(default)]
+    pub versions: Vec<String>,
+}
+
+impl ApiVersions {
+    /// Check if the server supports the given version.
+    pub fn supports(&self, version: &str) -> bool {
+        self.versions.iter().any(|supported| supported == version)
+    }
+
+    /// Check if the server supports the version implemented by this crate.
+    pub fn is_supported(&self) -> bool {
+        self.supports(API_VERSION)
+    }
+}
+
+/// The response of the versions endpoint.
+#[cfg(any(feature = "async", feature = "blocking", test))]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct VersionsResponse {
+    pub(crate) api: ApiVersions,
+}
+
+#[cfg(test)]
+mod versions_test {
+    use crate::osm_api::versions::VersionsResponse;
+
+    #[test]
+    fn parse() {
+        let response: VersionsResponse = serde_json::from_str(
+            r#"{"version": "0.6", "generator": "OpenStreetMap server", "api": {"versions": ["0.6"]}}"#,
+        )
+        .unwrap();
+
+        assert!(response.api.supports("0.6"));
+        assert!(response.api.is_supported());
+        assert!(!response.api.supports("0.7"));
+    }
+
+    #[test]
+    fn unsupported() {
+        let response: VersionsResponse =
+            serde_json::from_str(r#"{"api": {"versions": ["0.7"]}}"#).unwrap();
+
+        assert!(!response.api.is_supported());
+    }
+}
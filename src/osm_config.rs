@@ -0,0 +1,200 @@
+#[cfg(feature = "osm_api")]
+use std::sync::Arc;
+
+#[cfg(feature = "osm_api")]
+use crate::osm_api::{OSM_API_URL, auth::TokenProvider, credentials::Credentials};
+#[cfg(feature = "overpass")]
+use crate::overpass::OVERPASS_API_URL;
+#[cfg(feature = "replication")]
+use crate::replication::PLANET_REPLICATION_URL;
+use crate::{client_config::ClientConfig, retry::RetryPolicy};
+
+/// Settings shared by all clients of an application: the endpoints, the [`ClientConfig`] with
+/// the user agent and timeouts, the [`RetryPolicy`] and the credentials for the OSM API.
+///
+/// Build it once and pass it to the `from_config` constructor of every client, so all clients
+/// are configured in one place. Each endpoint is only available with the feature of its client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OsmConfig {
+    client_config: ClientConfig,
+    retry_policy: RetryPolicy,
+    #[cfg(feature = "osm_api")]
+    osm_api_url: String,
+    #[cfg(feature = "osm_api")]
+    credentials: Option<Credentials>,
+    #[cfg(feature = "overpass")]
+    overpass_url: String,
+    #[cfg(feature = "replication")]
+    replication_url: String,
+}
+
+impl OsmConfig {
+    /// Construct a new [`OsmConfig`] for the public servers of OpenStreetMap without
+    /// credentials.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the [`ClientConfig`] used to build the HTTP clients, e.g. with a user agent.
+    pub fn with_client_config(mut self, client_config: ClientConfig) -> Self {
+        self.client_config = client_config;
+        self
+    }
+
+    /// Set how failed requests are retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Set the base url of the OSM API, e.g. [`OSM_DEV_API_URL`].
+    ///
+    /// [`OSM_DEV_API_URL`]: crate::osm_api::OSM_DEV_API_URL
+    #[cfg(feature = "osm_api")]
+    pub fn with_osm_api_url(mut self, osm_api_url: impl Into<String>) -> Self {
+        self.osm_api_url = osm_api_url.into();
+        self
+    }
+
+    /// Authenticate requests to the OSM API with an OAuth 2.0 access token.
+    #[cfg(feature = "osm_api")]
+    pub fn with_access_token(mut self, access_token: impl Into<String>) -> Self {
+        self.credentials = Some(Credentials::AccessToken(access_token.into()));
+        self
+    }
+
+    /// Authenticate requests to the OSM API with HTTP Basic auth.
+    ///
+    /// The production API rejects Basic auth, so building a client for it fails.
+    #[cfg(feature = "osm_api")]
+    pub fn with_basic_auth(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.credentials = Some(Credentials::Basic {
+            username: username.into(),
+            password: password.into(),
+        });
+        self
+    }
+
+    /// Authenticate requests to the OSM API with the access tokens of a [`TokenProvider`].
+    #[cfg(feature = "osm_api")]
+    pub fn with_token_provider(mut self, token_provider: Arc<dyn TokenProvider>) -> Self {
+        self.credentials = Some(Credentials::TokenProvider(token_provider));
+        self
+    }
+
+    /// Set the url of the Overpass API instance.
+    #[cfg(feature = "overpass")]
+    pub fn with_overpass_url(mut self, overpass_url: impl Into<String>) -> Self {
+        self.overpass_url = overpass_url.into();
+        self
+    }
+
+    /// Set the base url of the replication diffs, without the interval.
+    #[cfg(feature = "replication")]
+    pub fn with_replication_url(mut self, replication_url: impl Into<String>) -> Self {
+        self.replication_url = replication_url.into();
+        self
+    }
+
+    /// Get the [`ClientConfig`].
+    pub fn client_config(&self) -> &ClientConfig {
+        &self.client_config
+    }
+
+    /// Get the [`RetryPolicy`].
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Get the base url of the OSM API.
+    #[cfg(feature = "osm_api")]
+    pub fn osm_api_url(&self) -> &str {
+        &self.osm_api_url
+    }
+
+    /// Get the [`Credentials`] for the OSM API, if set.
+    #[cfg(feature = "osm_api")]
+    pub fn credentials(&self) -> Option<&Credentials> {
+        self.credentials.as_ref()
+    }
+
+    /// Get the url of the Overpass API instance.
+    #[cfg(feature = "overpass")]
+    pub fn overpass_url(&self) -> &str {
+        &self.overpass_url
+    }
+
+    /// Get the base url of the replication diffs.
+    #[cfg(feature = "replication")]
+    pub fn replication_url(&self) -> &str {
+        &self.replication_url
+    }
+}
+
+// Derivable without the client features, but the endpoints need their default urls.
+#[allow(clippy::derivable_impls)]
+impl Default for OsmConfig {
+    fn default() -> Self {
+        Self {
+            client_config: ClientConfig::default(),
+            retry_policy: RetryPolicy::default(),
+            #[cfg(feature = "osm_api")]
+            osm_api_url: OSM_API_URL.to_string(),
+            #[cfg(feature = "osm_api")]
+            credentials: None,
+            #[cfg(feature = "overpass")]
+            overpass_url: OVERPASS_API_URL.to_string(),
+            #[cfg(feature = "replication")]
+            replication_url: PLANET_REPLICATION_URL.to_string(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "blocking", feature = "osm_api", feature = "overpass"))]
+mod osm_config_test {
+    use std::time::Duration;
+
+    use crate::{
+        client_config::ClientConfig,
+        osm_api::{OSM_API_URL, OSM_DEV_API_URL, error::Error, osm_api_blocking::OsmApiClient},
+        osm_config::OsmConfig,
+        overpass::overpass_blocking::OverpassAPI,
+        retry::RetryPolicy,
+    };
+
+    #[test]
+    fn shared() {
+        let config = OsmConfig::new()
+            .with_client_config(
+                ClientConfig::new()
+                    .with_user_agent("rust-osm-test/1.0")
+                    .with_timeout(Duration::from_secs(30)),
+            )
+            .with_retry_policy(RetryPolicy::disabled())
+            .with_osm_api_url(OSM_DEV_API_URL)
+            .with_overpass_url("https://overpass.example/api/interpreter")
+            .with_access_token("token");
+
+        let api = OsmApiClient::from_config(&config).unwrap();
+        assert_eq!(api.base_url(), OSM_DEV_API_URL);
+        assert!(OverpassAPI::from_config(&config).is_ok());
+        assert_eq!(OsmConfig::new().osm_api_url(), OSM_API_URL);
+    }
+
+    #[test]
+    fn basic_not_allowed() {
+        let config = OsmConfig::new().with_basic_auth("a", "b");
+        assert!(matches!(
+            OsmApiClient::from_config(&config),
+            Err(Error::BasicAuthNotAllowed(_))
+        ));
+        assert!(OsmApiClient::from_config(&config.with_osm_api_url(OSM_DEV_API_URL)).is_ok());
+        assert!(
+            !format!("{:?}", OsmConfig::new().with_basic_auth("a", "secret")).contains("secret")
+        );
+    }
+}
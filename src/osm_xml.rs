@@ -0,0 +1,294 @@
+pub mod error;
+
+use std::io::Read;
+
+use crate::{
+    element_reader::ElementReader,
+    model::element::{Element, read_xml_element},
+    osm_xml::error::Error,
+    xml::{self, Event, Reader},
+};
+
+/// The amount of bytes requested from the input at once.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A streaming reader for OSM XML documents like `.osm` files and API responses.
+///
+/// Elements are yielded one at a time while only the part of the document that is currently parsed
+/// is kept in memory, so files of any size can be processed.
+/// Attributes are matched by name, which covers both the attribute layout of the API and the one of
+/// planet dumps. Metadata that is missing in a layout, e.g. `visible` in planet dumps, is [`None`].
+/// Everything that is not a node, way or relation, e.g. `bounds`, is skipped.
+///
+/// See <https://wiki.openstreetmap.org/wiki/OSM_XML>
+#[derive(Debug)]
+pub struct OsmXmlReader<R> {
+    input: R,
+    buffer: Vec<u8>,
+    /// The start of the unconsumed part of `buffer`.
+    start: usize,
+    /// The amount of bytes dropped from the front of `buffer`.
+    offset: usize,
+    eof: bool,
+    done: bool,
+}
+
+/// The outcome of parsing the buffered part of a document.
+enum Parsed {
+    /// An element and the amount of bytes it consumed.
+    Element((Element, usize)),
+    /// No complete element is buffered. Contains the amount of bytes that could be skipped and the
+    /// error that stopped the parser, if any.
+    Pending((usize, Option<xml::Error>)),
+}
+
+impl<R: Read> OsmXmlReader<R> {
+    /// Construct a new [`OsmXmlReader`] that reads from `input`.
+    ///
+    /// The input is read in chunks, wrapping it in a [`std::io::BufReader`] is not required.
+    pub fn new(input: R) -> Self {
+        Self {
+            input,
+            buffer: Vec::new(),
+            start: 0,
+            offset: 0,
+            eof: false,
+            done: false,
+        }
+    }
+
+    /// Read the next chunk of the input into the buffer, dropping the consumed part.
+    fn fill(&mut self) -> Result<(), Error> {
+        self.buffer.drain(..self.start);
+        self.offset += self.start;
+        self.start = 0;
+
+        let length = self.buffer.len();
+        self.buffer.resize(length + CHUNK_SIZE, 0);
+        let read = loop {
+            match self.input.read(&mut self.buffer[length..]) {
+                Ok(read) => break read,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.buffer.truncate(length);
+                    return Err(e.into());
+                }
+            }
+        };
+        self.buffer.truncate(length + read);
+        self.eof = read == 0;
+
+        Ok(())
+    }
+
+    /// Get the buffered text that is valid UTF-8.
+    ///
+    /// A multi-byte character split between two chunks is left in the buffer until the rest is read.
+    fn buffered(&self) -> Result<&str, Error> {
+        let bytes = &self.buffer[self.start..];
+
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Ok(text),
+            Err(e) if e.error_len().is_none() && !self.eof => {
+                Ok(std::str::from_utf8(&bytes[..e.valid_up_to()]).unwrap_or_default())
+            }
+            Err(e) => Err(Error::Utf8(self.offset + self.start + e.valid_up_to())),
+        }
+    }
+
+    fn read_element(&mut self) -> Result<Option<Element>, Error> {
+        loop {
+            match parse(self.buffered()?) {
+                Parsed::Element((element, consumed)) => {
+                    self.start += consumed;
+                    return Ok(Some(element));
+                }
+                Parsed::Pending((consumed, error)) => {
+                    self.start += consumed;
+
+                    if self.eof {
+                        return match error {
+                            Some(e) => Err(e.into()),
+                            None if self.start < self.buffer.len() => {
+                                Err(Error::Utf8(self.offset + self.start))
+                            }
+                            None => Ok(None),
+                        };
+                    }
+
+                    self.fill()?;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "compression")]
+impl OsmXmlReader<crate::compression::Decoder<std::io::BufReader<std::fs::File>>> {
+    /// Open an OSM XML file like `.osm` or `.osc`, which may be gzip compressed.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`std::io::Error`] if the file can not be opened or is bzip2 compressed.
+    pub fn open(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        Ok(Self::new(crate::compression::open(path)?))
+    }
+}
+
+impl<R: Read> Iterator for OsmXmlReader<R> {
+    type Item = Result<Element, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let result = self.read_element().transpose();
+        if !matches!(result, Some(Ok(_))) {
+            // Stop after the end of the document or the first error.
+            self.done = true;
+        }
+
+        result
+    }
+}
+
+impl<R: Read> ElementReader for OsmXmlReader<R> {
+    type Error = Error;
+}
+
+/// Parse the next element from `text`, skipping everything else.
+fn parse(text: &str) -> Parsed {
+    let mut reader = Reader::new(text);
+    let mut consumed = 0;
+
+    while let Some(event) = reader.next() {
+        match event {
+            Ok(Event::Start(tag)) if matches!(tag.name, "node" | "way" | "relation") => {
+                return match read_xml_element(&tag, &mut reader) {
+                    Ok(Some(element)) => Parsed::Element((element, reader.position())),
+                    Ok(None) => Parsed::Pending((reader.position(), None)),
+                    Err(e) => Parsed::Pending((consumed, Some(e))),
+                };
+            }
+            // Containers like `osm` are entered, their other children are skipped one by one.
+            Ok(_) => consumed = reader.position(),
+            Err(e) => return Parsed::Pending((consumed, Some(e))),
+        }
+    }
+
+    Parsed::Pending((consumed, None))
+}
+
+#[cfg(test)]
+mod osm_xml_test {
+    use std::io::Read;
+
+    use crate::{
+        model::{
+            element::Element,
+            id::{ChangesetId, NodeId},
+        },
+        osm_xml::{OsmXmlReader, error::Error},
+    };
+
+    const API: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <osm version="0.6" generator="openstreetmap-cgimap" copyright="OpenStreetMap and contributors">
+            <bounds minlat="1" minlon="2" maxlat="3" maxlon="4"/>
+            <node id="1" visible="true" version="2" changeset="10" timestamp="2025-01-01T00:00:00Z" user="Zoë" uid="5" lat="1.5" lon="2.5">
+                <tag k="name" v="Café &amp; Bar"/>
+            </node>
+            <way id="3" visible="true" version="4">
+                <nd ref="1"/>
+                <nd ref="2"/>
+            </way>
+            <relation id="6" visible="true" version="1">
+                <member type="way" ref="3" role="outer"/>
+            </relation>
+        </osm>"#;
+
+    const PLANET: &str = r#"<?xml version='1.0' encoding='UTF-8'?>
+        <osm version="0.6" generator="planet-dump-ng 1.2.4" timestamp="2025-01-01T00:00:00Z">
+          <bound box="-90,-180,90,180" origin="http://www.openstreetmap.org/api/0.6"/>
+          <node id="1" version="2" timestamp="2025-01-01T00:00:00Z" uid="5" user="Zoë" changeset="10" lat="1.5" lon="2.5">
+            <tag k="name" v="Café &amp; Bar"/>
+          </node>
+          <way id="3" version="4" timestamp="2025-01-01T00:00:00Z" uid="5" user="Zoë" changeset="10">
+            <nd ref="1"/>
+            <nd ref="2"/>
+          </way>
+        </osm>"#;
+
+    /// Returns at most one byte per read to split the document at every possible position.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            match self.0.split_first() {
+                Some((byte, rest)) if !buf.is_empty() => {
+                    buf[0] = *byte;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    #[test]
+    fn api_layout() {
+        let elements: Vec<Element> = OsmXmlReader::new(API.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(elements.len(), 3);
+        let Element::Node(ref node) = elements[0] else {
+            panic!("expected a node");
+        };
+        assert_eq!(node.id, NodeId::new(1));
+        assert_eq!(node.tags["name"], "Café & Bar");
+        assert_eq!(node.metadata.visible, Some(true));
+        assert_eq!(node.metadata.changeset, Some(ChangesetId::new(10)));
+    }
+
+    #[test]
+    fn planet_layout() {
+        let elements: Vec<Element> = OsmXmlReader::new(PLANET.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(elements.len(), 2);
+        assert_eq!(elements[0].metadata().user.as_deref(), Some("Zoë"));
+        assert_eq!(elements[0].metadata().visible, None);
+        assert_eq!(elements[1].tags().len(), 0);
+    }
+
+    #[test]
+    fn split_input() {
+        let streamed: Vec<Element> = OsmXmlReader::new(Trickle(API.as_bytes()))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let buffered: Vec<Element> = OsmXmlReader::new(API.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(streamed, buffered);
+    }
+
+    #[test]
+    fn truncated() {
+        let truncated = &API[..API.find("<way").unwrap() + 10];
+        let mut reader = OsmXmlReader::new(Trickle(truncated.as_bytes()));
+
+        assert!(reader.next().unwrap().is_ok());
+        assert!(matches!(reader.next(), Some(Err(Error::Xml(_)))));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let mut reader = OsmXmlReader::new(&b"<osm><node id=\"1\" user=\"\xff\"/></osm>"[..]);
+
+        assert!(matches!(reader.next(), Some(Err(Error::Utf8(24)))));
+    }
+}
@@ -0,0 +1,9 @@
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("The input could not be read: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("The document could not be parsed: {0}")]
+    Xml(#[from] crate::xml::Error),
+    #[error("The document is not valid UTF-8 at byte {0}")]
+    Utf8(usize),
+}
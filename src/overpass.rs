@@ -1,6 +1,219 @@
 pub mod overpass_query_builder;
+pub mod response;
+pub mod status;
 
 #[cfg(feature = "async")]
 pub mod overpass_async;
 #[cfg(feature = "blocking")]
 pub mod overpass_blocking;
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+/// Url of the main public Overpass API instance.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Overpass_API#Public_Overpass_API_instances>
+pub const OVERPASS_API_URL: &str = "https://overpass-api.de/api/interpreter";
+
+/// Urls of other public Overpass API instances, e.g. to use as mirrors of [`OVERPASS_API_URL`].
+///
+/// See <https://wiki.openstreetmap.org/wiki/Overpass_API#Public_Overpass_API_instances>
+pub const OVERPASS_MIRRORS: [&str; 2] = [
+    "https://overpass.kumi.systems/api/interpreter",
+    "https://overpass.private.coffee/api/interpreter",
+];
+
+/// The url of an Overpass API instance and its mirrors, in the order they are tried.
+#[cfg(any(feature = "async", feature = "blocking"))]
+#[derive(Debug, Clone)]
+pub(crate) struct Endpoints {
+    urls: Vec<reqwest::Url>,
+    round_robin: bool,
+    /// The index of the url tried first by the next request when rotating, shared by clones.
+    next: Arc<AtomicUsize>,
+}
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+impl Endpoints {
+    pub(crate) fn new(url: reqwest::Url) -> Self {
+        Self {
+            urls: vec![url],
+            round_robin: false,
+            next: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Get the main url.
+    pub(crate) fn url(&self) -> &reqwest::Url {
+        &self.urls[0]
+    }
+
+    /// Get the urls tried after the main url.
+    pub(crate) fn mirrors(&self) -> &[reqwest::Url] {
+        &self.urls[1..]
+    }
+
+    /// Get the url of the status of the main instance, `status` next to `interpreter`.
+    pub(crate) fn status_url(&self) -> reqwest::Url {
+        let mut url = self.url().clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.pop().push("status");
+        }
+        url
+    }
+
+    pub(crate) fn set_mirrors(&mut self, mirrors: Vec<reqwest::Url>) {
+        self.urls.truncate(1);
+        self.urls.extend(mirrors);
+    }
+
+    pub(crate) fn set_round_robin(&mut self, round_robin: bool) {
+        self.round_robin = round_robin;
+    }
+
+    /// Get the url a request to `url` tries first and the urls it tries after it, starting with
+    /// the main url unless they are rotated.
+    ///
+    /// Only requests to the main url are sent to the mirrors, others like the status are sent to
+    /// their own url.
+    pub(crate) fn order_for(&self, url: &reqwest::Url) -> (reqwest::Url, Vec<reqwest::Url>) {
+        let main = self.url();
+        if url.origin() != main.origin() || url.path() != main.path() {
+            return (url.clone(), Vec::new());
+        }
+
+        let start = if self.round_robin {
+            self.next.fetch_add(1, Ordering::Relaxed) % self.urls.len()
+        } else {
+            0
+        };
+
+        (
+            self.urls[start].clone(),
+            self.urls[start + 1..]
+                .iter()
+                .chain(&self.urls[..start])
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+/// Get the url of a request sent to another endpoint, keeping the query of the request.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn endpoint_url(endpoint: &reqwest::Url, request_url: &reqwest::Url) -> reqwest::Url {
+    let mut url = endpoint.clone();
+    url.set_query(request_url.query());
+    url
+}
+
+/// Check if a query answered with `status` is sent to the next endpoint, i.e. the instance is
+/// overloaded or failing.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn is_failover_status(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod overpass_test {
+    use std::sync::Arc;
+
+    use reqwest::{Method, StatusCode};
+
+    use crate::{
+        overpass::overpass_blocking::OverpassAPI, rest_methods::error::Error, retry::RetryPolicy,
+        transport::MockTransport,
+    };
+
+    #[test]
+    fn query() {
+        let transport = Arc::new(MockTransport::new().with_response(
+            StatusCode::OK,
+            r#"{"version": 0.6, "elements": [{"type": "node", "id": 1, "lat": 1.0, "lon": 2.0}]}"#,
+        ));
+        let api = OverpassAPI::new("https://overpass.example/api/interpreter")
+            .unwrap()
+            .with_transport(transport.clone());
+
+        let response = api.query("[out:json];node(1);out;").unwrap();
+        assert_eq!(response.elements.len(), 1);
+        assert_eq!(response.elements[0].id().value(), 1);
+
+        let requests = transport.requests();
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(
+            requests[0].body.as_deref(),
+            Some(&b"[out:json];node(1);out;"[..])
+        );
+    }
+
+    #[test]
+    fn mirrors() {
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response(StatusCode::SERVICE_UNAVAILABLE, "overloaded")
+                .with_response(StatusCode::OK, r#"{"elements": []}"#)
+                .with_response(StatusCode::OK, r#"{"elements": []}"#)
+                .with_response(StatusCode::OK, r#"{"elements": []}"#)
+                .with_response(StatusCode::BAD_REQUEST, "syntax error"),
+        );
+        let api = OverpassAPI::new("https://main.example/api/interpreter")
+            .unwrap()
+            .with_mirrors(["https://mirror.example/api/interpreter"])
+            .unwrap()
+            .with_retry_policy(RetryPolicy::disabled())
+            .with_transport(transport.clone());
+        assert_eq!(api.mirrors().len(), 1);
+
+        let response = api.query("[out:json];node(1);out;").unwrap();
+        assert_eq!(
+            response.endpoint.unwrap().as_str(),
+            "https://mirror.example/api/interpreter"
+        );
+        let requests = transport.requests();
+        assert_eq!(requests[0].url, "https://main.example/api/interpreter");
+        assert_eq!(requests[1].url, "https://mirror.example/api/interpreter");
+        assert_eq!(requests[1].body, requests[0].body);
+
+        let api = api.with_round_robin(true);
+        let first = api.query("[out:json];node(1);out;").unwrap().endpoint;
+        let second = api.query("[out:json];node(1);out;").unwrap().endpoint;
+        assert_ne!(first, second);
+
+        assert!(matches!(
+            api.query("[out:json];node(1);out;"),
+            Err(Error::HttpStatus((StatusCode::BAD_REQUEST, _)))
+        ));
+        assert_eq!(transport.requests().len(), 5);
+    }
+
+    #[test]
+    fn wait_for_slot() {
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response(
+                    StatusCode::OK,
+                    "Rate limit: 1\n0 slots available now.\n\
+                     Slot available after: 2025-01-01T00:00:00Z, in 0 seconds.\n",
+                )
+                .with_response(StatusCode::OK, "Rate limit: 1\n1 slots available now.\n")
+                .with_response(StatusCode::OK, r#"{"elements": []}"#),
+        );
+        let api = OverpassAPI::new("https://overpass.example/api/interpreter")
+            .unwrap()
+            .with_mirrors(["https://mirror.example/api/interpreter"])
+            .unwrap()
+            .with_wait_for_slot(true)
+            .with_transport(transport.clone());
+
+        assert!(api.query("[out:json];node(1);out;").is_ok());
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].method, Method::GET);
+        assert_eq!(requests[0].url, "https://overpass.example/api/status");
+        assert_eq!(requests[2].url, "https://overpass.example/api/interpreter");
+    }
+}
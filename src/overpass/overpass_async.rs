@@ -1,44 +1,318 @@
-use crate::rest_methods::RESTMethods;
+use std::{fmt, future::Future, pin::Pin, sync::Arc};
 
-#[derive(Debug)]
-pub struct OverpassAPI<U: reqwest::IntoUrl + Clone> {
-    url: U,
+use serde::de::DeserializeOwned;
+
+use crate::{
+    client_config::{ClientConfig, redact_url},
+    osm_config::OsmConfig,
+    overpass::{
+        Endpoints, endpoint_url, is_failover_status, response::OverpassResponse,
+        status::OverpassStatus,
+    },
+    request_limit::RequestLimit,
+    rest_methods::{RESTMethods, error::Error},
+    retry::RetryPolicy,
+    scheduler::{ScheduledTransport, Scheduler},
+    transport::HttpTransport,
+};
+
+#[derive(Clone)]
+pub struct OverpassAPI {
+    inner: Arc<Inner>,
+}
+
+/// The state shared by the clones of an [`OverpassAPI`].
+#[derive(Clone)]
+struct Inner {
+    endpoints: Endpoints,
     client: reqwest::Client,
+    retry_policy: RetryPolicy,
+    transport: Option<Arc<dyn HttpTransport>>,
+    request_limit: Option<RequestLimit>,
+    wait_for_slot: bool,
+    scheduler: Option<Scheduler>,
+}
+
+impl fmt::Debug for OverpassAPI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OverpassAPI")
+            .field("url", &redact_url(self.inner.endpoints.url().as_str()))
+            .field(
+                "mirrors",
+                &self
+                    .inner
+                    .endpoints
+                    .mirrors()
+                    .iter()
+                    .map(|url| redact_url(url.as_str()))
+                    .collect::<Vec<_>>(),
+            )
+            .field("client", &self.inner.client)
+            .field("retry_policy", &self.inner.retry_policy)
+            .field("transport", &self.inner.transport)
+            .field("request_limit", &self.inner.request_limit)
+            .field("scheduler", &self.inner.scheduler)
+            .field("wait_for_slot", &self.inner.wait_for_slot)
+            .finish()
+    }
 }
 
-impl<U: reqwest::IntoUrl + Clone> OverpassAPI<U> {
-    pub fn new(url: U) -> Self {
-        Self {
-            url,
-            client: reqwest::Client::new(),
+impl OverpassAPI {
+    /// Construct a new [`OverpassAPI`] for the instance at `url`, e.g.
+    /// [`OVERPASS_API_URL`](super::OVERPASS_API_URL).
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::Request`] if `url` is not a valid url.
+    pub fn new(url: impl reqwest::IntoUrl) -> Result<Self, Error> {
+        Ok(Self {
+            inner: Arc::new(Inner {
+                endpoints: Endpoints::new(url.into_url()?),
+                client: reqwest::Client::new(),
+                retry_policy: RetryPolicy::default(),
+                transport: None,
+                request_limit: None,
+                scheduler: None,
+                wait_for_slot: false,
+            }),
+        })
+    }
+
+    /// Construct a new [`OverpassAPI`] with the url, HTTP client and retry policy of an
+    /// [`OsmConfig`].
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::Request`] if the url is not valid or the HTTP client can not be built.
+    pub fn from_config(config: &OsmConfig) -> Result<Self, Error> {
+        Ok(Self::new(config.overpass_url())?
+            .with_config(config.client_config())?
+            .with_retry_policy(config.retry_policy().clone()))
+    }
+
+    /// Get the url of the Overpass API instance.
+    pub fn url(&self) -> &reqwest::Url {
+        self.inner.endpoints.url()
+    }
+
+    /// Get the urls of the mirrors, see [`OverpassAPI::with_mirrors`].
+    pub fn mirrors(&self) -> &[reqwest::Url] {
+        self.inner.endpoints.mirrors()
+    }
+
+    /// Set mirrors of the Overpass API instance, e.g. [`OVERPASS_MIRRORS`](super::OVERPASS_MIRRORS)
+    /// or a private instance.
+    ///
+    /// A request that fails to connect or is answered with `429 Too Many Requests` or a server
+    /// error after all retries is sent to the next mirror, the error of the last one is returned.
+    /// Requests with a streamed body are only sent to one instance.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::Request`] if a url is not valid.
+    pub fn with_mirrors<U: reqwest::IntoUrl>(
+        mut self,
+        mirrors: impl IntoIterator<Item = U>,
+    ) -> Result<Self, Error> {
+        let mirrors = mirrors
+            .into_iter()
+            .map(|mirror| mirror.into_url())
+            .collect::<Result<_, _>>()?;
+        Arc::make_mut(&mut self.inner)
+            .endpoints
+            .set_mirrors(mirrors);
+        Ok(self)
+    }
+
+    /// Set whether consecutive requests start at the next instance instead of always at the main
+    /// url, spreading the load over the mirrors. Off by default.
+    pub fn with_round_robin(mut self, round_robin: bool) -> Self {
+        Arc::make_mut(&mut self.inner)
+            .endpoints
+            .set_round_robin(round_robin);
+        self
+    }
+
+    /// Use an HTTP client with the given [`ClientConfig`], e.g. with custom timeouts.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::Request`] if the HTTP client can not be built.
+    pub fn with_config(mut self, config: &ClientConfig) -> Result<Self, Error> {
+        Arc::make_mut(&mut self.inner).client = config.build_async()?;
+        Ok(self)
+    }
+
+    /// Set how failed requests are retried.
+    ///
+    /// Overpass queries are sent as `POST`, so only rate limited queries and connection failures
+    /// are retried, see [`RetryPolicy`].
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        Arc::make_mut(&mut self.inner).retry_policy = retry_policy;
+        self
+    }
+
+    /// Send the requests with a [`HttpTransport`] instead of the [`reqwest::Client`], e.g. a
+    /// [`MockTransport`](crate::transport::MockTransport) in tests.
+    pub fn with_transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        Arc::make_mut(&mut self.inner).transport = Some(transport);
+        self
+    }
+
+    /// Limit how many requests are in flight at once. The limit is shared by all clones of this
+    /// client and can be shared with other clients.
+    pub fn with_request_limit(mut self, request_limit: RequestLimit) -> Self {
+        Arc::make_mut(&mut self.inner).request_limit = Some(request_limit);
+        self
+    }
+
+    /// Send the requests in the slots of a [`Scheduler`] shared with other clients, queued in its
+    /// `overpass` lane.
+    pub fn with_scheduler(mut self, scheduler: Scheduler) -> Self {
+        Arc::make_mut(&mut self.inner).scheduler = Some(scheduler);
+        self
+    }
+    /// Set whether [`OverpassAPI::query`] waits until the rate limit of the instance allows
+    /// another query, see [`OverpassStatus::wait_time`]. Off by default.
+    ///
+    /// The status is requested before every query, which does not count against the limit.
+    pub fn with_wait_for_slot(mut self, wait_for_slot: bool) -> Self {
+        Arc::make_mut(&mut self.inner).wait_for_slot = wait_for_slot;
+        self
+    }
+
+    /// Get the rate limit state of this client at the main instance.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error`] if the request fails or the status can not be parsed.
+    pub async fn status(&self) -> Result<OverpassStatus, Error> {
+        self.execute_text(self.inner.client.get(self.inner.endpoints.status_url()))
+            .await?
+            .parse()
+    }
+
+    /// Run an Overpass QL query and decode its result.
+    ///
+    /// The query has to request the JSON format with `[out:json]`. Errors while running the
+    /// query are reported in the response, see [`OverpassResponse::runtime_error`].
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error`] if the query is rejected or the response can not be decoded.
+    ///
+    /// The url of the instance that answered is set as [`OverpassResponse::endpoint`].
+    pub async fn query(&self, query: &str) -> Result<OverpassResponse, Error> {
+        if self.inner.wait_for_slot {
+            while let Some(wait) = self.status().await?.wait_time() {
+                tokio::time::sleep(wait).await;
+            }
         }
+
+        let (response, endpoint) = send(&self.inner, self.post().body(query.to_string())).await?;
+        let mut response: OverpassResponse = serde_json::from_slice(&response.bytes().await?)?;
+        response.endpoint = Some(endpoint);
+
+        Ok(response)
     }
 }
 
-impl<U: reqwest::IntoUrl + Clone> RESTMethods for OverpassAPI<U> {
+impl RESTMethods for OverpassAPI {
     type RequestBuilder = reqwest::RequestBuilder;
+    type Execution<T> = Pin<Box<dyn Future<Output = Result<T, Error>> + Send>>;
 
     fn get(&self) -> Self::RequestBuilder {
-        self.client.get(self.url.clone())
+        self.inner.client.get(self.inner.endpoints.url().clone())
     }
 
     fn post(&self) -> Self::RequestBuilder {
-        self.client.post(self.url.clone())
+        self.inner.client.post(self.inner.endpoints.url().clone())
     }
 
     fn put(&self) -> Self::RequestBuilder {
-        self.client.put(self.url.clone())
+        self.inner.client.put(self.inner.endpoints.url().clone())
     }
 
     fn patch(&self) -> Self::RequestBuilder {
-        self.client.patch(self.url.clone())
+        self.inner.client.patch(self.inner.endpoints.url().clone())
     }
 
     fn delete(&self) -> Self::RequestBuilder {
-        self.client.delete(self.url.clone())
+        self.inner.client.delete(self.inner.endpoints.url().clone())
     }
 
     fn head(&self) -> Self::RequestBuilder {
-        self.client.head(self.url.clone())
+        self.inner.client.head(self.inner.endpoints.url().clone())
+    }
+
+    fn execute_json<T: DeserializeOwned + Send + 'static>(
+        &self,
+        request: Self::RequestBuilder,
+    ) -> Self::Execution<T> {
+        let inner = self.inner.clone();
+        Box::pin(async move {
+            let body = send(&inner, request).await?.0.bytes().await?;
+            Ok(serde_json::from_slice(&body)?)
+        })
+    }
+
+    fn execute_text(&self, request: Self::RequestBuilder) -> Self::Execution<String> {
+        let inner = self.inner.clone();
+        Box::pin(async move { Ok(send(&inner, request).await?.0.text().await?) })
+    }
+
+    fn execute_bytes(&self, request: Self::RequestBuilder) -> Self::Execution<Vec<u8>> {
+        let inner = self.inner.clone();
+        Box::pin(async move { Ok(send(&inner, request).await?.0.bytes().await?.to_vec()) })
+    }
+}
+
+/// Send a request with retries to the instance and its mirrors, turning responses with an error
+/// status into [`Error::HttpStatus`].
+///
+/// Returns the response together with the url of the instance that answered.
+async fn send(
+    inner: &Inner,
+    request: reqwest::RequestBuilder,
+) -> Result<(reqwest::Response, reqwest::Url), Error> {
+    let _permit = match inner.request_limit {
+        Some(ref request_limit) => Some(request_limit.acquire().await),
+        None => None,
+    };
+    let (client, request) = request.build_split();
+    let transport = ScheduledTransport::new(
+        inner.transport.as_deref().unwrap_or(&client),
+        inner.scheduler.as_ref(),
+        "overpass",
+    );
+
+    let mut request = request?;
+    let (mut endpoint, mirrors) = inner.endpoints.order_for(request.url());
+    let mut mirrors = mirrors.into_iter();
+    loop {
+        let url = endpoint_url(&endpoint, request.url());
+        let next = mirrors
+            .next()
+            .and_then(|mirror| Some((mirror, request.try_clone()?)));
+        *request.url_mut() = url.clone();
+
+        let response = inner
+            .retry_policy
+            .execute(&transport, request, |_| Ok::<_, Error>(()))
+            .await;
+        match (response, next) {
+            (Ok(response), _) if response.status().is_success() => return Ok((response, url)),
+            (Ok(response), Some(next)) if is_failover_status(response.status()) => {
+                (endpoint, request) = next;
+            }
+            (Err(_), Some(next)) => (endpoint, request) = next,
+            (Ok(response), _) => {
+                return Err(Error::HttpStatus((
+                    response.status(),
+                    response.text().await?,
+                )));
+            }
+            (Err(error), None) => return Err(error),
+        }
     }
 }
@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+
+pub use crate::model::{
+    element::{Element, Metadata, Node, Relation, RelationMember, Way, WayGeometry},
+    osm_data::Osm3s,
+    tags::Tags,
+};
+use crate::{
+    coord::polygon::Polygon,
+    model::{
+        id::{RelationId, WayId},
+        osm_data::{OsmData, deserialize_version, resolve_geometries},
+    },
+    multipolygon::{Assembler, error::Error},
+    xml,
+};
+
+/// The result of an Overpass query in the JSON format, requested with `[out:json]`.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Overpass_API/Overpass_QL#Output_Format_(out:)>
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OverpassResponse {
+    /// Overpass writes the version as a number, it is read as a string.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_version",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generator: Option<String>,
+    /// The state of the database the result was computed from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub osm3s: Option<Osm3s>,
+    #[serde(default)]
+    pub elements: Vec<Element>,
+    /// A message of the server, e.g. that the query timed out and the result is incomplete.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remark: Option<String>,
+    /// The url of the Overpass API instance that answered the query, set by `OverpassAPI::query`
+    /// to tell mirrors apart.
+    #[serde(skip)]
+    pub endpoint: Option<reqwest::Url>,
+}
+
+impl OverpassResponse {
+    /// Parse the result of an Overpass query in the XML format, requested with `[out:xml]`.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`xml::Error`] if the document is malformed.
+    pub fn from_xml(document: &str) -> Result<Self, xml::Error> {
+        OsmData::from_xml(document).map(Self::from)
+    }
+
+    /// Get the error the query failed with while running.
+    ///
+    /// Overpass reports such errors in the [`remark`](Self::remark) of a successful response, the
+    /// elements found until then are still returned but incomplete.
+    pub fn runtime_error(&self) -> Option<&str> {
+        self.remark
+            .as_deref()
+            .filter(|remark| remark.starts_with("runtime error"))
+    }
+
+    /// Resolve the geometries of all [`Way`]s from the [`Node`]s in this response, which must
+    /// be part of the output, e.g. with `(._;>;); out body;`.
+    ///
+    /// See [`resolve_geometries`]
+    pub fn resolve_geometries(&self) -> HashMap<WayId, WayGeometry> {
+        resolve_geometries(&self.elements)
+    }
+
+    /// Assemble the `multipolygon` and `boundary` relations of this response into [`Polygon`]s
+    /// with holes, in the order they were returned. Their member ways and nodes must be part of
+    /// the output, e.g. with `(._;>;); out body;`.
+    ///
+    /// See [`Assembler`]
+    pub fn assemble_multipolygons(&self) -> Vec<(RelationId, Result<Vec<Polygon>, Error>)> {
+        self.elements
+            .iter()
+            .cloned()
+            .collect::<Assembler>()
+            .assemble_all()
+    }
+
+    /// Serialize the elements as a GeoJSON `FeatureCollection`.
+    ///
+    /// See [`crate::geojson::feature_collection`]
+    #[cfg(feature = "geojson")]
+    pub fn to_geojson(&self, options: &crate::geojson::GeoJsonOptions) -> String {
+        crate::geojson::feature_collection(&self.elements, options).to_string()
+    }
+}
+
+impl From<OverpassResponse> for OsmData {
+    fn from(response: OverpassResponse) -> Self {
+        Self {
+            version: response.version,
+            generator: response.generator,
+            osm3s: response.osm3s,
+            elements: response.elements,
+            remark: response.remark,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<OsmData> for OverpassResponse {
+    fn from(data: OsmData) -> Self {
+        Self {
+            version: data.version,
+            generator: data.generator,
+            osm3s: data.osm3s,
+            elements: data.elements,
+            remark: data.remark,
+            endpoint: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod response_test {
+    use crate::{
+        model::{
+            id::{RelationId, WayId},
+            osm_data::OsmData,
+        },
+        multipolygon::error::Error,
+        overpass::response::{Element, OverpassResponse},
+    };
+
+    const RESPONSE: &str = r#"{
+        "version": 0.6,
+        "generator": "Overpass API 0.7.62.1 084b4234",
+        "osm3s": {
+            "timestamp_osm_base": "2025-01-01T00:00:00Z",
+            "copyright": "The data included in this document is from www.openstreetmap.org. The data is made available under ODbL."
+        },
+        "elements": [
+            {"type": "node", "id": 1, "lat": 52.5, "lon": 13.4, "tags": {"amenity": "cafe"}},
+            {"type": "way", "id": 2, "nodes": [1, 3], "center": {"lat": 52.5, "lon": 13.4}},
+            {"type": "relation", "id": 4, "members": [{"type": "way", "ref": 2, "role": "outer"}]}
+        ],
+        "remark": "runtime error: Query timed out in \"query\" at line 1 after 25 seconds."
+    }"#;
+
+    #[test]
+    fn deserialize() {
+        let response: OverpassResponse = serde_json::from_str(RESPONSE).unwrap();
+
+        assert_eq!(response.version.as_deref(), Some("0.6"));
+        assert_eq!(
+            response
+                .osm3s
+                .as_ref()
+                .unwrap()
+                .timestamp_osm_base
+                .as_deref(),
+            Some("2025-01-01T00:00:00Z")
+        );
+        assert_eq!(response.elements.len(), 3);
+        assert_eq!(response.elements[0].tags()["amenity"], "cafe");
+        assert!(matches!(
+            &response.elements[1],
+            Element::Way(way) if way.id == WayId::new(2) && way.nodes.len() == 2
+        ));
+        assert!(response.runtime_error().unwrap().contains("timed out"));
+
+        let data = OsmData::from(response.clone());
+        assert_eq!(data.elements, response.elements);
+        assert_eq!(data.osm3s, response.osm3s);
+    }
+
+    #[test]
+    fn assemble_multipolygons() {
+        let response: OverpassResponse = serde_json::from_str(
+            r#"{"elements": [
+                {"type": "relation", "id": 1, "members": [{"type": "way", "ref": 2, "role": "outer"}],
+                    "tags": {"type": "multipolygon", "landuse": "forest"}},
+                {"type": "relation", "id": 3, "members": [{"type": "way", "ref": 4, "role": "outer"}],
+                    "tags": {"type": "boundary"}},
+                {"type": "way", "id": 2, "nodes": [1, 2, 3, 1]},
+                {"type": "node", "id": 1, "lat": 0.0, "lon": 0.0},
+                {"type": "node", "id": 2, "lat": 0.0, "lon": 1.0},
+                {"type": "node", "id": 3, "lat": 1.0, "lon": 1.0}
+            ]}"#,
+        )
+        .unwrap();
+        let multipolygons = response.assemble_multipolygons();
+
+        assert_eq!(multipolygons.len(), 2);
+        assert_eq!(multipolygons[0].0, RelationId::new(1));
+        assert_eq!(multipolygons[0].1.as_ref().unwrap().len(), 1);
+        assert!(matches!(multipolygons[1].1, Err(Error::MissingWay(_))));
+    }
+
+    #[test]
+    fn from_xml() {
+        let response = OverpassResponse::from_xml(
+            r#"<osm version="0.6" generator="Overpass API">
+                <meta osm_base="2025-01-01T00:00:00Z"/>
+                <node id="1" lat="52.5" lon="13.4"><tag k="amenity" v="cafe"/></node>
+                <remark>runtime error: Query run out of memory</remark>
+            </osm>"#,
+        )
+        .unwrap();
+
+        assert_eq!(response.version.as_deref(), Some("0.6"));
+        assert_eq!(response.elements[0].tags()["amenity"], "cafe");
+        assert!(response.runtime_error().unwrap().contains("memory"));
+        assert_eq!(OsmData::from(response.clone()).remark, response.remark);
+    }
+
+    #[test]
+    fn empty() {
+        let response: OverpassResponse = serde_json::from_str("{\"elements\": []}").unwrap();
+
+        assert!(response.elements.is_empty());
+        assert!(response.runtime_error().is_none());
+    }
+
+    #[cfg(feature = "geojson")]
+    #[test]
+    fn to_geojson() {
+        let response: OverpassResponse = serde_json::from_str(RESPONSE).unwrap();
+        let collection: serde_json::Value =
+            serde_json::from_str(&response.to_geojson(&crate::geojson::GeoJsonOptions::new()))
+                .unwrap();
+
+        assert_eq!(collection["features"].as_array().unwrap().len(), 1);
+        assert_eq!(collection["features"][0]["id"], "node/1");
+    }
+}
@@ -0,0 +1,186 @@
+use std::{str::FromStr, time::Duration};
+
+use crate::rest_methods::error::Error;
+
+/// The rate limit state of the client at an Overpass API instance, as reported by `/api/status`.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Overpass_API/Overpass_QL#Rate_limiting>
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct OverpassStatus {
+    /// The id the instance identifies the client by, usually derived from its IP address.
+    pub connected_as: Option<String>,
+    /// The ISO 8601 timestamp of the instance.
+    pub current_time: Option<String>,
+    /// The host name the instance announces itself with, if any.
+    pub announced_endpoint: Option<String>,
+    /// How many queries the client may run at once, 0 if there is no limit.
+    pub rate_limit: u32,
+    /// How many queries the client can start right away.
+    pub slots_available: u32,
+    /// When the used slots become free again.
+    pub slot_resets: Vec<SlotReset>,
+    /// The queries of the client running at the moment.
+    pub running_queries: Vec<RunningQuery>,
+}
+
+/// A used slot of an [`OverpassStatus`] that becomes free again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotReset {
+    /// The ISO 8601 timestamp the slot becomes free at.
+    pub timestamp: String,
+    /// How long until the slot becomes free.
+    pub wait: Duration,
+}
+
+/// A running query of an [`OverpassStatus`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunningQuery {
+    pub pid: u64,
+    /// The memory limit in bytes.
+    pub space_limit: u64,
+    /// The time limit in seconds.
+    pub time_limit: u64,
+    /// The ISO 8601 timestamp the query started at.
+    pub start_time: String,
+}
+
+impl OverpassStatus {
+    /// Get how long to wait until a query can be started.
+    ///
+    /// Returns [`None`] if a slot is available now. If all slots are used by running queries,
+    /// which free their slot once they finish, one second is returned to check again.
+    pub fn wait_time(&self) -> Option<Duration> {
+        if self.rate_limit == 0 || self.slots_available > 0 {
+            return None;
+        }
+
+        Some(
+            self.slot_resets
+                .iter()
+                .map(|reset| reset.wait)
+                .min()
+                .unwrap_or(Duration::from_secs(1)),
+        )
+    }
+}
+
+impl FromStr for OverpassStatus {
+    type Err = Error;
+
+    /// Parse the plain text response of `/api/status`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut status = Self::default();
+        let mut rate_limit = None;
+        let invalid = |line: &str| Error::InvalidResponse(format!("Invalid status line: {line}"));
+
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if let Some(value) = line.strip_prefix("Connected as:") {
+                status.connected_as = Some(value.trim().to_string());
+            } else if let Some(value) = line.strip_prefix("Current time:") {
+                status.current_time = Some(value.trim().to_string());
+            } else if let Some(value) = line.strip_prefix("Announced endpoint:") {
+                status.announced_endpoint =
+                    Some(value.trim().to_string()).filter(|value| value != "none");
+            } else if let Some(value) = line.strip_prefix("Rate limit:") {
+                rate_limit = Some(value.trim().parse().map_err(|_| invalid(line))?);
+            } else if let Some(value) = line.strip_suffix("slots available now.") {
+                status.slots_available = value.trim().parse().map_err(|_| invalid(line))?;
+            } else if let Some(value) = line.strip_prefix("Slot available after:") {
+                // `Slot available after: 2025-01-01T00:00:10Z, in 8 seconds.`
+                let (timestamp, wait) = value.split_once(", in ").ok_or_else(|| invalid(line))?;
+                let seconds = wait
+                    .trim_end_matches(" seconds.")
+                    .trim()
+                    .parse()
+                    .map_err(|_| invalid(line))?;
+                status.slot_resets.push(SlotReset {
+                    timestamp: timestamp.trim().to_string(),
+                    wait: Duration::from_secs(seconds),
+                });
+            } else if let [pid, space_limit, time_limit, start_time] =
+                line.split_whitespace().collect::<Vec<_>>()[..]
+                && let (Ok(pid), Ok(space_limit), Ok(time_limit)) =
+                    (pid.parse(), space_limit.parse(), time_limit.parse())
+            {
+                status.running_queries.push(RunningQuery {
+                    pid,
+                    space_limit,
+                    time_limit,
+                    start_time: start_time.to_string(),
+                });
+            }
+        }
+
+        status.rate_limit = rate_limit
+            .ok_or_else(|| Error::InvalidResponse("The rate limit is missing".to_string()))?;
+        Ok(status)
+    }
+}
+
+#[cfg(test)]
+mod status_test {
+    use std::time::Duration;
+
+    use crate::{
+        overpass::status::{OverpassStatus, RunningQuery},
+        rest_methods::error::Error,
+    };
+
+    const STATUS: &str = "Connected as: 1234567890
+Current time: 2025-01-01T00:00:00Z
+Announced endpoint: none
+Rate limit: 2
+0 slots available now.
+Slot available after: 2025-01-01T00:00:10Z, in 10 seconds.
+Slot available after: 2025-01-01T00:00:04Z, in 4 seconds.
+Currently running queries (pid, space limit, time limit, start time):
+12345\t536870912\t180\t2025-01-01T00:00:00Z
+";
+
+    #[test]
+    fn parse() {
+        let status: OverpassStatus = STATUS.parse().unwrap();
+
+        assert_eq!(status.connected_as.as_deref(), Some("1234567890"));
+        assert_eq!(status.current_time.as_deref(), Some("2025-01-01T00:00:00Z"));
+        assert_eq!(status.announced_endpoint, None);
+        assert_eq!(status.rate_limit, 2);
+        assert_eq!(status.slots_available, 0);
+        assert_eq!(status.slot_resets.len(), 2);
+        assert_eq!(status.slot_resets[0].timestamp, "2025-01-01T00:00:10Z");
+        assert_eq!(
+            status.running_queries,
+            vec![RunningQuery {
+                pid: 12345,
+                space_limit: 536_870_912,
+                time_limit: 180,
+                start_time: "2025-01-01T00:00:00Z".to_string(),
+            }]
+        );
+        assert_eq!(status.wait_time(), Some(Duration::from_secs(4)));
+    }
+
+    #[test]
+    fn available() {
+        let status: OverpassStatus = "Rate limit: 2\n2 slots available now.\n".parse().unwrap();
+        assert_eq!(status.wait_time(), None);
+
+        let status: OverpassStatus = "Rate limit: 0\n".parse().unwrap();
+        assert_eq!(status.wait_time(), None);
+
+        let status: OverpassStatus = "Rate limit: 1\n0 slots available now.\n".parse().unwrap();
+        assert_eq!(status.wait_time(), Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(
+            "Connected as: 1".parse::<OverpassStatus>(),
+            Err(Error::InvalidResponse(_))
+        ));
+        assert!(matches!(
+            "Rate limit: many".parse::<OverpassStatus>(),
+            Err(Error::InvalidResponse(_))
+        ));
+    }
+}
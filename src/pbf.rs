@@ -0,0 +1,843 @@
+pub mod error;
+pub(crate) mod protobuf;
+
+use std::{collections::VecDeque, io::Read};
+
+use crate::{
+    coord::CoordinateType,
+    element_reader::ElementReader,
+    model::{
+        element::{Element, ElementType, Metadata, Node, Relation, RelationMember, Way},
+        id::{ChangesetId, NodeId, RelationId, WayId},
+        tags::Tags,
+    },
+    pbf::{
+        error::Error,
+        protobuf::{Message, Value, delta_decode, extend_varints, zigzag},
+    },
+};
+
+/// The maximum size of a blob header.
+const MAX_HEADER_SIZE: usize = 64 * 1024;
+/// The maximum size of a blob, compressed or decompressed.
+const MAX_BLOB_SIZE: usize = 32 * 1024 * 1024;
+/// The required features this reader can handle.
+const SUPPORTED_FEATURES: [&str; 3] = ["OsmSchema-V0.6", "DenseNodes", "HistoricalInformation"];
+
+/// A streaming reader for `.osm.pbf` files.
+///
+/// The file is decoded one block of a few thousand elements at a time, so files of any size can
+/// be processed. Only uncompressed and zlib compressed blocks are supported, which covers the files
+/// published by planet.osm.org and Geofabrik.
+///
+/// See <https://wiki.openstreetmap.org/wiki/PBF_Format>
+#[derive(Debug)]
+pub struct PbfReader<R> {
+    input: R,
+    elements: VecDeque<Element>,
+    done: bool,
+}
+
+impl<R: Read> PbfReader<R> {
+    /// Construct a new [`PbfReader`] that reads from `input`.
+    ///
+    /// Wrapping `input` in a [`std::io::BufReader`] is not required since blobs are read at once.
+    pub fn new(input: R) -> Self {
+        Self {
+            input,
+            elements: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Read the next blob and return its type and decompressed content.
+    ///
+    /// Returns [`None`] at the end of the file.
+    fn read_blob(&mut self) -> Result<Option<(String, Vec<u8>)>, Error> {
+        let Some(length) = self.read_length()? else {
+            return Ok(None);
+        };
+        if length > MAX_HEADER_SIZE {
+            return Err(Error::Malformed("blob header is too large"));
+        }
+        let mut header = vec![0; length];
+        self.input.read_exact(&mut header)?;
+
+        let (blob_type, size) = parse_blob_header(&header)?;
+        if size > MAX_BLOB_SIZE {
+            return Err(Error::Malformed("blob is too large"));
+        }
+        let mut blob = vec![0; size];
+        self.input.read_exact(&mut blob)?;
+
+        Ok(Some((blob_type, decode_blob(&blob)?)))
+    }
+
+    /// Read the big endian length prefix of a blob header.
+    ///
+    /// Returns [`None`] if the input ends before the prefix.
+    fn read_length(&mut self) -> Result<Option<usize>, Error> {
+        let mut length = [0; 4];
+        let mut read = 0;
+        while read < length.len() {
+            match self.input.read(&mut length[read..]) {
+                Ok(0) if read == 0 => return Ok(None),
+                Ok(0) => return Err(Error::Malformed("blob header length is truncated")),
+                Ok(count) => read += count,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(Some(u32::from_be_bytes(length) as usize))
+    }
+
+    /// Decode the next data block into the element queue.
+    ///
+    /// Returns `false` at the end of the file.
+    fn read_block(&mut self) -> Result<bool, Error> {
+        loop {
+            let Some((blob_type, data)) = self.read_blob()? else {
+                return Ok(false);
+            };
+
+            match blob_type.as_str() {
+                "OSMHeader" => check_header(&data)?,
+                "OSMData" => {
+                    self.elements.extend(parse_primitive_block(&data)?);
+                    return Ok(true);
+                }
+                // Unknown blob types must be skipped.
+                _ => (),
+            }
+        }
+    }
+}
+
+impl PbfReader<std::fs::File> {
+    /// Open an `.osm.pbf` file.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`std::io::Error`] if the file can not be opened.
+    pub fn open(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        Ok(Self::new(std::fs::File::open(path)?))
+    }
+}
+
+impl<R: Read> Iterator for PbfReader<R> {
+    type Item = Result<Element, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(element) = self.elements.pop_front() {
+                return Some(Ok(element));
+            }
+            if self.done {
+                return None;
+            }
+
+            match self.read_block() {
+                Ok(true) => (),
+                Ok(false) => self.done = true,
+                Err(e) => {
+                    // Stop after the first error.
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+impl<R: Read> ElementReader for PbfReader<R> {
+    type Error = Error;
+}
+
+/// Parse a `BlobHeader` into the blob type and the size of the following blob.
+fn parse_blob_header(data: &[u8]) -> Result<(String, usize), Error> {
+    let mut blob_type = None;
+    let mut size = None;
+
+    for field in Message::new(data) {
+        match field? {
+            (1, Value::Bytes(value)) => blob_type = Some(string(value)?.to_string()),
+            (3, Value::Varint(value)) => size = Some(value as usize),
+            _ => (),
+        }
+    }
+
+    Ok((
+        blob_type.ok_or(Error::Malformed("blob header has no type"))?,
+        size.ok_or(Error::Malformed("blob header has no size"))?,
+    ))
+}
+
+/// Decompress the content of a `Blob`.
+fn decode_blob(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut raw = None;
+    let mut raw_size = None;
+    let mut zlib = None;
+
+    for field in Message::new(data) {
+        match field? {
+            (1, Value::Bytes(value)) => raw = Some(value),
+            (2, Value::Varint(value)) => raw_size = Some(value as usize),
+            (3, Value::Bytes(value)) => zlib = Some(value),
+            (4, _) => return Err(Error::UnsupportedCompression("lzma")),
+            (5, _) => return Err(Error::UnsupportedCompression("bzip2")),
+            (6, _) => return Err(Error::UnsupportedCompression("lz4")),
+            (7, _) => return Err(Error::UnsupportedCompression("zstd")),
+            _ => (),
+        }
+    }
+
+    match (raw, zlib) {
+        (Some(raw), _) => Ok(raw.to_vec()),
+        (None, Some(zlib)) => miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(
+            zlib,
+            raw_size.unwrap_or(MAX_BLOB_SIZE).min(MAX_BLOB_SIZE),
+        )
+        .map_err(|_| Error::Decompression),
+        (None, None) => Err(Error::Malformed("blob has no content")),
+    }
+}
+
+/// Check that all required features of a `HeaderBlock` are supported.
+fn check_header(data: &[u8]) -> Result<(), Error> {
+    for field in Message::new(data) {
+        if let (4, Value::Bytes(feature)) = field? {
+            let feature = string(feature)?;
+            if !SUPPORTED_FEATURES.contains(&feature) {
+                return Err(Error::UnsupportedFeature(feature.to_string()));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Decode all elements of a `PrimitiveBlock`.
+fn parse_primitive_block(data: &[u8]) -> Result<Vec<Element>, Error> {
+    let mut block = Block {
+        strings: Vec::new(),
+        granularity: 100,
+        lat_offset: 0,
+        lon_offset: 0,
+        date_granularity: 1000,
+    };
+    let mut groups = Vec::new();
+
+    for field in Message::new(data) {
+        match field? {
+            (1, Value::Bytes(table)) => {
+                for entry in Message::new(table) {
+                    if let (1, Value::Bytes(value)) = entry? {
+                        block.strings.push(string(value)?);
+                    }
+                }
+            }
+            (2, Value::Bytes(group)) => groups.push(group),
+            (17, Value::Varint(value)) => block.granularity = value as i64,
+            (18, Value::Varint(value)) => block.date_granularity = value as i64,
+            (19, Value::Varint(value)) => block.lat_offset = value as i64,
+            (20, Value::Varint(value)) => block.lon_offset = value as i64,
+            _ => (),
+        }
+    }
+
+    let mut elements = Vec::new();
+    for group in groups {
+        for field in Message::new(group) {
+            match field? {
+                (1, Value::Bytes(node)) => elements.push(Element::Node(block.node(node)?)),
+                (2, Value::Bytes(dense)) => block.dense_nodes(dense, &mut elements)?,
+                (3, Value::Bytes(way)) => elements.push(Element::Way(block.way(way)?)),
+                (4, Value::Bytes(relation)) => {
+                    elements.push(Element::Relation(block.relation(relation)?))
+                }
+                _ => (),
+            }
+        }
+    }
+
+    Ok(elements)
+}
+
+/// The string table and coordinate encoding of a `PrimitiveBlock`.
+struct Block<'a> {
+    strings: Vec<&'a str>,
+    granularity: i64,
+    lat_offset: i64,
+    lon_offset: i64,
+    date_granularity: i64,
+}
+
+impl Block<'_> {
+    fn string(&self, index: i64) -> Result<&str, Error> {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.strings.get(index))
+            .copied()
+            .ok_or(Error::Malformed("string index is out of range"))
+    }
+
+    fn tags(&self, keys: &[u64], values: &[u64]) -> Result<Tags, Error> {
+        if keys.len() != values.len() {
+            return Err(Error::Malformed("tag keys and values differ in length"));
+        }
+
+        keys.iter()
+            .zip(values)
+            .map(|(key, value)| {
+                Ok((
+                    self.string(*key as i64)?.to_string(),
+                    self.string(*value as i64)?.to_string(),
+                ))
+            })
+            .collect()
+    }
+
+    fn coordinate(&self, value: i64, offset: i64) -> CoordinateType {
+        (1e-9 * offset.wrapping_add(self.granularity.wrapping_mul(value)) as f64) as CoordinateType
+    }
+
+    fn timestamp(&self, value: i64) -> String {
+        format_timestamp(value.wrapping_mul(self.date_granularity) / 1000)
+    }
+
+    /// Get the user name of a string table index, the empty name at index 0 is [`None`].
+    fn user(&self, index: i64) -> Result<Option<String>, Error> {
+        let user = self.string(index)?;
+
+        Ok((!user.is_empty()).then(|| user.to_string()))
+    }
+
+    /// Decode an `Info` message.
+    fn info(&self, data: &[u8]) -> Result<Metadata, Error> {
+        let mut metadata = Metadata::default();
+
+        for field in Message::new(data) {
+            match field? {
+                (1, Value::Varint(value)) => metadata.version = u64::try_from(value as i32).ok(),
+                (2, Value::Varint(value)) => {
+                    metadata.timestamp = Some(self.timestamp(value as i64))
+                }
+                (3, Value::Varint(value)) => {
+                    metadata.changeset = u64::try_from(value as i64).ok().map(ChangesetId::new)
+                }
+                (4, Value::Varint(value)) => metadata.uid = u64::try_from(value as i32).ok(),
+                (5, Value::Varint(value)) => metadata.user = self.user(value as i64)?,
+                (6, Value::Varint(value)) => metadata.visible = Some(value != 0),
+                _ => (),
+            }
+        }
+
+        Ok(metadata)
+    }
+
+    fn node(&self, data: &[u8]) -> Result<Node, Error> {
+        let (mut id, mut lat, mut lon) = (0, 0, 0);
+        let (mut keys, mut values) = (Vec::new(), Vec::new());
+        let mut metadata = Metadata::default();
+
+        for field in Message::new(data) {
+            match field? {
+                (1, Value::Varint(value)) => id = zigzag(value),
+                (2, value) => extend_varints(&mut keys, value)?,
+                (3, value) => extend_varints(&mut values, value)?,
+                (4, Value::Bytes(info)) => metadata = self.info(info)?,
+                (8, Value::Varint(value)) => lat = zigzag(value),
+                (9, Value::Varint(value)) => lon = zigzag(value),
+                _ => (),
+            }
+        }
+
+        Ok(self.build_node(id, (lat, lon), self.tags(&keys, &values)?, metadata))
+    }
+
+    /// Decode `DenseNodes` and append them to `elements`.
+    fn dense_nodes(&self, data: &[u8], elements: &mut Vec<Element>) -> Result<(), Error> {
+        let (mut ids, mut lats, mut lons, mut keys_values) =
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        let mut info = None;
+
+        for field in Message::new(data) {
+            match field? {
+                (1, value) => extend_varints(&mut ids, value)?,
+                (5, Value::Bytes(value)) => info = Some(DenseInfo::parse(value)?),
+                (8, value) => extend_varints(&mut lats, value)?,
+                (9, value) => extend_varints(&mut lons, value)?,
+                (10, value) => extend_varints(&mut keys_values, value)?,
+                _ => (),
+            }
+        }
+        if lats.len() != ids.len() || lons.len() != ids.len() {
+            return Err(Error::Malformed("dense node columns differ in length"));
+        }
+
+        let ids = delta_decode(&ids);
+        let lats = delta_decode(&lats);
+        let lons = delta_decode(&lons);
+        let mut keys_values = keys_values.into_iter();
+
+        for (index, id) in ids.into_iter().enumerate() {
+            // The tags of each node are terminated by a 0.
+            let mut tags = Tags::new();
+            while let Some(key) = keys_values.next().filter(|key| *key != 0) {
+                let value = keys_values
+                    .next()
+                    .ok_or(Error::Malformed("dense node tags are truncated"))?;
+                tags.insert(
+                    self.string(key as i64)?.to_string(),
+                    self.string(value as i64)?.to_string(),
+                );
+            }
+
+            let metadata = match info {
+                Some(ref info) => info.metadata(index, self)?,
+                None => Metadata::default(),
+            };
+            elements.push(Element::Node(self.build_node(
+                id,
+                (lats[index], lons[index]),
+                tags,
+                metadata,
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn build_node(&self, id: i64, (lat, lon): (i64, i64), tags: Tags, metadata: Metadata) -> Node {
+        // Deleted nodes in history files have no meaningful position.
+        let deleted = metadata.visible == Some(false);
+
+        Node {
+            id: NodeId::new(id),
+            lat: (!deleted).then(|| self.coordinate(lat, self.lat_offset)),
+            lon: (!deleted).then(|| self.coordinate(lon, self.lon_offset)),
+            tags,
+            metadata,
+        }
+    }
+
+    fn way(&self, data: &[u8]) -> Result<Way, Error> {
+        let mut id = 0;
+        let (mut keys, mut values, mut refs) = (Vec::new(), Vec::new(), Vec::new());
+        let mut metadata = Metadata::default();
+
+        for field in Message::new(data) {
+            match field? {
+                (1, Value::Varint(value)) => id = value as i64,
+                (2, value) => extend_varints(&mut keys, value)?,
+                (3, value) => extend_varints(&mut values, value)?,
+                (4, Value::Bytes(info)) => metadata = self.info(info)?,
+                (8, value) => extend_varints(&mut refs, value)?,
+                _ => (),
+            }
+        }
+
+        Ok(Way {
+            id: WayId::new(id),
+            nodes: delta_decode(&refs).into_iter().map(NodeId::new).collect(),
+            tags: self.tags(&keys, &values)?,
+            metadata,
+        })
+    }
+
+    fn relation(&self, data: &[u8]) -> Result<Relation, Error> {
+        let mut id = 0;
+        let (mut keys, mut values) = (Vec::new(), Vec::new());
+        let (mut roles, mut references, mut types) = (Vec::new(), Vec::new(), Vec::new());
+        let mut metadata = Metadata::default();
+
+        for field in Message::new(data) {
+            match field? {
+                (1, Value::Varint(value)) => id = value as i64,
+                (2, value) => extend_varints(&mut keys, value)?,
+                (3, value) => extend_varints(&mut values, value)?,
+                (4, Value::Bytes(info)) => metadata = self.info(info)?,
+                (8, value) => extend_varints(&mut roles, value)?,
+                (9, value) => extend_varints(&mut references, value)?,
+                (10, value) => extend_varints(&mut types, value)?,
+                _ => (),
+            }
+        }
+        if references.len() != roles.len() || references.len() != types.len() {
+            return Err(Error::Malformed("relation member columns differ in length"));
+        }
+
+        let members = delta_decode(&references)
+            .into_iter()
+            .zip(roles)
+            .zip(types)
+            .map(|((reference, role), member_type)| {
+                Ok(RelationMember {
+                    member_type: match member_type {
+                        0 => ElementType::Node,
+                        1 => ElementType::Way,
+                        2 => ElementType::Relation,
+                        _ => return Err(Error::Malformed("unknown relation member type")),
+                    },
+                    reference,
+                    role: self.string(role as i32 as i64)?.to_string(),
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Relation {
+            id: RelationId::new(id),
+            members,
+            tags: self.tags(&keys, &values)?,
+            metadata,
+        })
+    }
+}
+
+/// The columns of a `DenseInfo` message, with delta coded columns already decoded.
+struct DenseInfo {
+    versions: Vec<u64>,
+    timestamps: Vec<i64>,
+    changesets: Vec<i64>,
+    uids: Vec<i64>,
+    users: Vec<i64>,
+    visible: Vec<u64>,
+}
+
+impl DenseInfo {
+    fn parse(data: &[u8]) -> Result<Self, Error> {
+        let mut columns: [Vec<u64>; 6] = Default::default();
+
+        for field in Message::new(data) {
+            let (number, value) = field?;
+            if let Some(column) = (number as usize)
+                .checked_sub(1)
+                .and_then(|index| columns.get_mut(index))
+            {
+                extend_varints(column, value)?;
+            }
+        }
+        let [versions, timestamps, changesets, uids, users, visible] = columns;
+
+        Ok(Self {
+            versions,
+            timestamps: delta_decode(&timestamps),
+            changesets: delta_decode(&changesets),
+            uids: delta_decode(&uids),
+            users: delta_decode(&users),
+            visible,
+        })
+    }
+
+    fn metadata(&self, index: usize, block: &Block<'_>) -> Result<Metadata, Error> {
+        Ok(Metadata {
+            version: self
+                .versions
+                .get(index)
+                .and_then(|version| u64::try_from(*version as i32).ok()),
+            changeset: self
+                .changesets
+                .get(index)
+                .and_then(|changeset| u64::try_from(*changeset).ok())
+                .map(ChangesetId::new),
+            timestamp: self
+                .timestamps
+                .get(index)
+                .map(|timestamp| block.timestamp(*timestamp)),
+            user: match self.users.get(index) {
+                Some(user) => block.user(*user)?,
+                None => None,
+            },
+            uid: self
+                .uids
+                .get(index)
+                .and_then(|uid| u64::try_from(*uid).ok()),
+            visible: self.visible.get(index).map(|visible| *visible != 0),
+        })
+    }
+}
+
+fn string(value: &[u8]) -> Result<&str, Error> {
+    std::str::from_utf8(value).map_err(|_| Error::Malformed("string is not valid UTF-8"))
+}
+
+/// Format seconds since 1970-01-01 as an ISO 8601 timestamp like `2025-01-01T00:00:00Z`.
+fn format_timestamp(seconds: i64) -> String {
+    let days = seconds.div_euclid(86_400);
+    let time = seconds.rem_euclid(86_400);
+
+    // Inverse of `days_from_civil`, see <https://howardhinnant.github.io/date_algorithms.html>
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = era * 400 + year_of_era + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod pbf_test {
+    use crate::{
+        model::{
+            element::{Element, ElementType},
+            id::{ChangesetId, NodeId, WayId},
+            osm_data::OsmData,
+        },
+        pbf::{PbfReader, error::Error, format_timestamp},
+    };
+
+    fn varint(mut value: u64, out: &mut Vec<u8>) {
+        while value >= 0x80 {
+            out.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+
+    fn field_varint(out: &mut Vec<u8>, number: u64, value: u64) {
+        varint(number << 3, out);
+        varint(value, out);
+    }
+
+    fn field_bytes(out: &mut Vec<u8>, number: u64, value: &[u8]) {
+        varint(number << 3 | 2, out);
+        varint(value.len() as u64, out);
+        out.extend_from_slice(value);
+    }
+
+    fn packed(values: &[u64]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for value in values {
+            varint(*value, &mut out);
+        }
+        out
+    }
+
+    fn sint(value: i64) -> u64 {
+        ((value << 1) ^ (value >> 63)) as u64
+    }
+
+    fn deltas(values: &[i64]) -> Vec<u64> {
+        let mut previous = 0;
+        values
+            .iter()
+            .map(|value| {
+                let delta = sint(value - previous);
+                previous = *value;
+                delta
+            })
+            .collect()
+    }
+
+    fn blob(out: &mut Vec<u8>, blob_type: &str, data: &[u8], compressed: bool) {
+        let mut blob = Vec::new();
+        if compressed {
+            field_varint(&mut blob, 2, data.len() as u64);
+            field_bytes(
+                &mut blob,
+                3,
+                &miniz_oxide::deflate::compress_to_vec_zlib(data, 6),
+            );
+        } else {
+            field_bytes(&mut blob, 1, data);
+        }
+
+        let mut header = Vec::new();
+        field_bytes(&mut header, 1, blob_type.as_bytes());
+        field_varint(&mut header, 3, blob.len() as u64);
+
+        out.extend_from_slice(&(header.len() as u32).to_be_bytes());
+        out.extend_from_slice(&header);
+        out.extend_from_slice(&blob);
+    }
+
+    fn header_block(features: &[&str]) -> Vec<u8> {
+        let mut header = Vec::new();
+        for feature in features {
+            field_bytes(&mut header, 4, feature.as_bytes());
+        }
+        field_bytes(&mut header, 16, b"rust-osm");
+        header
+    }
+
+    fn data_block() -> Vec<u8> {
+        let strings = [
+            "", "name", "Café", "highway", "path", "outer", "type", "route", "alice",
+        ];
+        let mut table = Vec::new();
+        for string in strings {
+            field_bytes(&mut table, 1, string.as_bytes());
+        }
+
+        let mut info = Vec::new();
+        field_bytes(&mut info, 1, &packed(&[2, 1]));
+        field_bytes(
+            &mut info,
+            2,
+            &packed(&deltas(&[1_735_689_600, 1_735_689_660])),
+        );
+        field_bytes(&mut info, 3, &packed(&deltas(&[10, 11])));
+        field_bytes(&mut info, 4, &packed(&deltas(&[5, 5])));
+        field_bytes(&mut info, 5, &packed(&deltas(&[8, 8])));
+        let mut dense = Vec::new();
+        field_bytes(&mut dense, 1, &packed(&deltas(&[1, 2])));
+        field_bytes(&mut dense, 5, &info);
+        field_bytes(&mut dense, 8, &packed(&deltas(&[15_000_000, -5_000_000])));
+        field_bytes(&mut dense, 9, &packed(&deltas(&[25_000_000, 25_000_000])));
+        field_bytes(&mut dense, 10, &packed(&[1, 2, 0, 0]));
+        let mut nodes = Vec::new();
+        field_bytes(&mut nodes, 2, &dense);
+
+        let mut way = Vec::new();
+        field_varint(&mut way, 1, 3);
+        field_bytes(&mut way, 2, &packed(&[3]));
+        field_bytes(&mut way, 3, &packed(&[4]));
+        field_bytes(&mut way, 8, &packed(&deltas(&[1, 2])));
+        let mut ways = Vec::new();
+        field_bytes(&mut ways, 3, &way);
+
+        let mut relation = Vec::new();
+        field_varint(&mut relation, 1, 6);
+        field_bytes(&mut relation, 2, &packed(&[6]));
+        field_bytes(&mut relation, 3, &packed(&[7]));
+        field_bytes(&mut relation, 8, &packed(&[5]));
+        field_bytes(&mut relation, 9, &packed(&deltas(&[3])));
+        field_bytes(&mut relation, 10, &packed(&[1]));
+        let mut relations = Vec::new();
+        field_bytes(&mut relations, 4, &relation);
+
+        let mut block = Vec::new();
+        field_bytes(&mut block, 1, &table);
+        field_bytes(&mut block, 2, &nodes);
+        field_bytes(&mut block, 2, &ways);
+        field_bytes(&mut block, 2, &relations);
+        block
+    }
+
+    fn file(features: &[&str], compressed: bool) -> Vec<u8> {
+        let mut file = Vec::new();
+        blob(&mut file, "OSMHeader", &header_block(features), compressed);
+        blob(&mut file, "OSMData", &data_block(), compressed);
+        file
+    }
+
+    #[test]
+    fn read() {
+        let file = file(&["OsmSchema-V0.6", "DenseNodes"], true);
+        let elements: Vec<Element> = PbfReader::new(file.as_slice())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(elements.len(), 4);
+        let Element::Node(ref first) = elements[0] else {
+            panic!("expected a node");
+        };
+        assert_eq!(first.id, NodeId::new(1));
+        assert_eq!(first.lat, Some(1.5));
+        assert_eq!(first.lon, Some(2.5));
+        assert_eq!(first.tags["name"], "Café");
+        assert_eq!(first.metadata.version, Some(2));
+        assert_eq!(
+            first.metadata.timestamp.as_deref(),
+            Some("2025-01-01T00:00:00Z")
+        );
+        assert_eq!(first.metadata.changeset, Some(ChangesetId::new(10)));
+        assert_eq!(first.metadata.user.as_deref(), Some("alice"));
+        assert_eq!(first.metadata.uid, Some(5));
+
+        let Element::Node(ref second) = elements[1] else {
+            panic!("expected a node");
+        };
+        assert_eq!(second.id, NodeId::new(2));
+        assert_eq!(second.lat, Some(-0.5));
+        assert!(second.tags.is_empty());
+        assert_eq!(
+            second.metadata.timestamp.as_deref(),
+            Some("2025-01-01T00:01:00Z")
+        );
+
+        let Element::Way(ref way) = elements[2] else {
+            panic!("expected a way");
+        };
+        assert_eq!(way.id, WayId::new(3));
+        assert_eq!(way.nodes, vec![NodeId::new(1), NodeId::new(2)]);
+        assert_eq!(way.tags["highway"], "path");
+
+        let Element::Relation(ref relation) = elements[3] else {
+            panic!("expected a relation");
+        };
+        assert_eq!(relation.members[0].member_type, ElementType::Way);
+        assert_eq!(relation.members[0].reference, 3);
+        assert_eq!(relation.members[0].role, "outer");
+        assert_eq!(relation.tags["type"], "route");
+    }
+
+    #[test]
+    fn open() {
+        let path =
+            std::env::temp_dir().join(format!("rust-osm-extract-{}.osm.pbf", std::process::id()));
+        std::fs::write(&path, file(&["OsmSchema-V0.6"], true)).unwrap();
+        let data = OsmData::from_reader(PbfReader::open(&path).unwrap()).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(data.elements.len(), 4);
+        assert_eq!(data.way(WayId::new(3)).unwrap().tags["highway"], "path");
+    }
+
+    #[test]
+    fn uncompressed() {
+        let compressed: Vec<Element> = PbfReader::new(file(&["OsmSchema-V0.6"], true).as_slice())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let uncompressed: Vec<Element> =
+            PbfReader::new(file(&["OsmSchema-V0.6"], false).as_slice())
+                .collect::<Result<_, _>>()
+                .unwrap();
+
+        assert_eq!(compressed, uncompressed);
+    }
+
+    #[test]
+    fn unsupported_feature() {
+        let file = file(&["OsmSchema-V0.6", "LocationsOnWays"], false);
+        let mut reader = PbfReader::new(file.as_slice());
+
+        assert!(matches!(
+            reader.next(),
+            Some(Err(Error::UnsupportedFeature(feature))) if feature == "LocationsOnWays"
+        ));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn truncated() {
+        let file = file(&["OsmSchema-V0.6"], true);
+        let mut reader = PbfReader::new(&file[..file.len() - 10]);
+
+        assert!(matches!(reader.next(), Some(Err(Error::Io(_)))));
+        assert!(PbfReader::new(&[][..]).next().is_none());
+    }
+
+    #[test]
+    fn timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_timestamp(1_735_732_799), "2025-01-01T11:59:59Z");
+    }
+}
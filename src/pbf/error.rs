@@ -0,0 +1,13 @@
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("The input could not be read: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("The file is malformed: {0}")]
+    Malformed(&'static str),
+    #[error("The blob compression `{0}` is not supported")]
+    UnsupportedCompression(&'static str),
+    #[error("The blob could not be decompressed")]
+    Decompression,
+    #[error("The file requires the unsupported feature `{0}`")]
+    UnsupportedFeature(String),
+}
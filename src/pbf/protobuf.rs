@@ -0,0 +1,180 @@
+use crate::pbf::error::Error;
+
+/// The value of a single protobuf field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Value<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    /// A 32 or 64 bit fixed size value, which the OSM formats do not use.
+    Fixed,
+}
+
+/// An iterator over the fields of an encoded protobuf message, yielding field numbers and values.
+///
+/// See <https://protobuf.dev/programming-guides/encoding/>
+#[derive(Debug)]
+pub(crate) struct Message<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Message<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    fn read_field(&mut self) -> Result<(u64, Value<'a>), Error> {
+        let key = read_varint(self.data, &mut self.position)?;
+        let value = match key & 0b111 {
+            0 => Value::Varint(read_varint(self.data, &mut self.position)?),
+            1 => {
+                self.take(8)?;
+                Value::Fixed
+            }
+            2 => {
+                let length = read_varint(self.data, &mut self.position)?;
+                Value::Bytes(self.take(
+                    usize::try_from(length).map_err(|_| Error::Malformed("field is too long"))?,
+                )?)
+            }
+            5 => {
+                self.take(4)?;
+                Value::Fixed
+            }
+            _ => return Err(Error::Malformed("unsupported protobuf wire type")),
+        };
+
+        Ok((key >> 3, value))
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], Error> {
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.data.len())
+            .ok_or(Error::Malformed("field is truncated"))?;
+        let value = &self.data[self.position..end];
+        self.position = end;
+
+        Ok(value)
+    }
+}
+
+impl<'a> Iterator for Message<'a> {
+    type Item = Result<(u64, Value<'a>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position >= self.data.len() {
+            return None;
+        }
+
+        let field = self.read_field();
+        if field.is_err() {
+            // Stop after the first error.
+            self.position = self.data.len();
+        }
+
+        Some(field)
+    }
+}
+
+/// Read a base 128 varint starting at `position` and advance `position` past it.
+pub(crate) fn read_varint(data: &[u8], position: &mut usize) -> Result<u64, Error> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = *data
+            .get(*position)
+            .ok_or(Error::Malformed("varint is truncated"))?;
+        *position += 1;
+
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err(Error::Malformed("varint is too long"))
+}
+
+/// Decode a zigzag encoded signed integer.
+pub(crate) fn zigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+/// Append the values of a repeated varint field, which may or may not be packed.
+pub(crate) fn extend_varints(values: &mut Vec<u64>, value: Value<'_>) -> Result<(), Error> {
+    match value {
+        Value::Varint(value) => values.push(value),
+        Value::Bytes(data) => {
+            let mut position = 0;
+            while position < data.len() {
+                values.push(read_varint(data, &mut position)?);
+            }
+        }
+        Value::Fixed => return Err(Error::Malformed("expected a varint")),
+    }
+
+    Ok(())
+}
+
+/// Decode delta coded zigzag values into their absolute values.
+pub(crate) fn delta_decode(values: &[u64]) -> Vec<i64> {
+    values
+        .iter()
+        .scan(0i64, |current, value| {
+            *current = current.wrapping_add(zigzag(*value));
+            Some(*current)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod protobuf_test {
+    use crate::pbf::protobuf::{Message, Value, delta_decode, extend_varints, read_varint, zigzag};
+
+    #[test]
+    fn varint() {
+        let mut position = 0;
+
+        assert_eq!(
+            read_varint(&[0xac, 0x02, 0x01], &mut position).unwrap(),
+            300
+        );
+        assert_eq!(position, 2);
+        assert!(read_varint(&[0x80], &mut 0).is_err());
+    }
+
+    #[test]
+    fn signed() {
+        assert_eq!(zigzag(0), 0);
+        assert_eq!(zigzag(1), -1);
+        assert_eq!(zigzag(4), 2);
+        assert_eq!(delta_decode(&[2, 2, 3]), vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn message() {
+        // Field 1 varint 150, field 2 bytes "hi", field 3 fixed32.
+        let data = [0x08, 0x96, 0x01, 0x12, 0x02, b'h', b'i', 0x1d, 0, 0, 0, 0];
+        let fields: Vec<_> = Message::new(&data).collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(
+            fields,
+            vec![
+                (1, Value::Varint(150)),
+                (2, Value::Bytes(b"hi")),
+                (3, Value::Fixed)
+            ]
+        );
+        assert!(Message::new(&[0x12, 0x05, b'a']).next().unwrap().is_err());
+    }
+
+    #[test]
+    fn packed() {
+        let mut values = Vec::new();
+        extend_varints(&mut values, Value::Bytes(&[0x01, 0xac, 0x02])).unwrap();
+        extend_varints(&mut values, Value::Varint(7)).unwrap();
+
+        assert_eq!(values, vec![1, 300, 7]);
+    }
+}
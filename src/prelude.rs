@@ -0,0 +1,78 @@
+//! The types used in most code working with OSM data, to be imported with
+//! `use rust_osm::prelude::*`.
+//!
+//! The clients are the async ones. Code using the blocking clients imports
+//! `rust_osm::prelude::blocking::*` instead.
+
+pub use crate::{
+    coord::{
+        CoordinateType, bbox::BBox, coordinates::Coordinates, latitude::Latitude,
+        longitude::Longitude, tile::Tile,
+    },
+    model::{
+        element::{Element, ElementType, Metadata, Node, Relation, RelationMember, Way},
+        id::{ChangesetId, ElementId, NodeId, RelationId, WayId},
+        osm_change::OsmChange,
+        osm_data::OsmData,
+        tags::{TagValues, Tags},
+    },
+};
+
+#[cfg(feature = "overpass")]
+pub use crate::overpass::response::OverpassResponse;
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub use crate::{
+    client_config::ClientConfig, osm_config::OsmConfig, rest_methods::RESTMethods,
+    retry::RetryPolicy,
+};
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub use reqwest::{StatusCode, Url};
+
+#[cfg(all(feature = "async", feature = "download"))]
+pub use crate::download::download_async::Downloader;
+#[cfg(all(feature = "async", feature = "osm_api"))]
+pub use crate::osm_api::osm_api_async::OsmApiClient;
+#[cfg(all(feature = "async", feature = "overpass"))]
+pub use crate::overpass::overpass_async::OverpassAPI;
+#[cfg(all(feature = "async", feature = "replication"))]
+pub use crate::replication::replication_async::ReplicationClient;
+#[cfg(all(feature = "async", feature = "tile"))]
+pub use crate::tile::{TileServer, tile_async::TileClient};
+
+/// The prelude with the blocking clients instead of the async ones.
+#[cfg(feature = "blocking")]
+pub mod blocking {
+    pub use super::*;
+
+    #[cfg(feature = "download")]
+    pub use crate::download::download_blocking::Downloader;
+    #[cfg(feature = "osm_api")]
+    pub use crate::osm_api::osm_api_blocking::OsmApiClient;
+    #[cfg(feature = "overpass")]
+    pub use crate::overpass::overpass_blocking::OverpassAPI;
+    #[cfg(feature = "replication")]
+    pub use crate::replication::replication_blocking::ReplicationClient;
+    #[cfg(feature = "tile")]
+    pub use crate::tile::{TileServer, tile_blocking::TileClient};
+}
+
+#[cfg(all(test, feature = "blocking", feature = "overpass"))]
+mod prelude_test {
+    use crate::prelude::blocking::*;
+
+    #[test]
+    fn blocking() {
+        let api = OverpassAPI::new("https://overpass.example/api/interpreter").unwrap();
+        let coordinates = Coordinates::from_unchecked(52.52, 13.405);
+
+        assert_eq!(
+            api.url(),
+            &Url::parse("https://overpass.example/api/interpreter").unwrap()
+        );
+        assert_eq!(
+            Tile::from_coordinates(&coordinates, 0),
+            Tile::new(0, 0, 0).unwrap()
+        );
+        assert!(RESTMethods::get(&api).build().is_ok());
+    }
+}
@@ -0,0 +1,119 @@
+pub mod error;
+pub mod state;
+
+#[cfg(feature = "async")]
+pub mod replication_async;
+#[cfg(feature = "blocking")]
+pub mod replication_blocking;
+#[cfg(feature = "async")]
+pub mod stream_async;
+#[cfg(feature = "blocking")]
+pub mod stream_blocking;
+
+/// Base url of the replication diffs of planet.osm.org.
+pub const PLANET_REPLICATION_URL: &str = "https://planet.osm.org/replication";
+
+/// The interval in which replication diffs are published.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Planet.osm/diffs>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReplicationInterval {
+    Minute,
+    Hour,
+    Day,
+}
+
+impl ReplicationInterval {
+    /// Get the directory name of this interval.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Minute => "minute",
+            Self::Hour => "hour",
+            Self::Day => "day",
+        }
+    }
+
+    /// Get the length of this interval in seconds.
+    pub fn seconds(&self) -> i64 {
+        match self {
+            Self::Minute => 60,
+            Self::Hour => 60 * 60,
+            Self::Day => 24 * 60 * 60,
+        }
+    }
+}
+
+/// Get the path of a sequence number, e.g. `000/123/456` for `123456`.
+pub fn sequence_path(sequence: u64) -> String {
+    format!(
+        "{:03}/{:03}/{:03}",
+        sequence / 1_000_000,
+        sequence / 1_000 % 1_000,
+        sequence % 1_000
+    )
+}
+
+/// Parse an ISO 8601 UTC timestamp like `2025-01-31T12:00:00Z` into seconds since the unix epoch.
+///
+/// Returns [`None`] if the timestamp is malformed or its year is outside of `0..=9999`.
+pub fn parse_timestamp(timestamp: &str) -> Option<i64> {
+    let timestamp = timestamp.trim().strip_suffix('Z')?;
+    let (date, time) = timestamp.split_once('T')?;
+
+    let mut date = date.splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+    let mut time = time.splitn(3, ':').map(str::parse::<i64>);
+    let (hour, minute, second) = (time.next()?.ok()?, time.next()?.ok()?, time.next()?.ok()?);
+
+    if !(0..=9999).contains(&year)
+        || !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || !(0..24).contains(&hour)
+        || !(0..60).contains(&minute)
+        || !(0..=60).contains(&second)
+    {
+        return None;
+    }
+
+    Some(days_from_civil(year, month, day) * 86_400 + hour * 3_600 + minute * 60 + second)
+}
+
+/// Count the days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod replication_test {
+    use crate::replication::{ReplicationInterval, parse_timestamp, sequence_path};
+
+    #[test]
+    fn path() {
+        assert_eq!(sequence_path(123_456), "000/123/456");
+        assert_eq!(sequence_path(6_543_210), "006/543/210");
+        assert_eq!(sequence_path(0), "000/000/000");
+    }
+
+    #[test]
+    fn timestamp() {
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_timestamp("2000-03-01T00:00:00Z"), Some(951_868_800));
+        assert_eq!(parse_timestamp("2025-01-31T12:30:15Z"), Some(1_738_326_615));
+        assert_eq!(parse_timestamp("2025-13-01T00:00:00Z"), None);
+        assert_eq!(parse_timestamp("2025-01-01 00:00:00"), None);
+        assert_eq!(parse_timestamp("9999999999999-01-01T00:00:00Z"), None);
+        assert_eq!(parse_timestamp("-1-01-01T00:00:00Z"), None);
+    }
+
+    #[test]
+    fn interval() {
+        assert_eq!(ReplicationInterval::Minute.as_str(), "minute");
+        assert_eq!(ReplicationInterval::Day.seconds(), 86_400);
+    }
+}
@@ -0,0 +1,19 @@
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("The request could not be sent: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("The diff could not be parsed: {0}")]
+    Xml(#[from] crate::xml::Error),
+    #[error("The server responded with {}: {}", .0.0, .0.1)]
+    HttpStatus((reqwest::StatusCode, String)),
+    #[error("The state file is invalid: {0}")]
+    InvalidState(String),
+    #[error("The timestamp `{0}` is invalid")]
+    InvalidTimestamp(String),
+    #[error("The diff could not be decompressed: {0}")]
+    Gzip(std::io::Error),
+    #[error("The state file could not be read or written: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("The diff could not be applied: {0}")]
+    Store(#[from] crate::model::osm_store::error::Error),
+}
@@ -0,0 +1,260 @@
+use std::{fmt, sync::Arc};
+
+use crate::{
+    client_config::{ClientConfig, redact_url},
+    compression::gzip::gunzip,
+    model::osm_change::OsmChange,
+    osm_config::OsmConfig,
+    replication::{
+        PLANET_REPLICATION_URL, ReplicationInterval, error::Error, parse_timestamp, sequence_path,
+        state::ReplicationState,
+    },
+    request_limit::RequestLimit,
+    retry::RetryPolicy,
+    scheduler::{ScheduledTransport, Scheduler},
+    transport::HttpTransport,
+};
+
+/// An async client for the replication diffs used to keep local copies of the OSM data current.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Planet.osm/diffs>
+#[derive(Clone)]
+pub struct ReplicationClient {
+    inner: Arc<Inner>,
+}
+
+/// The state shared by the clones of a [`ReplicationClient`].
+#[derive(Clone)]
+struct Inner {
+    base_url: String,
+    interval: ReplicationInterval,
+    client: reqwest::Client,
+    retry_policy: RetryPolicy,
+    transport: Option<Arc<dyn HttpTransport>>,
+    request_limit: Option<RequestLimit>,
+    scheduler: Option<Scheduler>,
+}
+
+impl fmt::Debug for ReplicationClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReplicationClient")
+            .field("base_url", &redact_url(&self.inner.base_url))
+            .field("interval", &self.inner.interval)
+            .field("client", &self.inner.client)
+            .field("retry_policy", &self.inner.retry_policy)
+            .field("transport", &self.inner.transport)
+            .field("request_limit", &self.inner.request_limit)
+            .field("scheduler", &self.inner.scheduler)
+            .finish()
+    }
+}
+
+impl ReplicationClient {
+    /// Construct a new [`ReplicationClient`] for the diffs of planet.osm.org.
+    pub fn new(interval: ReplicationInterval) -> Self {
+        Self::with_base_url(PLANET_REPLICATION_URL, interval)
+    }
+
+    /// Construct a new [`ReplicationClient`] for a mirror, e.g. `https://planet.openstreetmap.org/replication`.
+    ///
+    /// The interval is appended to `base_url`.
+    pub fn with_base_url(base_url: impl Into<String>, interval: ReplicationInterval) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                base_url: base_url.into().trim_end_matches('/').to_string(),
+                interval,
+                client: reqwest::Client::new(),
+                retry_policy: RetryPolicy::default(),
+                transport: None,
+                request_limit: None,
+                scheduler: None,
+            }),
+        }
+    }
+
+    /// Construct a new [`ReplicationClient`] with the url, HTTP client and retry policy of an
+    /// [`OsmConfig`].
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::Request`] if the HTTP client can not be built.
+    pub fn from_config(config: &OsmConfig, interval: ReplicationInterval) -> Result<Self, Error> {
+        Ok(Self::with_base_url(config.replication_url(), interval)
+            .with_config(config.client_config())?
+            .with_retry_policy(config.retry_policy().clone()))
+    }
+
+    /// Use an HTTP client with the given [`ClientConfig`], e.g. with custom timeouts.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::Request`] if the HTTP client can not be built.
+    pub fn with_config(mut self, config: &ClientConfig) -> Result<Self, Error> {
+        Arc::make_mut(&mut self.inner).client = config.build_async()?;
+        Ok(self)
+    }
+
+    /// Set how failed requests are retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        Arc::make_mut(&mut self.inner).retry_policy = retry_policy;
+        self
+    }
+
+    /// Send the requests with a [`HttpTransport`] instead of the [`reqwest::Client`], e.g. a
+    /// [`MockTransport`](crate::transport::MockTransport) in tests.
+    pub fn with_transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        Arc::make_mut(&mut self.inner).transport = Some(transport);
+        self
+    }
+
+    /// Limit how many requests are in flight at once. The limit is shared by all clones of this
+    /// client and can be shared with other clients.
+    pub fn with_request_limit(mut self, request_limit: RequestLimit) -> Self {
+        Arc::make_mut(&mut self.inner).request_limit = Some(request_limit);
+        self
+    }
+
+    /// Send the requests in the slots of a [`Scheduler`] shared with other clients, queued in its
+    /// `replication` lane.
+    pub fn with_scheduler(mut self, scheduler: Scheduler) -> Self {
+        Arc::make_mut(&mut self.inner).scheduler = Some(scheduler);
+        self
+    }
+
+    /// Get the [`ReplicationInterval`] of this client.
+    pub fn interval(&self) -> ReplicationInterval {
+        self.inner.interval
+    }
+
+    /// Fetch the state of the newest diff.
+    pub async fn get_state(&self) -> Result<ReplicationState, Error> {
+        self.get_text("state.txt").await?.parse()
+    }
+
+    /// Fetch the state of a diff.
+    pub async fn get_state_for(&self, sequence: u64) -> Result<ReplicationState, Error> {
+        self.get_text(&format!("{}.state.txt", sequence_path(sequence)))
+            .await?
+            .parse()
+    }
+
+    /// Fetch and decompress a diff.
+    pub async fn get_diff(&self, sequence: u64) -> Result<OsmChange, Error> {
+        let response = self
+            .send(&format!("{}.osc.gz", sequence_path(sequence)))
+            .await?;
+        let data = gunzip(&response.bytes().await?).map_err(Error::Gzip)?;
+
+        Ok(OsmChange::from_xml(&String::from_utf8_lossy(&data))?)
+    }
+
+    /// Fetch all diffs published after `sequence` with their sequence numbers, oldest first.
+    pub async fn get_diffs_after(&self, sequence: u64) -> Result<Vec<(u64, OsmChange)>, Error> {
+        let current = self.get_state().await?.sequence_number;
+
+        let mut diffs = Vec::new();
+        for sequence in sequence + 1..=current {
+            diffs.push((sequence, self.get_diff(sequence).await?));
+        }
+
+        Ok(diffs)
+    }
+
+    /// Find the newest sequence number whose data is not newer than `timestamp`.
+    ///
+    /// Applying all diffs after the returned sequence to data of `timestamp` brings it up to date.
+    /// Starts with an estimate based on the [`ReplicationInterval`] and narrows it down by fetching
+    /// state files.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`Error::InvalidTimestamp`] if `timestamp` is not a valid ISO 8601 UTC timestamp.
+    pub async fn sequence_for_timestamp(&self, timestamp: &str) -> Result<u64, Error> {
+        let target = parse_timestamp(timestamp)
+            .ok_or_else(|| Error::InvalidTimestamp(timestamp.to_string()))?;
+        let current = self.get_state().await?;
+        let current_time = self.state_time(&current)?;
+
+        if target >= current_time {
+            return Ok(current.sequence_number);
+        }
+
+        let mut upper = current.sequence_number;
+        let mut lower = upper.saturating_sub(
+            ((current_time - target) / self.inner.interval.seconds()).unsigned_abs(),
+        );
+
+        // Walk back until the lower bound is old enough, doubling the step each time.
+        let mut step = 1u64;
+        loop {
+            match self.get_state_for(lower).await {
+                Ok(state) if self.state_time(&state)? <= target => break,
+                Ok(_) if lower == 0 => return Ok(0),
+                Ok(_) => {
+                    upper = lower;
+                    lower = lower.saturating_sub(step);
+                    step = step.saturating_mul(2);
+                }
+                // Old state files may be missing.
+                Err(Error::HttpStatus((reqwest::StatusCode::NOT_FOUND, _))) => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        // Binary search for the newest sequence not newer than the target.
+        while upper - lower > 1 {
+            let middle = lower + (upper - lower) / 2;
+            match self.get_state_for(middle).await {
+                Ok(state) if self.state_time(&state)? <= target => lower = middle,
+                Ok(_) => upper = middle,
+                Err(Error::HttpStatus((reqwest::StatusCode::NOT_FOUND, _))) => lower = middle,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(lower)
+    }
+
+    fn state_time(&self, state: &ReplicationState) -> Result<i64, Error> {
+        state
+            .unix_timestamp()
+            .ok_or_else(|| Error::InvalidTimestamp(state.timestamp.clone()))
+    }
+
+    async fn get_text(&self, path: &str) -> Result<String, Error> {
+        Ok(self.send(path).await?.text().await?)
+    }
+
+    async fn send(&self, path: &str) -> Result<reqwest::Response, Error> {
+        let request = self.inner.client.get(format!(
+            "{}/{}/{path}",
+            self.inner.base_url,
+            self.inner.interval.as_str()
+        ));
+        let _permit = match self.inner.request_limit {
+            Some(ref request_limit) => Some(request_limit.acquire().await),
+            None => None,
+        };
+        let (client, request) = request.build_split();
+        let response = self
+            .inner
+            .retry_policy
+            .execute(
+                &ScheduledTransport::new(
+                    self.inner.transport.as_deref().unwrap_or(&client),
+                    self.inner.scheduler.as_ref(),
+                    "replication",
+                ),
+                request?,
+                |_| Ok::<_, Error>(()),
+            )
+            .await?;
+        let status = response.status();
+
+        if status.is_success() {
+            Ok(response)
+        } else {
+            Err(Error::HttpStatus((status, response.text().await?)))
+        }
+    }
+}
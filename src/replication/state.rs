@@ -0,0 +1,143 @@
+use std::{fmt::Display, fs, path::Path, str::FromStr};
+
+use crate::replication::{error::Error, parse_timestamp};
+
+/// The state of a replication sequence as published in `state.txt`.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Planet.osm/diffs>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplicationState {
+    pub sequence_number: u64,
+    /// The ISO 8601 timestamp of the newest data contained in the diff.
+    pub timestamp: String,
+}
+
+impl ReplicationState {
+    /// Get the timestamp in seconds since the unix epoch.
+    pub fn unix_timestamp(&self) -> Option<i64> {
+        parse_timestamp(&self.timestamp)
+    }
+
+    /// Read a state file, e.g. one written by [`ReplicationState::save`] or Osmosis.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error`] if the file can not be read or is invalid.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// Write this state as state file.
+    ///
+    /// The file is replaced atomically, so it is never left half written.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::Io`] if the file can not be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+
+        fs::write(&temporary, self.to_string())?;
+        fs::rename(&temporary, path)?;
+
+        Ok(())
+    }
+}
+
+impl Display for ReplicationState {
+    /// Format as state file in the Java properties format.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "sequenceNumber={}", self.sequence_number)?;
+        writeln!(f, "timestamp={}", self.timestamp.replace(':', "\\:"))
+    }
+}
+
+impl FromStr for ReplicationState {
+    type Err = Error;
+
+    /// Parse a state file in the Java properties format.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut sequence_number = None;
+        let mut timestamp = None;
+
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(Error::InvalidState(line.to_string()));
+            };
+            // Properties files escape `:` and `=`.
+            let value = value.trim().replace('\\', "");
+
+            match key.trim() {
+                "sequenceNumber" => {
+                    sequence_number = Some(
+                        value
+                            .parse()
+                            .map_err(|_| Error::InvalidState(line.to_string()))?,
+                    )
+                }
+                "timestamp" => timestamp = Some(value),
+                _ => (),
+            }
+        }
+
+        Ok(Self {
+            sequence_number: sequence_number
+                .ok_or_else(|| Error::InvalidState("missing sequenceNumber".to_string()))?,
+            timestamp: timestamp
+                .ok_or_else(|| Error::InvalidState("missing timestamp".to_string()))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod state_test {
+    use crate::replication::state::ReplicationState;
+
+    #[test]
+    fn parse() {
+        let state: ReplicationState = "#Fri Jan 31 12:01:02 UTC 2025\nsequenceNumber=6543210\ntimestamp=2025-01-31T12\\:00\\:00Z\n"
+            .parse()
+            .unwrap();
+
+        assert_eq!(state.sequence_number, 6_543_210);
+        assert_eq!(state.timestamp, "2025-01-31T12:00:00Z");
+        assert_eq!(state.unix_timestamp(), Some(1_738_324_800));
+    }
+
+    #[test]
+    fn save() {
+        let state = ReplicationState {
+            sequence_number: 42,
+            timestamp: "2025-01-31T12:00:00Z".to_string(),
+        };
+        assert_eq!(
+            state.to_string(),
+            "sequenceNumber=42\ntimestamp=2025-01-31T12\\:00\\:00Z\n"
+        );
+
+        let path = std::env::temp_dir().join(format!("rust-osm-state-{}.txt", std::process::id()));
+        state.save(&path).unwrap();
+        assert_eq!(ReplicationState::load(&path).unwrap(), state);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn missing() {
+        assert!(
+            "timestamp=2025-01-31T12\\:00\\:00Z"
+                .parse::<ReplicationState>()
+                .is_err()
+        );
+        assert!(
+            "sequenceNumber=abc\ntimestamp=x"
+                .parse::<ReplicationState>()
+                .is_err()
+        );
+    }
+}
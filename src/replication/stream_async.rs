@@ -0,0 +1,144 @@
+use std::{ops::ControlFlow, path::PathBuf, time::Duration};
+
+use crate::{
+    model::{osm_change::OsmChange, osm_store::OsmStore},
+    replication::{error::Error, replication_async::ReplicationClient, state::ReplicationState},
+};
+
+/// The default time between two polls of the newest state.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Follows the replication diffs of a [`ReplicationClient`] as they are published.
+///
+/// Diffs are handed out one at a time, oldest first. Once a diff has been handled, its
+/// [`ReplicationState`] is written to the state file if one is set, so a restarted process can
+/// [`resume`](ReplicationStream::resume) without skipping or repeating diffs.
+#[derive(Debug, Clone)]
+pub struct ReplicationStream {
+    client: ReplicationClient,
+    /// The sequence number of the last handled diff.
+    sequence: u64,
+    /// The newest known published sequence number.
+    latest: u64,
+    state_file: Option<PathBuf>,
+    poll_interval: Duration,
+}
+
+impl ReplicationStream {
+    /// Construct a new [`ReplicationStream`] starting with the diff after `sequence`.
+    ///
+    /// Use [`ReplicationClient::sequence_for_timestamp`] to find the sequence of an extract.
+    pub fn new(client: ReplicationClient, sequence: u64) -> Self {
+        Self {
+            client,
+            sequence,
+            latest: sequence,
+            state_file: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Construct a new [`ReplicationStream`] continuing after the sequence stored in `state_file`.
+    ///
+    /// The state file is kept up to date as diffs are handled.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error`] if the state file can not be read or is invalid.
+    pub fn resume(
+        client: ReplicationClient,
+        state_file: impl Into<PathBuf>,
+    ) -> Result<Self, Error> {
+        let state_file = state_file.into();
+        let state = ReplicationState::load(&state_file)?;
+
+        Ok(Self::new(client, state.sequence_number).with_state_file(state_file))
+    }
+
+    /// Write the [`ReplicationState`] of every handled diff to `state_file`.
+    pub fn with_state_file(mut self, state_file: impl Into<PathBuf>) -> Self {
+        self.state_file = Some(state_file.into());
+        self
+    }
+
+    /// Set the time between two polls of the newest state while waiting for a new diff.
+    ///
+    /// Defaults to one minute.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Get the sequence number of the last handled diff.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Wait for the next diff and apply it to `store`.
+    ///
+    /// Returns the sequence number of the applied diff.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error`] if the diff can not be fetched or applied. The diff is not marked as
+    /// handled in that case.
+    pub async fn apply_next(&mut self, store: &mut OsmStore) -> Result<u64, Error> {
+        let (sequence, diff, state) = self.fetch_next().await?;
+        store.apply(&diff)?;
+        self.commit(sequence, state)?;
+
+        Ok(sequence)
+    }
+
+    /// Hand every diff to `handler` as it is published, until `handler` breaks.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error`] if a diff can not be fetched or the state file can not be written.
+    pub async fn run(
+        &mut self,
+        mut handler: impl FnMut(u64, OsmChange) -> ControlFlow<()>,
+    ) -> Result<(), Error> {
+        loop {
+            let (sequence, diff, state) = self.fetch_next().await?;
+            let flow = handler(sequence, diff);
+            self.commit(sequence, state)?;
+
+            if flow.is_break() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Wait until the diff after the last handled one is published and fetch it, along with its
+    /// [`ReplicationState`] if a state file is set.
+    ///
+    /// Fetching the state up front means a failure hands out no diff, instead of handing out the
+    /// same diff again after its state could not be fetched.
+    async fn fetch_next(&mut self) -> Result<(u64, OsmChange, Option<ReplicationState>), Error> {
+        let sequence = self.sequence + 1;
+        while self.latest < sequence {
+            self.latest = self.client.get_state().await?.sequence_number;
+            if self.latest < sequence {
+                tokio::time::sleep(self.poll_interval).await;
+            }
+        }
+
+        let diff = self.client.get_diff(sequence).await?;
+        let state = match self.state_file {
+            Some(_) => Some(self.client.get_state_for(sequence).await?),
+            None => None,
+        };
+
+        Ok((sequence, diff, state))
+    }
+
+    /// Mark a diff as handled and write its state to the state file.
+    fn commit(&mut self, sequence: u64, state: Option<ReplicationState>) -> Result<(), Error> {
+        self.sequence = sequence;
+        match (&self.state_file, state) {
+            (Some(state_file), Some(state)) => state.save(state_file),
+            _ => Ok(()),
+        }
+    }
+}
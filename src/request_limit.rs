@@ -0,0 +1,131 @@
+use std::sync::Arc;
+#[cfg(feature = "blocking")]
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+/// Limits how many requests of the async clients are in flight at once.
+///
+/// Clones share the same limit, so one [`RequestLimit`] can be given to several clients, e.g. to
+/// stay within the usage policy of a server queried by many tasks.
+///
+/// A request holds its slot from sending until the response headers arrived, including the
+/// delays between retries.
+#[cfg(feature = "async")]
+#[derive(Debug, Clone)]
+pub struct RequestLimit {
+    semaphore: Arc<tokio::sync::Semaphore>,
+}
+
+#[cfg(feature = "async")]
+impl RequestLimit {
+    /// Construct a new [`RequestLimit`] allowing `max` concurrent requests, at least one.
+    pub fn new(max: usize) -> Self {
+        Self {
+            semaphore: Arc::new(tokio::sync::Semaphore::new(max.max(1))),
+        }
+    }
+
+    /// Get the amount of requests that can be sent right now.
+    pub fn available(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+
+    /// Wait for a free slot, e.g. to count requests sent without the clients of this crate. The
+    /// slot is released when the permit is dropped.
+    pub async fn acquire(&self) -> tokio::sync::OwnedSemaphorePermit {
+        self.semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("the semaphore is never closed")
+    }
+}
+
+/// Limits how many requests of the blocking clients are in flight at once, see
+/// [`RequestLimit`].
+#[cfg(feature = "blocking")]
+#[derive(Debug, Clone)]
+pub struct BlockingRequestLimit {
+    state: Arc<(Mutex<usize>, Condvar)>,
+}
+
+/// A slot of a [`BlockingRequestLimit`], released when dropped.
+#[cfg(feature = "blocking")]
+#[derive(Debug)]
+pub struct BlockingPermit<'a> {
+    limit: &'a BlockingRequestLimit,
+}
+
+#[cfg(feature = "blocking")]
+impl BlockingRequestLimit {
+    /// Construct a new [`BlockingRequestLimit`] allowing `max` concurrent requests, at least one.
+    pub fn new(max: usize) -> Self {
+        Self {
+            state: Arc::new((Mutex::new(max.max(1)), Condvar::new())),
+        }
+    }
+
+    /// Get the amount of requests that can be sent right now.
+    pub fn available(&self) -> usize {
+        *self.lock()
+    }
+
+    /// Block until a slot is free, e.g. to count requests sent without the clients of this crate.
+    pub fn acquire(&self) -> BlockingPermit<'_> {
+        let mut available = self.lock();
+        while *available == 0 {
+            available = self
+                .state
+                .1
+                .wait(available)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+        *available -= 1;
+
+        BlockingPermit { limit: self }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, usize> {
+        // The count stays consistent even if a holder panicked.
+        self.state
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl Drop for BlockingPermit<'_> {
+    fn drop(&mut self) {
+        *self.limit.lock() += 1;
+        self.limit.state.1.notify_one();
+    }
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod request_limit_test {
+    use std::{sync::mpsc, thread, time::Duration};
+
+    use crate::request_limit::BlockingRequestLimit;
+
+    #[test]
+    fn blocking() {
+        let limit = BlockingRequestLimit::new(2);
+        let first = limit.acquire();
+        let _second = limit.acquire();
+        assert_eq!(limit.available(), 0);
+
+        let (sender, receiver) = mpsc::channel();
+        let waiting = limit.clone();
+        let handle = thread::spawn(move || {
+            let _third = waiting.acquire();
+            sender.send(()).unwrap();
+        });
+
+        assert!(receiver.recv_timeout(Duration::from_millis(50)).is_err());
+        drop(first);
+        assert!(receiver.recv_timeout(Duration::from_secs(5)).is_ok());
+        handle.join().unwrap();
+        assert_eq!(limit.available(), 1);
+        assert_eq!(BlockingRequestLimit::new(0).available(), 1);
+    }
+}
@@ -1,6 +1,16 @@
+pub mod error;
+
+use serde::de::DeserializeOwned;
+
 pub trait RESTMethods {
     type RequestBuilder;
 
+    /// The outcome of executing a request.
+    ///
+    /// Async clients return a future resolving to `Result<T, error::Error>`, blocking clients return the
+    /// `Result<T, error::Error>` itself.
+    type Execution<T>;
+
     fn get(&self) -> Self::RequestBuilder;
 
     fn post(&self) -> Self::RequestBuilder;
@@ -12,4 +22,22 @@ pub trait RESTMethods {
     fn delete(&self) -> Self::RequestBuilder;
 
     fn head(&self) -> Self::RequestBuilder;
+
+    /// Send a request and decode the JSON body of a successful response.
+    ///
+    /// Responses with an error status are returned as [`Error::HttpStatus`](error::Error::HttpStatus) with their body.
+    fn execute_json<T: DeserializeOwned + Send + 'static>(
+        &self,
+        request: Self::RequestBuilder,
+    ) -> Self::Execution<T>;
+
+    /// Send a request and get the text body of a successful response.
+    ///
+    /// Responses with an error status are returned as [`Error::HttpStatus`](error::Error::HttpStatus) with their body.
+    fn execute_text(&self, request: Self::RequestBuilder) -> Self::Execution<String>;
+
+    /// Send a request and get the raw body of a successful response.
+    ///
+    /// Responses with an error status are returned as [`Error::HttpStatus`](error::Error::HttpStatus) with their body.
+    fn execute_bytes(&self, request: Self::RequestBuilder) -> Self::Execution<Vec<u8>>;
 }
@@ -0,0 +1,11 @@
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("The request could not be sent: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("The server responded with {}: {}", .0.0, .0.1)]
+    HttpStatus((reqwest::StatusCode, String)),
+    #[error("The response could not be decoded: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("The response is invalid: {0}")]
+    InvalidResponse(String),
+}
@@ -0,0 +1,364 @@
+use std::{collections::hash_map::RandomState, hash::BuildHasher, time::Duration};
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+use reqwest::{Method, StatusCode, header::HeaderMap};
+
+#[cfg(feature = "blocking")]
+use crate::transport::BlockingHttpTransport;
+#[cfg(feature = "async")]
+use crate::transport::HttpTransport;
+
+/// A kind of failure a [`RetryPolicy`] can retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RetryClass {
+    /// `429 Too Many Requests` and `509 Bandwidth Limit Exceeded`, the server did not process the
+    /// request.
+    RateLimited,
+    /// `500`, `502`, `503` and `504`, which are usually temporary.
+    ServerError,
+    /// `408 Request Timeout` and requests timing out locally.
+    Timeout,
+    /// Failures to connect to the server, the request was never sent.
+    Connection,
+}
+
+/// How failed requests are retried, shared by all clients of this crate.
+///
+/// Retries wait with an exponential backoff plus a random jitter, so many clients failing at
+/// once do not retry at the same time. A `Retry-After` header of the server takes precedence
+/// over the backoff.
+///
+/// Server errors and timeouts are only retried for `GET`, `HEAD` and `OPTIONS` requests, as
+/// the server might have processed other requests already, e.g. an upload. Requests with a
+/// streamed body are never retried.
+///
+/// A client uses its policy for every request. To use another policy for a single request, send
+/// it with a clone of the client configured with that policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    classes: Vec<RetryClass>,
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: Duration,
+}
+
+impl RetryPolicy {
+    /// Construct the default [`RetryPolicy`], retrying all [`RetryClass`]es up to 3 times.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Never retry.
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Set the [`RetryClass`]es that are retried.
+    pub fn with_classes(mut self, classes: impl IntoIterator<Item = RetryClass>) -> Self {
+        self.classes = classes.into_iter().collect();
+        self
+    }
+
+    /// Set the maximum amount of attempts of a single request, including the first one.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the delay before the first retry and the upper limit of the exponential backoff.
+    pub fn with_backoff(mut self, initial: Duration, maximum: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = maximum;
+        self
+    }
+
+    /// Set the upper limit of the random delay added to every backoff.
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Get the maximum amount of attempts of a single request, including the first one.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Check if a [`RetryClass`] is retried.
+    pub fn retries(&self, class: RetryClass) -> bool {
+        self.classes.contains(&class)
+    }
+
+    /// Get the delay before retry number `attempt`, starting at `0`.
+    ///
+    /// A `Retry-After` of the server takes precedence but is capped by the maximum backoff.
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let backoff = retry_after.unwrap_or_else(|| {
+            self.initial_backoff
+                .saturating_mul(2u32.saturating_pow(attempt))
+        });
+        let random = RandomState::new().hash_one(attempt) as f64 / u64::MAX as f64;
+
+        backoff.min(self.max_backoff) + self.jitter.mul_f64(random)
+    }
+
+    /// Send an async request, retrying it according to this policy.
+    ///
+    /// Returns the last response, whatever its status.
+    #[cfg(feature = "async")]
+    pub async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let (client, request) = request.build_split();
+
+        self.execute(&client, request?, |_| Ok::<_, reqwest::Error>(()))
+            .await
+    }
+
+    /// Send an async request with an [`HttpTransport`], retrying it according to this policy and
+    /// calling `on_status` after every response, e.g. to track failures. Returning an error from
+    /// `on_status` stops retrying.
+    ///
+    /// Returns the last response, whatever its status.
+    #[cfg(feature = "async")]
+    pub async fn execute<E: From<reqwest::Error>>(
+        &self,
+        transport: &dyn HttpTransport,
+        mut request: reqwest::Request,
+        mut on_status: impl FnMut(StatusCode) -> Result<(), E>,
+    ) -> Result<reqwest::Response, E> {
+        let mut attempt = 0;
+
+        loop {
+            // Requests with a streamed body can not be cloned and are never retried.
+            let retry = request
+                .try_clone()
+                .filter(|_| attempt + 1 < self.max_attempts);
+            let method = request.method().clone();
+
+            let delay = match transport.execute(request).await {
+                Ok(response) => {
+                    on_status(response.status())?;
+                    if retry.is_none() || !self.retries_status(&method, response.status()) {
+                        return Ok(response);
+                    }
+                    self.delay(attempt, retry_after(response.headers()))
+                }
+                Err(error) if retry.is_some() && self.retries_error(&method, &error) => {
+                    self.delay(attempt, None)
+                }
+                Err(error) => return Err(error.into()),
+            };
+            tokio::time::sleep(delay).await;
+
+            attempt += 1;
+            request = retry.expect("only retried if cloned");
+        }
+    }
+
+    /// Send a blocking request, retrying it according to this policy.
+    ///
+    /// Returns the last response, whatever its status.
+    #[cfg(feature = "blocking")]
+    pub fn send_blocking(
+        &self,
+        request: reqwest::blocking::RequestBuilder,
+    ) -> Result<reqwest::blocking::Response, reqwest::Error> {
+        let (client, request) = request.build_split();
+
+        self.execute_blocking(&client, request?, |_| Ok::<_, reqwest::Error>(()))
+    }
+
+    /// Send a blocking request with a [`BlockingHttpTransport`], like [`RetryPolicy::execute`].
+    ///
+    /// Returns the last response, whatever its status.
+    #[cfg(feature = "blocking")]
+    pub fn execute_blocking<E: From<reqwest::Error>>(
+        &self,
+        transport: &dyn BlockingHttpTransport,
+        mut request: reqwest::blocking::Request,
+        mut on_status: impl FnMut(StatusCode) -> Result<(), E>,
+    ) -> Result<reqwest::blocking::Response, E> {
+        let mut attempt = 0;
+
+        loop {
+            // Requests with a streamed body can not be cloned and are never retried.
+            let retry = request
+                .try_clone()
+                .filter(|_| attempt + 1 < self.max_attempts);
+            let method = request.method().clone();
+
+            let delay = match transport.execute(request) {
+                Ok(response) => {
+                    on_status(response.status())?;
+                    if retry.is_none() || !self.retries_status(&method, response.status()) {
+                        return Ok(response);
+                    }
+                    self.delay(attempt, retry_after(response.headers()))
+                }
+                Err(error) if retry.is_some() && self.retries_error(&method, &error) => {
+                    self.delay(attempt, None)
+                }
+                Err(error) => return Err(error.into()),
+            };
+            std::thread::sleep(delay);
+
+            attempt += 1;
+            request = retry.expect("only retried if cloned");
+        }
+    }
+
+    /// Check if a response with `status` to a `method` request is retried.
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    fn retries_status(&self, method: &Method, status: StatusCode) -> bool {
+        if is_rate_limited(status) {
+            return self.retries(RetryClass::RateLimited);
+        }
+
+        let class = match status.as_u16() {
+            408 => RetryClass::Timeout,
+            500 | 502 | 503 | 504 => RetryClass::ServerError,
+            _ => return false,
+        };
+        self.retries(class) && is_safe(method)
+    }
+
+    /// Check if a `method` request failing with `error` is retried.
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    fn retries_error(&self, method: &Method, error: &reqwest::Error) -> bool {
+        if error.is_connect() {
+            self.retries(RetryClass::Connection)
+        } else {
+            error.is_timeout() && self.retries(RetryClass::Timeout) && is_safe(method)
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            classes: vec![
+                RetryClass::RateLimited,
+                RetryClass::ServerError,
+                RetryClass::Timeout,
+                RetryClass::Connection,
+            ],
+            max_attempts: 4,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            jitter: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Check if the server rejected a request because of its rate limits.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn is_rate_limited(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.as_u16() == 509
+}
+
+/// Parse the `Retry-After` header given in seconds.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Check if a request with `method` does not change anything on the server.
+#[cfg(any(feature = "async", feature = "blocking"))]
+fn is_safe(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+#[cfg(test)]
+mod retry_test {
+    use std::time::Duration;
+
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    use reqwest::{Method, StatusCode, header::HeaderMap};
+
+    use crate::retry::RetryPolicy;
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    use crate::retry::{RetryClass, is_rate_limited, retry_after};
+
+    #[test]
+    fn delay() {
+        let policy = RetryPolicy::new()
+            .with_backoff(Duration::from_secs(1), Duration::from_secs(5))
+            .with_jitter(Duration::ZERO);
+
+        assert_eq!(policy.delay(0, None), Duration::from_secs(1));
+        assert_eq!(policy.delay(2, None), Duration::from_secs(4));
+        assert_eq!(policy.delay(3, None), Duration::from_secs(5));
+        assert_eq!(
+            policy.delay(0, Some(Duration::from_secs(3))),
+            Duration::from_secs(3)
+        );
+        assert_eq!(
+            policy.delay(0, Some(Duration::from_secs(30))),
+            Duration::from_secs(5)
+        );
+
+        let jittered = RetryPolicy::new()
+            .with_backoff(Duration::from_secs(1), Duration::from_secs(5))
+            .with_jitter(Duration::from_secs(1));
+        for attempt in 0..10 {
+            let delay = jittered.delay(0, None);
+            assert!(delay >= Duration::from_secs(1), "{attempt}: {delay:?}");
+            assert!(delay <= Duration::from_secs(2), "{attempt}: {delay:?}");
+        }
+    }
+
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    #[test]
+    fn classes() {
+        let policy = RetryPolicy::new();
+        assert!(policy.retries_status(&Method::GET, StatusCode::SERVICE_UNAVAILABLE));
+        assert!(policy.retries_status(&Method::GET, StatusCode::REQUEST_TIMEOUT));
+        assert!(!policy.retries_status(&Method::GET, StatusCode::NOT_FOUND));
+        assert!(!policy.retries_status(&Method::GET, StatusCode::NOT_IMPLEMENTED));
+        assert!(!policy.retries_status(&Method::POST, StatusCode::BAD_GATEWAY));
+        assert!(policy.retries_status(&Method::POST, StatusCode::TOO_MANY_REQUESTS));
+
+        let rate_limits = RetryPolicy::new().with_classes([RetryClass::RateLimited]);
+        assert!(rate_limits.retries(RetryClass::RateLimited));
+        assert!(!rate_limits.retries_status(&Method::GET, StatusCode::BAD_GATEWAY));
+
+        assert_eq!(RetryPolicy::disabled().max_attempts(), 1);
+        assert_eq!(RetryPolicy::new().with_max_attempts(0).max_attempts(), 1);
+    }
+
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    #[test]
+    fn rate_limited() {
+        assert!(is_rate_limited(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_rate_limited(StatusCode::from_u16(509).unwrap()));
+        assert!(!is_rate_limited(StatusCode::SERVICE_UNAVAILABLE));
+    }
+
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    #[test]
+    fn parse_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(reqwest::header::RETRY_AFTER, "120".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(120)));
+
+        headers.insert(
+            reqwest::header::RETRY_AFTER,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers), None);
+    }
+}
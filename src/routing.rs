@@ -0,0 +1,317 @@
+pub mod profile;
+
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+
+use crate::{
+    coord::{
+        coordinates::{Coordinates, distance_m},
+        polyline::Polyline,
+    },
+    model::{
+        id::{NodeId, WayId},
+        osm_store::OsmStore,
+    },
+    routing::profile::{Direction, RoutingProfile},
+};
+
+/// A directed graph of the ways usable with a [`RoutingProfile`], weighted by travel time.
+///
+/// Every node of a usable way becomes a vertex, so routes can start and end anywhere along a way.
+#[derive(Debug, Clone, Default)]
+pub struct RoadGraph {
+    edges: HashMap<NodeId, Vec<Edge>>,
+    coordinates: HashMap<NodeId, Coordinates>,
+    /// The highest speed in meters per second, used to estimate the remaining time.
+    max_speed: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Edge {
+    to: NodeId,
+    way: WayId,
+    /// The length in meters.
+    length: f64,
+    /// The travel time in seconds.
+    duration: f64,
+}
+
+/// The search algorithm used by [`RoadGraph::route`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    Dijkstra,
+    /// Dijkstra's algorithm guided towards the destination, visiting fewer nodes.
+    #[default]
+    AStar,
+}
+
+/// The fastest route between two nodes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    /// The visited nodes, including the start and the destination.
+    pub nodes: Vec<NodeId>,
+    /// The used ways in order, each listed once per consecutive use.
+    pub ways: Vec<WayId>,
+    pub geometry: Polyline,
+    /// The length in meters.
+    pub distance: f64,
+    /// The travel time in seconds.
+    pub duration: f64,
+}
+
+/// An entry of the priority queue, ordered so the lowest estimate is popped first.
+#[derive(Debug, PartialEq)]
+struct Candidate {
+    estimate: f64,
+    node: NodeId,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl RoadGraph {
+    /// Build the graph of all ways in `store` usable with `profile`.
+    ///
+    /// Segments whose nodes are missing in `store` are skipped.
+    pub fn build(store: &OsmStore, profile: &impl RoutingProfile) -> Self {
+        let mut graph = Self::default();
+
+        for way in store.ways() {
+            let forward = profile.speed(&way.tags, Direction::Forward);
+            let backward = profile.speed(&way.tags, Direction::Backward);
+            if forward.is_none() && backward.is_none() {
+                continue;
+            }
+
+            for pair in way.nodes.windows(2) {
+                let (Some(from), Some(to)) = (
+                    store.node(pair[0]).and_then(|node| node.coordinates()),
+                    store.node(pair[1]).and_then(|node| node.coordinates()),
+                ) else {
+                    continue;
+                };
+                graph.coordinates.insert(pair[0], from);
+                graph.coordinates.insert(pair[1], to);
+
+                let length = distance_m(from, to);
+                for (speed, start, end) in
+                    [(forward, pair[0], pair[1]), (backward, pair[1], pair[0])]
+                {
+                    let Some(speed) = speed.filter(|speed| *speed > 0.0) else {
+                        continue;
+                    };
+                    let speed = speed / 3.6;
+                    graph.max_speed = graph.max_speed.max(speed);
+                    graph.edges.entry(start).or_default().push(Edge {
+                        to: end,
+                        way: way.id,
+                        length,
+                        duration: length / speed,
+                    });
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// Get the amount of nodes in the graph.
+    pub fn len(&self) -> usize {
+        self.coordinates.len()
+    }
+
+    /// Check if the graph has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.coordinates.is_empty()
+    }
+
+    /// Find the node of the graph closest to [`Coordinates`], e.g. to start a route at an address.
+    pub fn nearest_node(&self, coordinates: &Coordinates) -> Option<NodeId> {
+        self.coordinates
+            .iter()
+            .map(|(id, position)| (distance_m(*coordinates, *position), *id))
+            .min_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)))
+            .map(|(_, id)| id)
+    }
+
+    /// Find the fastest [`Route`] from one node to another.
+    ///
+    /// Returns [`None`] if either node is not part of the graph or the destination can not be
+    /// reached.
+    pub fn route(&self, from: NodeId, to: NodeId, algorithm: Algorithm) -> Option<Route> {
+        let target = *self.coordinates.get(&to)?;
+        self.coordinates.get(&from)?;
+
+        // The straight line at the highest speed never overestimates the remaining time.
+        let remaining = |node: NodeId| match algorithm {
+            Algorithm::Dijkstra => 0.0,
+            Algorithm::AStar => self.coordinates.get(&node).map_or(0.0, |position| {
+                distance_m(*position, target) / self.max_speed
+            }),
+        };
+
+        let mut durations: HashMap<NodeId, f64> = HashMap::from([(from, 0.0)]);
+        let mut previous: HashMap<NodeId, Edge> = HashMap::new();
+        let mut queue = BinaryHeap::from([Candidate {
+            estimate: remaining(from),
+            node: from,
+        }]);
+
+        while let Some(Candidate { estimate, node }) = queue.pop() {
+            if node == to {
+                break;
+            }
+            let duration = durations[&node];
+            // Skip outdated entries of nodes that were reached faster in the meantime.
+            if estimate > duration + remaining(node) {
+                continue;
+            }
+
+            for edge in self.edges.get(&node).into_iter().flatten() {
+                let next = duration + edge.duration;
+                if durations.get(&edge.to).is_none_or(|known| next < *known) {
+                    durations.insert(edge.to, next);
+                    previous.insert(edge.to, Edge { to: node, ..*edge });
+                    queue.push(Candidate {
+                        estimate: next + remaining(edge.to),
+                        node: edge.to,
+                    });
+                }
+            }
+        }
+
+        let duration = *durations.get(&to)?;
+        let mut nodes = vec![to];
+        let mut ways = Vec::new();
+        let mut distance = 0.0;
+        let mut current = to;
+        while let Some(edge) = previous.get(&current).filter(|_| current != from) {
+            nodes.push(edge.to);
+            if ways.last() != Some(&edge.way) {
+                ways.push(edge.way);
+            }
+            distance += edge.length;
+            current = edge.to;
+        }
+        nodes.reverse();
+        ways.reverse();
+
+        Some(Route {
+            geometry: Polyline::new(nodes.iter().map(|node| self.coordinates[node]).collect()),
+            nodes,
+            ways,
+            distance,
+            duration,
+        })
+    }
+}
+
+#[cfg(test)]
+mod routing_test {
+    use crate::{
+        coord::coordinates::Coordinates,
+        model::{
+            fixture::{node, tagged, way},
+            id::{NodeId, WayId},
+            osm_store::OsmStore,
+        },
+        routing::{
+            Algorithm, RoadGraph,
+            profile::{Car, Foot},
+        },
+    };
+
+    /// A direct residential street from 1 to 3, a longer but faster primary road around it via 4
+    /// and a primary oneway shortcut from 3 back to 1.
+    fn store() -> OsmStore {
+        [
+            node(1, 0.0, 0.0),
+            node(2, 0.0, 0.01),
+            node(3, 0.0, 0.02),
+            node(4, 0.005, 0.01),
+            node(5, 0.02, 0.0),
+            tagged(way(10, &[1, 2, 3]), &[("highway", "residential")]),
+            tagged(way(11, &[1, 4]), &[("highway", "primary")]),
+            tagged(way(12, &[4, 3]), &[("highway", "primary")]),
+            tagged(
+                way(13, &[3, 1]),
+                &[("highway", "primary"), ("oneway", "yes")],
+            ),
+            tagged(way(14, &[1, 5]), &[("highway", "footway")]),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn fastest_route() {
+        let graph = RoadGraph::build(&store(), &Car);
+
+        for algorithm in [Algorithm::Dijkstra, Algorithm::AStar] {
+            let route = graph
+                .route(NodeId::new(1), NodeId::new(3), algorithm)
+                .unwrap();
+            assert_eq!(route.ways, vec![WayId::new(11), WayId::new(12)]);
+            assert_eq!(
+                route.nodes,
+                vec![NodeId::new(1), NodeId::new(4), NodeId::new(3)]
+            );
+            assert_eq!(route.geometry.coordinates().len(), 3);
+            assert!((route.distance - 2_486.0).abs() < 5.0, "{}", route.distance);
+        }
+
+        let back = graph
+            .route(NodeId::new(3), NodeId::new(1), Algorithm::AStar)
+            .unwrap();
+        assert_eq!(back.ways, vec![WayId::new(13)]);
+    }
+
+    #[test]
+    fn profiles() {
+        let car = RoadGraph::build(&store(), &Car);
+        let foot = RoadGraph::build(&store(), &Foot);
+
+        assert!(!car.is_empty());
+        assert!(
+            car.route(NodeId::new(1), NodeId::new(5), Algorithm::AStar)
+                .is_none()
+        );
+        let walk = foot
+            .route(NodeId::new(1), NodeId::new(3), Algorithm::AStar)
+            .unwrap();
+        assert!(walk.distance < 2_300.0, "{}", walk.distance);
+        assert!(
+            foot.route(NodeId::new(1), NodeId::new(5), Algorithm::AStar)
+                .is_some()
+        );
+        assert_eq!(
+            foot.nearest_node(&Coordinates::from_unchecked(0.019, 0.001)),
+            Some(NodeId::new(5))
+        );
+    }
+
+    #[test]
+    fn same_node() {
+        let graph = RoadGraph::build(&store(), &Car);
+        let route = graph
+            .route(NodeId::new(2), NodeId::new(2), Algorithm::AStar)
+            .unwrap();
+
+        assert_eq!(route.nodes, vec![NodeId::new(2)]);
+        assert!(route.ways.is_empty());
+        assert_eq!(route.duration, 0.0);
+    }
+}
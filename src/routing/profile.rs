@@ -0,0 +1,218 @@
+use crate::model::{
+    tag_value::MaxSpeed,
+    tags::{TagValues, Tags},
+};
+
+/// The direction a way is traversed in, relative to the order of its nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Forward,
+    Backward,
+}
+
+/// Decides which ways can be used and how fast, e.g. by car, bike or foot.
+pub trait RoutingProfile {
+    /// Get the speed in km/h on a way with `tags` in `direction`.
+    ///
+    /// Returns [`None`] if the way can not be used in that direction.
+    fn speed(&self, tags: &Tags, direction: Direction) -> Option<f64>;
+}
+
+/// Routing by car, using the `maxspeed` where it is tagged.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Car;
+
+/// Routing by bicycle at a constant speed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Bicycle;
+
+/// Routing on foot at walking speed, ignoring `oneway`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Foot;
+
+impl RoutingProfile for Car {
+    fn speed(&self, tags: &Tags, direction: Direction) -> Option<f64> {
+        let default = match tags.value("highway")? {
+            "motorway" => 120.0,
+            "trunk" => 100.0,
+            "primary" => 80.0,
+            "secondary" => 70.0,
+            "tertiary" => 60.0,
+            "motorway_link" | "trunk_link" | "primary_link" | "secondary_link"
+            | "tertiary_link" => 50.0,
+            "unclassified" => 50.0,
+            "residential" => 30.0,
+            "service" => 20.0,
+            "living_street" => 7.0,
+            _ => return None,
+        };
+        if !allowed(tags, &["access", "vehicle", "motor_vehicle", "motorcar"])
+            || !oneway_allows(tags, direction, &[])
+        {
+            return None;
+        }
+
+        Some(match tags.max_speed() {
+            Some(MaxSpeed::Limit(limit)) if limit > 0.0 => limit,
+            Some(MaxSpeed::Walk) => 7.0,
+            _ => default,
+        })
+    }
+}
+
+impl RoutingProfile for Bicycle {
+    fn speed(&self, tags: &Tags, direction: Direction) -> Option<f64> {
+        let designated = matches!(tags.value("bicycle"), Some("yes" | "designated"));
+        let speed = match tags.value("highway")? {
+            "motorway" | "motorway_link" | "trunk" | "trunk_link" => return None,
+            "footway" | "pedestrian" | "steps" if !designated => return None,
+            "steps" => 4.0,
+            "footway" | "pedestrian" | "living_street" => 10.0,
+            "track" | "path" | "bridleway" => 12.0,
+            "cycleway" | "primary" | "primary_link" | "secondary" | "secondary_link"
+            | "tertiary" | "tertiary_link" | "unclassified" | "residential" | "service"
+            | "road" => 16.0,
+            _ => return None,
+        };
+        if !allowed(tags, &["access", "vehicle", "bicycle"])
+            || !oneway_allows(tags, direction, &["oneway:bicycle"])
+        {
+            return None;
+        }
+
+        Some(speed)
+    }
+}
+
+impl RoutingProfile for Foot {
+    fn speed(&self, tags: &Tags, _: Direction) -> Option<f64> {
+        let speed = match tags.value("highway")? {
+            "motorway" | "motorway_link" | "trunk" | "trunk_link" | "cycleway"
+                if !matches!(tags.value("foot"), Some("yes" | "designated")) =>
+            {
+                return None;
+            }
+            "steps" => 3.0,
+            "proposed" | "construction" | "abandoned" | "platform" | "raceway" => return None,
+            _ => 5.0,
+        };
+        if !allowed(tags, &["access", "foot"]) {
+            return None;
+        }
+
+        Some(speed)
+    }
+}
+
+/// Check the access keys, from the most general to the most specific, the last tagged one wins.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Key:access>
+fn allowed(tags: &Tags, keys: &[&str]) -> bool {
+    keys.iter()
+        .rev()
+        .find_map(|key| tags.value(key))
+        .is_none_or(|access| !matches!(access, "no" | "private"))
+}
+
+/// Check if the `oneway` restrictions allow `direction`. Keys in `overrides` take precedence
+/// over `oneway`, e.g. `oneway:bicycle=no`.
+///
+/// See <https://wiki.openstreetmap.org/wiki/Key:oneway>
+fn oneway_allows(tags: &Tags, direction: Direction, overrides: &[&str]) -> bool {
+    let oneway = overrides
+        .iter()
+        .chain(&["oneway"])
+        .find_map(|key| tags.value(key));
+    let implied =
+        tags.value("junction") == Some("roundabout") || tags.value("highway") == Some("motorway");
+
+    match (oneway, direction) {
+        (Some("-1" | "reverse"), direction) => direction == Direction::Backward,
+        (Some("yes" | "true" | "1"), direction) => direction == Direction::Forward,
+        (Some("no" | "false" | "0"), _) => true,
+        (_, direction) => !implied || direction == Direction::Forward,
+    }
+}
+
+#[cfg(test)]
+mod profile_test {
+    use crate::{
+        model::tags::Tags,
+        routing::profile::{Bicycle, Car, Direction, Foot, RoutingProfile},
+    };
+
+    fn tags(pairs: &[(&str, &str)]) -> Tags {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn car() {
+        let speed = |pairs: &[(&str, &str)], direction| Car.speed(&tags(pairs), direction);
+
+        assert_eq!(
+            speed(&[("highway", "primary")], Direction::Backward),
+            Some(80.0)
+        );
+        let limited = speed(
+            &[("highway", "primary"), ("maxspeed", "30 mph")],
+            Direction::Forward,
+        );
+        assert!((limited.unwrap() - 48.28).abs() < 0.01);
+        assert_eq!(speed(&[("highway", "footway")], Direction::Forward), None);
+        assert_eq!(
+            speed(
+                &[("highway", "residential"), ("oneway", "yes")],
+                Direction::Backward
+            ),
+            None
+        );
+        assert_eq!(
+            speed(
+                &[("highway", "primary"), ("junction", "roundabout")],
+                Direction::Backward
+            ),
+            None
+        );
+        assert_eq!(
+            speed(
+                &[
+                    ("highway", "service"),
+                    ("access", "no"),
+                    ("motorcar", "yes")
+                ],
+                Direction::Forward
+            ),
+            Some(20.0)
+        );
+    }
+
+    #[test]
+    fn bicycle_and_foot() {
+        let contraflow = tags(&[
+            ("highway", "residential"),
+            ("oneway", "yes"),
+            ("oneway:bicycle", "no"),
+        ]);
+        assert_eq!(Bicycle.speed(&contraflow, Direction::Backward), Some(16.0));
+        assert_eq!(Car.speed(&contraflow, Direction::Backward), None);
+        assert_eq!(
+            Bicycle.speed(&tags(&[("highway", "footway")]), Direction::Forward),
+            None
+        );
+
+        assert_eq!(
+            Foot.speed(
+                &tags(&[("highway", "residential"), ("oneway", "yes")]),
+                Direction::Backward
+            ),
+            Some(5.0)
+        );
+        assert_eq!(
+            Foot.speed(&tags(&[("highway", "motorway")]), Direction::Forward),
+            None
+        );
+    }
+}
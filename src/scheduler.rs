@@ -0,0 +1,384 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "blocking")]
+use std::sync::Condvar;
+
+#[cfg(feature = "blocking")]
+use crate::transport::BlockingHttpTransport;
+#[cfg(feature = "async")]
+use crate::transport::{Execution, HttpTransport};
+
+/// A requests per second budget shared by the async clients of a process.
+///
+/// Clients given the same [`Scheduler`], or clones of it, send their requests one after another
+/// with at least `1 / requests_per_second` between them. Waiting requests are queued in lanes,
+/// one per kind of client, e.g. `overpass` and `osm_api`, and the lanes take turns, so a burst of
+/// Overpass queries does not hold back the requests to the OSM API. Within a lane requests are
+/// sent in the order they arrived.
+///
+/// Every attempt of a retried request waits for its own slot.
+#[cfg(feature = "async")]
+#[derive(Debug, Clone)]
+pub struct Scheduler {
+    state: Arc<AsyncState>,
+}
+
+#[cfg(feature = "async")]
+#[derive(Debug)]
+struct AsyncState {
+    queue: Mutex<Queue>,
+    notify: tokio::sync::Notify,
+}
+
+/// A requests per second budget shared by the blocking clients of a process, see [`Scheduler`].
+#[cfg(feature = "blocking")]
+#[derive(Debug, Clone)]
+pub struct BlockingScheduler {
+    state: Arc<(Mutex<Queue>, Condvar)>,
+}
+
+/// A transport sending the requests of another transport in the slots of a [`Scheduler`], used
+/// by the clients and usable for requests sent without them.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct ScheduledTransport<'a> {
+    inner: &'a dyn HttpTransport,
+    scheduler: Option<&'a Scheduler>,
+    lane: &'static str,
+}
+
+/// A transport sending the requests of another transport in the slots of a
+/// [`BlockingScheduler`], see [`ScheduledTransport`].
+#[cfg(feature = "blocking")]
+#[derive(Debug)]
+pub struct BlockingScheduledTransport<'a> {
+    inner: &'a dyn BlockingHttpTransport,
+    scheduler: Option<&'a BlockingScheduler>,
+    lane: &'static str,
+}
+
+/// The lanes of waiting requests and the time of the next free slot.
+#[derive(Debug)]
+struct Queue {
+    interval: Duration,
+    next_slot: Option<Instant>,
+    lanes: Vec<(&'static str, VecDeque<u64>)>,
+    /// The lane whose turn is next.
+    cursor: usize,
+    next_ticket: u64,
+}
+
+enum Turn {
+    Send,
+    WaitUntil(Instant),
+    Wait,
+}
+
+impl Queue {
+    fn new(requests_per_second: f64) -> Self {
+        Self {
+            // A budget of zero or less is not limited.
+            interval: Duration::try_from_secs_f64(1.0 / requests_per_second)
+                .unwrap_or(Duration::ZERO),
+            next_slot: None,
+            lanes: Vec::new(),
+            cursor: 0,
+            next_ticket: 0,
+        }
+    }
+
+    fn push(&mut self, lane: &'static str) -> u64 {
+        let ticket = self.next_ticket;
+        self.next_ticket += 1;
+
+        match self.lanes.iter_mut().find(|(name, _)| *name == lane) {
+            Some((_, tickets)) => tickets.push_back(ticket),
+            None => self.lanes.push((lane, VecDeque::from([ticket]))),
+        }
+
+        ticket
+    }
+
+    #[cfg(feature = "async")]
+    fn remove(&mut self, ticket: u64) {
+        for (_, tickets) in self.lanes.iter_mut() {
+            tickets.retain(|waiting| *waiting != ticket);
+        }
+    }
+
+    fn queued(&self) -> usize {
+        self.lanes.iter().map(|(_, tickets)| tickets.len()).sum()
+    }
+
+    /// Check whether `ticket` may be sent at `now`, taking its slot if so.
+    fn turn(&mut self, ticket: u64, now: Instant) -> Turn {
+        let count = self.lanes.len();
+        let Some(lane) = (0..count)
+            .map(|offset| (self.cursor + offset) % count)
+            .find(|&lane| !self.lanes[lane].1.is_empty())
+        else {
+            return Turn::Wait;
+        };
+        if self.lanes[lane].1.front() != Some(&ticket) {
+            return Turn::Wait;
+        }
+
+        match self.next_slot {
+            Some(next_slot) if next_slot > now => Turn::WaitUntil(next_slot),
+            _ => {
+                self.lanes[lane].1.pop_front();
+                self.cursor = (lane + 1) % count;
+                self.next_slot = Some(now + self.interval);
+                Turn::Send
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl Scheduler {
+    /// Construct a new [`Scheduler`] sending at most `requests_per_second` requests per second,
+    /// e.g. `0.5` for one request every two seconds.
+    pub fn new(requests_per_second: f64) -> Self {
+        Self {
+            state: Arc::new(AsyncState {
+                queue: Mutex::new(Queue::new(requests_per_second)),
+                notify: tokio::sync::Notify::new(),
+            }),
+        }
+    }
+
+    /// Get the amount of requests waiting for a slot.
+    pub fn queued(&self) -> usize {
+        lock(&self.state.queue).queued()
+    }
+
+    /// Wait for a slot in `lane`, e.g. to count requests sent without the clients of this crate
+    /// like requests to Nominatim.
+    ///
+    /// A request dropped while waiting leaves the queue.
+    pub async fn acquire(&self, lane: &'static str) {
+        let mut ticket = Ticket {
+            queue: &self.state.queue,
+            notify: &self.state.notify,
+            id: lock(&self.state.queue).push(lane),
+            sent: false,
+        };
+
+        loop {
+            let mut notified = std::pin::pin!(self.state.notify.notified());
+            notified.as_mut().enable();
+
+            let turn = lock(&self.state.queue).turn(ticket.id, Instant::now());
+            match turn {
+                Turn::Send => {
+                    ticket.sent = true;
+                    self.state.notify.notify_waiters();
+                    return;
+                }
+                Turn::WaitUntil(next_slot) => {
+                    let deadline = tokio::time::Instant::from_std(next_slot);
+                    let _ = tokio::time::timeout_at(deadline, notified).await;
+                }
+                Turn::Wait => notified.await,
+            }
+        }
+    }
+
+    /// Wrap a transport, so its requests wait for a slot in `lane`.
+    pub fn transport<'a>(
+        &'a self,
+        inner: &'a dyn HttpTransport,
+        lane: &'static str,
+    ) -> ScheduledTransport<'a> {
+        ScheduledTransport::new(inner, Some(self), lane)
+    }
+}
+
+/// A waiting request of a [`Scheduler`], removed from the queue if dropped before it was sent.
+#[cfg(feature = "async")]
+struct Ticket<'a> {
+    queue: &'a Mutex<Queue>,
+    notify: &'a tokio::sync::Notify,
+    id: u64,
+    sent: bool,
+}
+
+#[cfg(feature = "async")]
+impl Drop for Ticket<'_> {
+    fn drop(&mut self) {
+        if !self.sent {
+            lock(self.queue).remove(self.id);
+            self.notify.notify_waiters();
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'a> ScheduledTransport<'a> {
+    /// Construct a new [`ScheduledTransport`], sending the requests right away without a
+    /// `scheduler`.
+    pub fn new(
+        inner: &'a dyn HttpTransport,
+        scheduler: Option<&'a Scheduler>,
+        lane: &'static str,
+    ) -> Self {
+        Self {
+            inner,
+            scheduler,
+            lane,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl HttpTransport for ScheduledTransport<'_> {
+    fn execute(&self, request: reqwest::Request) -> Execution<'_> {
+        Box::pin(async move {
+            if let Some(scheduler) = self.scheduler {
+                scheduler.acquire(self.lane).await;
+            }
+            self.inner.execute(request).await
+        })
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl BlockingScheduler {
+    /// Construct a new [`BlockingScheduler`] sending at most `requests_per_second` requests per
+    /// second, e.g. `0.5` for one request every two seconds.
+    pub fn new(requests_per_second: f64) -> Self {
+        Self {
+            state: Arc::new((Mutex::new(Queue::new(requests_per_second)), Condvar::new())),
+        }
+    }
+
+    /// Get the amount of requests waiting for a slot.
+    pub fn queued(&self) -> usize {
+        lock(&self.state.0).queued()
+    }
+
+    /// Block until there is a slot in `lane`, e.g. to count requests sent without the clients of
+    /// this crate like requests to Nominatim.
+    pub fn acquire(&self, lane: &'static str) {
+        let (queue, condvar) = &*self.state;
+        let mut queue = lock(queue);
+        let ticket = queue.push(lane);
+
+        loop {
+            let now = Instant::now();
+            queue = match queue.turn(ticket, now) {
+                Turn::Send => {
+                    condvar.notify_all();
+                    return;
+                }
+                Turn::WaitUntil(next_slot) => {
+                    condvar
+                        .wait_timeout(queue, next_slot - now)
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .0
+                }
+                Turn::Wait => condvar
+                    .wait(queue)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner()),
+            };
+        }
+    }
+
+    /// Wrap a transport, so its requests wait for a slot in `lane`.
+    pub fn transport<'a>(
+        &'a self,
+        inner: &'a dyn BlockingHttpTransport,
+        lane: &'static str,
+    ) -> BlockingScheduledTransport<'a> {
+        BlockingScheduledTransport::new(inner, Some(self), lane)
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<'a> BlockingScheduledTransport<'a> {
+    /// Construct a new [`BlockingScheduledTransport`], sending the requests right away without
+    /// a `scheduler`.
+    pub fn new(
+        inner: &'a dyn BlockingHttpTransport,
+        scheduler: Option<&'a BlockingScheduler>,
+        lane: &'static str,
+    ) -> Self {
+        Self {
+            inner,
+            scheduler,
+            lane,
+        }
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl BlockingHttpTransport for BlockingScheduledTransport<'_> {
+    fn execute(
+        &self,
+        request: reqwest::blocking::Request,
+    ) -> Result<reqwest::blocking::Response, reqwest::Error> {
+        if let Some(scheduler) = self.scheduler {
+            scheduler.acquire(self.lane);
+        }
+        self.inner.execute(request)
+    }
+}
+
+fn lock(queue: &Mutex<Queue>) -> MutexGuard<'_, Queue> {
+    // The queue stays consistent even if a holder panicked.
+    queue
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod scheduler_test {
+    use std::time::{Duration, Instant};
+
+    use crate::scheduler::{Queue, Turn};
+
+    #[test]
+    fn lanes() {
+        let mut queue = Queue::new(10.0);
+        let start = Instant::now();
+        let overpass = [queue.push("overpass"), queue.push("overpass")];
+        let osm_api = queue.push("osm_api");
+        assert_eq!(queue.queued(), 3);
+
+        assert!(matches!(queue.turn(osm_api, start), Turn::Wait));
+        assert!(matches!(queue.turn(overpass[0], start), Turn::Send));
+        assert!(matches!(queue.turn(overpass[1], start), Turn::Wait));
+        assert!(matches!(
+            queue.turn(osm_api, start),
+            Turn::WaitUntil(next_slot) if next_slot == start + Duration::from_millis(100)
+        ));
+
+        let later = start + Duration::from_millis(100);
+        assert!(matches!(queue.turn(osm_api, later), Turn::Send));
+        assert_eq!(queue.queued(), 1);
+        #[cfg(feature = "async")]
+        {
+            queue.remove(overpass[1]);
+            assert_eq!(queue.queued(), 0);
+        }
+        assert_eq!(Queue::new(0.0).interval, Duration::ZERO);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn blocking() {
+        let scheduler = crate::scheduler::BlockingScheduler::new(50.0);
+        let start = Instant::now();
+        for _ in 0..3 {
+            scheduler.acquire("overpass");
+        }
+
+        assert!(start.elapsed() >= Duration::from_millis(40));
+        assert_eq!(scheduler.queued(), 0);
+    }
+}
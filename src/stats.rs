@@ -0,0 +1,279 @@
+use std::{borrow::Borrow, collections::HashMap};
+
+use crate::{
+    coord::{coordinates::Coordinates, tile::Tile},
+    model::element::{Element, ElementType},
+};
+
+/// The default zoom level of the tiles counting nodes.
+const DEFAULT_ZOOM: u8 = 8;
+
+/// Statistics over the tags of a dataset, collected in a single pass.
+///
+/// Elements can be added one at a time, so datasets larger than memory can be streamed through,
+/// e.g. from an [`ElementReader`](crate::element_reader::ElementReader). Statistics of separate
+/// passes can be combined with [`TagStatistics::merge`].
+///
+/// Besides the frequency of keys, values and combinations of two keys, the nodes are counted
+/// per web mercator tile to show the geographic distribution. Ways and relations have no
+/// position without their nodes, so they are not part of the distribution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagStatistics {
+    zoom: u8,
+    elements: HashMap<ElementType, u64>,
+    keys: HashMap<String, u64>,
+    values: HashMap<(String, String), u64>,
+    combinations: HashMap<(String, String), u64>,
+    tiles: HashMap<(u32, u32), u64>,
+}
+
+impl TagStatistics {
+    /// Construct new empty [`TagStatistics`] counting nodes per tile at zoom level 8.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the zoom level of the tiles counting nodes, at most 24.
+    pub fn with_zoom(mut self, zoom: u8) -> Self {
+        self.zoom = zoom.min(24);
+        self
+    }
+
+    /// Add an [`Element`].
+    pub fn add(&mut self, element: &Element) {
+        *self.elements.entry(element.element_type()).or_default() += 1;
+
+        let mut keys: Vec<&String> = element.tags().keys().collect();
+        keys.sort();
+        for (index, key) in keys.iter().enumerate() {
+            *self.keys.entry((*key).clone()).or_default() += 1;
+            *self
+                .values
+                .entry(((*key).clone(), element.tags()[*key].clone()))
+                .or_default() += 1;
+            for other in keys[index + 1..].iter() {
+                *self
+                    .combinations
+                    .entry(((*key).clone(), (*other).clone()))
+                    .or_default() += 1;
+            }
+        }
+
+        if let Element::Node(node) = element
+            && let Some(coordinates) = node.coordinates()
+        {
+            *self.tiles.entry(tile(&coordinates, self.zoom)).or_default() += 1;
+        }
+    }
+
+    /// Add the counts of other [`TagStatistics`], e.g. of another part of the dataset.
+    ///
+    /// The tiles of `other` are only merged if both use the same zoom level.
+    pub fn merge(&mut self, other: &Self) {
+        fn add<K: Clone + Eq + std::hash::Hash>(
+            into: &mut HashMap<K, u64>,
+            from: &HashMap<K, u64>,
+        ) {
+            for (key, count) in from.iter() {
+                *into.entry(key.clone()).or_default() += count;
+            }
+        }
+
+        add(&mut self.elements, &other.elements);
+        add(&mut self.keys, &other.keys);
+        add(&mut self.values, &other.values);
+        add(&mut self.combinations, &other.combinations);
+        if self.zoom == other.zoom {
+            add(&mut self.tiles, &other.tiles);
+        }
+    }
+
+    /// Get the amount of added elements of an [`ElementType`].
+    pub fn element_count(&self, element_type: ElementType) -> u64 {
+        self.elements
+            .get(&element_type)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Get the amount of elements having a key.
+    pub fn key_count(&self, key: &str) -> u64 {
+        self.keys.get(key).copied().unwrap_or_default()
+    }
+
+    /// Get the amount of elements having a tag.
+    pub fn value_count(&self, key: &str, value: &str) -> u64 {
+        self.values
+            .get(&(key.to_string(), value.to_string()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Get the amount of elements having both keys.
+    pub fn combination_count(&self, a: &str, b: &str) -> u64 {
+        let pair = if a <= b { (a, b) } else { (b, a) };
+
+        self.combinations
+            .get(&(pair.0.to_string(), pair.1.to_string()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Get the `limit` most frequent keys with their counts.
+    pub fn top_keys(&self, limit: usize) -> Vec<(&str, u64)> {
+        top(
+            self.keys.iter().map(|(key, count)| (key.as_str(), *count)),
+            limit,
+        )
+    }
+
+    /// Get the `limit` most frequent values of a key with their counts.
+    pub fn top_values(&self, key: &str, limit: usize) -> Vec<(&str, u64)> {
+        top(
+            self.values
+                .iter()
+                .filter(|((other, _), _)| other == key)
+                .map(|((_, value), count)| (value.as_str(), *count)),
+            limit,
+        )
+    }
+
+    /// Get the `limit` most frequent combinations of two keys with their counts.
+    pub fn top_combinations(&self, limit: usize) -> Vec<((&str, &str), u64)> {
+        top(
+            self.combinations
+                .iter()
+                .map(|((a, b), count)| ((a.as_str(), b.as_str()), *count)),
+            limit,
+        )
+    }
+
+    /// Get the zoom level of the tiles.
+    pub fn zoom(&self) -> u8 {
+        self.zoom
+    }
+
+    /// Iterate over the `x` and `y` of every tile containing nodes with their node counts.
+    ///
+    /// See <https://wiki.openstreetmap.org/wiki/Slippy_map_tilenames>
+    pub fn tiles(&self) -> impl Iterator<Item = ((u32, u32), u64)> + '_ {
+        self.tiles.iter().map(|(tile, count)| (*tile, *count))
+    }
+}
+
+impl Default for TagStatistics {
+    fn default() -> Self {
+        Self {
+            zoom: DEFAULT_ZOOM,
+            elements: HashMap::new(),
+            keys: HashMap::new(),
+            values: HashMap::new(),
+            combinations: HashMap::new(),
+            tiles: HashMap::new(),
+        }
+    }
+}
+
+impl<E: Borrow<Element>> Extend<E> for TagStatistics {
+    fn extend<T: IntoIterator<Item = E>>(&mut self, iter: T) {
+        for element in iter {
+            self.add(element.borrow());
+        }
+    }
+}
+
+impl<E: Borrow<Element>> FromIterator<E> for TagStatistics {
+    fn from_iter<T: IntoIterator<Item = E>>(iter: T) -> Self {
+        let mut statistics = Self::new();
+        statistics.extend(iter);
+        statistics
+    }
+}
+
+/// Sort by descending count and then by key, so the result is deterministic.
+fn top<K: Ord>(counts: impl Iterator<Item = (K, u64)>, limit: usize) -> Vec<(K, u64)> {
+    let mut counts: Vec<(K, u64)> = counts.collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.truncate(limit);
+
+    counts
+}
+
+/// Get the web mercator tile containing [`Coordinates`].
+fn tile(coordinates: &Coordinates, zoom: u8) -> (u32, u32) {
+    let tile = Tile::from_coordinates(coordinates, zoom);
+
+    (tile.x(), tile.y())
+}
+
+#[cfg(test)]
+mod stats_test {
+    use crate::{
+        coord::coordinates::Coordinates,
+        model::{
+            element::{Element, ElementType, Way},
+            fixture::{node, tagged},
+            id::WayId,
+            tags::Tags,
+        },
+        stats::{TagStatistics, tile},
+    };
+
+    fn elements() -> Vec<Element> {
+        vec![
+            tagged(node(1, 52.5, 13.4), &[("amenity", "cafe"), ("name", "A")]),
+            tagged(node(2, 52.5, 13.4), &[("amenity", "cafe"), ("name", "B")]),
+            tagged(node(3, 48.1, 11.6), &[("amenity", "bar")]),
+            Element::Way(Way {
+                id: WayId::new(4),
+                tags: Tags::from([("highway".to_string(), "primary".to_string())]),
+                ..Default::default()
+            }),
+        ]
+    }
+
+    #[test]
+    fn frequencies() {
+        let statistics: TagStatistics = elements().iter().collect();
+
+        assert_eq!(statistics.element_count(ElementType::Node), 3);
+        assert_eq!(statistics.element_count(ElementType::Relation), 0);
+        assert_eq!(statistics.key_count("amenity"), 3);
+        assert_eq!(statistics.value_count("amenity", "cafe"), 2);
+        assert_eq!(statistics.combination_count("name", "amenity"), 2);
+        assert_eq!(statistics.top_keys(2), vec![("amenity", 3), ("name", 2)]);
+        assert_eq!(
+            statistics.top_values("amenity", 5),
+            vec![("cafe", 2), ("bar", 1)]
+        );
+        assert_eq!(
+            statistics.top_combinations(1),
+            vec![(("amenity", "name"), 2)]
+        );
+    }
+
+    #[test]
+    fn distribution() {
+        let mut statistics = TagStatistics::new().with_zoom(4);
+        statistics.extend(elements());
+
+        let mut tiles: Vec<_> = statistics.tiles().collect();
+        tiles.sort();
+        assert_eq!(tiles, vec![((8, 5), 3)]);
+
+        let mut other = TagStatistics::new().with_zoom(4);
+        other.add(&node(5, -33.9, 151.2));
+        statistics.merge(&other);
+        assert_eq!(statistics.tiles().count(), 2);
+        assert_eq!(statistics.element_count(ElementType::Node), 4);
+    }
+
+    #[test]
+    fn tiles() {
+        let tile_of = |lat, lon, zoom| tile(&Coordinates::from_unchecked(lat, lon), zoom);
+
+        assert_eq!(tile_of(0.0, 0.0, 0), (0, 0));
+        assert_eq!(tile_of(52.5, 13.4, 10), (550, 335));
+        assert_eq!(tile_of(-90.0, 180.0, 2), (3, 3));
+    }
+}
@@ -0,0 +1,237 @@
+pub mod error;
+
+#[cfg(feature = "async")]
+pub mod tile_async;
+#[cfg(feature = "blocking")]
+pub mod tile_blocking;
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::header::{
+    CONTENT_TYPE, ETAG, HeaderMap, HeaderName, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+
+use crate::{cache::CachedResponse, coord::tile::Tile, tile::error::Error};
+
+/// Url template of the standard tile layer of openstreetmap.org.
+///
+/// See <https://operations.osmfoundation.org/policies/tiles/>
+pub const OSM_TILE_URL: &str = "https://tile.openstreetmap.org/{z}/{x}/{y}.png";
+
+/// How long a cached tile is used without asking the server, a week as required by the tile
+/// usage policy of the OSM servers.
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// How many tiles are downloaded at once by default.
+const DEFAULT_REQUEST_LIMIT: usize = 2;
+
+/// The header of a cached tile storing when it was downloaded, in seconds since the unix epoch.
+const FETCHED_AT: &str = "x-fetched-at";
+
+/// A server of raster tiles, described by the template of its tile urls.
+///
+/// The template contains `{z}`, `{x}` and `{y}` for the [`Tile`] and optionally `{s}` for one of
+/// the subdomains, e.g. `https://{s}.tile.example.com/{z}/{x}/{y}.png`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TileServer {
+    url_template: String,
+    subdomains: Vec<String>,
+    min_zoom: u8,
+    max_zoom: u8,
+}
+
+impl TileServer {
+    /// Construct a new [`TileServer`] from the template of its tile urls, serving zoom levels 0
+    /// to 19.
+    pub fn new(url_template: impl Into<String>) -> Self {
+        Self {
+            url_template: url_template.into(),
+            subdomains: Vec::new(),
+            min_zoom: 0,
+            max_zoom: 19,
+        }
+    }
+
+    /// Construct a new [`TileServer`] for the standard tile layer of openstreetmap.org, see
+    /// [`OSM_TILE_URL`].
+    pub fn osm() -> Self {
+        Self::new(OSM_TILE_URL)
+    }
+
+    /// Set the subdomains filled into `{s}`, alternating between neighbouring tiles.
+    pub fn with_subdomains<S: Into<String>>(
+        mut self,
+        subdomains: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.subdomains = subdomains.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the zoom levels served.
+    pub fn with_zoom_range(mut self, min_zoom: u8, max_zoom: u8) -> Self {
+        self.min_zoom = min_zoom;
+        self.max_zoom = max_zoom;
+        self
+    }
+
+    /// Get the template of the tile urls.
+    pub fn url_template(&self) -> &str {
+        &self.url_template
+    }
+
+    /// Get the url of a [`Tile`].
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::ZoomNotSupported`] if the zoom level of the tile is not served.
+    pub fn url(&self, tile: &Tile) -> Result<String, Error> {
+        if !(self.min_zoom..=self.max_zoom).contains(&tile.zoom()) {
+            return Err(Error::ZoomNotSupported((
+                tile.zoom(),
+                self.min_zoom..=self.max_zoom,
+            )));
+        }
+
+        let subdomain = match self.subdomains.len() {
+            0 => "",
+            count => {
+                &self.subdomains
+                    [((u64::from(tile.x()) + u64::from(tile.y())) % count as u64) as usize]
+            }
+        };
+
+        Ok(self
+            .url_template
+            .replace("{z}", &tile.zoom().to_string())
+            .replace("{x}", &tile.x().to_string())
+            .replace("{y}", &tile.y().to_string())
+            .replace("{s}", subdomain))
+    }
+}
+
+/// Check if a cached tile was downloaded less than `max_age` ago.
+fn is_fresh(cached: &CachedResponse, max_age: Duration) -> bool {
+    cached
+        .header(FETCHED_AT)
+        .and_then(|fetched_at| fetched_at.parse::<u64>().ok())
+        .is_some_and(|fetched_at| unix_time().saturating_sub(fetched_at) < max_age.as_secs())
+}
+
+/// Get the headers revalidating a cached tile.
+fn conditions(cached: &CachedResponse) -> Vec<(HeaderName, String)> {
+    [(ETAG, IF_NONE_MATCH), (LAST_MODIFIED, IF_MODIFIED_SINCE)]
+        .into_iter()
+        .filter_map(|(header, condition)| {
+            Some((condition, cached.header(header.as_str())?.to_string()))
+        })
+        .collect()
+}
+
+/// Build the cache entry of a downloaded tile, keeping only the headers needed later.
+fn to_cached(headers: &HeaderMap, body: Vec<u8>) -> CachedResponse {
+    let headers = [ETAG, LAST_MODIFIED, CONTENT_TYPE]
+        .into_iter()
+        .filter_map(|name| {
+            let value = headers.get(&name)?.to_str().ok()?;
+            Some((name.as_str().to_string(), value.to_string()))
+        })
+        .collect();
+
+    refreshed(CachedResponse { headers, body })
+}
+
+/// Mark a cached tile as downloaded now.
+fn refreshed(mut cached: CachedResponse) -> CachedResponse {
+    cached.headers.retain(|(name, _)| name != FETCHED_AT);
+    cached
+        .headers
+        .push((FETCHED_AT.to_string(), unix_time().to_string()));
+
+    cached
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+#[cfg(test)]
+mod tile_test {
+    use crate::{
+        coord::tile::Tile,
+        tile::{OSM_TILE_URL, TileServer, error::Error},
+    };
+
+    #[test]
+    fn url() {
+        let tile = Tile::new(10, 550, 335).unwrap();
+        assert_eq!(
+            TileServer::osm().url(&tile).unwrap(),
+            "https://tile.openstreetmap.org/10/550/335.png"
+        );
+        assert_eq!(TileServer::osm().url_template(), OSM_TILE_URL);
+
+        let server = TileServer::new("https://{s}.tiles.example/{z}/{x}/{y}.png")
+            .with_subdomains(["a", "b"])
+            .with_zoom_range(0, 10);
+        assert_eq!(
+            server.url(&tile).unwrap(),
+            "https://b.tiles.example/10/550/335.png"
+        );
+        assert!(matches!(
+            server.url(&Tile::new(11, 0, 0).unwrap()),
+            Err(Error::ZoomNotSupported((11, _)))
+        ));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn cached() {
+        use std::{sync::Arc, time::Duration};
+
+        use reqwest::{
+            StatusCode,
+            header::{ETAG, HeaderMap, HeaderValue},
+        };
+
+        use crate::{
+            cache::{CacheStore, MemoryCache},
+            tile::tile_blocking::TileClient,
+            transport::MockTransport,
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, HeaderValue::from_static("\"v1\""));
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response_headers(StatusCode::OK, headers, "png")
+                .with_response(StatusCode::NOT_MODIFIED, ""),
+        );
+        let cache = Arc::new(MemoryCache::new());
+        let client = TileClient::new(TileServer::osm())
+            .with_transport(transport.clone())
+            .with_cache(cache.clone());
+        let tile = Tile::new(1, 0, 1).unwrap();
+
+        assert_eq!(client.fetch(&tile).unwrap(), b"png");
+        assert_eq!(client.fetch(&tile).unwrap(), b"png");
+        assert_eq!(transport.requests().len(), 1);
+
+        let client = client.with_max_age(Duration::ZERO);
+        assert_eq!(client.fetch(&tile).unwrap(), b"png");
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].headers["if-none-match"], "\"v1\"");
+        assert!(cache.get(&requests[1].url).is_some());
+
+        assert!(matches!(
+            client.fetch(&Tile::new(20, 0, 0).unwrap()),
+            Err(Error::ZoomNotSupported(_))
+        ));
+        assert!(matches!(
+            client.fetch(&Tile::new(2, 0, 0).unwrap()),
+            Err(Error::HttpStatus((StatusCode::NOT_FOUND, _)))
+        ));
+    }
+}
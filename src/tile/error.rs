@@ -0,0 +1,11 @@
+use std::ops::RangeInclusive;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("The request could not be sent: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("The server responded with {}: {}", .0.0, .0.1)]
+    HttpStatus((reqwest::StatusCode, String)),
+    #[error("The tile server does not support zoom level {}, only {:?}", .0.0, .0.1)]
+    ZoomNotSupported((u8, RangeInclusive<u8>)),
+}
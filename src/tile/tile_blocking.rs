@@ -0,0 +1,178 @@
+use std::{sync::Arc, time::Duration};
+
+use reqwest::StatusCode;
+
+use crate::{
+    cache::CacheStore,
+    client_config::ClientConfig,
+    coord::tile::Tile,
+    request_limit::BlockingRequestLimit,
+    retry::RetryPolicy,
+    scheduler::{BlockingScheduledTransport, BlockingScheduler},
+    tile::{
+        DEFAULT_MAX_AGE, DEFAULT_REQUEST_LIMIT, TileServer, conditions, error::Error, is_fresh,
+        refreshed, to_cached,
+    },
+    transport::BlockingHttpTransport,
+};
+
+/// A blocking client downloading raster tiles of a [`TileServer`], e.g. to draw OSM data over a
+/// basemap.
+///
+/// At most two tiles are downloaded at once unless another [`BlockingRequestLimit`] is set, as
+/// asked by the tile usage policy of the OSM servers. The policy also requires a user agent, see
+/// [`ClientConfig::with_user_agent`]. Downloaded tiles can be kept in a [`CacheStore`], which
+/// answers requests for a week before the tile is revalidated with the server.
+///
+/// See <https://operations.osmfoundation.org/policies/tiles/>
+#[derive(Debug, Clone)]
+pub struct TileClient {
+    inner: Arc<Inner>,
+}
+
+/// The state shared by the clones of a [`TileClient`].
+#[derive(Debug, Clone)]
+struct Inner {
+    server: TileServer,
+    client: reqwest::blocking::Client,
+    retry_policy: RetryPolicy,
+    transport: Option<Arc<dyn BlockingHttpTransport>>,
+    request_limit: Option<BlockingRequestLimit>,
+    scheduler: Option<BlockingScheduler>,
+    cache: Option<Arc<dyn CacheStore>>,
+    max_age: Duration,
+}
+
+impl TileClient {
+    /// Construct a new [`TileClient`] for a [`TileServer`].
+    pub fn new(server: TileServer) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                server,
+                client: reqwest::blocking::Client::new(),
+                retry_policy: RetryPolicy::default(),
+                transport: None,
+                request_limit: Some(BlockingRequestLimit::new(DEFAULT_REQUEST_LIMIT)),
+                scheduler: None,
+                cache: None,
+                max_age: DEFAULT_MAX_AGE,
+            }),
+        }
+    }
+
+    /// Use an HTTP client with the given [`ClientConfig`], e.g. with a user agent.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error::Request`] if the HTTP client can not be built.
+    pub fn with_config(mut self, config: &ClientConfig) -> Result<Self, Error> {
+        Arc::make_mut(&mut self.inner).client = config.build_blocking()?;
+        Ok(self)
+    }
+
+    /// Set how failed requests are retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        Arc::make_mut(&mut self.inner).retry_policy = retry_policy;
+        self
+    }
+
+    /// Send the requests with a [`BlockingHttpTransport`] instead of the [`reqwest::blocking::Client`], e.g. a
+    /// [`MockTransport`](crate::transport::MockTransport) in tests.
+    pub fn with_transport(mut self, transport: Arc<dyn BlockingHttpTransport>) -> Self {
+        Arc::make_mut(&mut self.inner).transport = Some(transport);
+        self
+    }
+
+    /// Limit how many requests are in flight at once, replacing the default of two. The limit is
+    /// shared by all clones of this client and can be shared with other clients.
+    pub fn with_request_limit(mut self, request_limit: BlockingRequestLimit) -> Self {
+        Arc::make_mut(&mut self.inner).request_limit = Some(request_limit);
+        self
+    }
+
+    /// Send the requests in the slots of a [`BlockingScheduler`] shared with other clients, queued in its
+    /// `tile` lane.
+    pub fn with_scheduler(mut self, scheduler: BlockingScheduler) -> Self {
+        Arc::make_mut(&mut self.inner).scheduler = Some(scheduler);
+        self
+    }
+
+    /// Keep the downloaded tiles in a [`CacheStore`], e.g. a
+    /// [`DiskCache`](crate::cache::DiskCache).
+    pub fn with_cache(mut self, cache: Arc<dyn CacheStore>) -> Self {
+        Arc::make_mut(&mut self.inner).cache = Some(cache);
+        self
+    }
+
+    /// Set how long a cached tile is used before it is revalidated with the server, a week by
+    /// default.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        Arc::make_mut(&mut self.inner).max_age = max_age;
+        self
+    }
+
+    /// Get the [`TileServer`].
+    pub fn server(&self) -> &TileServer {
+        &self.inner.server
+    }
+
+    /// Get the image of a [`Tile`], from the cache if it is fresh.
+    ///
+    /// # Error
+    ///
+    /// Returns an [`Error`] if the zoom level is not served or the download fails.
+    pub fn fetch(&self, tile: &Tile) -> Result<Vec<u8>, Error> {
+        let url = self.inner.server.url(tile)?;
+        let cached = self.inner.cache.as_ref().and_then(|cache| cache.get(&url));
+        if let Some(cached) = cached
+            .as_ref()
+            .filter(|cached| is_fresh(cached, self.inner.max_age))
+        {
+            return Ok(cached.body.clone());
+        }
+
+        let mut request = self.inner.client.get(&url);
+        for (name, value) in cached.iter().flat_map(conditions) {
+            request = request.header(name, value);
+        }
+        let response = self.send(request)?;
+
+        let status = response.status();
+        let downloaded = match cached {
+            Some(cached) if status == StatusCode::NOT_MODIFIED => refreshed(cached),
+            _ if status.is_success() => {
+                let headers = response.headers().clone();
+                to_cached(&headers, response.bytes()?.to_vec())
+            }
+            _ => return Err(Error::HttpStatus((status, response.text()?))),
+        };
+
+        if let Some(ref cache) = self.inner.cache {
+            cache.put(&url, downloaded.clone());
+        }
+        Ok(downloaded.body)
+    }
+
+    /// Send a request with retries, leaving the status to the caller.
+    fn send(
+        &self,
+        request: reqwest::blocking::RequestBuilder,
+    ) -> Result<reqwest::blocking::Response, Error> {
+        let _permit = self
+            .inner
+            .request_limit
+            .as_ref()
+            .map(BlockingRequestLimit::acquire);
+        let (client, request) = request.build_split();
+
+        self.inner.retry_policy.execute_blocking(
+            &BlockingScheduledTransport::new(
+                self.inner.transport.as_deref().unwrap_or(&client),
+                self.inner.scheduler.as_ref(),
+                "tile",
+            ),
+            request?,
+            |_| Ok::<_, Error>(()),
+        )
+    }
+}
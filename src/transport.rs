@@ -0,0 +1,428 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+#[cfg(feature = "async")]
+use std::{future::Future, pin::Pin};
+
+#[cfg(feature = "compression")]
+use reqwest::header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, HeaderValue, RANGE};
+use reqwest::{Method, StatusCode, header::HeaderMap};
+
+#[cfg(feature = "compression")]
+use crate::compression::gzip::gunzip;
+
+/// The future returned by [`HttpTransport::execute`].
+#[cfg(feature = "async")]
+pub type Execution<'a> =
+    Pin<Box<dyn Future<Output = Result<reqwest::Response, reqwest::Error>> + Send + 'a>>;
+
+/// Sends the requests of the async clients.
+///
+/// Clients build their requests with reqwest and execute them with the transport, which is the
+/// [`reqwest::Client`] they were built with unless another one is set, e.g. a [`MockTransport`]
+/// in tests or an adapter to another HTTP library.
+#[cfg(feature = "async")]
+pub trait HttpTransport: fmt::Debug + Send + Sync {
+    /// Send a request and receive the response.
+    fn execute(&self, request: reqwest::Request) -> Execution<'_>;
+}
+
+/// Sends the requests of the blocking clients, see [`HttpTransport`].
+#[cfg(feature = "blocking")]
+pub trait BlockingHttpTransport: fmt::Debug + Send + Sync {
+    /// Send a request and receive the response.
+    fn execute(
+        &self,
+        request: reqwest::blocking::Request,
+    ) -> Result<reqwest::blocking::Response, reqwest::Error>;
+}
+
+#[cfg(feature = "async")]
+impl HttpTransport for reqwest::Client {
+    fn execute(&self, request: reqwest::Request) -> Execution<'_> {
+        Box::pin(reqwest::Client::execute(self, request))
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl BlockingHttpTransport for reqwest::blocking::Client {
+    fn execute(
+        &self,
+        request: reqwest::blocking::Request,
+    ) -> Result<reqwest::blocking::Response, reqwest::Error> {
+        reqwest::blocking::Client::execute(self, request)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: HttpTransport + ?Sized> HttpTransport for Arc<T> {
+    fn execute(&self, request: reqwest::Request) -> Execution<'_> {
+        T::execute(self, request)
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<T: BlockingHttpTransport + ?Sized> BlockingHttpTransport for Arc<T> {
+    fn execute(
+        &self,
+        request: reqwest::blocking::Request,
+    ) -> Result<reqwest::blocking::Response, reqwest::Error> {
+        T::execute(self, request)
+    }
+}
+
+/// An [`HttpTransport`] or [`BlockingHttpTransport`] asking for gzip compressed responses and
+/// decompressing them, to save bandwidth on large responses like map data.
+///
+/// `Accept-Encoding: gzip` is sent unless the request has its own `Accept-Encoding` or a `Range`,
+/// as a range of a compressed body can not be decompressed. Responses with `Content-Encoding:
+/// gzip` are decompressed in memory and passed on without the header, a body that is not valid
+/// gzip is passed on unchanged.
+#[cfg(feature = "compression")]
+#[derive(Debug)]
+pub struct DecompressingTransport<T> {
+    inner: T,
+}
+
+#[cfg(feature = "compression")]
+impl<T> DecompressingTransport<T> {
+    /// Construct a new [`DecompressingTransport`] sending requests with `inner`, e.g. a
+    /// [`reqwest::Client`].
+    pub fn new(inner: T) -> Self {
+        Self { inner }
+    }
+
+    /// Ask for a gzip compressed response if the request allows it.
+    fn prepare(headers: &mut HeaderMap) {
+        if !headers.contains_key(ACCEPT_ENCODING) && !headers.contains_key(RANGE) {
+            headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("gzip"));
+        }
+    }
+
+    /// Check if a response with `headers` is gzip compressed.
+    fn is_compressed(headers: &HeaderMap) -> bool {
+        headers
+            .get(CONTENT_ENCODING)
+            .is_some_and(|encoding| encoding.as_bytes().eq_ignore_ascii_case(b"gzip"))
+    }
+
+    /// Build the decompressed response, or the unchanged one if `body` is not valid gzip.
+    fn decompress(
+        status: StatusCode,
+        mut headers: HeaderMap,
+        body: &[u8],
+    ) -> http::Response<Vec<u8>> {
+        let body = match gunzip(body) {
+            Ok(decompressed) => {
+                headers.remove(CONTENT_ENCODING);
+                headers.insert(CONTENT_LENGTH, HeaderValue::from(decompressed.len()));
+                decompressed
+            }
+            Err(_) => body.to_vec(),
+        };
+
+        let mut response = http::Response::new(body);
+        *response.status_mut() = status;
+        *response.headers_mut() = headers;
+
+        response
+    }
+}
+
+#[cfg(all(feature = "compression", feature = "async"))]
+impl<T: HttpTransport> HttpTransport for DecompressingTransport<T> {
+    fn execute(&self, mut request: reqwest::Request) -> Execution<'_> {
+        Box::pin(async move {
+            Self::prepare(request.headers_mut());
+
+            let response = self.inner.execute(request).await?;
+            if !Self::is_compressed(response.headers()) {
+                return Ok(response);
+            }
+
+            let (status, headers) = (response.status(), response.headers().clone());
+            let body = response.bytes().await?;
+
+            Ok(reqwest::Response::from(Self::decompress(
+                status, headers, &body,
+            )))
+        })
+    }
+}
+
+#[cfg(all(feature = "compression", feature = "blocking"))]
+impl<T: BlockingHttpTransport> BlockingHttpTransport for DecompressingTransport<T> {
+    fn execute(
+        &self,
+        mut request: reqwest::blocking::Request,
+    ) -> Result<reqwest::blocking::Response, reqwest::Error> {
+        Self::prepare(request.headers_mut());
+
+        let response = self.inner.execute(request)?;
+        if !Self::is_compressed(response.headers()) {
+            return Ok(response);
+        }
+
+        let (status, headers) = (response.status(), response.headers().clone());
+        let body = response.bytes()?;
+
+        Ok(reqwest::blocking::Response::from(Self::decompress(
+            status, headers, &body,
+        )))
+    }
+}
+
+/// A transport answering requests with canned responses instead of sending them, for testing
+/// code using the clients without a server.
+///
+/// Responses are returned in the order they were added. Once all are used, requests are
+/// answered with `404 Not Found`. The received requests are recorded and can be inspected with
+/// [`MockTransport::requests`].
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    responses: Mutex<VecDeque<MockResponse>>,
+    requests: Mutex<Vec<MockRequest>>,
+}
+
+/// A request received by a [`MockTransport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: HeaderMap,
+    /// The body, unless it was streamed.
+    pub body: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone)]
+struct MockResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl MockTransport {
+    /// Construct a new [`MockTransport`] without responses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a response with `status` and `body`.
+    pub fn with_response(self, status: StatusCode, body: impl Into<Vec<u8>>) -> Self {
+        self.with_response_headers(status, HeaderMap::new(), body)
+    }
+
+    /// Add a response with `status`, `headers` and `body`.
+    pub fn with_response_headers(
+        self,
+        status: StatusCode,
+        headers: HeaderMap,
+        body: impl Into<Vec<u8>>,
+    ) -> Self {
+        lock(&self.responses).push_back(MockResponse {
+            status,
+            headers,
+            body: body.into(),
+        });
+        self
+    }
+
+    /// Get the requests received so far.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        lock(&self.requests).clone()
+    }
+
+    fn respond(&self, request: MockRequest) -> http::Response<Vec<u8>> {
+        lock(&self.requests).push(request);
+        let response = lock(&self.responses)
+            .pop_front()
+            .unwrap_or_else(|| MockResponse {
+                status: StatusCode::NOT_FOUND,
+                headers: HeaderMap::new(),
+                body: b"no response left".to_vec(),
+            });
+
+        let mut http_response = http::Response::new(response.body);
+        *http_response.status_mut() = response.status;
+        *http_response.headers_mut() = response.headers;
+
+        http_response
+    }
+}
+
+#[cfg(feature = "async")]
+impl HttpTransport for MockTransport {
+    fn execute(&self, request: reqwest::Request) -> Execution<'_> {
+        let response = self.respond(MockRequest {
+            method: request.method().clone(),
+            url: request.url().to_string(),
+            headers: request.headers().clone(),
+            body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map(<[u8]>::to_vec),
+        });
+
+        Box::pin(async move { Ok(reqwest::Response::from(response)) })
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl BlockingHttpTransport for MockTransport {
+    fn execute(
+        &self,
+        request: reqwest::blocking::Request,
+    ) -> Result<reqwest::blocking::Response, reqwest::Error> {
+        let response = self.respond(MockRequest {
+            method: request.method().clone(),
+            url: request.url().to_string(),
+            headers: request.headers().clone(),
+            body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map(<[u8]>::to_vec),
+        });
+
+        Ok(reqwest::blocking::Response::from(response))
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // The state stays consistent even if a holder panicked.
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(all(test, feature = "blocking", feature = "overpass"))]
+mod transport_test {
+    use std::{sync::Arc, time::Duration};
+
+    use reqwest::{Method, StatusCode};
+
+    use crate::{
+        overpass::overpass_blocking::OverpassAPI,
+        rest_methods::{RESTMethods, error::Error},
+        retry::RetryPolicy,
+        transport::MockTransport,
+    };
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy::new()
+            .with_backoff(Duration::ZERO, Duration::ZERO)
+            .with_jitter(Duration::ZERO)
+    }
+
+    #[test]
+    fn canned_responses() {
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response(StatusCode::SERVICE_UNAVAILABLE, "busy")
+                .with_response(StatusCode::OK, "{\"elements\":[]}"),
+        );
+        let api = OverpassAPI::new("https://overpass.example/api/interpreter")
+            .unwrap()
+            .with_retry_policy(policy())
+            .with_transport(transport.clone());
+
+        let text = api.execute_text(api.get().query(&[("data", "node(1);out;")]));
+        assert_eq!(text.unwrap(), "{\"elements\":[]}");
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0], requests[1]);
+        assert_eq!(requests[0].method, Method::GET);
+        assert_eq!(
+            requests[0].url,
+            "https://overpass.example/api/interpreter?data=node%281%29%3Bout%3B"
+        );
+    }
+
+    #[test]
+    fn not_retried() {
+        let transport =
+            Arc::new(MockTransport::new().with_response(StatusCode::BAD_GATEWAY, "down"));
+        let api = OverpassAPI::new("https://overpass.example/api/interpreter")
+            .unwrap()
+            .with_retry_policy(policy())
+            .with_transport(transport.clone());
+
+        let result = api.execute_text(api.post().body("node(1);out;"));
+        assert!(matches!(
+            result,
+            Err(Error::HttpStatus((StatusCode::BAD_GATEWAY, ref body))) if body == "down"
+        ));
+        assert_eq!(transport.requests().len(), 1);
+        assert_eq!(
+            transport.requests()[0].body.as_deref(),
+            Some(&b"node(1);out;"[..])
+        );
+
+        let result = api.execute_text(api.get());
+        assert!(matches!(
+            result,
+            Err(Error::HttpStatus((StatusCode::NOT_FOUND, _)))
+        ));
+    }
+
+    #[test]
+    fn handles() {
+        fn assert_handle<T: Clone + Send + Sync + 'static>() {}
+        assert_handle::<OverpassAPI>();
+        #[cfg(feature = "async")]
+        assert_handle::<crate::overpass::overpass_async::OverpassAPI>();
+
+        let transport = Arc::new(MockTransport::new().with_response(StatusCode::OK, "[]"));
+        let api = OverpassAPI::new("https://overpass.example/api/interpreter")
+            .unwrap()
+            .with_transport(transport);
+        let clone = api.clone();
+        assert_eq!(
+            clone.url().as_str(),
+            "https://overpass.example/api/interpreter"
+        );
+        assert_eq!(clone.execute_text(clone.get()).unwrap(), "[]");
+        assert!(OverpassAPI::new("not a url").is_err());
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn decompression() {
+        use reqwest::header::{ACCEPT_ENCODING, CONTENT_ENCODING, HeaderMap, RANGE};
+
+        use crate::transport::DecompressingTransport;
+
+        // `printf 'hello\n' | gzip -n`
+        const HELLO: [u8; 26] = [
+            0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xcb, 0x48, 0xcd, 0xc9,
+            0xc9, 0xe7, 0x02, 0x00, 0x20, 0x30, 0x3a, 0x36, 0x06, 0x00, 0x00, 0x00,
+        ];
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_ENCODING, "gzip".parse().unwrap());
+        let mock = Arc::new(
+            MockTransport::new()
+                .with_response_headers(StatusCode::OK, headers.clone(), HELLO)
+                .with_response_headers(StatusCode::OK, headers, "not gzip")
+                .with_response(StatusCode::OK, "plain"),
+        );
+        let api = OverpassAPI::new("https://overpass.example/api/interpreter")
+            .unwrap()
+            .with_retry_policy(policy())
+            .with_transport(Arc::new(DecompressingTransport::new(mock.clone())));
+
+        assert_eq!(api.execute_text(api.get()).unwrap(), "hello\n");
+        assert_eq!(api.execute_text(api.get()).unwrap(), "not gzip");
+        assert_eq!(
+            api.execute_text(api.get().header(RANGE, "bytes=0-"))
+                .unwrap(),
+            "plain"
+        );
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].headers[ACCEPT_ENCODING], "gzip");
+        assert!(!requests[2].headers.contains_key(ACCEPT_ENCODING));
+    }
+}
@@ -0,0 +1,428 @@
+use std::borrow::Cow;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid XML at byte {}: {}", .0.0, .0.1)]
+    Syntax((usize, &'static str)),
+    #[error("Missing attribute `{}` on `{}`", .0.1, .0.0)]
+    MissingAttribute((String, &'static str)),
+    #[error("Invalid value for attribute `{}`: {}", .0.0, .0.1)]
+    InvalidAttribute((&'static str, String)),
+}
+
+/// A single XML event.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Event<'a> {
+    Start(Tag<'a>),
+    End(&'a str),
+    Text(Cow<'a, str>),
+}
+
+/// A start tag with its attributes.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Tag<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) attributes: Vec<(&'a str, Cow<'a, str>)>,
+    pub(crate) self_closing: bool,
+}
+
+impl Tag<'_> {
+    /// Get the value of an attribute.
+    pub(crate) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_ref())
+    }
+
+    /// Get the value of an attribute that must be present.
+    pub(crate) fn required(&self, name: &'static str) -> Result<&str, Error> {
+        self.attribute(name)
+            .ok_or_else(|| Error::MissingAttribute((self.name.to_string(), name)))
+    }
+
+    /// Get and parse the value of an attribute that must be present.
+    pub(crate) fn parse<T: std::str::FromStr>(&self, name: &'static str) -> Result<T, Error> {
+        let value = self.required(name)?;
+        value
+            .parse()
+            .map_err(|_| Error::InvalidAttribute((name, value.to_string())))
+    }
+
+    /// Get and parse the value of an optional attribute.
+    pub(crate) fn parse_optional<T: std::str::FromStr>(
+        &self,
+        name: &'static str,
+    ) -> Result<Option<T>, Error> {
+        match self.attribute(name) {
+            Some(value) => value
+                .parse()
+                .map(Some)
+                .map_err(|_| Error::InvalidAttribute((name, value.to_string()))),
+            None => Ok(None),
+        }
+    }
+}
+
+/// A minimal pull parser covering the subset of XML used by OSM documents, yielding [`Event`]s.
+///
+/// Declarations, processing instructions, comments and doctypes are skipped.
+/// Whitespace-only text is not reported.
+#[derive(Debug)]
+pub(crate) struct Reader<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    /// Get the amount of bytes consumed so far.
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Skip all events until the end tag matching an already consumed start tag.
+    pub(crate) fn skip_element(&mut self) -> Result<(), Error> {
+        let mut depth = 1usize;
+        while depth > 0 {
+            match self.next() {
+                Some(Ok(Event::Start(tag))) if !tag.self_closing => depth += 1,
+                Some(Ok(Event::End(_))) => depth -= 1,
+                Some(Ok(_)) => (),
+                Some(Err(e)) => return Err(e),
+                None => return Err(self.error("unexpected end of document")),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read the text of an already consumed start tag until its end tag.
+    ///
+    /// Text inside nested elements is skipped. Surrounding whitespace is trimmed.
+    pub(crate) fn read_text_content(&mut self) -> Result<String, Error> {
+        let mut text = String::new();
+        loop {
+            match self.next() {
+                Some(Ok(Event::Start(tag))) if !tag.self_closing => self.skip_element()?,
+                Some(Ok(Event::Text(value))) => text.push_str(&value),
+                Some(Ok(Event::End(_))) => return Ok(text.trim().to_string()),
+                Some(Ok(_)) => (),
+                Some(Err(e)) => return Err(e),
+                None => return Err(self.error("unexpected end of document")),
+            }
+        }
+    }
+
+    fn error(&self, message: &'static str) -> Error {
+        Error::Syntax((self.position, message))
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn skip_past(&mut self, pattern: &str) -> Result<(), Error> {
+        match self.rest().find(pattern) {
+            Some(offset) => {
+                self.position += offset + pattern.len();
+                Ok(())
+            }
+            None => Err(self.error("unterminated markup")),
+        }
+    }
+
+    fn read_text(&mut self) -> Result<Option<Event<'a>>, Error> {
+        let rest = self.rest();
+        let end = rest.find('<').unwrap_or(rest.len());
+        let text = &rest[..end];
+        self.position += end;
+
+        if text.trim().is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(Event::Text(
+                unescape(text).ok_or_else(|| self.error("invalid entity"))?,
+            )))
+        }
+    }
+
+    fn read_end(&mut self) -> Result<Event<'a>, Error> {
+        // Skip `</`.
+        self.position += 2;
+        let rest = self.rest();
+        let end = rest
+            .find('>')
+            .ok_or_else(|| self.error("unterminated end tag"))?;
+        let name = rest[..end].trim();
+        self.position += end + 1;
+
+        Ok(Event::End(name))
+    }
+
+    fn read_start(&mut self) -> Result<Event<'a>, Error> {
+        // Skip `<`.
+        self.position += 1;
+        let name = self.read_name()?;
+        let mut attributes = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+
+            if rest.starts_with("/>") {
+                self.position += 2;
+                return Ok(Event::Start(Tag {
+                    name,
+                    attributes,
+                    self_closing: true,
+                }));
+            } else if rest.starts_with('>') {
+                self.position += 1;
+                return Ok(Event::Start(Tag {
+                    name,
+                    attributes,
+                    self_closing: false,
+                }));
+            } else if rest.is_empty() {
+                return Err(self.error("unterminated start tag"));
+            }
+
+            let key = self.read_name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(self.error("expected `=` after attribute name"));
+            }
+            self.position += 1;
+            self.skip_whitespace();
+
+            let rest = self.rest();
+            let quote = match rest.chars().next() {
+                Some(quote @ ('"' | '\'')) => quote,
+                _ => return Err(self.error("expected quoted attribute value")),
+            };
+            let end = rest[1..]
+                .find(quote)
+                .ok_or_else(|| self.error("unterminated attribute value"))?;
+            let value = &rest[1..end + 1];
+            self.position += end + 2;
+
+            attributes.push((
+                key,
+                unescape(value).ok_or_else(|| self.error("invalid entity"))?,
+            ));
+        }
+    }
+
+    fn read_name(&mut self) -> Result<&'a str, Error> {
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(rest.len());
+
+        if end == 0 {
+            return Err(self.error("expected a name"));
+        }
+        self.position += end;
+
+        Ok(&rest[..end])
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+}
+
+impl<'a> Iterator for Reader<'a> {
+    type Item = Result<Event<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let rest = self.rest();
+
+            let result = if rest.is_empty() {
+                return None;
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>").map(|_| None)
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->").map(|_| None)
+            } else if rest.starts_with("<![CDATA[") {
+                let start = self.position + "<![CDATA[".len();
+                self.skip_past("]]>").map(|_| {
+                    Some(Event::Text(Cow::Borrowed(
+                        &self.input[start..self.position - "]]>".len()],
+                    )))
+                })
+            } else if rest.starts_with("<!") {
+                self.skip_past(">").map(|_| None)
+            } else if rest.starts_with("</") {
+                self.read_end().map(Some)
+            } else if rest.starts_with('<') {
+                self.read_start().map(Some)
+            } else {
+                self.read_text()
+            };
+
+            match result {
+                Ok(Some(event)) => return Some(Ok(event)),
+                Ok(None) => continue,
+                Err(e) => {
+                    // Stop after the first error.
+                    self.position = self.input.len();
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+/// Replace XML entities and character references.
+///
+/// Returns [`None`] if an entity is invalid.
+pub(crate) fn unescape(value: &str) -> Option<Cow<'_, str>> {
+    if !value.contains('&') {
+        return Some(Cow::Borrowed(value));
+    }
+
+    let mut unescaped = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        let end = rest[start..].find(';')? + start;
+        let entity = &rest[start + 1..end];
+
+        let c = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()?
+                } else if let Some(decimal) = entity.strip_prefix('#') {
+                    decimal.parse().ok()?
+                } else {
+                    return None;
+                };
+                char::from_u32(code)?
+            }
+        };
+        unescaped.push(c);
+        rest = &rest[end + 1..];
+    }
+    unescaped.push_str(rest);
+
+    Some(Cow::Owned(unescaped))
+}
+
+/// Escape a string for use inside an XML attribute.
+///
+/// Line breaks and tabs are written as character references, parsers normalize them to spaces
+/// otherwise.
+pub(crate) fn escape(value: &str) -> Cow<'_, str> {
+    if !value.contains(['&', '<', '>', '"', '\'', '\n', '\r', '\t']) {
+        return Cow::Borrowed(value);
+    }
+
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\t' => escaped.push_str("&#9;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    Cow::Owned(escaped)
+}
+
+#[cfg(test)]
+mod xml_test {
+    use std::borrow::Cow;
+
+    use crate::xml::{Event, Reader, escape, unescape};
+
+    #[test]
+    fn events() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <!-- comment -->
+            <osm version="0.6">
+                <node id="1" lat='1.5'/>
+                <note>a &amp; b</note>
+            </osm>"#;
+        let events: Vec<Event<'_>> = Reader::new(xml).collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(events.len(), 6);
+        match &events[0] {
+            Event::Start(tag) => {
+                assert_eq!(tag.name, "osm");
+                assert_eq!(tag.attribute("version"), Some("0.6"));
+                assert!(!tag.self_closing);
+            }
+            _ => panic!("expected start tag"),
+        }
+        match &events[1] {
+            Event::Start(tag) => {
+                assert_eq!(tag.name, "node");
+                assert_eq!(tag.parse::<i64>("id").unwrap(), 1);
+                assert_eq!(tag.attribute("lat"), Some("1.5"));
+                assert!(tag.self_closing);
+            }
+            _ => panic!("expected start tag"),
+        }
+        assert_eq!(events[3], Event::Text(Cow::Borrowed("a & b")));
+        assert_eq!(events[5], Event::End("osm"));
+    }
+
+    #[test]
+    fn skip_element() {
+        let mut reader = Reader::new("<a><b><c/></b><d>text</d></a><e/>");
+        reader.next();
+        reader.skip_element().unwrap();
+
+        match reader.next() {
+            Some(Ok(Event::Start(tag))) => assert_eq!(tag.name, "e"),
+            _ => panic!("expected start tag"),
+        }
+    }
+
+    #[test]
+    fn read_text_content() {
+        let mut reader = Reader::new("<a> x <b>y</b>&amp;z </a><e/>");
+        reader.next();
+
+        assert_eq!(reader.read_text_content().unwrap(), "x &z");
+        assert!(matches!(reader.next(), Some(Ok(Event::Start(tag))) if tag.name == "e"));
+    }
+
+    #[test]
+    fn unterminated() {
+        let mut reader = Reader::new(r#"<node id="1"#);
+
+        assert!(reader.next().unwrap().is_err());
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn entities() {
+        assert_eq!(unescape("&lt;&#65;&#x42;&gt;").unwrap(), "<AB>");
+        assert!(unescape("&unknown;").is_none());
+        assert_eq!(
+            escape(r#"a "b" & 'c'"#),
+            "a &quot;b&quot; &amp; &apos;c&apos;"
+        );
+        assert_eq!(escape("a\r\n\tb"), "a&#13;&#10;&#9;b");
+        assert_eq!(unescape(&escape("a\r\n\tb")).unwrap(), "a\r\n\tb");
+    }
+}